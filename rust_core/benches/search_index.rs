@@ -0,0 +1,50 @@
+//! Search-as-you-type benchmark comparing a full scan with the search index
+//!
+//! Run with `cargo bench --bench search_index`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mindmap_core::graph::Graph;
+use mindmap_core::models::Node;
+use mindmap_core::search::SearchOptions;
+
+const NODE_COUNT: usize = 20_000;
+const TOPICS: [&str; 8] = ["design", "budget", "research", "hiring", "launch", "support", "legal", "travel"];
+
+fn create_large_graph() -> Graph {
+    let mut graph = Graph::new();
+    let root_id = graph.add_node(Node::new("Company Plan")).unwrap();
+    for i in 1..NODE_COUNT {
+        let topic = TOPICS[i % TOPICS.len()];
+        graph
+            .add_node(Node::new_child(root_id, format!("{} item {}", topic, i)))
+            .unwrap();
+    }
+    graph
+}
+
+fn bench_search_as_you_type(c: &mut Criterion) {
+    let options = SearchOptions::default();
+    let keystrokes = ["l", "la", "lau", "laun", "launc", "launch 4217"];
+
+    let scanning = create_large_graph();
+    c.bench_function("search_full_scan_20k", |b| {
+        b.iter(|| {
+            for query in keystrokes {
+                black_box(scanning.search(query, &options));
+            }
+        })
+    });
+
+    let mut indexed = create_large_graph();
+    indexed.enable_search_index();
+    c.bench_function("search_indexed_20k", |b| {
+        b.iter(|| {
+            for query in keystrokes {
+                black_box(indexed.search(query, &options));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_search_as_you_type);
+criterion_main!(benches);
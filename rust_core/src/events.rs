@@ -0,0 +1,132 @@
+//! Structured engine events for host telemetry
+//!
+//! The engine logs through the `log` crate, which only yields formatted text.
+//! Hosts that want to record what happened (which operation, on which
+//! document, how many nodes, how long it took) can register an
+//! [`EngineListener`] with [`add_listener`] and receive an [`EngineEvent`] at
+//! each key point. Listeners are process-wide and called synchronously on the
+//! thread doing the work, so they should return quickly.
+
+use crate::io::FileFormat;
+use crate::layout::LayoutType;
+use crate::types::DocumentId;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Something the engine did that hosts may want to record
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// A document was loaded or created and is now the open document
+    DocumentOpened {
+        document_id: DocumentId,
+        title: String,
+    },
+    /// A document was written to storage
+    DocumentSaved {
+        document_id: DocumentId,
+        duration: Duration,
+    },
+    /// A layout engine finished positioning a graph
+    LayoutComputed {
+        layout_type: LayoutType,
+        node_count: usize,
+        iterations: u32,
+        duration: Duration,
+    },
+    /// A file was parsed into a document
+    ImportFinished {
+        format: FileFormat,
+        document_id: DocumentId,
+        node_count: usize,
+        warning_count: usize,
+        duration: Duration,
+    },
+}
+
+/// Receiver of [`EngineEvent`]s
+///
+/// Implemented for any `Fn(&EngineEvent)` closure that is `Send + Sync`.
+pub trait EngineListener: Send + Sync {
+    /// Handle one event
+    fn on_event(&self, event: &EngineEvent);
+}
+
+impl<F> EngineListener for F
+where
+    F: Fn(&EngineEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &EngineEvent) {
+        self(event)
+    }
+}
+
+/// Handle for removing a registered listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+/// Registered listeners, in registration order
+static LISTENERS: RwLock<Vec<(ListenerId, Arc<dyn EngineListener>)>> = RwLock::new(Vec::new());
+
+/// Source of listener IDs
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Register a listener for all subsequent engine events
+pub fn add_listener(listener: Arc<dyn EngineListener>) -> ListenerId {
+    let id = ListenerId(NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed));
+    if let Ok(mut listeners) = LISTENERS.write() {
+        listeners.push((id, listener));
+    }
+    id
+}
+
+/// Unregister a listener, returning whether it was registered
+pub fn remove_listener(id: ListenerId) -> bool {
+    let Ok(mut listeners) = LISTENERS.write() else {
+        return false;
+    };
+    let before = listeners.len();
+    listeners.retain(|(listener_id, _)| *listener_id != id);
+    listeners.len() != before
+}
+
+/// Deliver an event to every registered listener
+pub(crate) fn emit(event: EngineEvent) {
+    log::debug!("Engine event: {:?}", event);
+
+    // Call listeners outside the lock so they may register or remove listeners
+    let listeners: Vec<Arc<dyn EngineListener>> = match LISTENERS.read() {
+        Ok(listeners) => listeners.iter().map(|(_, listener)| listener.clone()).collect(),
+        Err(_) => return,
+    };
+    for listener in listeners {
+        listener.on_event(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_listener_receives_events_until_removed() {
+        let document_id = DocumentId::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let id = add_listener(Arc::new(move |event: &EngineEvent| {
+            // Other tests emit events concurrently; keep only this test's
+            if matches!(event, EngineEvent::DocumentSaved { document_id: saved, .. } if *saved == document_id) {
+                sink.lock().unwrap().push(event.clone());
+            }
+        }));
+
+        let saved = EngineEvent::DocumentSaved { document_id, duration: Duration::from_millis(3) };
+        emit(saved.clone());
+        assert!(remove_listener(id));
+        emit(saved.clone());
+
+        assert_eq!(*received.lock().unwrap(), vec![saved]);
+        assert!(!remove_listener(id));
+    }
+}
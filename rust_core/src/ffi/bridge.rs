@@ -4,24 +4,107 @@
 //! handling the communication between Flutter UI and Rust core engine.
 
 use super::{
-    BridgeError, ExportFormat, FfiBatchResult, FfiLayoutResult, FfiLayoutType, FfiMindmapData,
-    FfiNodeData, FfiNodeUpdate, FfiPerformanceMetrics, FfiPoint, FfiResult, FfiSearchResult,
-    MindmapFFI,
+    BridgeError, ExportFormat, FfiBatchResult, FfiImportReport, FfiMindmapEvent, FfiNodeDelta, FfiLayoutFrame, FfiLayoutResult, FfiLayoutType, FfiMindmapData,
+    FfiNodeData, FfiNodeUpdate, FfiPerformanceMetrics, FfiPoint, FfiRepairReport, FfiResult, FfiSearchContext, FfiSearchOptions, FfiSearchResult,
+    FfiStatus, MindmapFFI,
 };
 use crate::{
-    graph::Graph,
-    layout::{LayoutEngineImpl, LayoutType},
-    models::{MindmapDocument, Node},
-    search::SearchEngine,
-    types::{MindmapId, NodeId},
+    events::{self, EngineEvent, ListenerId},
+    graph::{Graph, RepairOptions, UndoCommand, UndoStack},
+    io::{FileFormat, FormatManager, ImportExportOptions},
+    layout::{self, Easing, LayoutBounds, LayoutConfig, LayoutEngine, LayoutEngineImpl, LayoutResult, LayoutType},
+    models::{Edge, MindmapDocument, Node},
+    search::{SearchContext, SearchEngine, SearchOptions},
+    types::{AttachmentId, MindmapId, NodeId},
 };
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 #[cfg(feature = "flutter_rust_bridge_feature")]
 use flutter_rust_bridge::frb;
 
+/// Receiver of [`FfiMindmapEvent`]s, e.g. a Dart stream
+///
+/// Implemented for any `Fn(FfiMindmapEvent) -> bool` closure that is
+/// `Send + Sync`; returning false unsubscribes the receiver. The generated
+/// Dart glue wraps its `StreamSink` in such a closure.
+pub trait FfiEventSink: Send + Sync {
+    /// Deliver one event, returning whether the receiver is still listening
+    fn send(&self, event: FfiMindmapEvent) -> bool;
+}
+
+impl<F> FfiEventSink for F
+where
+    F: Fn(FfiMindmapEvent) -> bool + Send + Sync,
+{
+    fn send(&self, event: FfiMindmapEvent) -> bool {
+        self(event)
+    }
+}
+
+/// Subscribed event receivers, in subscription order
+#[derive(Default)]
+struct EventSinks(Vec<Arc<dyn FfiEventSink>>);
+
+impl std::fmt::Debug for EventSinks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EventSinks({} subscribers)", self.0.len())
+    }
+}
+
+impl EventSinks {
+    /// Deliver events to every subscriber, dropping those that stopped listening
+    fn publish(sinks: &RwLock<EventSinks>, events: impl IntoIterator<Item = FfiMindmapEvent>) {
+        let receivers: Vec<Arc<dyn FfiEventSink>> = match sinks.read() {
+            Ok(sinks) if !sinks.0.is_empty() => sinks.0.clone(),
+            _ => return,
+        };
+
+        let mut closed = Vec::new();
+        for event in events {
+            for receiver in &receivers {
+                if !receiver.send(event.clone()) {
+                    closed.push(receiver.clone());
+                }
+            }
+        }
+
+        if !closed.is_empty() {
+            if let Ok(mut sinks) = sinks.write() {
+                sinks.0.retain(|sink| !closed.iter().any(|closed| Arc::ptr_eq(closed, sink)));
+            }
+        }
+    }
+}
+
+/// Forward engine events about the open document, e.g. auto-saves, to subscribers
+///
+/// Holds only weak references so the process-wide listener does not keep a
+/// dropped bridge alive.
+fn forward_engine_events(
+    document: &Arc<RwLock<Option<MindmapDocument>>>,
+    sinks: &Arc<RwLock<EventSinks>>,
+) -> ListenerId {
+    let document = Arc::downgrade(document);
+    let sinks = Arc::downgrade(sinks);
+    events::add_listener(Arc::new(move |event: &EngineEvent| {
+        let EngineEvent::DocumentSaved { document_id, .. } = event else {
+            return;
+        };
+        let (Some(document), Some(sinks)) = (document.upgrade(), sinks.upgrade()) else {
+            return;
+        };
+        let is_open = matches!(document.read().as_deref(), Ok(Some(open)) if open.id == *document_id);
+        if is_open {
+            EventSinks::publish(&sinks, [FfiMindmapEvent::DocumentSaved {
+                document_id: document_id.to_string(),
+            }]);
+        }
+    }))
+}
+
 /// Main bridge implementation for mindmap operations
 #[derive(Debug)]
 pub struct MindmapBridge {
@@ -29,12 +112,28 @@ pub struct MindmapBridge {
     document: Arc<RwLock<Option<MindmapDocument>>>,
     /// Graph engine for node and edge operations
     pub graph: Arc<RwLock<Graph>>,
-    /// Layout engine for positioning algorithms
-    layout_engine: Arc<LayoutEngineImpl>,
+    /// Configuration for layouts and their animations
+    layout_config: Arc<RwLock<LayoutConfig>>,
     /// Search engine for text and tag queries
     pub search_engine: Arc<RwLock<SearchEngine>>,
     /// Performance metrics tracking
     metrics: Arc<RwLock<Vec<FfiPerformanceMetrics>>>,
+    /// Deleted node IDs with deletion time in milliseconds, for change polling
+    ///
+    /// Entries at or before a polled marker are pruned by that poll.
+    deleted_nodes: Arc<RwLock<Vec<(NodeId, i64)>>>,
+    /// Edits made through the bridge, for undo and redo
+    undo_stack: Arc<RwLock<UndoStack>>,
+    /// Receivers of change events
+    event_sinks: Arc<RwLock<EventSinks>>,
+    /// Engine listener forwarding saves of the open document to `event_sinks`
+    engine_listener: ListenerId,
+}
+
+impl Drop for MindmapBridge {
+    fn drop(&mut self) {
+        events::remove_listener(self.engine_listener);
+    }
 }
 
 impl Default for MindmapBridge {
@@ -46,15 +145,68 @@ impl Default for MindmapBridge {
 impl MindmapBridge {
     /// Create a new bridge instance
     pub fn new() -> Self {
+        let document = Arc::new(RwLock::new(None));
+        let event_sinks = Arc::new(RwLock::new(EventSinks::default()));
+        let engine_listener = forward_engine_events(&document, &event_sinks);
         Self {
-            document: Arc::new(RwLock::new(None)),
+            document,
             graph: Arc::new(RwLock::new(Graph::new())),
-            layout_engine: Arc::new(LayoutEngineImpl::new()),
+            layout_config: Arc::new(RwLock::new(LayoutConfig::default())),
             search_engine: Arc::new(RwLock::new(SearchEngine::new())),
             metrics: Arc::new(RwLock::new(Vec::new())),
+            deleted_nodes: Arc::new(RwLock::new(Vec::new())),
+            undo_stack: Arc::new(RwLock::new(UndoStack::new())),
+            event_sinks,
+            engine_listener,
         }
     }
 
+    /// Use `config` for subsequent layouts and layout animations
+    pub fn set_layout_config(&self, config: LayoutConfig) -> Result<(), BridgeError> {
+        config.validate().map_err(|e| BridgeError::InvalidOperation {
+            message: format!("Invalid layout configuration: {}", e),
+        })?;
+        *self.layout_config.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire layout config lock".to_string(),
+        })? = config;
+        Ok(())
+    }
+
+    /// Get the configuration layouts and animations currently use
+    fn active_layout_config(&self) -> Result<LayoutConfig, BridgeError> {
+        self.layout_config
+            .read()
+            .map(|config| config.clone())
+            .map_err(|_| BridgeError::GenericError {
+                message: "Failed to acquire layout config lock".to_string(),
+            })
+    }
+
+    /// Calculate a layout of `graph` with the active configuration
+    fn layout_with_active_config(&self, graph: &Graph, layout_type: LayoutType) -> Result<LayoutResult, BridgeError> {
+        let config = self.active_layout_config()?;
+        let engine = LayoutEngineImpl::with_layout_type(layout_type);
+        LayoutEngine::calculate_layout(&engine, graph, &config).map_err(|e| BridgeError::LayoutComputationError {
+            message: format!("Layout calculation failed: {}", e),
+        })
+    }
+
+    /// Push every subsequent change event to `sink`, e.g. after auto-save
+    /// or a background import
+    pub fn subscribe(&self, sink: impl FfiEventSink + 'static) {
+        if let Ok(mut sinks) = self.event_sinks.write() {
+            sinks.0.push(Arc::new(sink));
+        }
+    }
+
+    /// Deliver events to every subscriber, dropping those that stopped listening
+    ///
+    /// Must be called without holding the graph lock, since subscribers may
+    /// call back into the bridge.
+    fn publish(&self, events: impl IntoIterator<Item = FfiMindmapEvent>) {
+        EventSinks::publish(&self.event_sinks, events);
+    }
+
     /// Record performance metrics for an operation
     pub fn record_metrics(&self, operation: &str, start_time: Instant, nodes_processed: u32) {
         let duration = start_time.elapsed();
@@ -75,7 +227,7 @@ impl MindmapBridge {
     }
 
     /// Get the current document or return error if none loaded
-    fn get_document(&self) -> Result<MindmapDocument, BridgeError> {
+    pub(crate) fn get_document(&self) -> Result<MindmapDocument, BridgeError> {
         self.document
             .read()
             .map_err(|_| BridgeError::GenericError {
@@ -88,16 +240,93 @@ impl MindmapBridge {
     }
 
     /// Update the current document
-    fn set_document(&self, document: MindmapDocument) -> Result<(), BridgeError> {
+    pub(crate) fn set_document(&self, document: MindmapDocument) -> Result<(), BridgeError> {
         *self.document.write().map_err(|_| BridgeError::GenericError {
             message: "Failed to acquire document lock".to_string(),
         })? = Some(document);
         Ok(())
     }
 
+    /// Flag the current document, if any, as having unsaved changes
+    fn mark_document_dirty(&self) {
+        if let Ok(mut document) = self.document.write() {
+            if let Some(document) = document.as_mut() {
+                document.mark_dirty();
+            }
+        }
+    }
+
+    /// Record an edit already applied to the graph
+    fn record_undo(&self, command: UndoCommand) {
+        if let Ok(mut undo_stack) = self.undo_stack.write() {
+            undo_stack.push(command);
+        }
+    }
+
+    /// Forget the edit history, e.g. when the whole graph is replaced
+    fn clear_undo(&self) {
+        if let Ok(mut undo_stack) = self.undo_stack.write() {
+            undo_stack.clear();
+        }
+    }
+
+    /// Undo or redo one edit, then re-index the graph
+    fn step_history(
+        &self,
+        operation: &str,
+        step: impl FnOnce(&mut UndoStack, &mut Graph) -> crate::types::MindmapResult<()>,
+        available: impl FnOnce(&UndoStack) -> bool,
+    ) -> Result<bool, BridgeError> {
+        let start_time = Instant::now();
+
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+        let mut undo_stack = self.undo_stack.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire undo lock".to_string(),
+        })?;
+
+        if !available(&undo_stack) {
+            return Ok(false);
+        }
+        step(&mut undo_stack, &mut graph).map_err(|e| BridgeError::InvalidOperation {
+            message: format!("Failed to {}: {}", operation, e),
+        })?;
+        drop(undo_stack);
+
+        // The edit may touch any node, so rebuild the index
+        if let Ok(mut search) = self.search_engine.write() {
+            *search = SearchEngine::new();
+            for node in graph.nodes() {
+                search.index_node(node);
+            }
+        }
+        let node_count = graph.node_count() as u32;
+        drop(graph);
+        self.mark_document_dirty();
+        self.publish([FfiMindmapEvent::GraphReplaced { node_count }]);
+
+        self.record_metrics(operation, start_time, 1);
+        Ok(true)
+    }
+
     /// Parse string ID to UUID
     pub fn parse_node_id(&self, id: &str) -> Result<NodeId, BridgeError> {
-        super::utils::parse_uuid(id).map(NodeId::from)
+        // Accepts both the `node:<uuid>` form handed out by the bridge and a bare UUID
+        id.parse().map_err(|_| BridgeError::InvalidOperation {
+            message: format!("Invalid UUID format: {}", id),
+        })
+    }
+
+    /// Convert an FFI search scope to the core search context
+    fn search_context_from_ffi(&self, context: FfiSearchContext) -> Result<SearchContext, BridgeError> {
+        Ok(match context {
+            FfiSearchContext::All => SearchContext::All,
+            FfiSearchContext::Subtree { node_id } => SearchContext::Subtree(self.parse_node_id(&node_id)?),
+            FfiSearchContext::Depth { depth } => SearchContext::Depth(depth as usize),
+            FfiSearchContext::Roots => SearchContext::Roots,
+            FfiSearchContext::Tagged { tags } => SearchContext::Tagged(tags),
+        })
     }
 
     /// Convert internal node to FFI format
@@ -146,15 +375,86 @@ impl MindmapFFI for MindmapBridge {
 
         // Update search index
         if let Ok(mut search) = self.search_engine.write() {
-            if let Ok(updated_node) = graph.get_node(node_id) {
-                search.index_node(&updated_node);
+            if let Some(updated_node) = graph.get_node(node_id) {
+                search.index_node(updated_node);
             }
         }
+        if let Some(added) = graph.get_node(node_id) {
+            self.record_undo(UndoCommand::AddNode(added.clone()));
+        }
+        drop(graph);
+        self.mark_document_dirty();
+        self.publish([FfiMindmapEvent::NodeAdded { node_id: node_id.to_string() }]);
 
         self.record_metrics("create_node", start_time, 1);
         Ok(node_id.to_string())
     }
 
+    fn create_nodes_batch(
+        &self,
+        parent_id: Option<String>,
+        texts: Vec<String>,
+    ) -> Result<Vec<String>, BridgeError> {
+        let start_time = Instant::now();
+
+        let parent_uuid = match parent_id {
+            Some(parent_str) => Some(self.parse_node_id(&parent_str)?),
+            None => None,
+        };
+
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        let mut created = Vec::with_capacity(texts.len());
+        for text in texts {
+            let added = super::utils::validate_node_text(&text).and_then(|_| {
+                let node = match parent_uuid {
+                    Some(parent) => Node::new_child(parent, text),
+                    None => Node::new(text),
+                };
+                graph.add_node(node).map_err(|e| BridgeError::InvalidOperation {
+                    message: format!("Failed to add node: {}", e),
+                })
+            });
+
+            match added {
+                Ok(node_id) => created.push(node_id),
+                Err(error) => {
+                    // Roll back the nodes added so far
+                    for node_id in created.into_iter().rev() {
+                        let _ = graph.remove_node(node_id);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+
+        if let Ok(mut search) = self.search_engine.write() {
+            for &node_id in &created {
+                if let Some(node) = graph.get_node(node_id) {
+                    search.index_node(node);
+                }
+            }
+        }
+        // The batch is undone and redone as one edit
+        let added: Vec<UndoCommand> = created.iter()
+            .filter_map(|&node_id| graph.get_node(node_id).cloned())
+            .map(UndoCommand::AddNode)
+            .collect();
+        if !added.is_empty() {
+            self.record_undo(UndoCommand::Batch(added));
+        }
+        drop(graph);
+        if !created.is_empty() {
+            self.mark_document_dirty();
+        }
+        self.publish(created.iter().map(|id| FfiMindmapEvent::NodeAdded { node_id: id.to_string() }));
+
+        self.record_metrics("create_nodes_batch", start_time, created.len() as u32);
+        Ok(created.into_iter().map(|id| id.to_string()).collect())
+    }
+
     fn update_node(
         &self,
         node_id: String,
@@ -167,9 +467,10 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let mut node = graph.get_node(id).map_err(|_| BridgeError::NodeNotFound {
+        let mut node = graph.get_node(id).cloned().ok_or_else(|| BridgeError::NodeNotFound {
             id: node_id.clone(),
         })?;
+        let before = node.clone();
 
         // Apply updates
         if let Some(text) = update.text {
@@ -184,18 +485,26 @@ impl MindmapFFI for MindmapBridge {
 
         if let Some(tags) = update.tags {
             super::utils::validate_tags(&tags)?;
-            node.tags = tags;
-            node.updated_at = chrono::Utc::now();
+            node.set_tags(tags);
         }
 
         if let Some(metadata) = update.metadata {
-            node.metadata = metadata;
-            node.updated_at = chrono::Utc::now();
+            node.set_all_metadata(metadata);
+        }
+
+        if let Some(style) = update.style {
+            super::utils::validate_style(&style)?;
+            node.style = style.into();
+            node.touch();
+        }
+
+        if let Some(note) = update.note {
+            node.set_note(Some(note));
         }
 
         // Update node in graph
         graph
-            .update_node(id, node.clone())
+            .update_node(node.clone())
             .map_err(|e| BridgeError::InvalidOperation {
                 message: format!("Failed to update node: {}", e),
             })?;
@@ -204,6 +513,10 @@ impl MindmapFFI for MindmapBridge {
         if let Ok(mut search) = self.search_engine.write() {
             search.index_node(&node);
         }
+        drop(graph);
+        self.record_undo(UndoCommand::UpdateNode { before: Box::new(before), after: Box::new(node) });
+        self.mark_document_dirty();
+        self.publish([FfiMindmapEvent::NodeUpdated { node_id }]);
 
         self.record_metrics("update_node", start_time, 1);
         Ok(())
@@ -219,6 +532,8 @@ impl MindmapFFI for MindmapBridge {
             position: None,
             tags: None,
             metadata: None,
+            style: None,
+            note: None,
         };
         self.update_node(node_id, update)
     }
@@ -233,10 +548,56 @@ impl MindmapFFI for MindmapBridge {
             position: Some(position),
             tags: None,
             metadata: None,
+            style: None,
+            note: None,
         };
         self.update_node(node_id, update)
     }
 
+    fn update_positions_batch(
+        &self,
+        positions: HashMap<String, FfiPoint>,
+    ) -> Result<(), BridgeError> {
+        let start_time = Instant::now();
+
+        // Validate the whole batch before touching the graph
+        let mut updates = Vec::with_capacity(positions.len());
+        for (node_id, position) in positions {
+            super::utils::validate_position(&position)?;
+            updates.push((self.parse_node_id(&node_id)?, node_id, position));
+        }
+
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        if let Some((_, node_id, _)) = updates.iter().find(|(id, _, _)| !graph.contains_node(*id)) {
+            return Err(BridgeError::NodeNotFound { id: node_id.clone() });
+        }
+
+        let count = updates.len() as u32;
+        let mut events = Vec::with_capacity(updates.len());
+        let mut moved = Vec::with_capacity(updates.len());
+        for (id, node_id, position) in updates {
+            if let Some(node) = graph.get_node_mut(id) {
+                let before = node.clone();
+                node.set_position(position.into());
+                moved.push(UndoCommand::UpdateNode { before: Box::new(before), after: Box::new(node.clone()) });
+                events.push(FfiMindmapEvent::NodeUpdated { node_id });
+            }
+        }
+        drop(graph);
+
+        if count > 0 {
+            self.record_undo(UndoCommand::Batch(moved));
+            self.mark_document_dirty();
+        }
+        self.publish(events);
+
+        self.record_metrics("update_positions_batch", start_time, count);
+        Ok(())
+    }
+
     fn delete_node(&self, node_id: String) -> Result<(), BridgeError> {
         let start_time = Instant::now();
         let id = self.parse_node_id(&node_id)?;
@@ -245,26 +606,43 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        // Get children count for metrics
-        let children = graph.get_children(id).unwrap_or_default();
-        let nodes_affected = 1 + children.len() as u32;
-
-        // Remove node and all children
-        graph
-            .remove_node(id)
+        // Remove the node with every descendant, recording the nodes and
+        // edges needed to undo it as one edit
+        let mut undo_stack = self.undo_stack.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire undo lock".to_string(),
+        })?;
+        let removed: Vec<NodeId> = undo_stack
+            .remove_subtree(&mut graph, id)
             .map_err(|_| BridgeError::NodeNotFound {
                 id: node_id.clone(),
-            })?;
+            })?
+            .iter()
+            .map(|node| node.id)
+            .collect();
+        drop(undo_stack);
+
+        // Record deletions for change polling
+        if let Ok(mut deleted) = self.deleted_nodes.write() {
+            let now = chrono::Utc::now().timestamp_millis();
+            deleted.extend(removed.iter().map(|&removed_id| (removed_id, now)));
+        }
+
+        let events: Vec<FfiMindmapEvent> = removed
+            .iter()
+            .map(|id| FfiMindmapEvent::NodeDeleted { node_id: id.to_string() })
+            .collect();
 
         // Update search index
         if let Ok(mut search) = self.search_engine.write() {
-            search.remove_node(id);
-            for child in children {
-                search.remove_node(child.id);
+            for &removed_id in &removed {
+                search.remove_node(removed_id);
             }
         }
+        drop(graph);
+        self.mark_document_dirty();
+        self.publish(events);
 
-        self.record_metrics("delete_node", start_time, nodes_affected);
+        self.record_metrics("delete_node", start_time, removed.len() as u32);
         Ok(())
     }
 
@@ -276,12 +654,12 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let node = graph.get_node(id).map_err(|_| BridgeError::NodeNotFound {
+        let node = graph.get_node(id).ok_or(BridgeError::NodeNotFound {
             id: node_id,
         })?;
 
         self.record_metrics("get_node", start_time, 1);
-        Ok(self.node_to_ffi(&node))
+        Ok(self.node_to_ffi(node))
     }
 
     fn get_node_children(&self, node_id: String) -> Result<Vec<FfiNodeData>, BridgeError> {
@@ -292,16 +670,64 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let children = graph.get_children(id).map_err(|_| BridgeError::NodeNotFound {
-            id: node_id,
-        })?;
+        if !graph.contains_node(id) {
+            return Err(BridgeError::NodeNotFound { id: node_id });
+        }
 
+        let children = graph.get_children(id);
         let result: Vec<FfiNodeData> = children.iter().map(|node| self.node_to_ffi(node)).collect();
 
         self.record_metrics("get_node_children", start_time, result.len() as u32);
         Ok(result)
     }
 
+    fn get_ancestors(&self, node_id: String) -> Result<Vec<FfiNodeData>, BridgeError> {
+        let start_time = Instant::now();
+        let id = self.parse_node_id(&node_id)?;
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        if !graph.contains_node(id) {
+            return Err(BridgeError::NodeNotFound { id: node_id });
+        }
+
+        // The root path ends with the node itself
+        let mut path = graph.root_path(id);
+        path.pop();
+        let result: Vec<FfiNodeData> = path
+            .into_iter()
+            .filter_map(|ancestor_id| graph.get_node(ancestor_id))
+            .map(|node| self.node_to_ffi(node))
+            .collect();
+
+        self.record_metrics("get_ancestors", start_time, result.len() as u32);
+        Ok(result)
+    }
+
+    fn get_subtree(&self, node_id: String) -> Result<Vec<FfiNodeData>, BridgeError> {
+        let start_time = Instant::now();
+        let id = self.parse_node_id(&node_id)?;
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        if !graph.contains_node(id) {
+            return Err(BridgeError::NodeNotFound { id: node_id });
+        }
+
+        let result: Vec<FfiNodeData> = std::iter::once(id)
+            .chain(graph.get_descendants(id))
+            .filter_map(|subtree_id| graph.get_node(subtree_id))
+            .map(|node| self.node_to_ffi(node))
+            .collect();
+
+        self.record_metrics("get_subtree", start_time, result.len() as u32);
+        Ok(result)
+    }
+
     fn get_all_nodes(&self) -> Result<Vec<FfiNodeData>, BridgeError> {
         let start_time = Instant::now();
 
@@ -309,13 +735,110 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let nodes = graph.get_all_nodes();
-        let result: Vec<FfiNodeData> = nodes.iter().map(|node| self.node_to_ffi(node)).collect();
+        let result: Vec<FfiNodeData> = graph.nodes().map(|node| self.node_to_ffi(node)).collect();
 
         self.record_metrics("get_all_nodes", start_time, result.len() as u32);
         Ok(result)
     }
 
+    fn get_nodes_changed_since(&self, since_ms: i64) -> Result<FfiNodeDelta, BridgeError> {
+        let start_time = Instant::now();
+        let marker = chrono::Utc::now().timestamp_millis();
+
+        let since = chrono::DateTime::from_timestamp_millis(since_ms).ok_or_else(|| {
+            BridgeError::InvalidOperation {
+                message: format!("Invalid change marker: {}", since_ms),
+            }
+        })?;
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        let (added, updated): (Vec<&Node>, Vec<&Node>) = graph
+            .nodes_modified_since(since)
+            .into_iter()
+            .partition(|node| node.created_at > since);
+
+        let mut deleted = self.deleted_nodes.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire deleted nodes lock".to_string(),
+        })?;
+
+        // Deletions at or before the marker were reported by an earlier poll
+        deleted.retain(|(_, deleted_at)| *deleted_at > since_ms);
+
+        let removed = deleted
+            .iter()
+            .filter(|(id, _)| !graph.contains_node(*id))
+            .map(|(id, _)| id.to_string())
+            .collect();
+        drop(deleted);
+
+        let delta = FfiNodeDelta {
+            added: added.into_iter().map(|node| self.node_to_ffi(node)).collect(),
+            updated: updated.into_iter().map(|node| self.node_to_ffi(node)).collect(),
+            removed,
+            marker,
+        };
+
+        self.record_metrics(
+            "get_nodes_changed_since",
+            start_time,
+            (delta.added.len() + delta.updated.len() + delta.removed.len()) as u32,
+        );
+        Ok(delta)
+    }
+
+    fn attach_file(
+        &self,
+        node_id: String,
+        name: String,
+        data: Vec<u8>,
+        mime_type: String,
+    ) -> Result<String, BridgeError> {
+        let start_time = Instant::now();
+        let id = self.parse_node_id(&node_id)?;
+
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        let before = graph.get_node(id).cloned().ok_or_else(|| BridgeError::NodeNotFound {
+            id: node_id.clone(),
+        })?;
+        let attachment_id = graph.add_attachment(id, &name, data, &mime_type)?;
+        let after = graph.get_node(id).cloned().ok_or_else(|| BridgeError::NodeNotFound {
+            id: node_id.clone(),
+        })?;
+        drop(graph);
+
+        // The contents stay with the graph, so undo only needs the node
+        self.record_undo(UndoCommand::UpdateNode { before: Box::new(before), after: Box::new(after) });
+        self.mark_document_dirty();
+        self.publish([FfiMindmapEvent::NodeUpdated { node_id }]);
+
+        self.record_metrics("attach_file", start_time, 1);
+        Ok(attachment_id.to_string())
+    }
+
+    fn get_attachment(&self, attachment_id: String) -> Result<Vec<u8>, BridgeError> {
+        let start_time = Instant::now();
+        let id: AttachmentId = attachment_id.parse().map_err(|_| BridgeError::InvalidOperation {
+            message: format!("Invalid attachment ID: {}", attachment_id),
+        })?;
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        let data = graph.get_attachment(id).map(<[u8]>::to_vec).ok_or_else(|| BridgeError::InvalidOperation {
+            message: format!("Attachment not found: {}", attachment_id),
+        })?;
+
+        self.record_metrics("get_attachment", start_time, 1);
+        Ok(data)
+    }
+
     fn calculate_layout(&self, layout_type: FfiLayoutType) -> Result<FfiLayoutResult, BridgeError> {
         let start_time = Instant::now();
 
@@ -323,12 +846,9 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let layout_result = self
-            .layout_engine
-            .calculate_layout(&*graph, layout_type.into())
-            .map_err(|e| BridgeError::LayoutComputationError {
-                message: format!("Layout calculation failed: {}", e),
-            })?;
+        let layout_start = Instant::now();
+        let layout_result = self.layout_with_active_config(&graph, layout_type.into())?;
+        let computation_time = layout_start.elapsed();
 
         let node_positions: HashMap<String, FfiPoint> = layout_result
             .positions
@@ -339,7 +859,7 @@ impl MindmapFFI for MindmapBridge {
         let result = FfiLayoutResult {
             node_positions,
             layout_type,
-            computation_time_ms: layout_result.computation_time.as_millis() as u64,
+            computation_time_ms: computation_time.as_millis() as u64,
         };
 
         self.record_metrics("calculate_layout", start_time, result.node_positions.len() as u32);
@@ -354,45 +874,170 @@ impl MindmapFFI for MindmapBridge {
         })?;
 
         let mut updates_count = 0;
+        let mut moved = Vec::new();
         for (node_id_str, position) in layout_result.node_positions {
             let node_id = self.parse_node_id(&node_id_str)?;
 
-            if let Ok(mut node) = graph.get_node(node_id) {
+            if let Some(before) = graph.get_node(node_id).cloned() {
+                let mut node = before.clone();
                 node.set_position(position.into());
-                if graph.update_node(node_id, node).is_ok() {
+                if graph.update_node(node.clone()).is_ok() {
                     updates_count += 1;
+                    moved.push(UndoCommand::UpdateNode { before: Box::new(before), after: Box::new(node) });
                 }
             }
         }
+        drop(graph);
+        if !moved.is_empty() {
+            self.record_undo(UndoCommand::Batch(moved));
+        }
+        self.publish([FfiMindmapEvent::LayoutApplied { node_count: updates_count }]);
 
         self.record_metrics("apply_layout", start_time, updates_count);
         Ok(())
     }
 
-    fn search_nodes(&self, query: String) -> Result<Vec<FfiSearchResult>, BridgeError> {
+    fn get_layout_frame(
+        &self,
+        target: FfiLayoutResult,
+        progress: f64,
+        easing: Easing,
+    ) -> Result<FfiLayoutFrame, BridgeError> {
+        let start_time = Instant::now();
+
+        if !(0.0..=1.0).contains(&progress) {
+            return Err(BridgeError::InvalidOperation {
+                message: format!("Animation progress must be between 0.0 and 1.0, got {}", progress),
+            });
+        }
+
+        let mut positions = HashMap::new();
+        for (node_id_str, position) in target.node_positions {
+            positions.insert(self.parse_node_id(&node_id_str)?, position.into());
+        }
+        let points: Vec<_> = positions.values().copied().collect();
+        let target = LayoutResult {
+            bounds: LayoutBounds::from_points(&points),
+            positions,
+            converged: true,
+            iterations: 0,
+            energy: 0.0,
+            node_bounds: None,
+        };
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+        let current: HashMap<NodeId, _> = target
+            .positions
+            .keys()
+            .filter_map(|&node_id| graph.get_node(node_id).map(|node| (node_id, node.position)))
+            .collect();
+        drop(graph);
+
+        let mut entering_nodes: Vec<NodeId> = target
+            .positions
+            .keys()
+            .filter(|node_id| !current.contains_key(node_id))
+            .copied()
+            .collect();
+        entering_nodes.sort_by_key(|id| id.as_uuid());
+
+        let duration_ms = self.active_layout_config()?.animation_duration as f64;
+        let frame = FfiLayoutFrame {
+            progress,
+            time_ms: (duration_ms * progress).round() as u64,
+            node_positions: layout::animate_with_easing(&current, &target, progress, easing)
+                .into_iter()
+                .map(|(id, pos)| (id.to_string(), pos.into()))
+                .collect(),
+            entering_nodes: entering_nodes.into_iter().map(|id| id.to_string()).collect(),
+        };
+
+        self.record_metrics("get_layout_frame", start_time, frame.node_positions.len() as u32);
+        Ok(frame)
+    }
+
+    fn compute_layout_animation(
+        &self,
+        layout_type: FfiLayoutType,
+        frame_count: u32,
+    ) -> Result<Vec<FfiLayoutFrame>, BridgeError> {
+        let start_time = Instant::now();
+
+        if frame_count < 2 {
+            return Err(BridgeError::InvalidOperation {
+                message: format!("An animation needs at least 2 frames, got {}", frame_count),
+            });
+        }
+
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+        let target = self.layout_with_active_config(&graph, layout_type.into())?;
+        let current: HashMap<NodeId, _> = graph.nodes().map(|node| (node.id, node.position)).collect();
+        drop(graph);
+
+        // Every laid out node already has a position, so none enter from the center
+        let duration_ms = self.active_layout_config()?.animation_duration as u64;
+        let last = (frame_count - 1) as u64;
+        let frames: Vec<FfiLayoutFrame> = layout::animation_frames(&current, &target, frame_count as usize, Easing::EaseInOut)
+            .into_iter()
+            .enumerate()
+            .map(|(index, positions)| FfiLayoutFrame {
+                progress: index as f64 / last as f64,
+                time_ms: duration_ms * index as u64 / last,
+                node_positions: positions.into_iter().map(|(id, pos)| (id.to_string(), pos.into())).collect(),
+                entering_nodes: Vec::new(),
+            })
+            .collect();
+
+        self.record_metrics("compute_layout_animation", start_time, frames.len() as u32);
+        Ok(frames)
+    }
+
+    fn undo(&self) -> Result<bool, BridgeError> {
+        self.step_history("undo", |undo_stack, graph| undo_stack.undo(graph), UndoStack::can_undo)
+    }
+
+    fn redo(&self) -> Result<bool, BridgeError> {
+        self.step_history("redo", |undo_stack, graph| undo_stack.redo(graph), UndoStack::can_redo)
+    }
+
+    fn can_undo(&self) -> bool {
+        self.undo_stack.read().map(|undo_stack| undo_stack.can_undo()).unwrap_or(false)
+    }
+
+    fn can_redo(&self) -> bool {
+        self.undo_stack.read().map(|undo_stack| undo_stack.can_redo()).unwrap_or(false)
+    }
+
+    fn search_nodes(&self, query: String, options: FfiSearchOptions) -> Result<Vec<FfiSearchResult>, BridgeError> {
         let start_time = Instant::now();
 
         if query.trim().is_empty() {
             return Ok(Vec::new());
         }
+        if !(0.0..=1.0).contains(&options.min_score) {
+            return Err(BridgeError::InvalidOperation {
+                message: format!("Minimum score must be between 0.0 and 1.0, got {}", options.min_score),
+            });
+        }
 
-        let search = self.search_engine.read().map_err(|_| BridgeError::GenericError {
-            message: "Failed to acquire search lock".to_string(),
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let results = search
-            .search(&query)
-            .map_err(|e| BridgeError::SearchError {
-                message: format!("Search failed: {}", e),
-            })?;
-
-        let ffi_results: Vec<FfiSearchResult> = results
+        let ffi_results: Vec<FfiSearchResult> = graph
+            .search_with_highlights(&query, &options.into())
             .into_iter()
-            .map(|result| FfiSearchResult {
+            .map(|(result, highlighted)| FfiSearchResult {
                 node_id: result.node_id.to_string(),
-                text: result.text,
+                text: graph.get_node(result.node_id).map(|node| node.text.clone()).unwrap_or_default(),
                 score: result.score,
                 match_positions: result.match_positions,
+                snippet: result.snippet,
+                highlighted,
             })
             .collect();
 
@@ -407,23 +1052,26 @@ impl MindmapFFI for MindmapBridge {
             return Ok(Vec::new());
         }
 
-        let search = self.search_engine.read().map_err(|_| BridgeError::GenericError {
-            message: "Failed to acquire search lock".to_string(),
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let results = search
-            .search_by_tags(&tags)
-            .map_err(|e| BridgeError::SearchError {
-                message: format!("Tag search failed: {}", e),
-            })?;
+        // Any of the tags matches; nodes carrying more of them rank higher
+        let results = graph.search_by_tags(&tags, false);
 
         let ffi_results: Vec<FfiSearchResult> = results
             .into_iter()
-            .map(|result| FfiSearchResult {
-                node_id: result.node_id.to_string(),
-                text: result.text,
-                score: result.score,
-                match_positions: vec![], // Tag searches don't have text match positions
+            .map(|result| {
+                let text = graph.get_node(result.node_id).map(|node| node.text.clone()).unwrap_or_default();
+                FfiSearchResult {
+                    node_id: result.node_id.to_string(),
+                    // Tag searches don't have text match positions
+                    highlighted: text.clone(),
+                    text,
+                    score: result.score,
+                    match_positions: vec![],
+                    snippet: result.snippet,
+                }
             })
             .collect();
 
@@ -431,6 +1079,50 @@ impl MindmapFFI for MindmapBridge {
         Ok(ffi_results)
     }
 
+    fn search_scoped(&self, query: String, context: FfiSearchContext) -> Result<Vec<FfiSearchResult>, BridgeError> {
+        let start_time = Instant::now();
+
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let context = self.search_context_from_ffi(context)?;
+        let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        if let SearchContext::Subtree(node_id) = context {
+            if !graph.contains_node(node_id) {
+                return Err(BridgeError::NodeNotFound { id: node_id.to_string() });
+            }
+        }
+
+        let results = graph
+            .try_search_result_set(&query, &SearchOptions::default(), &context)
+            .map_err(|e| BridgeError::SearchError {
+                message: format!("Search failed: {}", e),
+            })?
+            .results;
+
+        let ffi_results: Vec<FfiSearchResult> = results
+            .into_iter()
+            .map(|result| {
+                let text = graph.get_node(result.node_id).map(|node| node.text.clone()).unwrap_or_default();
+                FfiSearchResult {
+                    node_id: result.node_id.to_string(),
+                    highlighted: crate::search::highlight_matches(&text, &result.match_positions),
+                    text,
+                    score: result.score,
+                    match_positions: result.match_positions,
+                    snippet: result.snippet,
+                }
+            })
+            .collect();
+
+        self.record_metrics("search_scoped", start_time, ffi_results.len() as u32);
+        Ok(ffi_results)
+    }
+
     fn create_mindmap(&self, title: String) -> Result<String, BridgeError> {
         let start_time = Instant::now();
 
@@ -459,6 +1151,9 @@ impl MindmapFFI for MindmapBridge {
                 message: format!("Failed to add root node: {}", e),
             })?;
 
+        drop(graph);
+        self.clear_undo();
+
         // Set the document
         self.set_document(document)?;
 
@@ -467,6 +1162,7 @@ impl MindmapFFI for MindmapBridge {
             *search = SearchEngine::new();
             search.index_node(&root_node);
         }
+        self.publish([FfiMindmapEvent::GraphReplaced { node_count: 1 }]);
 
         self.record_metrics("create_mindmap", start_time, 1);
         Ok(document_id.to_string())
@@ -485,11 +1181,32 @@ impl MindmapFFI for MindmapBridge {
     fn save_mindmap(&self, path: String) -> Result<(), BridgeError> {
         let start_time = Instant::now();
 
-        // TODO: Implement actual file saving
-        // For now, return an error indicating not implemented
-        Err(BridgeError::GenericError {
-            message: "File saving not yet implemented".to_string(),
-        })
+        let document = self.get_document()?;
+        let (nodes, edges): (Vec<Node>, Vec<Edge>) = {
+            let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+                message: "Failed to acquire graph lock".to_string(),
+            })?;
+            (graph.nodes().cloned().collect(), graph.edges().cloned().collect())
+        };
+
+        let result = FormatManager::new()
+            .export_with_edges(&document, &nodes, &edges, FileFormat::Json, &ImportExportOptions::default())
+            .map_err(|e| BridgeError::SerializationError {
+                message: format!("Failed to serialize mindmap: {}", e),
+            })?;
+
+        std::fs::write(&path, &result.content).map_err(|e| BridgeError::FileSystemError {
+            message: format!("Failed to write {}: {}", path, e),
+        })?;
+
+        if let Ok(mut document) = self.document.write() {
+            if let Some(document) = document.as_mut() {
+                document.mark_saved();
+            }
+        }
+
+        self.record_metrics("save_mindmap", start_time, result.node_count as u32);
+        Ok(())
     }
 
     fn export_mindmap(&self, path: String, format: ExportFormat) -> Result<(), BridgeError> {
@@ -502,6 +1219,51 @@ impl MindmapFFI for MindmapBridge {
         })
     }
 
+    fn import_file_auto(&self, path: String) -> Result<FfiImportReport, BridgeError> {
+        let start_time = Instant::now();
+
+        let format_manager = FormatManager::new();
+        let (format, import_result) = format_manager
+            .import_file(Path::new(&path), &ImportExportOptions::default())
+            .map_err(|e| BridgeError::FileSystemError {
+                message: format!("Failed to import {}: {}", path, e),
+            })?;
+
+        // Replace the current graph with the imported nodes
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        *graph = import_result.to_graph().map_err(|e| BridgeError::InvalidOperation {
+            message: format!("Failed to add imported nodes: {}", e),
+        })?;
+        self.clear_undo();
+
+        // Rebuild search index
+        if let Ok(mut search) = self.search_engine.write() {
+            *search = SearchEngine::new();
+            for node in &import_result.nodes {
+                search.index_node(node);
+            }
+        }
+
+        let report = FfiImportReport {
+            document_id: import_result.document.id.to_string(),
+            title: import_result.document.title.clone(),
+            detected_format: format.extension().to_string(),
+            node_count: import_result.node_count as u32,
+            edge_count: import_result.edge_count as u32,
+            warnings: import_result.warnings,
+        };
+
+        drop(graph);
+        self.set_document(import_result.document)?;
+        self.publish([FfiMindmapEvent::GraphReplaced { node_count: report.node_count }]);
+
+        self.record_metrics("import_file_auto", start_time, report.node_count);
+        Ok(report)
+    }
+
     fn get_mindmap_data(&self) -> Result<FfiMindmapData, BridgeError> {
         let start_time = Instant::now();
 
@@ -510,8 +1272,7 @@ impl MindmapFFI for MindmapBridge {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let nodes = graph.get_all_nodes();
-        let ffi_nodes: Vec<FfiNodeData> = nodes.iter().map(|node| self.node_to_ffi(node)).collect();
+        let ffi_nodes: Vec<FfiNodeData> = graph.nodes().map(|node| self.node_to_ffi(node)).collect();
 
         let mindmap_data = FfiMindmapData {
             id: document.id.to_string(),
@@ -526,6 +1287,26 @@ impl MindmapFFI for MindmapBridge {
         Ok(mindmap_data)
     }
 
+    fn get_status(&self) -> Result<FfiStatus, BridgeError> {
+        let (node_count, edge_count) = {
+            let graph = self.graph.read().map_err(|_| BridgeError::GenericError {
+                message: "Failed to acquire graph lock".to_string(),
+            })?;
+            (graph.node_count() as u32, graph.edge_count() as u32)
+        };
+
+        let document = self.document.read().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire document lock".to_string(),
+        })?;
+
+        Ok(FfiStatus {
+            node_count,
+            edge_count,
+            has_unsaved_changes: document.as_ref().is_some_and(|document| document.is_dirty),
+            last_modified: document.as_ref().map_or(0, |document| document.updated_at.timestamp_millis()),
+        })
+    }
+
     fn validate_mindmap(&self) -> Result<bool, BridgeError> {
         let start_time = Instant::now();
 
@@ -534,8 +1315,7 @@ impl MindmapFFI for MindmapBridge {
         })?;
 
         // Validate all nodes
-        let nodes = graph.get_all_nodes();
-        for node in &nodes {
+        for node in graph.nodes() {
             if let Err(e) = node.validate() {
                 return Err(BridgeError::InvalidOperation { message: e });
             }
@@ -543,10 +1323,47 @@ impl MindmapFFI for MindmapBridge {
 
         // TODO: Add more validation rules (e.g., graph connectivity, cycles)
 
-        self.record_metrics("validate_mindmap", start_time, nodes.len() as u32);
+        self.record_metrics("validate_mindmap", start_time, graph.node_count() as u32);
         Ok(true)
     }
 
+    fn validate_and_repair(&self, options: RepairOptions) -> Result<FfiRepairReport, BridgeError> {
+        let start_time = Instant::now();
+
+        let mut graph = self.graph.write().map_err(|_| BridgeError::GenericError {
+            message: "Failed to acquire graph lock".to_string(),
+        })?;
+
+        let validation_error = graph.validate().err().map(|e| e.to_string());
+        let report = if validation_error.is_some() {
+            graph.repair(&options)
+        } else {
+            Default::default()
+        };
+        let remaining_error = graph.validate().err().map(|e| e.to_string());
+        let node_count = graph.node_count() as u32;
+        drop(graph);
+
+        if !report.is_empty() {
+            // Repairs remove edges and move nodes outside the edit history,
+            // so earlier edits may no longer revert cleanly
+            self.clear_undo();
+            self.mark_document_dirty();
+        }
+
+        let to_strings = |ids: Vec<NodeId>| ids.into_iter().map(|id| id.to_string()).collect();
+        let ffi_report = FfiRepairReport {
+            validation_error,
+            reparented_orphans: to_strings(report.reparented_orphans),
+            removed_edges: report.removed_edges.into_iter().map(|id| id.to_string()).collect(),
+            broken_cycle_nodes: to_strings(report.broken_cycle_nodes),
+            remaining_error,
+        };
+
+        self.record_metrics("validate_and_repair", start_time, node_count);
+        Ok(ffi_report)
+    }
+
     fn get_engine_info(&self) -> Result<String, BridgeError> {
         let info = crate::info();
         let engine_info = format!(
@@ -587,6 +1404,12 @@ impl MindmapFFI for MindmapBridge {
             metrics.clear();
         }
 
+        if let Ok(mut deleted) = self.deleted_nodes.write() {
+            deleted.clear();
+        }
+
+        self.clear_undo();
+
         self.record_metrics("cleanup", start_time, 0);
         Ok(())
     }
@@ -618,6 +1441,209 @@ mod tests {
         assert!(node.parent_id.is_none());
     }
 
+    #[test]
+    fn test_create_nodes_batch() {
+        let bridge = MindmapBridge::new();
+        let root_id = bridge.create_node(None, "Root".to_string()).unwrap();
+
+        let texts: Vec<String> = (1..=5).map(|i| format!("Child {}", i)).collect();
+        let ids = bridge.create_nodes_batch(Some(root_id.clone()), texts.clone()).unwrap();
+        assert_eq!(ids.len(), 5);
+        for (id, text) in ids.iter().zip(&texts) {
+            let node = bridge.get_node(id.clone()).unwrap();
+            assert_eq!(&node.text, text);
+            assert_eq!(node.parent_id.as_deref(), Some(root_id.as_str()));
+        }
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 6);
+
+        // An invalid text rolls back the whole batch
+        let texts = vec!["Valid".to_string(), String::new(), "Never added".to_string()];
+        assert!(bridge.create_nodes_batch(Some(root_id), texts).is_err());
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 6);
+
+        // One undo removes the whole batch
+        assert!(bridge.undo().unwrap());
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 1);
+        assert!(bridge.redo().unwrap());
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_undo_redo_node_deletion() {
+        let bridge = MindmapBridge::new();
+        assert!(!bridge.can_undo());
+        assert!(!bridge.undo().unwrap());
+
+        let node_id = bridge.create_node(None, "Temporary".to_string()).unwrap();
+        assert!(bridge.get_node(node_id.clone()).is_ok());
+        assert!(bridge.can_undo());
+
+        bridge.delete_node(node_id.clone()).unwrap();
+        assert!(bridge.get_node(node_id.clone()).is_err());
+
+        // Undo brings the node back, redo deletes it again
+        assert!(bridge.undo().unwrap());
+        assert!(bridge.get_node(node_id.clone()).is_ok());
+        assert!(bridge.can_redo());
+        assert_eq!(bridge.search_nodes("Temporary".to_string(), FfiSearchOptions::default()).unwrap().len(), 1);
+
+        assert!(bridge.redo().unwrap());
+        assert!(bridge.get_node(node_id.clone()).is_err());
+        assert!(!bridge.can_redo());
+        assert!(!bridge.redo().unwrap());
+
+        // Undoing the creation too leaves an empty graph
+        assert!(bridge.undo().unwrap());
+        assert!(bridge.undo().unwrap());
+        assert!(bridge.get_all_nodes().unwrap().is_empty());
+        assert!(!bridge.can_undo());
+    }
+
+    #[test]
+    fn test_search_by_tags_returns_node_text() {
+        let bridge = MindmapBridge::new();
+        let node_id = bridge.create_node(None, "Quarterly planning".to_string()).unwrap();
+        bridge.update_node(node_id.clone(), FfiNodeUpdate {
+            text: None,
+            position: None,
+            tags: Some(vec!["work".to_string()]),
+            metadata: None,
+            style: None,
+            note: None,
+        }).unwrap();
+
+        let results = bridge.search_by_tags(vec!["work".to_string()]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].node_id, node_id);
+        assert_eq!(results[0].text, "Quarterly planning");
+        assert_eq!(results[0].highlighted, "Quarterly planning");
+    }
+
+    #[test]
+    fn test_undo_subtree_deletion_restores_descendants() {
+        let bridge = MindmapBridge::new();
+        let root = bridge.create_node(None, "Root".to_string()).unwrap();
+        let child = bridge.create_node(Some(root.clone()), "Child".to_string()).unwrap();
+        let grandchild = bridge.create_node(Some(child.clone()), "Grandchild".to_string()).unwrap();
+
+        bridge.delete_node(child.clone()).unwrap();
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 1);
+
+        assert!(bridge.undo().unwrap());
+        let subtree: Vec<String> = bridge.get_subtree(root.clone()).unwrap().into_iter().map(|n| n.id).collect();
+        assert_eq!(subtree, vec![root, child.clone(), grandchild.clone()]);
+        assert_eq!(bridge.get_node(grandchild.clone()).unwrap().parent_id, Some(child));
+        assert_eq!(bridge.search_nodes("Grandchild".to_string(), FfiSearchOptions::default()).unwrap().len(), 1);
+
+        assert!(bridge.redo().unwrap());
+        assert!(bridge.get_node(grandchild).is_err());
+        assert_eq!(bridge.get_all_nodes().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_subscriber_receives_change_events() {
+        let bridge = MindmapBridge::new();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        bridge.subscribe(move |event| {
+            sink.lock().unwrap().push(event);
+            true
+        });
+
+        let node_id = bridge.create_node(None, "Observed".to_string()).unwrap();
+        bridge.update_node_text(node_id.clone(), "Renamed".to_string()).unwrap();
+        bridge.delete_node(node_id.clone()).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![
+            FfiMindmapEvent::NodeAdded { node_id: node_id.clone() },
+            FfiMindmapEvent::NodeUpdated { node_id: node_id.clone() },
+            FfiMindmapEvent::NodeDeleted { node_id },
+        ]);
+
+        // A subscriber that stops listening is dropped
+        bridge.subscribe(|_| false);
+        bridge.create_node(None, "Second".to_string()).unwrap();
+        assert_eq!(bridge.event_sinks.read().unwrap().0.len(), 1);
+        assert_eq!(received.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_subscriber_receives_saves_of_the_open_document() {
+        let bridge = MindmapBridge::new();
+        let document_id = bridge.create_mindmap("Saved".to_string()).unwrap();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        bridge.subscribe(move |event| {
+            sink.lock().unwrap().push(event);
+            true
+        });
+
+        // Saves of other documents, e.g. by another bridge, are not forwarded
+        let open_id = bridge.get_document().unwrap().id;
+        events::emit(EngineEvent::DocumentSaved { document_id: crate::types::DocumentId::new(), duration: Default::default() });
+        events::emit(EngineEvent::DocumentSaved { document_id: open_id, duration: Default::default() });
+
+        assert_eq!(*received.lock().unwrap(), vec![FfiMindmapEvent::DocumentSaved { document_id }]);
+    }
+
+    #[test]
+    fn test_search_results_carry_highlighted_matches() {
+        let bridge = MindmapBridge::new();
+        let root_id = bridge.create_node(None, "Project plan".to_string()).unwrap();
+        bridge.create_node(Some(root_id.clone()), "Plan the budget".to_string()).unwrap();
+        bridge.create_node(Some(root_id), "Hire designers".to_string()).unwrap();
+
+        let results = bridge.search_nodes("budget".to_string(), FfiSearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "Plan the budget");
+        assert!(results[0].highlighted.contains("**budget**"));
+        assert!(results[0].snippet.contains("budget"));
+
+        let options = FfiSearchOptions { limit: Some(1), ..FfiSearchOptions::default() };
+        assert_eq!(bridge.search_nodes("plan".to_string(), options).unwrap().len(), 1);
+
+        let invalid = FfiSearchOptions { min_score: 2.0, ..FfiSearchOptions::default() };
+        assert!(bridge.search_nodes("plan".to_string(), invalid).is_err());
+    }
+
+    #[test]
+    fn test_ancestors_and_subtree_of_three_level_tree() {
+        let bridge = MindmapBridge::new();
+        let root_id = bridge.create_node(None, "Root".to_string()).unwrap();
+        let branch_id = bridge.create_node(Some(root_id.clone()), "Branch".to_string()).unwrap();
+        let sibling_id = bridge.create_node(Some(root_id.clone()), "Sibling".to_string()).unwrap();
+        let leaf_ids = bridge
+            .create_nodes_batch(Some(branch_id.clone()), vec!["Leaf 1".to_string(), "Leaf 2".to_string()])
+            .unwrap();
+
+        // Ancestors run from the root down to the parent
+        let ancestors: Vec<String> = bridge.get_ancestors(leaf_ids[0].clone()).unwrap()
+            .into_iter().map(|node| node.id).collect();
+        assert_eq!(ancestors, vec![root_id.clone(), branch_id.clone()]);
+        assert!(bridge.get_ancestors(root_id.clone()).unwrap().is_empty());
+
+        // The subtree starts at the node and lists every node after its parent
+        let subtree = bridge.get_subtree(branch_id.clone()).unwrap();
+        assert_eq!(subtree[0].id, branch_id);
+        let mut members: Vec<String> = subtree.iter().map(|node| node.id.clone()).collect();
+        members.sort();
+        let mut expected = vec![branch_id, leaf_ids[0].clone(), leaf_ids[1].clone()];
+        expected.sort();
+        assert_eq!(members, expected);
+
+        let whole = bridge.get_subtree(root_id.clone()).unwrap();
+        assert_eq!(whole.len(), 5);
+        for (index, node) in whole.iter().enumerate().skip(1) {
+            let parent_index = whole.iter().position(|other| Some(&other.id) == node.parent_id.as_ref()).unwrap();
+            assert!(parent_index < index);
+        }
+        assert!(whole.iter().any(|node| node.id == sibling_id));
+
+        let unknown = uuid::Uuid::new_v4().to_string();
+        assert!(matches!(bridge.get_ancestors(unknown.clone()), Err(BridgeError::NodeNotFound { .. })));
+        assert!(matches!(bridge.get_subtree(unknown), Err(BridgeError::NodeNotFound { .. })));
+    }
+
     #[test]
     fn test_node_text_validation() {
         let bridge = MindmapBridge::new();
@@ -631,6 +1657,26 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_attach_file_round_trip() {
+        let bridge = MindmapBridge::new();
+        let node_id = bridge.create_node(None, "Report".to_string()).unwrap();
+
+        let data = b"quarterly numbers".to_vec();
+        let attachment_id = bridge
+            .attach_file(node_id.clone(), "q3.txt".to_string(), data.clone(), "text/plain".to_string())
+            .unwrap();
+        assert_eq!(bridge.get_attachment(attachment_id).unwrap(), data);
+
+        let oversized = vec![0u8; crate::ffi::constants::MAX_ATTACHMENT_SIZE as usize + 1];
+        let error = bridge
+            .attach_file(node_id, "big.bin".to_string(), oversized, "application/octet-stream".to_string())
+            .unwrap_err();
+        assert!(matches!(error, BridgeError::InvalidOperation { message } if message.contains("limit")));
+
+        assert!(bridge.get_attachment("not-an-id".to_string()).is_err());
+    }
+
     #[test]
     fn test_update_node() {
         let bridge = MindmapBridge::new();
@@ -642,6 +1688,8 @@ mod tests {
             position: Some(FfiPoint { x: 10.0, y: 20.0 }),
             tags: Some(vec!["tag1".to_string()]),
             metadata: None,
+            style: None,
+            note: None,
         };
 
         assert!(bridge.update_node(node_id.clone(), update).is_ok());
@@ -665,10 +1713,328 @@ mod tests {
         assert_eq!(mindmap_data.nodes.len(), 1); // Should have root node
     }
 
+    #[test]
+    fn test_import_file_auto() {
+        let bridge = MindmapBridge::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let opml_path = dir.path().join("plan.opml");
+        std::fs::write(&opml_path, r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+<head><title>Plan</title></head>
+<body>
+<outline text="First" />
+<outline text="Second" />
+</body>
+</opml>"#).unwrap();
+
+        let report = bridge.import_file_auto(opml_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(report.detected_format, "opml");
+        assert_eq!(report.node_count, 3);
+        assert_eq!(report.title, "Plan");
+        assert_eq!(bridge.get_mindmap_data().unwrap().nodes.len(), 3);
+
+        let md_path = dir.path().join("notes.md");
+        std::fs::write(&md_path, "- One\n  - Two\n- Three\n").unwrap();
+
+        let report = bridge.import_file_auto(md_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(report.detected_format, "md");
+        assert_eq!(report.node_count, 4);
+        assert_eq!(bridge.get_mindmap_data().unwrap().nodes.len(), 4);
+    }
+
+    #[test]
+    fn test_get_nodes_changed_since() {
+        let bridge = MindmapBridge::new();
+
+        let first = bridge.create_node(None, "First".to_string()).unwrap();
+        let second = bridge.create_node(None, "Second".to_string()).unwrap();
+        let third = bridge.create_node(None, "Third".to_string()).unwrap();
+        let _untouched = bridge.create_node(None, "Untouched".to_string()).unwrap();
+
+        // Let the creations fall before the marker's millisecond
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let marker = bridge.get_nodes_changed_since(0).unwrap().marker;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        bridge.update_node_text(first.clone(), "First (edited)".to_string()).unwrap();
+        bridge.update_node_position(second.clone(), FfiPoint { x: 5.0, y: 5.0 }).unwrap();
+        bridge.delete_node(third.clone()).unwrap();
+
+        let delta = bridge.get_nodes_changed_since(marker).unwrap();
+        assert!(delta.added.is_empty());
+
+        let mut updated: Vec<String> = delta.updated.iter().map(|n| n.id.clone()).collect();
+        updated.sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(updated, expected);
+        assert_eq!(delta.removed, vec![third]);
+        assert!(delta.marker >= marker);
+    }
+
+    #[test]
+    fn test_delete_node_reports_all_descendants() {
+        let bridge = MindmapBridge::new();
+
+        let root = bridge.create_node(None, "Root".to_string()).unwrap();
+        let child = bridge.create_node(Some(root.clone()), "Child".to_string()).unwrap();
+        let grandchild = bridge.create_node(Some(child.clone()), "Grandchild".to_string()).unwrap();
+
+        let marker = bridge.get_nodes_changed_since(0).unwrap().marker;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        bridge.delete_node(child.clone()).unwrap();
+        let remaining: Vec<String> = bridge.get_all_nodes().unwrap().into_iter().map(|n| n.id).collect();
+        assert_eq!(remaining, vec![root]);
+
+        let delta = bridge.get_nodes_changed_since(marker).unwrap();
+        let mut removed = delta.removed.clone();
+        removed.sort();
+        let mut expected = vec![child, grandchild];
+        expected.sort();
+        assert_eq!(removed, expected);
+
+        // Reported deletions are pruned once a later marker is polled
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        bridge.get_nodes_changed_since(delta.marker).unwrap();
+        assert!(bridge.deleted_nodes.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_positions_batch() {
+        let bridge = MindmapBridge::new();
+        let node_ids: Vec<String> = (0..100)
+            .map(|i| bridge.create_node(None, format!("Node {}", i)).unwrap())
+            .collect();
+
+        let positions: HashMap<String, FfiPoint> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.clone(), FfiPoint { x: i as f64, y: -(i as f64) }))
+            .collect();
+        bridge.update_positions_batch(positions).unwrap();
+
+        for (i, id) in node_ids.iter().enumerate() {
+            let node = bridge.get_node(id.clone()).unwrap();
+            assert_eq!(node.position.x, i as f64);
+            assert_eq!(node.position.y, -(i as f64));
+        }
+
+        // A single invalid position rejects the whole batch
+        let mut positions: HashMap<String, FfiPoint> = node_ids
+            .iter()
+            .map(|id| (id.clone(), FfiPoint { x: 1000.0, y: 1000.0 }))
+            .collect();
+        positions.insert(node_ids[50].clone(), FfiPoint { x: f64::NAN, y: 0.0 });
+        assert!(bridge.update_positions_batch(positions).is_err());
+
+        for (i, id) in node_ids.iter().enumerate() {
+            assert_eq!(bridge.get_node(id.clone()).unwrap().position.x, i as f64);
+        }
+
+        // One undo moves every node back
+        assert!(bridge.undo().unwrap());
+        for id in &node_ids {
+            assert_eq!(bridge.get_node(id.clone()).unwrap().position.x, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_search_scoped_to_subtree() {
+        let bridge = MindmapBridge::new();
+
+        let root = bridge.create_node(None, "Projects".to_string()).unwrap();
+        let work = bridge.create_node(Some(root.clone()), "Work budget".to_string()).unwrap();
+        let work_item = bridge.create_node(Some(work.clone()), "Travel budget".to_string()).unwrap();
+        let home = bridge.create_node(Some(root), "Home".to_string()).unwrap();
+        let home_item = bridge.create_node(Some(home), "Grocery budget".to_string()).unwrap();
+
+        let everywhere = bridge.search_scoped("budget".to_string(), FfiSearchContext::All).unwrap();
+        assert_eq!(everywhere.len(), 3);
+
+        let context = FfiSearchContext::Subtree { node_id: work.clone() };
+        let mut found: Vec<String> = bridge
+            .search_scoped("budget".to_string(), context)
+            .unwrap()
+            .into_iter()
+            .map(|result| result.node_id)
+            .collect();
+        found.sort();
+        let mut expected = vec![work, work_item];
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(!found.contains(&home_item));
+
+        let missing = FfiSearchContext::Subtree { node_id: NodeId::new().to_string() };
+        assert!(matches!(
+            bridge.search_scoped("budget".to_string(), missing),
+            Err(BridgeError::NodeNotFound { .. })
+        ));
+    }
+
     #[test]
     fn test_engine_info() {
         let bridge = MindmapBridge::new();
         let info = bridge.get_engine_info().unwrap();
         assert!(info.contains("Mindmap Core Engine"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_get_status_tracks_counts_and_dirty_state() {
+        let bridge = MindmapBridge::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let status = bridge.get_status().unwrap();
+        assert_eq!(status.node_count, 0);
+        assert!(!status.has_unsaved_changes);
+        assert_eq!(status.last_modified, 0);
+
+        bridge.create_mindmap("Status".to_string()).unwrap();
+        let root_id = bridge.get_mindmap_data().unwrap().root_node_id;
+        let status = bridge.get_status().unwrap();
+        assert_eq!(status.node_count, 1);
+        assert!(!status.has_unsaved_changes);
+
+        let child = bridge.create_node(Some(root_id.clone()), "Child".to_string()).unwrap();
+        bridge.create_node(Some(root_id), "Other".to_string()).unwrap();
+        bridge.update_node_text(child.clone(), "Renamed".to_string()).unwrap();
+
+        let status = bridge.get_status().unwrap();
+        assert_eq!(status.node_count, 3);
+        assert_eq!(status.edge_count, 0);
+        assert!(status.has_unsaved_changes);
+        assert!(status.last_modified > 0);
+
+        let path = dir.path().join("status.json");
+        bridge.save_mindmap(path.to_string_lossy().to_string()).unwrap();
+        let saved = bridge.get_status().unwrap();
+        assert_eq!(saved.node_count, 3);
+        assert!(!saved.has_unsaved_changes);
+
+        bridge.delete_node(child).unwrap();
+        let status = bridge.get_status().unwrap();
+        assert_eq!(status.node_count, 2);
+        assert!(status.has_unsaved_changes);
+        assert!(status.last_modified >= saved.last_modified);
+    }
+
+    #[test]
+    fn test_validate_and_repair_removes_dangling_edge() {
+        let bridge = MindmapBridge::new();
+        bridge.create_mindmap("Repair".to_string()).unwrap();
+        let root_id = bridge.get_mindmap_data().unwrap().root_node_id;
+        let keep = bridge.create_node(Some(root_id.clone()), "Keep".to_string()).unwrap();
+        let gone = bridge.create_node(Some(root_id.clone()), "Gone".to_string()).unwrap();
+
+        let root = bridge.parse_node_id(&root_id).unwrap();
+        let edge_id = {
+            let mut graph = bridge.graph.write().unwrap();
+            let keep_id = bridge.parse_node_id(&keep).unwrap();
+            let gone_id = bridge.parse_node_id(&gone).unwrap();
+            graph.add_edge(Edge::new(root, keep_id)).unwrap();
+            let edge_id = graph.add_edge(Edge::new(keep_id, gone_id)).unwrap();
+
+            // Drop the edge's target from the serialized form, as a corrupt save would
+            let mut value = serde_json::to_value(&*graph).unwrap();
+            value["nodes"].as_object_mut().unwrap().remove(&gone_id.as_uuid().to_string());
+            *graph = serde_json::from_value(value).unwrap();
+            edge_id
+        };
+
+        let report = bridge.validate_and_repair(RepairOptions::default()).unwrap();
+        assert!(report.validation_error.is_some());
+        assert_eq!(report.removed_edges, vec![edge_id.to_string()]);
+        assert!(report.reparented_orphans.is_empty());
+        assert!(report.remaining_error.is_none());
+        assert_eq!(bridge.get_status().unwrap().edge_count, 1);
+        assert!(!bridge.can_undo());
+
+        // A healthy graph is reported as such and left alone
+        let report = bridge.validate_and_repair(RepairOptions::default()).unwrap();
+        assert!(report.validation_error.is_none());
+        assert!(report.removed_edges.is_empty());
+    }
+
+    #[test]
+    fn test_layout_frame_moves_from_current_positions() {
+        let bridge = MindmapBridge::new();
+        bridge.create_mindmap("Frames".to_string()).unwrap();
+        let root_id = bridge.get_mindmap_data().unwrap().root_node_id;
+        let child_id = bridge.create_node(Some(root_id.clone()), "Child".to_string()).unwrap();
+        bridge.update_node_position(root_id.clone(), FfiPoint { x: 0.0, y: 0.0 }).unwrap();
+        bridge.update_node_position(child_id.clone(), FfiPoint { x: 0.0, y: 0.0 }).unwrap();
+
+        let target = FfiLayoutResult {
+            node_positions: [
+                (root_id.clone(), FfiPoint { x: 100.0, y: 0.0 }),
+                (child_id.clone(), FfiPoint { x: 100.0, y: 200.0 }),
+            ].into(),
+            layout_type: FfiLayoutType::Tree,
+            computation_time_ms: 0,
+        };
+
+        let start = bridge.get_layout_frame(target.clone(), 0.0, Easing::Linear).unwrap();
+        assert_eq!(start.node_positions[&child_id].y, 0.0);
+        assert!(start.entering_nodes.is_empty());
+
+        let middle = bridge.get_layout_frame(target.clone(), 0.5, Easing::Linear).unwrap();
+        assert_eq!(middle.node_positions[&child_id].x, 50.0);
+        assert_eq!(middle.node_positions[&child_id].y, 100.0);
+
+        let end = bridge.get_layout_frame(target.clone(), 1.0, Easing::EaseInOut).unwrap();
+        assert_eq!(end.node_positions[&root_id].x, 100.0);
+
+        assert!(bridge.get_layout_frame(target, 1.5, Easing::Linear).is_err());
+    }
+
+    #[test]
+    fn test_layout_animation_runs_from_current_positions_to_layout() {
+        let bridge = MindmapBridge::new();
+        bridge.create_mindmap("Animation".to_string()).unwrap();
+        let root_id = bridge.get_mindmap_data().unwrap().root_node_id;
+        for text in ["One", "Two", "Three"] {
+            let child_id = bridge.create_node(Some(root_id.clone()), text.to_string()).unwrap();
+            bridge.update_node_position(child_id, FfiPoint { x: 0.0, y: 0.0 }).unwrap();
+        }
+
+        let frames = bridge.compute_layout_animation(FfiLayoutType::Tree, 10).unwrap();
+        assert_eq!(frames.len(), 10);
+
+        let first = &frames[0];
+        for node in bridge.get_all_nodes().unwrap() {
+            let position = &first.node_positions[&node.id];
+            assert_eq!((position.x, position.y), (node.position.x, node.position.y));
+        }
+
+        let target = bridge.calculate_layout(FfiLayoutType::Tree).unwrap();
+        let last = frames.last().unwrap();
+        for (node_id, position) in &target.node_positions {
+            assert!((last.node_positions[node_id].x - position.x).abs() < 1e-9);
+            assert!((last.node_positions[node_id].y - position.y).abs() < 1e-9);
+        }
+        assert_eq!(last.progress, 1.0);
+        assert_eq!(last.time_ms, LayoutConfig::default().animation_duration as u64);
+        assert_eq!(first.time_ms, 0);
+
+        assert!(bridge.compute_layout_animation(FfiLayoutType::Tree, 1).is_err());
+    }
+
+    #[test]
+    fn test_layout_animation_uses_active_animation_duration() {
+        let bridge = MindmapBridge::new();
+        bridge.create_mindmap("Timed".to_string()).unwrap();
+        let root_id = bridge.get_mindmap_data().unwrap().root_node_id;
+        bridge.create_node(Some(root_id.clone()), "Child".to_string()).unwrap();
+
+        let config = LayoutConfig { animation_duration: 250, ..LayoutConfig::default() };
+        bridge.set_layout_config(config).unwrap();
+
+        let frames = bridge.compute_layout_animation(FfiLayoutType::Tree, 5).unwrap();
+        assert_eq!(frames.last().unwrap().time_ms, 250);
+        assert_eq!(frames[2].time_ms, 125);
+
+        let target = bridge.calculate_layout(FfiLayoutType::Tree).unwrap();
+        let middle = bridge.get_layout_frame(target, 0.5, Easing::Linear).unwrap();
+        assert_eq!(middle.time_ms, 125);
+    }
+}
@@ -296,6 +296,62 @@ pub struct FfiPerformanceMetrics {
     pub nodes_processed: u32,
 }
 
+/// Search options for FFI communication
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiSearchOptions {
+    /// Maximum number of results, unlimited when `None`
+    pub limit: Option<u32>,
+    /// Minimum score a result needs (0.0 to 1.0)
+    pub min_score: f64,
+    /// Search node tags as well as text
+    pub include_tags: bool,
+    /// Search node notes as well as text
+    pub include_notes: bool,
+}
+
+impl Default for FfiSearchOptions {
+    fn default() -> Self {
+        let defaults = crate::search::SearchOptions::default();
+        Self {
+            limit: defaults.limit.map(|limit| limit as u32),
+            min_score: defaults.min_score,
+            include_tags: defaults.include_tags,
+            include_notes: defaults.include_notes,
+        }
+    }
+}
+
+impl From<FfiSearchOptions> for crate::search::SearchOptions {
+    fn from(ffi_options: FfiSearchOptions) -> Self {
+        Self {
+            limit: ffi_options.limit.map(|limit| limit as usize),
+            min_score: ffi_options.min_score,
+            include_tags: ffi_options.include_tags,
+            include_notes: ffi_options.include_notes,
+            ..Self::default()
+        }
+    }
+}
+
+/// Change to the mindmap pushed to event subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub enum FfiMindmapEvent {
+    /// A node was created
+    NodeAdded { node_id: String },
+    /// A node's text, position, tags, metadata or style changed
+    NodeUpdated { node_id: String },
+    /// A node was deleted
+    NodeDeleted { node_id: String },
+    /// A layout was applied, moving `node_count` nodes
+    LayoutApplied { node_count: u32 },
+    /// The whole graph was replaced or rolled back, e.g. by an import or undo
+    GraphReplaced { node_count: u32 },
+    /// The open document was written to storage, e.g. by auto-save
+    DocumentSaved { document_id: String },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +205,17 @@ impl From<super::BridgeError> for EnhancedBridgeError {
             .with_recovery("Try a simpler search query")
             .with_recovery("Rebuild the search index"),
 
+            super::BridgeError::ConflictError { current, expected } => EnhancedBridgeError::new(
+                "CONFLICT_ERROR",
+                format!(
+                    "Document was saved by another writer (version {}, expected {})",
+                    current, expected
+                ),
+                ErrorSeverity::Error,
+            )
+            .with_recovery("Reload the document to pick up the latest version")
+            .with_recovery("Reapply your changes and save again"),
+
             super::BridgeError::GenericError { message } => EnhancedBridgeError::new(
                 "GENERIC_ERROR",
                 message,
@@ -219,6 +230,7 @@ impl From<super::BridgeError> for EnhancedBridgeError {
 /// Error recovery strategies
 pub mod recovery {
     use super::*;
+    use super::super::MindmapFFI;
 
     /// Attempt to recover from a node operation error
     pub fn recover_node_operation(
@@ -283,6 +295,9 @@ pub mod recovery {
             "SEARCH_ERROR" => {
                 "The search operation failed. Please try a different search term or rebuild the search index.".to_string()
             }
+            "CONFLICT_ERROR" => {
+                "The mindmap was changed elsewhere since it was loaded. Please reload it and try again.".to_string()
+            }
             _ => {
                 "An unexpected error occurred. Please try again or contact support if the problem persists.".to_string()
             }
@@ -398,6 +413,17 @@ mod tests {
         assert!(!enhanced_error.recovery_suggestions.is_empty());
     }
 
+    #[test]
+    fn test_conflict_error_conversion() {
+        let bridge_error = super::super::BridgeError::ConflictError { current: 4, expected: 3 };
+
+        let enhanced_error: EnhancedBridgeError = bridge_error.into();
+
+        assert_eq!(enhanced_error.details.code, "CONFLICT_ERROR");
+        assert!(enhanced_error.details.message.contains("version 4"));
+        assert!(!enhanced_error.recovery_suggestions.is_empty());
+    }
+
     #[test]
     fn test_user_friendly_messages() {
         let error = EnhancedBridgeError::new("NODE_NOT_FOUND", "Test", ErrorSeverity::Error);
@@ -5,7 +5,8 @@
 
 use super::{BridgeError, ExportFormat, FfiMindmapData, MindmapBridge};
 use crate::{
-    io::{FormatManager, FileFormat, ImportExportOptions, ImportResult, ExportResult},
+    graph::MissingRootPolicy,
+    io::{FormatManager, FileFormat, ImportExportOptions, ImportResult, TimestampFormat},
     models::{Document, Node},
     persistence::PersistenceManager,
     types::{NodeId, DocumentId},
@@ -40,7 +41,7 @@ impl FileOperations {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let nodes: Vec<Node> = graph.get_all_nodes().values().cloned().collect();
+        let nodes: Vec<Node> = graph.nodes().cloned().collect();
 
         // Determine format from file extension or explicit format
         let format = options.format.unwrap_or_else(|| {
@@ -52,10 +53,14 @@ impl FileOperations {
         let export_options = ImportExportOptions {
             preserve_ids: options.preserve_ids,
             include_metadata: options.include_metadata,
+            include_tags: true,
             include_timestamps: options.include_timestamps,
+            timestamp_format: options.timestamp_format,
+            timestamp_offset: options.timestamp_offset,
             max_depth: options.max_depth.unwrap_or(-1),
             include_empty_nodes: options.include_empty_nodes,
             encoding: options.encoding.clone(),
+            position_encoding: Default::default(),
         };
 
         // Save using appropriate method
@@ -64,7 +69,7 @@ impl FileOperations {
                 // Save as native JSON format
                 Self::save_json_format(&document, &nodes, &path, &export_options)?
             }
-            FileFormat::Opml | FileFormat::Markdown => {
+            FileFormat::Opml | FileFormat::Markdown | FileFormat::Freemind | FileFormat::Dot | FileFormat::Csv | FileFormat::Html => {
                 // Save using format manager
                 Self::save_with_format_manager(&document, &nodes, &path, format, &export_options)?
             }
@@ -122,10 +127,14 @@ impl FileOperations {
         let import_options = ImportExportOptions {
             preserve_ids: options.preserve_ids,
             include_metadata: true,
+            include_tags: true,
             include_timestamps: true,
+            timestamp_format: TimestampFormat::Rfc3339,
+            timestamp_offset: None,
             max_depth: -1,
             include_empty_nodes: false,
             encoding: "UTF-8".to_string(),
+            position_encoding: Default::default(),
         };
 
         // Load based on detected format
@@ -133,7 +142,7 @@ impl FileOperations {
             FileFormat::Json => {
                 Self::load_json_format(&content, &import_options)?
             }
-            FileFormat::Opml | FileFormat::Markdown => {
+            FileFormat::Opml | FileFormat::Markdown | FileFormat::Freemind | FileFormat::Dot | FileFormat::Csv | FileFormat::Html => {
                 Self::load_with_format_manager(&content, detected_format, &import_options)?
             }
             FileFormat::Text => {
@@ -142,7 +151,7 @@ impl FileOperations {
         };
 
         // Update bridge with loaded data
-        Self::update_bridge_with_loaded_data(bridge, document, nodes)?;
+        Self::update_bridge_with_loaded_data(bridge, document, nodes, options.missing_root_policy)?;
 
         let result = LoadResult {
             file_path: file_path.clone(),
@@ -173,17 +182,21 @@ impl FileOperations {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let nodes: Vec<Node> = graph.get_all_nodes().values().cloned().collect();
+        let nodes: Vec<Node> = graph.nodes().cloned().collect();
         let mut results = Vec::new();
 
         // Convert options to internal format
         let export_options = ImportExportOptions {
             preserve_ids: false, // Usually not needed for export
             include_metadata: options.include_metadata,
+            include_tags: true,
             include_timestamps: options.include_timestamps,
+            timestamp_format: options.timestamp_format,
+            timestamp_offset: options.timestamp_offset,
             max_depth: options.max_depth.unwrap_or(-1),
             include_empty_nodes: options.include_empty_nodes,
             encoding: "UTF-8".to_string(),
+            position_encoding: Default::default(),
         };
 
         // Export to each requested format
@@ -239,10 +252,14 @@ impl FileOperations {
         let internal_options = ImportExportOptions {
             preserve_ids: import_options.preserve_ids,
             include_metadata: true,
+            include_tags: true,
             include_timestamps: true,
+            timestamp_format: TimestampFormat::Rfc3339,
+            timestamp_offset: None,
             max_depth: import_options.max_depth.unwrap_or(-1),
             include_empty_nodes: import_options.include_empty_nodes,
             encoding: "UTF-8".to_string(),
+            position_encoding: Default::default(),
         };
 
         // Import content
@@ -256,8 +273,8 @@ impl FileOperations {
             Self::merge_imported_data(bridge, &import_result)?;
         } else {
             // Replace current data
-            let nodes: Vec<Node> = Vec::new(); // Will be populated from document
-            Self::update_bridge_with_loaded_data(bridge, import_result.document.clone(), nodes)?;
+            let nodes = import_result.nodes.clone();
+            Self::update_bridge_with_loaded_data(bridge, import_result.document.clone(), nodes, MissingRootPolicy::Error)?;
         }
 
         let result = ImportResultFFI {
@@ -347,6 +364,10 @@ impl FileOperations {
                 "md" | "markdown" => FileFormat::Markdown,
                 "json" => FileFormat::Json,
                 "txt" | "text" => FileFormat::Text,
+                "mm" => FileFormat::Freemind,
+                "dot" | "gv" => FileFormat::Dot,
+                "csv" => FileFormat::Csv,
+                "html" | "htm" => FileFormat::Html,
                 _ => FileFormat::Json,
             }
         })
@@ -440,8 +461,10 @@ impl FileOperations {
 
         let import_result = ImportResult {
             document: document.clone(),
+            nodes: nodes.clone(),
             node_count: nodes.len(),
             edge_count: 0, // Will be calculated from node relationships
+            edges: Vec::new(),
             warnings: Vec::new(),
         };
 
@@ -459,7 +482,7 @@ impl FileOperations {
                 message: format!("Import failed: {}", e),
             })?;
 
-        let nodes = Vec::new(); // Nodes will be extracted from document structure
+        let nodes = import_result.nodes.clone();
         Ok((import_result.document.clone(), nodes, import_result))
     }
 
@@ -489,8 +512,10 @@ impl FileOperations {
 
         let import_result = ImportResult {
             document: document.clone(),
+            nodes: nodes.clone(),
             node_count: nodes.len(),
             edge_count: nodes.len() - 1, // All children connected to root
+            edges: Vec::new(),
             warnings: vec!["Imported as plain text with basic structure".to_string()],
         };
 
@@ -574,9 +599,23 @@ impl FileOperations {
 
     fn update_bridge_with_loaded_data(
         bridge: &MindmapBridge,
-        document: Document,
+        mut document: Document,
         nodes: Vec<Node>,
+        missing_root_policy: MissingRootPolicy,
     ) -> Result<(), BridgeError> {
+        // Build the graph and repair the root before the document is exposed
+        let mut loaded = crate::graph::Graph::new();
+        for node in nodes {
+            loaded.restore_node(node).map_err(|e| BridgeError::InvalidOperation {
+                message: format!("Failed to add node: {}", e),
+            })?;
+        }
+
+        loaded.resolve_document_root(&mut document, missing_root_policy)
+            .map_err(|e| BridgeError::InvalidOperation {
+                message: format!("Invalid document root: {}", e),
+            })?;
+
         // Update document
         bridge.set_document(document)?;
 
@@ -585,17 +624,12 @@ impl FileOperations {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        *graph = crate::graph::Graph::new();
-        for node in nodes {
-            graph.add_node(node).map_err(|e| BridgeError::InvalidOperation {
-                message: format!("Failed to add node: {}", e),
-            })?;
-        }
+        *graph = loaded;
 
         // Update search index
         if let Ok(mut search) = bridge.search_engine.write() {
             *search = crate::search::SearchEngine::new();
-            for (_, node) in graph.get_all_nodes() {
+            for node in graph.nodes() {
                 search.index_node(node);
             }
         }
@@ -653,6 +687,10 @@ impl FileOperations {
                 }
             }
             FileFormat::Text => content.lines().filter(|line| !line.trim().is_empty()).count(),
+            FileFormat::Freemind => content.matches("<node").count(),
+            FileFormat::Dot => content.matches("[label=").count(),
+            FileFormat::Csv => content.lines().skip(1).filter(|line| !line.trim().is_empty()).count(),
+            FileFormat::Html => content.matches("<li").count() + content.matches("<h").count(),
         }
     }
 }
@@ -665,6 +703,8 @@ pub struct SaveOptions {
     pub preserve_ids: bool,
     pub include_metadata: bool,
     pub include_timestamps: bool,
+    pub timestamp_format: TimestampFormat,
+    pub timestamp_offset: Option<i32>, // Seconds east of UTC, None for UTC
     pub max_depth: Option<i32>,
     pub include_empty_nodes: bool,
     pub encoding: String,
@@ -678,6 +718,8 @@ impl Default for SaveOptions {
             preserve_ids: false,
             include_metadata: true,
             include_timestamps: true,
+            timestamp_format: TimestampFormat::Rfc3339,
+            timestamp_offset: None,
             max_depth: None,
             include_empty_nodes: false,
             encoding: "UTF-8".to_string(),
@@ -692,6 +734,7 @@ impl Default for SaveOptions {
 pub struct LoadOptions {
     pub force_format: Option<FileFormat>,
     pub preserve_ids: bool,
+    pub missing_root_policy: MissingRootPolicy,
 }
 
 impl Default for LoadOptions {
@@ -699,6 +742,7 @@ impl Default for LoadOptions {
         Self {
             force_format: None,
             preserve_ids: false,
+            missing_root_policy: MissingRootPolicy::default(),
         }
     }
 }
@@ -709,6 +753,8 @@ impl Default for LoadOptions {
 pub struct ExportOptions {
     pub include_metadata: bool,
     pub include_timestamps: bool,
+    pub timestamp_format: TimestampFormat,
+    pub timestamp_offset: Option<i32>, // Seconds east of UTC, None for UTC
     pub max_depth: Option<i32>,
     pub include_empty_nodes: bool,
 }
@@ -718,6 +764,8 @@ impl Default for ExportOptions {
         Self {
             include_metadata: true,
             include_timestamps: true,
+            timestamp_format: TimestampFormat::Rfc3339,
+            timestamp_offset: None,
             max_depth: None,
             include_empty_nodes: false,
         }
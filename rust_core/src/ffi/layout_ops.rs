@@ -5,8 +5,7 @@
 
 use super::{BridgeError, FfiLayoutResult, FfiLayoutType, FfiPoint, MindmapBridge, utils};
 use crate::{
-    graph::Graph,
-    layout::{LayoutConfig, LayoutEngine, LayoutEngineImpl, LayoutType},
+    layout::{params, LayoutConfig, LayoutEngine, LayoutEngineImpl},
     models::Node,
     types::{NodeId, Point},
 };
@@ -44,26 +43,29 @@ impl LayoutOperations {
             });
         }
 
-        // Convert FFI config to internal config
-        let internal_config = LayoutConfig {
-            canvas_width: config.canvas_width,
-            canvas_height: config.canvas_height,
-            margin: config.margin,
-            node_spacing: config.node_spacing,
-            level_spacing: config.level_spacing.unwrap_or(50.0),
-            preserve_aspect_ratio: config.preserve_aspect_ratio,
-        };
+        // Convert FFI config to internal config; the margin shrinks the
+        // usable canvas around the same center
+        let internal_config = LayoutConfig::builder()
+            .canvas_size(
+                config.canvas_width - 2.0 * config.margin,
+                config.canvas_height - 2.0 * config.margin,
+            )
+            .center(Point::new(config.canvas_width / 2.0, config.canvas_height / 2.0))
+            .min_distance(config.node_spacing)
+            .parameter(params::HORIZONTAL_SPACING, config.node_spacing)
+            .parameter(params::VERTICAL_SPACING, config.level_spacing.unwrap_or(50.0))
+            .build()?;
 
         // Calculate layout using specified algorithm
-        let layout_result = bridge.layout_engine
-            .calculate_layout(&*graph, &internal_config)
+        let engine = LayoutEngineImpl::with_layout_type(layout_type.into());
+        let layout_result = LayoutEngine::calculate_layout(&engine, &graph, &internal_config)
             .map_err(|e| BridgeError::LayoutComputationError {
                 message: format!("Layout calculation failed: {}", e),
             })?;
 
         // Convert result to FFI format
         let node_positions: HashMap<String, FfiPoint> = layout_result
-            .node_positions
+            .positions
             .into_iter()
             .map(|(id, pos)| (id.to_string(), FfiPoint { x: pos.x, y: pos.y }))
             .collect();
@@ -71,7 +73,7 @@ impl LayoutOperations {
         let result = FfiLayoutResult {
             node_positions,
             layout_type,
-            computation_time_ms: layout_result.computation_time.as_millis() as u64,
+            computation_time_ms: start_time.elapsed().as_millis() as u64,
         };
 
         bridge.record_metrics("calculate_layout_with_config", start_time, result.node_positions.len() as u32);
@@ -96,10 +98,9 @@ impl LayoutOperations {
 
         // Get current positions
         let current_positions: HashMap<String, FfiPoint> = graph
-            .get_all_nodes()
-            .iter()
-            .map(|(id, node)| {
-                (id.to_string(), FfiPoint { x: node.position.x, y: node.position.y })
+            .nodes()
+            .map(|node| {
+                (node.id.to_string(), FfiPoint { x: node.position.x, y: node.position.y })
             })
             .collect();
 
@@ -113,7 +114,7 @@ impl LayoutOperations {
         // Apply final positions
         for (node_id_str, position) in &layout_result.node_positions {
             let node_id = bridge.parse_node_id(node_id_str)?;
-            if let Ok(mut node) = graph.get_node(node_id).cloned() {
+            if let Some(mut node) = graph.get_node(node_id).cloned() {
                 node.position = Point::new(position.x, position.y);
                 node.touch();
                 graph.update_node(node).map_err(|e| BridgeError::InvalidOperation {
@@ -144,10 +145,11 @@ impl LayoutOperations {
         })?;
 
         // Apply all position updates
+        let update_count = position_updates.len() as u32;
         for update in position_updates {
             let node_id = bridge.parse_node_id(&update.node_id)?;
             let mut node = graph.get_node(node_id)
-                .map_err(|_| BridgeError::NodeNotFound { id: update.node_id.clone() })?
+                .ok_or_else(|| BridgeError::NodeNotFound { id: update.node_id.clone() })?
                 .clone();
 
             node.position = Point::new(update.position.x, update.position.y);
@@ -164,7 +166,7 @@ impl LayoutOperations {
             })?;
         }
 
-        bridge.record_metrics("batch_update_positions", start_time, position_updates.len() as u32);
+        bridge.record_metrics("batch_update_positions", start_time, update_count);
         Ok(())
     }
 
@@ -193,7 +195,7 @@ impl LayoutOperations {
             });
         }
 
-        let nodes: Vec<&Node> = graph.get_all_nodes().values().collect();
+        let nodes: Vec<&Node> = graph.nodes().collect();
         let positions: Vec<&Point> = nodes.iter().map(|n| &n.position).collect();
 
         let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
@@ -268,12 +270,11 @@ impl LayoutOperations {
         })?;
 
         let node_positions: HashMap<String, FfiPoint> = graph
-            .get_all_nodes()
-            .iter()
-            .map(|(id, node)| {
+            .nodes()
+            .map(|node| {
                 let new_x = (node.position.x * scale) + offset_x;
                 let new_y = (node.position.y * scale) + offset_y;
-                (id.to_string(), FfiPoint { x: new_x, y: new_y })
+                (node.id.to_string(), FfiPoint { x: new_x, y: new_y })
             })
             .collect();
 
@@ -314,12 +315,12 @@ impl LayoutOperations {
                 .filter_map(|id_str| bridge.parse_node_id(&id_str).ok())
                 .collect()
         } else {
-            graph.get_all_nodes().keys().cloned().collect()
+            graph.nodes().map(|node| node.id).collect()
         };
 
         // Snap positions to grid
         for node_id in nodes_to_snap {
-            if let Ok(mut node) = graph.get_node(node_id).cloned() {
+            if let Some(mut node) = graph.get_node(node_id).cloned() {
                 let snapped_x = (node.position.x / grid_size).round() * grid_size;
                 let snapped_y = (node.position.y / grid_size).round() * grid_size;
 
@@ -412,8 +413,7 @@ impl LayoutOperations {
             let mut frame_positions = HashMap::new();
 
             for (node_id, end_pos) in end_positions {
-                let start_pos = start_positions.get(node_id)
-                    .unwrap_or(&FfiPoint { x: end_pos.x, y: end_pos.y });
+                let start_pos = start_positions.get(node_id).unwrap_or(end_pos);
 
                 let interpolated_x = start_pos.x + (end_pos.x - start_pos.x) * eased_progress;
                 let interpolated_y = start_pos.y + (end_pos.y - start_pos.y) * eased_progress;
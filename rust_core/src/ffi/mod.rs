@@ -6,7 +6,8 @@
 
 use crate::models::{Node, Edge, MindmapDocument, NodeStyle, Attachment};
 use crate::types::{NodeId, EdgeId, MindmapId, Point, Color, MindmapError, MindmapResult};
-use crate::layout::LayoutType;
+use crate::layout::{Easing, LayoutType};
+use crate::graph::RepairOptions;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -52,6 +53,8 @@ pub enum BridgeError {
     SearchError { message: String },
     /// Generic error with message
     GenericError { message: String },
+    /// Another writer saved a newer version of the document
+    ConflictError { current: u64, expected: u64 },
 }
 
 /// Convert internal MindmapError to FFI-compatible BridgeError
@@ -71,6 +74,11 @@ impl From<MindmapError> for BridgeError {
             MindmapError::IoError { message } => BridgeError::FileSystemError { message },
             MindmapError::ParseError { message } => BridgeError::SerializationError { message },
             MindmapError::DatabaseError { message } => BridgeError::GenericError { message },
+            MindmapError::MetricsError(message) => BridgeError::GenericError { message },
+            MindmapError::ConflictError { current, expected } => BridgeError::ConflictError { current, expected },
+            error @ MindmapError::AttachmentTooLarge { .. } => BridgeError::InvalidOperation {
+                message: error.to_string()
+            },
         }
     }
 }
@@ -85,6 +93,10 @@ pub enum FfiLayoutType {
     Tree,
     /// Force-directed layout with physics simulation
     ForceDirected,
+    /// Hyperbolic cone tree with deep levels shrinking towards the edge
+    Hyperbolic,
+    /// Evenly spaced rows and columns in breadth-first order
+    Grid,
 }
 
 impl From<FfiLayoutType> for LayoutType {
@@ -93,6 +105,8 @@ impl From<FfiLayoutType> for LayoutType {
             FfiLayoutType::Radial => LayoutType::Radial,
             FfiLayoutType::Tree => LayoutType::Tree,
             FfiLayoutType::ForceDirected => LayoutType::Force,
+            FfiLayoutType::Hyperbolic => LayoutType::Hyperbolic,
+            FfiLayoutType::Grid => LayoutType::Grid,
         }
     }
 }
@@ -145,6 +159,8 @@ pub struct FfiNodeData {
     pub created_at: i64, // Unix timestamp
     pub updated_at: i64, // Unix timestamp
     pub metadata: HashMap<String, String>,
+    pub style: FfiNodeStyle,
+    pub note: Option<String>, // Markdown
 }
 
 impl From<Node> for FfiNodeData {
@@ -158,6 +174,8 @@ impl From<Node> for FfiNodeData {
             created_at: node.created_at.timestamp(),
             updated_at: node.updated_at.timestamp(),
             metadata: node.metadata,
+            style: node.style.into(),
+            note: node.note,
         }
     }
 }
@@ -171,6 +189,16 @@ pub struct FfiLayoutResult {
     pub computation_time_ms: u64,
 }
 
+/// One frame of an animated transition to a layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiLayoutFrame {
+    pub progress: f64, // Requested progress, 0.0 at the current positions and 1.0 at the target
+    pub time_ms: u64, // Offset into the animation, reaching the layout's animation duration at 1.0
+    pub node_positions: HashMap<String, FfiPoint>,
+    pub entering_nodes: Vec<String>, // Nodes moving out from the layout center, for fading in
+}
+
 /// FFI-compatible search result data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
@@ -179,6 +207,24 @@ pub struct FfiSearchResult {
     pub text: String,
     pub score: f64,
     pub match_positions: Vec<(usize, usize)>, // (start, end) positions of matches
+    pub snippet: String, // Excerpt around the best match
+    pub highlighted: String, // Node text with matches wrapped in ** for bold rendering
+}
+
+/// FFI-compatible scope for searches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub enum FfiSearchContext {
+    /// Search all nodes
+    All,
+    /// Search the node and its descendants
+    Subtree { node_id: String },
+    /// Search nodes at a depth below the roots (roots are at depth 0)
+    Depth { depth: u32 },
+    /// Search root nodes only
+    Roots,
+    /// Search nodes carrying any of the tags
+    Tagged { tags: Vec<String> },
 }
 
 /// FFI-compatible mindmap data structure
@@ -201,6 +247,51 @@ pub struct FfiNodeUpdate {
     pub position: Option<FfiPoint>,
     pub tags: Option<Vec<String>>,
     pub metadata: Option<HashMap<String, String>>,
+    pub style: Option<FfiNodeStyle>, // Replaces the whole style
+    pub note: Option<String>, // Markdown; an empty note removes it
+}
+
+/// FFI-compatible set of node changes since a marker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiNodeDelta {
+    pub added: Vec<FfiNodeData>,
+    pub updated: Vec<FfiNodeData>,
+    pub removed: Vec<String>,
+    pub marker: i64, // Unix timestamp in milliseconds; pass to the next call
+}
+
+/// FFI-compatible report of an auto-detected file import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiImportReport {
+    pub document_id: String,
+    pub title: String,
+    pub detected_format: String, // File extension of the detected format, e.g. "opml"
+    pub node_count: u32,
+    pub edge_count: u32,
+    pub warnings: Vec<String>,
+}
+
+/// FFI-compatible summary of the current mindmap for status displays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiStatus {
+    pub node_count: u32,
+    pub edge_count: u32,
+    pub has_unsaved_changes: bool,
+    pub last_modified: i64, // Unix timestamp in milliseconds, 0 if no document is loaded
+}
+
+/// FFI-compatible report of a validate-and-repair pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "flutter_rust_bridge_feature", frb)]
+pub struct FfiRepairReport {
+    pub validation_error: Option<String>, // First problem found before repairing, None if the graph was valid
+    pub reparented_orphans: Vec<String>,
+    pub removed_edges: Vec<String>,
+    pub broken_cycle_nodes: Vec<String>,
+    pub remaining_error: Option<String>, // Problem still present after repairing, None if the graph is now valid
 }
 
 /// Main FFI interface for mindmap operations
@@ -219,6 +310,15 @@ pub trait MindmapFFI {
         text: String,
     ) -> Result<String, BridgeError>;
 
+    /// Create several nodes under the same parent, returning their IDs in order
+    ///
+    /// Either all nodes are created or, on the first failure, none are.
+    fn create_nodes_batch(
+        &self,
+        parent_id: Option<String>,
+        texts: Vec<String>,
+    ) -> Result<Vec<String>, BridgeError>;
+
     /// Update an existing node's properties
     fn update_node(
         &self,
@@ -240,6 +340,15 @@ pub trait MindmapFFI {
         position: FfiPoint,
     ) -> Result<(), BridgeError>;
 
+    /// Update the positions of many nodes in a single call
+    ///
+    /// The whole batch is rejected if any node is unknown or any position is
+    /// not finite.
+    fn update_positions_batch(
+        &self,
+        positions: HashMap<String, FfiPoint>,
+    ) -> Result<(), BridgeError>;
+
     /// Delete a node and all its children
     fn delete_node(
         &self,
@@ -258,9 +367,44 @@ pub trait MindmapFFI {
         node_id: String,
     ) -> Result<Vec<FfiNodeData>, BridgeError>;
 
+    /// Get the ancestors of a node, root first, e.g. for breadcrumbs
+    fn get_ancestors(
+        &self,
+        node_id: String,
+    ) -> Result<Vec<FfiNodeData>, BridgeError>;
+
+    /// Get a node followed by all its descendants, each after its parent
+    fn get_subtree(
+        &self,
+        node_id: String,
+    ) -> Result<Vec<FfiNodeData>, BridgeError>;
+
     /// Get all nodes in the mindmap
     fn get_all_nodes(&self) -> Result<Vec<FfiNodeData>, BridgeError>;
 
+    /// Get nodes added, updated or removed since a marker (Unix timestamp in milliseconds)
+    fn get_nodes_changed_since(
+        &self,
+        since_ms: i64,
+    ) -> Result<FfiNodeDelta, BridgeError>;
+
+    /// Attach file contents to a node, returning the attachment ID
+    ///
+    /// Contents larger than `constants::MAX_ATTACHMENT_SIZE` are rejected.
+    fn attach_file(
+        &self,
+        node_id: String,
+        name: String,
+        data: Vec<u8>,
+        mime_type: String,
+    ) -> Result<String, BridgeError>;
+
+    /// Get the contents of a node attachment
+    fn get_attachment(
+        &self,
+        attachment_id: String,
+    ) -> Result<Vec<u8>, BridgeError>;
+
     // Layout Operations
 
     /// Calculate layout for all nodes using specified algorithm
@@ -275,12 +419,46 @@ pub trait MindmapFFI {
         layout_result: FfiLayoutResult,
     ) -> Result<(), BridgeError>;
 
+    /// Positions part way from the current node positions to `target`
+    fn get_layout_frame(
+        &self,
+        target: FfiLayoutResult,
+        progress: f64,
+        easing: Easing,
+    ) -> Result<FfiLayoutFrame, BridgeError>;
+
+    /// Eased keyframes from the current node positions to a freshly computed layout
+    fn compute_layout_animation(
+        &self,
+        layout_type: FfiLayoutType,
+        frame_count: u32,
+    ) -> Result<Vec<FfiLayoutFrame>, BridgeError>;
+
+    // History Operations
+
+    /// Reverse the most recent edit made through the bridge
+    ///
+    /// Returns false if there was nothing to undo.
+    fn undo(&self) -> Result<bool, BridgeError>;
+
+    /// Re-apply the most recently undone edit
+    ///
+    /// Returns false if there was nothing to redo.
+    fn redo(&self) -> Result<bool, BridgeError>;
+
+    /// Whether there is an edit to undo
+    fn can_undo(&self) -> bool;
+
+    /// Whether there is an undone edit to redo
+    fn can_redo(&self) -> bool;
+
     // Search Operations
 
     /// Search nodes by text content with fuzzy matching
     fn search_nodes(
         &self,
         query: String,
+        options: FfiSearchOptions,
     ) -> Result<Vec<FfiSearchResult>, BridgeError>;
 
     /// Search nodes by tags
@@ -289,6 +467,13 @@ pub trait MindmapFFI {
         tags: Vec<String>,
     ) -> Result<Vec<FfiSearchResult>, BridgeError>;
 
+    /// Search nodes by text content within a scope, e.g. one branch
+    fn search_scoped(
+        &self,
+        query: String,
+        context: FfiSearchContext,
+    ) -> Result<Vec<FfiSearchResult>, BridgeError>;
+
     // File Operations
 
     /// Create a new mindmap document
@@ -316,14 +501,29 @@ pub trait MindmapFFI {
         format: ExportFormat,
     ) -> Result<(), BridgeError>;
 
+    /// Import a file, detecting its format from the extension and then the content
+    fn import_file_auto(
+        &self,
+        path: String,
+    ) -> Result<FfiImportReport, BridgeError>;
+
     /// Get current mindmap data
     fn get_mindmap_data(&self) -> Result<FfiMindmapData, BridgeError>;
 
+    /// Get node/edge counts and unsaved-changes state without fetching nodes
+    fn get_status(&self) -> Result<FfiStatus, BridgeError>;
+
     // Utility Operations
 
     /// Validate mindmap data integrity
     fn validate_mindmap(&self) -> Result<bool, BridgeError>;
 
+    /// Validate the graph and fix the kinds of damage enabled in `options`
+    fn validate_and_repair(
+        &self,
+        options: RepairOptions,
+    ) -> Result<FfiRepairReport, BridgeError>;
+
     /// Get engine version and platform information
     fn get_engine_info(&self) -> Result<String, BridgeError>;
 
@@ -352,7 +552,7 @@ pub mod constants {
     pub const MAX_TAG_LENGTH: usize = 100;
 
     /// Maximum file size for attachments (100MB)
-    pub const MAX_ATTACHMENT_SIZE: u64 = 100 * 1024 * 1024;
+    pub const MAX_ATTACHMENT_SIZE: u64 = crate::models::MAX_ATTACHMENT_SIZE;
 }
 
 /// Utility functions for FFI operations
@@ -420,6 +620,25 @@ pub mod utils {
         Ok(())
     }
 
+    /// Validate node style values
+    pub fn validate_style(style: &FfiNodeStyle) -> Result<(), BridgeError> {
+        if !style.font_size.is_finite() || style.font_size <= 0.0 {
+            return Err(BridgeError::InvalidOperation {
+                message: format!("Font size must be a positive number, got {}", style.font_size),
+            });
+        }
+
+        for (name, value) in [("Border width", style.border_width), ("Corner radius", style.corner_radius)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(BridgeError::InvalidOperation {
+                    message: format!("{} must be a non-negative number, got {}", name, value),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate coordinate values
     pub fn validate_position(position: &FfiPoint) -> Result<(), BridgeError> {
         if !position.x.is_finite() || !position.y.is_finite() {
@@ -439,7 +658,7 @@ mod tests {
 
     #[test]
     fn test_bridge_error_conversion() {
-        let node_id = uuid::Uuid::new_v4();
+        let node_id = NodeId::new();
         let mindmap_error = MindmapError::NodeNotFound { id: node_id };
         let bridge_error: BridgeError = mindmap_error.into();
 
@@ -511,4 +730,26 @@ mod tests {
         let infinite_position = FfiPoint { x: f64::INFINITY, y: 20.0 };
         assert!(validate_position(&infinite_position).is_err());
     }
+
+    #[test]
+    fn test_node_style_round_trip() {
+        let mut node = Node::new("Styled");
+        node.style.background_color = crate::types::utils::rgba_to_color(255, 204, 0, 128);
+        node.style.text_color = crate::types::utils::rgb_to_color(20, 40, 60);
+        node.style.font_size = 22.5;
+        node.style.shape = crate::models::NodeShape::Ellipse;
+
+        let data: FfiNodeData = node.clone().into();
+        assert_eq!(data.style.background_color, FfiColor { r: 255, g: 204, b: 0, a: 128 });
+        assert_eq!(data.style.font_size, 22.5);
+        assert_eq!(data.style.shape, FfiNodeShape::Ellipse);
+        assert!(validate_style(&data.style).is_ok());
+
+        let style: NodeStyle = data.style.into();
+        assert_eq!(style, node.style);
+
+        let mut invalid = FfiNodeStyle::from(NodeStyle::default());
+        invalid.font_size = 0.0;
+        assert!(validate_style(&invalid).is_err());
+    }
 }
\ No newline at end of file
@@ -64,7 +64,7 @@ impl NodeOperations {
         }
 
         // Add to graph through bridge
-        let graph = bridge.graph.write().map_err(|_| BridgeError::GenericError {
+        let mut graph = bridge.graph.write().map_err(|_| BridgeError::GenericError {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
@@ -176,6 +176,9 @@ impl NodeOperations {
             if let Some(ref pos) = update.update.position {
                 utils::validate_position(pos)?;
             }
+            if let Some(ref style) = update.update.style {
+                utils::validate_style(style)?;
+            }
         }
 
         let mut graph = bridge.graph.write().map_err(|_| BridgeError::GenericError {
@@ -183,11 +186,12 @@ impl NodeOperations {
         })?;
 
         // Apply all updates
+        let update_count = updates.len() as u32;
         for update in updates {
             let node_id = bridge.parse_node_id(&update.node_id)?;
 
             let mut node = graph.get_node(node_id)
-                .map_err(|_| BridgeError::NodeNotFound { id: update.node_id.clone() })?
+                .ok_or_else(|| BridgeError::NodeNotFound { id: update.node_id.clone() })?
                 .clone();
 
             // Apply updates
@@ -203,6 +207,13 @@ impl NodeOperations {
             if let Some(metadata) = update.update.metadata {
                 node.metadata.extend(metadata);
             }
+            if let Some(style) = update.update.style {
+                node.style = style.into();
+                node.touch();
+            }
+            if let Some(note) = update.update.note {
+                node.set_note(Some(note));
+            }
 
             node.touch();
             graph.update_node(node).map_err(|e| BridgeError::InvalidOperation {
@@ -210,7 +221,7 @@ impl NodeOperations {
             })?;
         }
 
-        bridge.record_metrics("batch_update_nodes", start_time, updates.len() as u32);
+        bridge.record_metrics("batch_update_nodes", start_time, update_count);
         Ok(())
     }
 
@@ -229,7 +240,7 @@ impl NodeOperations {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let node = graph.get_node(id).map_err(|_| BridgeError::NodeNotFound {
+        let node = graph.get_node(id).ok_or(BridgeError::NodeNotFound {
             id: node_id,
         })?;
 
@@ -300,7 +311,7 @@ impl NodeOperations {
 
         // Get and update node
         let mut node = graph.get_node(id)
-            .map_err(|_| BridgeError::NodeNotFound { id: node_id })?
+            .ok_or(BridgeError::NodeNotFound { id: node_id })?
             .clone();
 
         node.parent_id = new_parent;
@@ -367,11 +378,9 @@ impl NodeOperations {
             return;
         }
 
-        if let Ok(children) = graph.get_children(parent_id) {
-            for child in children {
-                descendants.push(bridge.node_to_ffi(child));
-                Self::collect_descendants(graph, bridge, child.id, descendants, current_depth + 1, max_depth);
-            }
+        for child in graph.get_children(parent_id) {
+            descendants.push(bridge.node_to_ffi(child));
+            Self::collect_descendants(graph, bridge, child.id, descendants, current_depth + 1, max_depth);
         }
     }
 
@@ -389,7 +398,7 @@ impl NodeOperations {
                 return Ok(true);
             }
             current = graph.get_node(current_id)
-                .map_err(|_| BridgeError::InvalidOperation {
+                .ok_or_else(|| BridgeError::InvalidOperation {
                     message: "Invalid node in hierarchy".to_string(),
                 })?
                 .parent_id;
@@ -400,7 +409,7 @@ impl NodeOperations {
     fn find_nodes_by_criteria(graph: &Graph, criteria: &NodeDeletionCriteria) -> Result<Vec<NodeId>, BridgeError> {
         let mut matching_nodes = Vec::new();
 
-        for (node_id, node) in graph.get_all_nodes() {
+        for node in graph.nodes() {
             let mut matches = true;
 
             // Check tag criteria
@@ -423,7 +432,7 @@ impl NodeOperations {
             }
 
             if matches {
-                matching_nodes.push(*node_id);
+                matching_nodes.push(node.id);
             }
         }
 
@@ -502,6 +511,8 @@ mod tests {
             created_at: 1234567890,
             updated_at: 1234567890,
             metadata: HashMap::new(),
+            style: crate::models::NodeStyle::default().into(),
+            note: None,
         };
 
         let hierarchy = NodeHierarchy {
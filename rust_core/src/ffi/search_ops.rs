@@ -5,7 +5,7 @@
 
 use super::{BridgeError, FfiSearchResult, MindmapBridge, utils};
 use crate::{
-    search::{SearchEngine, SearchResult, SearchOptions},
+    search::{engine::SearchResult, SearchEngine},
     types::NodeId,
 };
 use std::collections::HashMap;
@@ -43,25 +43,13 @@ impl SearchOperations {
             message: "Failed to acquire search lock".to_string(),
         })?;
 
-        // Convert FFI options to internal options
-        let internal_options = SearchOptions {
-            limit: options.limit.unwrap_or(50),
-            min_score: options.min_score.unwrap_or(0.1),
-            include_highlights: options.include_highlights,
-            case_sensitive: options.case_sensitive,
-            whole_words_only: options.whole_words_only,
-        };
-
         // Perform search based on query type
-        let mut results = if !query.trim().is_empty() && !options.tags.is_empty() {
+        let results = if !query.trim().is_empty() && !options.tags.is_empty() {
             // Combined text and tag search
-            Self::search_combined(&*search, &query, &options.tags, &internal_options)?
+            Self::search_combined(&search, &query, &options.tags, &options)?
         } else if !query.trim().is_empty() {
             // Text search only
-            search.search_with_options(&query, &internal_options)
-                .map_err(|e| BridgeError::SearchError {
-                    message: format!("Search failed: {}", e),
-                })?
+            Self::search_text(&search, &query, &options)?
         } else {
             // Tag search only
             search.search_by_tags(&options.tags)
@@ -70,40 +58,39 @@ impl SearchOperations {
                 })?
         };
 
-        // Apply sorting if requested
-        if let Some(sort_by) = options.sort_by {
-            Self::sort_results(&mut results, sort_by);
-        }
-
-        // Apply pagination
-        let total_count = results.len();
-        let offset = options.offset.unwrap_or(0);
-        let limit = options.limit.unwrap_or(50);
-        let has_more = total_count > offset + limit;
-
-        if offset > 0 || limit < total_count {
-            let end = std::cmp::min(offset + limit, total_count);
-            if offset < total_count {
-                results = results[offset..end].to_vec();
-            } else {
-                results.clear();
-            }
-        }
-
         // Convert to FFI format
-        let ffi_results: Vec<FfiSearchResult> = results
+        let mut ffi_results: Vec<FfiSearchResult> = results
             .into_iter()
             .map(|result| FfiSearchResult {
                 node_id: result.node_id.to_string(),
+                highlighted: if options.include_highlights {
+                    crate::search::highlight_matches(&result.text, &result.match_positions)
+                } else {
+                    result.text.clone()
+                },
+                snippet: result.text.clone(),
                 text: result.text,
                 score: result.score,
                 match_positions: result.match_positions,
             })
             .collect();
 
+        // Apply sorting if requested
+        if let Some(sort_by) = options.sort_by {
+            Self::sort_results(&mut ffi_results, sort_by);
+        }
+
+        // Apply pagination
+        let total_count = ffi_results.len();
+        let offset = options.offset.unwrap_or(0).min(total_count);
+        let limit = options.limit.unwrap_or(50);
+        let has_more = total_count > offset + limit;
+        ffi_results.truncate(offset.saturating_add(limit));
+        ffi_results.drain(..offset);
+
         // Generate suggestions if no results found
         let suggestions = if ffi_results.is_empty() && !query.trim().is_empty() {
-            Self::generate_suggestions(&*search, &query, 5)?
+            Self::generate_suggestions(&search, &query, 5)
         } else {
             Vec::new()
         };
@@ -140,17 +127,17 @@ impl SearchOperations {
             Vec::new()
         };
 
-        let tag_suggestions = if partial_query.trim().len() >= 1 {
+        let tag_suggestions = if !partial_query.trim().is_empty() {
             search.get_tag_completions(&partial_query, max_suggestions as usize)
         } else {
             Vec::new()
         };
 
         let results = AutocompleteResults {
+            suggestion_count: suggestions.len() + tag_suggestions.len(),
             word_suggestions: suggestions,
             tag_suggestions,
             query: partial_query,
-            suggestion_count: suggestions.len() + tag_suggestions.len(),
         };
 
         bridge.record_metrics("search_with_autocomplete", start_time, results.suggestion_count as u32);
@@ -173,9 +160,9 @@ impl SearchOperations {
             message: "Failed to acquire graph lock".to_string(),
         })?;
 
-        let source_node = graph.get_node(id).map_err(|_| BridgeError::NodeNotFound {
-            id: node_id.clone(),
-        })?;
+        if !graph.contains_node(id) {
+            return Err(BridgeError::NodeNotFound { id: node_id });
+        }
 
         let search = bridge.search_engine.read().map_err(|_| BridgeError::GenericError {
             message: "Failed to acquire search lock".to_string(),
@@ -193,7 +180,7 @@ impl SearchOperations {
                 text: result.text,
                 similarity_score: result.score,
                 common_words: result.match_positions.len(),
-                relationship: Self::determine_relationship(&*graph, id, result.node_id),
+                relationship: Self::determine_relationship(&graph, id, result.node_id),
             })
             .collect();
 
@@ -217,7 +204,7 @@ impl SearchOperations {
             message: "Failed to acquire search lock".to_string(),
         })?;
 
-        let mut candidate_nodes: Vec<NodeId> = graph.get_all_nodes().keys().cloned().collect();
+        let mut candidate_nodes: Vec<NodeId> = graph.nodes().map(|node| node.id).collect();
 
         // Apply text filter
         if let Some(text_query) = filters.text_contains {
@@ -295,30 +282,15 @@ impl SearchOperations {
                     text: node.text.clone(),
                     score: 1.0, // All filtered results have equal score
                     match_positions: vec![],
+                    snippet: node.text.clone(),
+                    highlighted: node.text.clone(),
                 })
             })
             .collect();
 
         // Apply sorting and limits
         if let Some(sort_by) = filters.sort_by {
-            let sort_results: Vec<SearchResult> = results.iter()
-                .map(|r| SearchResult {
-                    node_id: bridge.parse_node_id(&r.node_id).unwrap_or_default(),
-                    text: r.text.clone(),
-                    score: r.score,
-                    match_positions: r.match_positions.clone(),
-                })
-                .collect();
-            let mut sort_results = sort_results;
-            Self::sort_results(&mut sort_results, sort_by);
-            results = sort_results.into_iter()
-                .map(|r| FfiSearchResult {
-                    node_id: r.node_id.to_string(),
-                    text: r.text,
-                    score: r.score,
-                    match_positions: r.match_positions,
-                })
-                .collect();
+            Self::sort_results(&mut results, sort_by);
         }
 
         if let Some(limit) = filters.limit {
@@ -340,21 +312,24 @@ impl SearchOperations {
             message: "Failed to acquire search lock".to_string(),
         })?;
 
-        let stats = search.get_statistics();
+        let total_nodes = search.get_stats().indexed_nodes;
+        let words = search.word_frequencies();
+        let tags = search.tag_frequencies();
+        let total_words: usize = words.values().sum();
 
         let search_stats = SearchStats {
-            total_indexed_nodes: stats.total_nodes,
-            total_words: stats.total_words,
-            unique_words: stats.unique_words,
-            total_tags: stats.total_tags,
-            unique_tags: stats.unique_tags,
-            average_words_per_node: if stats.total_nodes > 0 {
-                stats.total_words as f64 / stats.total_nodes as f64
+            total_indexed_nodes: total_nodes,
+            total_words,
+            unique_words: words.len(),
+            total_tags: tags.values().sum(),
+            unique_tags: tags.len(),
+            average_words_per_node: if total_nodes > 0 {
+                total_words as f64 / total_nodes as f64
             } else {
                 0.0
             },
-            most_common_words: stats.most_common_words.into_iter().take(10).collect(),
-            most_common_tags: stats.most_common_tags.into_iter().take(10).collect(),
+            most_common_words: Self::most_common(words, 10),
+            most_common_tags: Self::most_common(tags, 10),
         };
 
         bridge.record_metrics("get_search_stats", start_time, 1);
@@ -363,17 +338,47 @@ impl SearchOperations {
 
     // Helper methods
 
+    fn search_text(
+        search: &SearchEngine,
+        query: &str,
+        options: &SearchOptionsFFI,
+    ) -> Result<Vec<SearchResult>, BridgeError> {
+        let mut results = search.search(query)
+            .map_err(|e| BridgeError::SearchError {
+                message: format!("Search failed: {}", e),
+            })?;
+
+        let min_score = options.min_score.unwrap_or(0.1);
+        results.retain(|result| {
+            result.score >= min_score
+                && (!options.case_sensitive || result.text.contains(query))
+                && (!options.whole_words_only || Self::contains_whole_word(&result.text, query, options.case_sensitive))
+        });
+        Ok(results)
+    }
+
+    /// Whether `query` occurs in `text` without letters or digits on either side
+    fn contains_whole_word(text: &str, query: &str, case_sensitive: bool) -> bool {
+        let (text, query) = if case_sensitive {
+            (text.to_string(), query.to_string())
+        } else {
+            (text.to_lowercase(), query.to_lowercase())
+        };
+        text.match_indices(&query).any(|(start, matched)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + matched.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+    }
+
     fn search_combined(
         search: &SearchEngine,
         query: &str,
         tags: &[String],
-        options: &SearchOptions,
+        options: &SearchOptionsFFI,
     ) -> Result<Vec<SearchResult>, BridgeError> {
         // First get text search results
-        let text_results = search.search_with_options(query, options)
-            .map_err(|e| BridgeError::SearchError {
-                message: format!("Text search failed: {}", e),
-            })?;
+        let text_results = Self::search_text(search, query, options)?;
 
         // Then get tag search results
         let tag_results = search.search_by_tags(tags)
@@ -401,7 +406,7 @@ impl SearchOperations {
         Ok(combined_results)
     }
 
-    fn sort_results(results: &mut Vec<SearchResult>, sort_by: SortBy) {
+    fn sort_results(results: &mut [FfiSearchResult], sort_by: SortBy) {
         match sort_by {
             SortBy::Score => {
                 results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
@@ -415,19 +420,21 @@ impl SearchOperations {
         }
     }
 
-    fn generate_suggestions(
-        search: &SearchEngine,
-        query: &str,
-        max_suggestions: usize,
-    ) -> Result<Vec<String>, BridgeError> {
-        let words: Vec<&str> = query.split_whitespace().collect();
-        if let Some(last_word) = words.last() {
-            Ok(search.get_word_completions(last_word, max_suggestions))
-        } else {
-            Ok(Vec::new())
+    fn generate_suggestions(search: &SearchEngine, query: &str, max_suggestions: usize) -> Vec<String> {
+        match query.split_whitespace().last() {
+            Some(last_word) => search.get_word_completions(last_word, max_suggestions),
+            None => Vec::new(),
         }
     }
 
+    /// The `count` most frequent entries, ties broken alphabetically
+    fn most_common(frequencies: HashMap<String, usize>, count: usize) -> Vec<(String, usize)> {
+        let mut entries: Vec<(String, usize)> = frequencies.into_iter().collect();
+        entries.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        entries.truncate(count);
+        entries
+    }
+
     fn determine_relationship(graph: &crate::graph::Graph, source_id: NodeId, target_id: NodeId) -> NodeRelationship {
         if let (Some(source), Some(target)) = (graph.get_node(source_id), graph.get_node(target_id)) {
             if source.parent_id == Some(target_id) {
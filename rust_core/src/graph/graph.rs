@@ -3,10 +3,91 @@
 //! This module implements the main Graph struct that manages nodes and edges
 //! in a mindmap with validation and manipulation methods.
 
-use crate::models::{Node, Edge};
-use crate::types::{ids::{NodeId, EdgeId}, MindmapResult, MindmapError};
+use crate::models::{Attachment, Node, Edge, MetadataMergePolicy, TagDedupe, MAX_ATTACHMENT_SIZE};
+use crate::search::SearchIndex;
+use super::stats::StatsCache;
+use crate::types::{ids::{AttachmentId, NodeId, EdgeId}, MindmapResult, MindmapError, Timestamp};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Which kinds of damage `Graph::repair` should fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairOptions {
+    /// Turn nodes whose parent is missing into root nodes
+    pub fix_orphans: bool,
+    /// Remove edges whose endpoints are missing
+    pub remove_dangling_edges: bool,
+    /// Detach one node of every parent-link cycle so the hierarchy is a forest
+    pub break_cycles: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            fix_orphans: true,
+            remove_dangling_edges: true,
+            break_cycles: true,
+        }
+    }
+}
+
+/// What `Graph::repair` changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// Orphaned nodes that became root nodes
+    pub reparented_orphans: Vec<NodeId>,
+    /// Edges removed because an endpoint was missing
+    pub removed_edges: Vec<EdgeId>,
+    /// Nodes detached from their parent to break a cycle
+    pub broken_cycle_nodes: Vec<NodeId>,
+}
+
+impl RepairReport {
+    /// Whether the repair changed nothing
+    pub fn is_empty(&self) -> bool {
+        self.reparented_orphans.is_empty() && self.removed_edges.is_empty() && self.broken_cycle_nodes.is_empty()
+    }
+}
+
+/// How `Graph::merge_siblings` combines the two nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiblingMergePolicy {
+    /// Placed between the two texts; an empty text is dropped instead
+    pub text_separator: String,
+    /// How conflicting metadata values are resolved
+    pub metadata: MetadataMergePolicy,
+}
+
+impl Default for SiblingMergePolicy {
+    fn default() -> Self {
+        Self {
+            text_separator: " ".to_string(),
+            metadata: MetadataMergePolicy::default(),
+        }
+    }
+}
+
+impl SiblingMergePolicy {
+    /// Concatenate the surviving node's text with the merged node's
+    pub fn merge_text(&self, first: &str, second: &str) -> String {
+        match (first.is_empty(), second.is_empty()) {
+            (_, true) => first.to_string(),
+            (true, false) => second.to_string(),
+            (false, false) => format!("{}{}{}", first, self.text_separator, second),
+        }
+    }
+}
+
+/// Whether parent links in `parent_id` are mirrored by edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ParentEdgePolicy {
+    /// The hierarchy lives only in `parent_id`; edges are managed by the caller
+    #[default]
+    Implicit,
+    /// Every child gets exactly one parent→child edge, created, moved and
+    /// removed together with its parent link
+    Mirror,
+}
 
 /// Core graph structure for mindmap data
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -19,6 +100,36 @@ pub struct Graph {
     outgoing_edges: HashMap<NodeId, HashSet<EdgeId>>,
     /// Index of incoming edges for each node (node_id -> set of edge_ids)
     incoming_edges: HashMap<NodeId, HashSet<EdgeId>>,
+    /// How duplicate node tags are detected when nodes are added or updated
+    #[serde(default)]
+    tag_dedupe: TagDedupe,
+    /// Maximum number of children a node may have (unlimited when `None`)
+    #[serde(default)]
+    max_children_per_node: Option<usize>,
+    /// How `merge_siblings` combines text and metadata
+    #[serde(default)]
+    sibling_merge_policy: SiblingMergePolicy,
+    /// Whether adding or re-parenting a child also maintains its parent edge
+    #[serde(default)]
+    parent_edge_policy: ParentEdgePolicy,
+    /// Whether a search index is kept; only this setting is serialized
+    #[serde(default)]
+    search_index_enabled: bool,
+    /// Inverted index kept in sync with node changes, once built
+    #[serde(skip)]
+    search_index: Option<SearchIndex>,
+    /// Nodes handed out through `get_node_mut` that the index has not seen since
+    #[serde(skip)]
+    stale_search_nodes: HashSet<NodeId>,
+    /// Subtree sizes kept in sync with node changes, if enabled
+    #[serde(skip)]
+    stats_cache: Option<StatsCache>,
+    /// Contents of node attachments, stored out of line by the persistence layer
+    #[serde(skip)]
+    attachment_data: HashMap<AttachmentId, Vec<u8>>,
+    /// Child counts and sibling orders per parent, rebuilt on demand
+    #[serde(skip)]
+    child_index: ChildIndex,
 }
 
 impl Graph {
@@ -29,11 +140,273 @@ impl Graph {
             edges: HashMap::new(),
             outgoing_edges: HashMap::new(),
             incoming_edges: HashMap::new(),
+            tag_dedupe: TagDedupe::default(),
+            max_children_per_node: None,
+            sibling_merge_policy: SiblingMergePolicy::default(),
+            parent_edge_policy: ParentEdgePolicy::default(),
+            search_index_enabled: false,
+            search_index: None,
+            stale_search_nodes: HashSet::new(),
+            stats_cache: None,
+            attachment_data: HashMap::new(),
+            child_index: ChildIndex::default(),
+        }
+    }
+
+    /// Get the tag duplicate detection mode
+    pub fn tag_dedupe(&self) -> TagDedupe {
+        self.tag_dedupe
+    }
+
+    /// Set the tag duplicate detection mode used by subsequent node mutations
+    pub fn set_tag_dedupe(&mut self, dedupe: TagDedupe) {
+        self.tag_dedupe = dedupe;
+    }
+
+    /// Get the per-node child limit, `None` meaning unlimited
+    pub fn max_children_per_node(&self) -> Option<usize> {
+        self.max_children_per_node
+    }
+
+    /// Limit how many children a node may have
+    ///
+    /// Only enforced when nodes are added or re-parented; nodes already over
+    /// the limit are left alone.
+    pub fn set_max_children_per_node(&mut self, limit: Option<usize>) {
+        self.max_children_per_node = limit;
+    }
+
+    /// Get the policy used when merging sibling nodes
+    pub fn sibling_merge_policy(&self) -> &SiblingMergePolicy {
+        &self.sibling_merge_policy
+    }
+
+    /// Set the policy used by subsequent `merge_siblings` calls
+    pub fn set_sibling_merge_policy(&mut self, policy: SiblingMergePolicy) {
+        self.sibling_merge_policy = policy;
+    }
+
+    /// Get the parent edge policy
+    pub fn parent_edge_policy(&self) -> ParentEdgePolicy {
+        self.parent_edge_policy
+    }
+
+    /// Set whether parent links are mirrored by edges
+    ///
+    /// Only applies to nodes added or re-parented afterwards; existing nodes
+    /// keep the edges they have.
+    pub fn set_parent_edge_policy(&mut self, policy: ParentEdgePolicy) {
+        self.parent_edge_policy = policy;
+    }
+
+    /// Build a search index over all nodes and keep it updated from now on
+    ///
+    /// While enabled, `search` over the whole graph only scores nodes the
+    /// index reports as candidates. Does nothing if already enabled.
+    pub fn enable_search_index(&mut self) {
+        self.search_index_enabled = true;
+        self.refresh_search_index();
+    }
+
+    /// Drop the search index, going back to scanning every node on search
+    pub fn disable_search_index(&mut self) {
+        self.search_index_enabled = false;
+        self.search_index = None;
+        self.stale_search_nodes.clear();
+    }
+
+    /// Get the search index, if enabled and built
+    ///
+    /// The index is not serialized with the graph. A deserialized graph that
+    /// had it enabled scans every node on search until its next mutation or
+    /// `refresh_search_index` rebuilds it.
+    pub fn search_index(&self) -> Option<&SearchIndex> {
+        self.search_index.as_ref()
+    }
+
+    /// Nodes that may have changed since they were last indexed
+    ///
+    /// Search always scans these directly until the next mutation through the
+    /// graph or an explicit `refresh_search_index`.
+    pub fn stale_search_nodes(&self) -> &HashSet<NodeId> {
+        &self.stale_search_nodes
+    }
+
+    /// Re-index nodes that were modified through `get_node_mut`, building the
+    /// index first if it is enabled but missing
+    pub fn refresh_search_index(&mut self) {
+        if !self.search_index_enabled {
+            return;
+        }
+        let Some(index) = self.search_index.as_mut() else {
+            let mut index = SearchIndex::new();
+            index.rebuild_from_nodes(self.nodes.values());
+            self.search_index = Some(index);
+            self.stale_search_nodes.clear();
+            return;
+        };
+        for node_id in self.stale_search_nodes.drain() {
+            match self.nodes.get(&node_id) {
+                Some(node) => index.update_node(node),
+                None => index.remove_node(node_id),
+            }
+        }
+    }
+
+    /// Bring the search index up to date after `node_id` was added, changed or removed
+    fn sync_search_index(&mut self, node_id: NodeId) {
+        if !self.search_index_enabled {
+            return;
+        }
+        self.stale_search_nodes.insert(node_id);
+        self.refresh_search_index();
+    }
+
+    /// Compute subtree sizes once and keep them updated from now on
+    ///
+    /// Mutations through the graph adjust only the ancestors of the changed
+    /// node. Does nothing if already enabled.
+    pub fn enable_stats_cache(&mut self) {
+        if self.stats_cache.is_none() {
+            self.stats_cache = Some(StatsCache::build(self.nodes.values()));
+        }
+    }
+
+    /// Drop the statistics cache
+    pub fn disable_stats_cache(&mut self) {
+        self.stats_cache = None;
+    }
+
+    /// Get the statistics cache, if enabled
+    pub fn stats_cache(&self) -> Option<&StatsCache> {
+        self.stats_cache.as_ref()
+    }
+
+    /// Re-check the parents of nodes that were modified through `get_node_mut`
+    pub fn refresh_stats_cache(&mut self) {
+        let Some(cache) = self.stats_cache.as_mut() else {
+            return;
+        };
+        for node_id in cache.take_stale() {
+            if let Some(node) = self.nodes.get(&node_id) {
+                cache.set_parent(node_id, node.parent_id);
+            }
+        }
+    }
+
+    /// Bring the statistics cache up to date after `node_id` was added, moved or removed
+    fn sync_stats_cache(&mut self, node_id: NodeId) {
+        self.refresh_stats_cache();
+        let Some(cache) = self.stats_cache.as_mut() else {
+            return;
+        };
+        match self.nodes.get(&node_id) {
+            Some(node) => cache.insert(node_id, node.parent_id),
+            None => cache.remove(node_id),
         }
     }
 
+    /// Number of nodes in the subtree rooted at `node_id`, including itself
+    ///
+    /// Read from the statistics cache when enabled, otherwise counted by
+    /// walking the subtree. Returns `None` if the node doesn't exist.
+    pub fn subtree_size(&self, node_id: NodeId) -> Option<usize> {
+        if !self.nodes.contains_key(&node_id) {
+            return None;
+        }
+        if let Some(cache) = self.stats_cache.as_ref().filter(|cache| cache.stale_nodes().is_empty()) {
+            return cache.subtree_size(node_id);
+        }
+
+        let mut children: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+        for node in self.nodes.values() {
+            if let Some(parent_id) = node.parent_id {
+                children.entry(parent_id).or_default().insert(node.id);
+            }
+        }
+        super::stats::subtree_sizes(self.nodes.keys().copied(), &children).get(&node_id).copied()
+    }
+
+    /// Edges from `parent_id` to `child_id`
+    fn edges_between(&self, parent_id: NodeId, child_id: NodeId) -> Vec<EdgeId> {
+        self.incoming_edges.get(&child_id)
+            .into_iter()
+            .flatten()
+            .filter(|edge_id| self.edges.get(edge_id).is_some_and(|edge| edge.from_node == parent_id))
+            .copied()
+            .collect()
+    }
+
+    /// Keep the parent edge of `node_id` in line with its parent link
+    ///
+    /// With [`ParentEdgePolicy::Mirror`], an edge from the old parent is moved
+    /// to the new parent, keeping its style, and a missing edge is created.
+    fn sync_parent_edge(&mut self, node_id: NodeId, old_parent_id: Option<NodeId>) -> MindmapResult<()> {
+        if self.parent_edge_policy != ParentEdgePolicy::Mirror {
+            return Ok(());
+        }
+        let new_parent_id = self.nodes.get(&node_id).and_then(|node| node.parent_id);
+
+        let mut moved = None;
+        if let Some(old_parent_id) = old_parent_id.filter(|&id| Some(id) != new_parent_id) {
+            for edge_id in self.edges_between(old_parent_id, node_id) {
+                let edge = self.remove_edge(edge_id)?;
+                moved.get_or_insert(edge);
+            }
+        }
+
+        let Some(new_parent_id) = new_parent_id else {
+            return Ok(());
+        };
+        if !self.edges_between(new_parent_id, node_id).is_empty() {
+            return Ok(());
+        }
+        let edge = match moved {
+            Some(mut edge) => {
+                edge.from_node = new_parent_id;
+                edge.updated_at = chrono::Utc::now();
+                edge
+            }
+            None => Edge::new(new_parent_id, node_id),
+        };
+        self.add_edge(edge)?;
+        Ok(())
+    }
+
+    /// Check that `parent_id` can take `child_id` as one more child
+    ///
+    /// Relies on the child index, so callers sync it first.
+    fn check_child_capacity(&self, parent_id: NodeId, child_id: NodeId) -> MindmapResult<()> {
+        let Some(limit) = self.max_children_per_node else {
+            return Ok(());
+        };
+
+        let already_child = self.nodes.get(&child_id).is_some_and(|node| node.parent_id == Some(parent_id));
+        let existing = self.child_index.child_count(parent_id) - usize::from(already_child);
+        if existing >= limit {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Node {} already has the maximum of {} children", parent_id, limit)
+            });
+        }
+
+        Ok(())
+    }
+
     /// Add a node to the graph
+    ///
+    /// A new child is ordered after its existing siblings, whatever order it
+    /// carries.
     pub fn add_node(&mut self, node: Node) -> MindmapResult<NodeId> {
+        self.insert_node(node, true)
+    }
+
+    /// Add a node to the graph keeping its sibling order, e.g. when undo
+    /// restores a deleted node into its old place
+    pub(crate) fn restore_node(&mut self, node: Node) -> MindmapResult<NodeId> {
+        self.insert_node(node, false)
+    }
+
+    fn insert_node(&mut self, mut node: Node, append: bool) -> MindmapResult<NodeId> {
         // Validate the node
         node.validate().map_err(|msg| MindmapError::InvalidOperation { message: msg })?;
 
@@ -42,8 +415,16 @@ impl Graph {
             if !self.nodes.contains_key(&parent_id) {
                 return Err(MindmapError::NodeNotFound { id: parent_id });
             }
+            self.sync_child_index();
+            self.check_child_capacity(parent_id, node.id)?;
+            if append {
+                if let Some(order) = self.append_order(parent_id, &node) {
+                    node.order = order;
+                }
+            }
         }
 
+        node.dedupe_tags(self.tag_dedupe);
         let node_id = node.id;
 
         // Initialize edge indices for this node
@@ -51,15 +432,112 @@ impl Graph {
         self.incoming_edges.entry(node_id).or_insert_with(HashSet::new);
 
         // Insert the node
-        self.nodes.insert(node_id, node);
+        let old_parent_id = self.put_node(node).and_then(|existing| existing.parent_id);
+        self.sync_search_index(node_id);
+        self.sync_stats_cache(node_id);
+        self.sync_parent_edge(node_id, old_parent_id)?;
 
         Ok(node_id)
     }
 
+    /// Sibling order that puts a new child after its siblings
+    ///
+    /// Nodes already in the graph keep their order, as does a first child.
+    /// Relies on the child index, so callers sync it first.
+    fn append_order(&self, parent_id: NodeId, node: &Node) -> Option<i64> {
+        if self.nodes.contains_key(&node.id) {
+            return None;
+        }
+        self.child_index.last_order(parent_id).map(|last| last + 1)
+    }
+
+    /// Bring the child index up to date, building it if needed
+    fn sync_child_index(&mut self) {
+        if !self.child_index.built {
+            self.child_index = ChildIndex::build(self.nodes.values());
+            return;
+        }
+        for (node_id, (parent_id, order)) in self.child_index.take_stale() {
+            self.child_index.remove(parent_id, order);
+            if let Some(node) = self.nodes.get(&node_id) {
+                self.child_index.insert(node.parent_id, node.order);
+            }
+        }
+    }
+
+    /// Store a node, keeping the child index in step, and return the one it replaces
+    fn put_node(&mut self, node: Node) -> Option<Node> {
+        self.sync_child_index();
+        self.child_index.insert(node.parent_id, node.order);
+        let old = self.nodes.insert(node.id, node);
+        if let Some(old) = &old {
+            self.child_index.remove(old.parent_id, old.order);
+        }
+        old
+    }
+
+    /// Insert a node whose parent has already been checked by the caller
+    pub(crate) fn insert_validated_node(&mut self, mut node: Node) -> NodeId {
+        node.dedupe_tags(self.tag_dedupe);
+        let node_id = node.id;
+
+        self.outgoing_edges.entry(node_id).or_default();
+        self.incoming_edges.entry(node_id).or_default();
+        let old_parent_id = self.put_node(node).and_then(|existing| existing.parent_id);
+        self.sync_search_index(node_id);
+        self.sync_stats_cache(node_id);
+        // The parent exists, so creating its edge can't fail
+        let _ = self.sync_parent_edge(node_id, old_parent_id);
+
+        node_id
+    }
+
+    /// Attach file contents to a node
+    ///
+    /// The node records the attachment's name, MIME type and size, while the
+    /// bytes are kept beside the graph and saved out of line by the
+    /// persistence layer. Contents larger than [`MAX_ATTACHMENT_SIZE`] are
+    /// rejected with `AttachmentTooLarge`.
+    pub fn add_attachment(&mut self, node_id: NodeId, name: &str, data: Vec<u8>, mime: &str) -> MindmapResult<AttachmentId> {
+        let size = data.len() as u64;
+        if size > MAX_ATTACHMENT_SIZE {
+            return Err(MindmapError::AttachmentTooLarge { size, limit: MAX_ATTACHMENT_SIZE });
+        }
+        if name.trim().is_empty() {
+            return Err(MindmapError::InvalidOperation {
+                message: "Attachment name cannot be empty".to_string()
+            });
+        }
+        let node = self.nodes.get_mut(&node_id).ok_or(MindmapError::NodeNotFound { id: node_id })?;
+
+        let attachment_id = AttachmentId::new();
+        let mut attachment = Attachment::new(name, mime, size, format!("attachments/{}", attachment_id.as_uuid()));
+        attachment.id = attachment_id.as_uuid().to_string();
+        node.add_attachment(attachment);
+        self.attachment_data.insert(attachment_id, data);
+
+        Ok(attachment_id)
+    }
+
+    /// Get the contents of an attachment added to or loaded with this graph
+    ///
+    /// Contents stay available after their node is removed, so undo can
+    /// restore it; saving the graph drops them from storage.
+    pub fn get_attachment(&self, attachment_id: AttachmentId) -> Option<&[u8]> {
+        self.attachment_data.get(&attachment_id).map(Vec::as_slice)
+    }
+
+    /// Keep the contents of an attachment loaded from storage
+    pub(crate) fn insert_attachment_data(&mut self, attachment_id: AttachmentId, data: Vec<u8>) {
+        self.attachment_data.insert(attachment_id, data);
+    }
+
     /// Remove a node and all its associated edges
     pub fn remove_node(&mut self, node_id: NodeId) -> MindmapResult<Node> {
+        self.sync_child_index();
         let node = self.nodes.remove(&node_id)
             .ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        self.child_index.remove(node.parent_id, node.order);
 
         // Remove all edges connected to this node
         let outgoing = self.outgoing_edges.remove(&node_id).unwrap_or_default();
@@ -82,6 +560,8 @@ impl Graph {
             }
         }
 
+        self.sync_search_index(node_id);
+        self.sync_stats_cache(node_id);
         Ok(node)
     }
 
@@ -92,11 +572,20 @@ impl Graph {
 
     /// Get a mutable reference to a node by ID
     pub fn get_node_mut(&mut self, node_id: NodeId) -> Option<&mut Node> {
+        if self.search_index.is_some() && self.nodes.contains_key(&node_id) {
+            self.stale_search_nodes.insert(node_id);
+        }
+        if let Some(cache) = self.stats_cache.as_mut().filter(|_| self.nodes.contains_key(&node_id)) {
+            cache.mark_stale(node_id);
+        }
+        if let Some(node) = self.nodes.get(&node_id) {
+            self.child_index.mark_stale(node);
+        }
         self.nodes.get_mut(&node_id)
     }
 
     /// Update a node in the graph
-    pub fn update_node(&mut self, node: Node) -> MindmapResult<()> {
+    pub fn update_node(&mut self, mut node: Node) -> MindmapResult<()> {
         // Validate the node
         node.validate().map_err(|msg| MindmapError::InvalidOperation { message: msg })?;
 
@@ -110,10 +599,30 @@ impl Graph {
             if !self.nodes.contains_key(&parent_id) {
                 return Err(MindmapError::NodeNotFound { id: parent_id });
             }
+            self.sync_child_index();
+            self.check_child_capacity(parent_id, node.id)?;
         }
 
-        self.nodes.insert(node.id, node);
-        Ok(())
+        node.dedupe_tags(self.tag_dedupe);
+        let node_id = node.id;
+        let old_parent_id = self.put_node(node).and_then(|old| old.parent_id);
+        self.sync_search_index(node_id);
+        self.sync_stats_cache(node_id);
+        self.sync_parent_edge(node_id, old_parent_id)
+    }
+
+    /// Add a tag to a node using the graph's duplicate detection mode
+    ///
+    /// Returns true if the tag was added, false if an equivalent tag exists.
+    pub fn add_node_tag(&mut self, node_id: NodeId, tag: impl Into<String>) -> MindmapResult<bool> {
+        let dedupe = self.tag_dedupe;
+        let node = self.nodes.get_mut(&node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        let added = node.add_tag_with(tag, dedupe);
+        if added {
+            self.sync_search_index(node_id);
+        }
+        Ok(added)
     }
 
     /// Add an edge to the graph
@@ -131,6 +640,17 @@ impl Graph {
 
         let edge_id = edge.id;
 
+        // A restored parent edge replaces the one mirrored when its child was added
+        if self.parent_edge_policy == ParentEdgePolicy::Mirror
+            && self.nodes[&edge.to_node].parent_id == Some(edge.from_node)
+        {
+            for existing_id in self.edges_between(edge.from_node, edge.to_node) {
+                if existing_id != edge_id {
+                    self.remove_edge(existing_id)?;
+                }
+            }
+        }
+
         // Update edge indices
         self.outgoing_edges.entry(edge.from_node)
             .or_insert_with(HashSet::new)
@@ -171,6 +691,16 @@ impl Graph {
         self.nodes.values()
     }
 
+    /// Get all nodes modified after the given time
+    ///
+    /// A node counts as modified if its `updated_at` timestamp is later than `since`,
+    /// which includes nodes created after that time.
+    pub fn nodes_modified_since(&self, since: Timestamp) -> Vec<&Node> {
+        self.nodes.values()
+            .filter(|node| node.updated_at > since)
+            .collect()
+    }
+
     /// Get all edges in the graph
     pub fn edges(&self) -> impl Iterator<Item = &Edge> {
         self.edges.values()
@@ -196,11 +726,33 @@ impl Graph {
         self.edges.contains_key(&edge_id)
     }
 
-    /// Get all children of a node
+    /// Get all children of a node, ordered by their sibling order
+    ///
+    /// Siblings sharing an order fall back to creation time, then ID, so the
+    /// sequence is the same on every call.
     pub fn get_children(&self, node_id: NodeId) -> Vec<&Node> {
-        self.nodes.values()
+        let mut children: Vec<&Node> = self.nodes.values()
             .filter(|node| node.parent_id == Some(node_id))
-            .collect()
+            .collect();
+        children.sort_by_key(|node| sibling_key(node));
+        children
+    }
+
+    /// Children of every node with children, each list ordered as by [`get_children`](Self::get_children)
+    ///
+    /// Builds the whole index in one pass, for walks that would otherwise
+    /// scan all nodes once per visited node.
+    pub(crate) fn children_by_parent(&self) -> HashMap<NodeId, Vec<&Node>> {
+        let mut children: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        for node in self.nodes.values() {
+            if let Some(parent_id) = node.parent_id {
+                children.entry(parent_id).or_default().push(node);
+            }
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|node| sibling_key(node));
+        }
+        children
     }
 
     /// Get the parent of a node
@@ -284,6 +836,49 @@ impl Graph {
         false
     }
 
+    /// Find cycles in the parent-child hierarchy
+    ///
+    /// Each cycle lists its node IDs following parent links, starting from the
+    /// node with the smallest ID. A consistent graph has no cycles.
+    pub fn find_cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut ids: Vec<NodeId> = self.nodes.keys().copied().collect();
+        ids.sort_by_key(|id| id.as_uuid());
+
+        let mut finished: HashSet<NodeId> = HashSet::new();
+        let mut cycles = Vec::new();
+
+        for start in ids {
+            let mut path: Vec<NodeId> = Vec::new();
+            let mut on_path: HashSet<NodeId> = HashSet::new();
+            let mut current = Some(start);
+
+            // Walk up until reaching a root, a dangling parent or an explored node
+            while let Some(node_id) = current {
+                if finished.contains(&node_id) {
+                    break;
+                }
+                if !on_path.insert(node_id) {
+                    let cycle_start = path.iter().position(|&id| id == node_id).unwrap_or(0);
+                    let mut cycle = path[cycle_start..].to_vec();
+                    let smallest = cycle
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, id)| id.as_uuid())
+                        .map_or(0, |(index, _)| index);
+                    cycle.rotate_left(smallest);
+                    cycles.push(cycle);
+                    break;
+                }
+                path.push(node_id);
+                current = self.nodes.get(&node_id).and_then(|node| node.parent_id);
+            }
+
+            finished.extend(path);
+        }
+
+        cycles
+    }
+
     /// Validate the entire graph for consistency
     pub fn validate(&self) -> MindmapResult<()> {
         // Validate all nodes
@@ -298,6 +893,14 @@ impl Graph {
             }
         }
 
+        // Parent links must form a forest
+        if let Some(cycle) = self.find_cycles().first() {
+            let ids: Vec<String> = cycle.iter().map(|id| id.to_string()).collect();
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Cycle in parent hierarchy: {}", ids.join(" -> "))
+            });
+        }
+
         // Validate all edges
         for edge in self.edges.values() {
             edge.validate().map_err(|msg| MindmapError::InvalidOperation { message: msg })?;
@@ -339,12 +942,96 @@ impl Graph {
         Ok(())
     }
 
+    /// Find nodes whose parent does not exist, sorted by ID
+    pub fn find_orphans(&self) -> Vec<NodeId> {
+        let mut orphans: Vec<NodeId> = self
+            .nodes
+            .values()
+            .filter(|node| node.parent_id.is_some_and(|parent_id| !self.nodes.contains_key(&parent_id)))
+            .map(|node| node.id)
+            .collect();
+        orphans.sort_by_key(|id| id.as_uuid());
+        orphans
+    }
+
+    /// Find edges with a missing endpoint, sorted by ID
+    pub fn find_dangling_edges(&self) -> Vec<EdgeId> {
+        let mut dangling: Vec<EdgeId> = self
+            .edges
+            .values()
+            .filter(|edge| !self.nodes.contains_key(&edge.from_node) || !self.nodes.contains_key(&edge.to_node))
+            .map(|edge| edge.id)
+            .collect();
+        dangling.sort_by_key(|id| id.as_uuid());
+        dangling
+    }
+
+    /// Fix the structural problems reported by `validate`
+    ///
+    /// Only the kinds of damage enabled in `options` are touched. Cycles are
+    /// broken by detaching the node with the smallest ID in each cycle, which
+    /// keeps repairs deterministic.
+    pub fn repair(&mut self, options: &RepairOptions) -> RepairReport {
+        let mut report = RepairReport::default();
+
+        if options.remove_dangling_edges {
+            for edge_id in self.find_dangling_edges() {
+                if let Some(edge) = self.edges.remove(&edge_id) {
+                    if let Some(outgoing) = self.outgoing_edges.get_mut(&edge.from_node) {
+                        outgoing.remove(&edge_id);
+                    }
+                    if let Some(incoming) = self.incoming_edges.get_mut(&edge.to_node) {
+                        incoming.remove(&edge_id);
+                    }
+                }
+                report.removed_edges.push(edge_id);
+            }
+
+            // Drop index entries left behind for missing nodes or edges
+            let nodes = &self.nodes;
+            let edges = &self.edges;
+            for index in [&mut self.outgoing_edges, &mut self.incoming_edges] {
+                index.retain(|node_id, _| nodes.contains_key(node_id));
+                for edge_ids in index.values_mut() {
+                    edge_ids.retain(|edge_id| edges.contains_key(edge_id));
+                }
+            }
+        }
+
+        if options.fix_orphans {
+            report.reparented_orphans = self.find_orphans();
+        }
+        if options.break_cycles {
+            report.broken_cycle_nodes = self.find_cycles().into_iter().filter_map(|cycle| cycle.first().copied()).collect();
+        }
+
+        for &node_id in report.reparented_orphans.iter().chain(&report.broken_cycle_nodes) {
+            if let Some(node) = self.nodes.get_mut(&node_id) {
+                self.child_index.mark_stale(node);
+                node.parent_id = None;
+                node.updated_at = chrono::Utc::now();
+            }
+            self.sync_stats_cache(node_id);
+        }
+
+        report
+    }
+
     /// Clear all nodes and edges from the graph
     pub fn clear(&mut self) {
         self.nodes.clear();
         self.edges.clear();
         self.outgoing_edges.clear();
         self.incoming_edges.clear();
+        if let Some(index) = self.search_index.as_mut() {
+            index.clear();
+        }
+        self.stale_search_nodes.clear();
+        if let Some(cache) = self.stats_cache.as_mut() {
+            cache.recompute([]);
+        }
+        self.attachment_data.clear();
+        self.child_index = ChildIndex::default();
     }
 
     /// Check if the graph is empty
@@ -353,6 +1040,96 @@ impl Graph {
     }
 }
 
+/// Number of children and their sibling orders under each parent
+///
+/// Lets adding a child find its capacity and order without scanning every
+/// node. The index mirrors the nodes, so it is not serialized and is built
+/// from them on first use; nodes handed out through `Graph::get_node_mut`
+/// are re-indexed on the next sync.
+#[derive(Debug, Clone, Default)]
+struct ChildIndex {
+    /// Whether the index has been built from the graph's nodes
+    built: bool,
+    /// How many children each parent has with each order
+    orders: HashMap<NodeId, BTreeMap<i64, usize>>,
+    /// Number of children of each parent
+    counts: HashMap<NodeId, usize>,
+    /// Nodes that may have changed, with the parent and order they are indexed under
+    stale: HashMap<NodeId, (Option<NodeId>, i64)>,
+}
+
+impl ChildIndex {
+    fn build<'a>(nodes: impl Iterator<Item = &'a Node>) -> Self {
+        let mut index = Self { built: true, ..Self::default() };
+        for node in nodes {
+            index.insert(node.parent_id, node.order);
+        }
+        index
+    }
+
+    fn insert(&mut self, parent_id: Option<NodeId>, order: i64) {
+        let Some(parent_id) = parent_id else {
+            return;
+        };
+        *self.orders.entry(parent_id).or_default().entry(order).or_default() += 1;
+        *self.counts.entry(parent_id).or_default() += 1;
+    }
+
+    fn remove(&mut self, parent_id: Option<NodeId>, order: i64) {
+        let Some(parent_id) = parent_id else {
+            return;
+        };
+        if let Some(orders) = self.orders.get_mut(&parent_id) {
+            if let Some(count) = orders.get_mut(&order) {
+                *count -= 1;
+                if *count == 0 {
+                    orders.remove(&order);
+                }
+            }
+            if orders.is_empty() {
+                self.orders.remove(&parent_id);
+            }
+        }
+        if let Some(count) = self.counts.get_mut(&parent_id) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&parent_id);
+            }
+        }
+    }
+
+    fn child_count(&self, parent_id: NodeId) -> usize {
+        self.counts.get(&parent_id).copied().unwrap_or(0)
+    }
+
+    fn last_order(&self, parent_id: NodeId) -> Option<i64> {
+        self.orders.get(&parent_id).and_then(|orders| orders.keys().next_back().copied())
+    }
+
+    /// Remember where a node is indexed before it may be changed in place
+    fn mark_stale(&mut self, node: &Node) {
+        if self.built {
+            self.stale.entry(node.id).or_insert((node.parent_id, node.order));
+        }
+    }
+
+    fn take_stale(&mut self) -> HashMap<NodeId, (Option<NodeId>, i64)> {
+        std::mem::take(&mut self.stale)
+    }
+}
+
+impl PartialEq for ChildIndex {
+    /// The index only mirrors the nodes, which the graphs compare themselves
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Sort key that orders siblings deterministically
+pub(crate) fn sibling_key(node: &Node) -> (i64, Timestamp, uuid::Uuid) {
+    (node.order, node.created_at, node.id.as_uuid())
+}
+
 impl Default for Graph {
     fn default() -> Self {
         Self::new()
@@ -541,4 +1318,317 @@ mod tests {
         graph.clear();
         assert!(graph.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_nodes_modified_since() {
+        let mut graph = Graph::new();
+
+        let old = Node::new("Old");
+        let old_id = old.id;
+        graph.add_node(old).unwrap();
+        graph.add_node(Node::new("Untouched")).unwrap();
+
+        let marker = chrono::Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        graph.get_node_mut(old_id).unwrap().set_text("Changed");
+        let new = Node::new("New");
+        let new_id = new.id;
+        graph.add_node(new).unwrap();
+
+        let mut modified: Vec<NodeId> = graph.nodes_modified_since(marker).iter().map(|n| n.id).collect();
+        modified.sort_by_key(|id| id.to_string());
+        let mut expected = vec![old_id, new_id];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(modified, expected);
+    }
+
+    #[test]
+    fn test_tag_dedupe_on_add_and_update() {
+        let mut graph = Graph::new();
+        let mut node = Node::new("Tagged");
+        node.tags = vec!["AI".into(), "ml".into(), "ai".into()];
+        let node_id = graph.add_node(node).unwrap();
+        assert_eq!(graph.get_node(node_id).unwrap().tags, vec!["AI".to_string(), "ml".to_string()]);
+
+        assert!(!graph.add_node_tag(node_id, "ML").unwrap());
+        assert!(graph.add_node_tag(node_id, "new").unwrap());
+        assert_eq!(graph.get_node(node_id).unwrap().tags.len(), 3);
+
+        graph.set_tag_dedupe(TagDedupe::CaseSensitive);
+        assert!(graph.add_node_tag(node_id, "ML").unwrap());
+
+        let mut updated = graph.get_node(node_id).unwrap().clone();
+        updated.tags.push("new".into());
+        updated.tags.push("NEW".into());
+        graph.update_node(updated).unwrap();
+        assert_eq!(graph.get_node(node_id).unwrap().tags, vec!["AI", "ml", "new", "ML", "NEW"]);
+
+        assert!(graph.add_node_tag(NodeId::new(), "x").is_err());
+    }
+
+    #[test]
+    fn test_parent_cycle_detection() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let a_id = graph.add_node(Node::new_child(root_id, "A")).unwrap();
+        let b_id = graph.add_node(Node::new_child(a_id, "B")).unwrap();
+        let c_id = graph.add_node(Node::new_child(b_id, "C")).unwrap();
+        graph.add_node(Node::new_child(c_id, "Tail")).unwrap();
+        assert!(graph.find_cycles().is_empty());
+        assert!(graph.validate().is_ok());
+
+        // Close the loop A -> B -> C -> A through a direct update
+        let mut a = graph.get_node(a_id).unwrap().clone();
+        a.parent_id = Some(c_id);
+        graph.update_node(a).unwrap();
+
+        let cycles = graph.find_cycles();
+        assert_eq!(cycles.len(), 1);
+        let mut cycle = cycles[0].clone();
+        assert_eq!(cycle.len(), 3);
+        cycle.sort_by_key(|id| id.as_uuid());
+        let mut expected = vec![a_id, b_id, c_id];
+        expected.sort_by_key(|id| id.as_uuid());
+        assert_eq!(cycle, expected);
+
+        match graph.validate() {
+            Err(MindmapError::InvalidOperation { message }) => {
+                for id in [a_id, b_id, c_id] {
+                    assert!(message.contains(&id.to_string()));
+                }
+                assert!(!message.contains(&root_id.to_string()));
+            }
+            other => panic!("expected cycle error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_repair_fixes_orphans_dangling_edges_and_cycles() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let a_id = graph.add_node(Node::new_child(root_id, "A")).unwrap();
+        let b_id = graph.add_node(Node::new_child(a_id, "B")).unwrap();
+        let kept_edge = graph.add_edge(Edge::new(root_id, b_id)).unwrap();
+        assert!(graph.repair(&RepairOptions::default()).is_empty());
+
+        // Damage the graph the way a corrupt file could
+        let missing = NodeId::new();
+        let dangling = Edge::new(a_id, missing);
+        let dangling_id = dangling.id;
+        graph.outgoing_edges.get_mut(&a_id).unwrap().insert(dangling_id);
+        graph.edges.insert(dangling_id, dangling);
+        let orphan = Node::new_child(missing, "Orphan");
+        let orphan_id = orphan.id;
+        graph.nodes.insert(orphan_id, orphan);
+        graph.nodes.get_mut(&a_id).unwrap().parent_id = Some(b_id);
+        assert!(graph.validate().is_err());
+        assert_eq!(graph.find_orphans(), vec![orphan_id]);
+        assert_eq!(graph.find_dangling_edges(), vec![dangling_id]);
+
+        // Only the requested repairs run
+        let report = graph.repair(&RepairOptions { fix_orphans: false, break_cycles: false, ..RepairOptions::default() });
+        assert_eq!(report.removed_edges, vec![dangling_id]);
+        assert!(report.reparented_orphans.is_empty());
+        assert!(graph.validate().is_err());
+
+        let report = graph.repair(&RepairOptions::default());
+        assert_eq!(report.reparented_orphans, vec![orphan_id]);
+        let expected_detached = if a_id.as_uuid() < b_id.as_uuid() { a_id } else { b_id };
+        assert_eq!(report.broken_cycle_nodes, vec![expected_detached]);
+        assert!(graph.validate().is_ok());
+        assert!(graph.contains_edge(kept_edge));
+        assert!(!graph.contains_edge(dangling_id));
+        assert!(graph.get_node(orphan_id).unwrap().parent_id.is_none());
+    }
+
+    fn assert_stats_match_recomputation(graph: &Graph) {
+        let cache = graph.stats_cache().unwrap();
+        let mut fresh = graph.clone();
+        fresh.disable_stats_cache();
+        assert_eq!(cache.node_count(), graph.node_count());
+        for node in graph.nodes() {
+            assert_eq!(cache.subtree_size(node.id), fresh.subtree_size(node.id), "subtree of {}", node.text);
+        }
+    }
+
+    #[test]
+    fn test_stats_cache_follows_mutations() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let a_id = graph.add_node(Node::new_child(root_id, "A")).unwrap();
+        let b_id = graph.add_node(Node::new_child(root_id, "B")).unwrap();
+        let a1_id = graph.add_node(Node::new_child(a_id, "A1")).unwrap();
+        graph.enable_stats_cache();
+        assert_eq!(graph.subtree_size(root_id), Some(4));
+
+        // Adding under a grandchild updates every ancestor
+        let a1x_id = graph.add_node(Node::new_child(a1_id, "A1x")).unwrap();
+        graph.add_node(Node::new_child(b_id, "B1")).unwrap();
+        assert_eq!(graph.subtree_size(root_id), Some(6));
+        assert_eq!(graph.subtree_size(a_id), Some(3));
+        assert_stats_match_recomputation(&graph);
+
+        // Moving a subtree with update_node shifts its size between branches
+        let mut a1 = graph.get_node(a1_id).unwrap().clone();
+        a1.parent_id = Some(b_id);
+        graph.update_node(a1).unwrap();
+        assert_eq!(graph.subtree_size(a_id), Some(1));
+        assert_eq!(graph.subtree_size(b_id), Some(4));
+        assert_stats_match_recomputation(&graph);
+
+        // Moves through get_node_mut are picked up on refresh
+        graph.get_node_mut(a1_id).unwrap().parent_id = Some(a_id);
+        graph.refresh_stats_cache();
+        assert_eq!(graph.subtree_size(a_id), Some(3));
+        assert_stats_match_recomputation(&graph);
+
+        // Removing a node drops its subtree from the ancestors; undo restores it
+        let removed = graph.remove_node(a1_id).unwrap();
+        assert_eq!(graph.subtree_size(root_id), Some(4));
+        assert_eq!(graph.subtree_size(a1x_id), Some(1));
+        assert_stats_match_recomputation(&graph);
+        graph.add_node(removed).unwrap();
+        assert_eq!(graph.subtree_size(root_id), Some(6));
+        assert_stats_match_recomputation(&graph);
+
+        graph.remove_node(b_id).unwrap();
+        assert_stats_match_recomputation(&graph);
+
+        // Only enabling the cache computed sizes from scratch
+        assert_eq!(graph.stats_cache().unwrap().full_recomputes(), 1);
+    }
+
+    #[test]
+    fn test_stats_cache_reads_do_not_recompute() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        for i in 0..50 {
+            graph.add_node(Node::new_child(root_id, format!("Child {}", i))).unwrap();
+        }
+        graph.enable_stats_cache();
+
+        for _ in 0..100 {
+            assert_eq!(graph.subtree_size(root_id), Some(51));
+            assert_eq!(graph.stats_cache().unwrap().descendant_count(root_id), Some(50));
+        }
+        assert_eq!(graph.stats_cache().unwrap().full_recomputes(), 1);
+    }
+
+    #[test]
+    fn test_mirrored_parent_edges() {
+        let mut graph = Graph::new();
+        graph.set_parent_edge_policy(ParentEdgePolicy::Mirror);
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let other_id = graph.add_node(Node::new("Other")).unwrap();
+        let child_ids: Vec<NodeId> = (0..3)
+            .map(|i| graph.add_node(Node::new_child(root_id, format!("Child {}", i))).unwrap())
+            .collect();
+
+        // One parent edge per child
+        assert_eq!(graph.edge_count(), 3);
+        for &child_id in &child_ids {
+            let incoming = graph.get_incoming_edges(child_id);
+            assert_eq!(incoming.len(), 1);
+            assert_eq!(incoming[0].from_node, root_id);
+        }
+
+        // Removing a child removes its edge
+        graph.remove_node(child_ids[0]).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.get_outgoing_edges(root_id).len(), 2);
+
+        // Re-parenting moves the edge, keeping its ID
+        let edge_id = graph.get_incoming_edges(child_ids[1])[0].id;
+        let mut child = graph.get_node(child_ids[1]).unwrap().clone();
+        child.parent_id = Some(other_id);
+        graph.update_node(child).unwrap();
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert_eq!((edge.from_node, edge.to_node), (other_id, child_ids[1]));
+        assert_eq!(graph.edge_count(), 2);
+
+        // Undoing a removal restores the original edge instead of duplicating it
+        let edge = edge.clone();
+        let node = graph.remove_node(child_ids[1]).unwrap();
+        graph.add_node(node).unwrap();
+        graph.add_edge(edge).unwrap();
+        let incoming = graph.get_incoming_edges(child_ids[1]);
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].id, edge_id);
+    }
+
+    #[test]
+    fn test_child_index_follows_in_place_changes() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(Node::new("A")).unwrap();
+        let b = graph.add_node(Node::new("B")).unwrap();
+        let first = graph.add_node(Node::new_child(a, "First")).unwrap();
+        graph.add_node(Node::new_child(a, "Second")).unwrap();
+        graph.set_max_children_per_node(Some(2));
+        assert!(graph.add_node(Node::new_child(a, "Third")).is_err());
+
+        // Moving a child behind the graph's back frees its slot under the old parent
+        graph.get_node_mut(first).unwrap().parent_id = Some(b);
+        let third = graph.add_node(Node::new_child(a, "Third")).unwrap();
+        assert_eq!(graph.get_node(third).unwrap().order, 2);
+        let b_child = graph.add_node(Node::new_child(b, "Next")).unwrap();
+        assert_eq!(graph.get_node(b_child).unwrap().order, 1);
+
+        // A deserialized graph builds its index before the first change
+        let mut restored: Graph = serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+        assert_eq!(restored, graph);
+        assert!(restored.add_node(Node::new_child(b, "Overflow")).is_err());
+        restored.remove_node(third).unwrap();
+        let last = restored.add_node(Node::new_child(a, "Last")).unwrap();
+        assert_eq!(restored.get_node(last).unwrap().order, 2);
+    }
+
+    #[test]
+    fn test_implicit_parent_edges_by_default() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Child")).unwrap();
+        assert_eq!(graph.parent_edge_policy(), ParentEdgePolicy::Implicit);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_add_and_get_attachment() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("Report")).unwrap();
+
+        let data = b"%PDF-1.7 quarterly numbers".to_vec();
+        let attachment_id = graph.add_attachment(node_id, "q3.pdf", data.clone(), "application/pdf").unwrap();
+        assert_eq!(graph.get_attachment(attachment_id), Some(data.as_slice()));
+
+        let attachment = &graph.get_node(node_id).unwrap().attachments[0];
+        assert_eq!(attachment.attachment_id(), Some(attachment_id));
+        assert_eq!(attachment.size, data.len() as u64);
+        assert!(attachment.is_document());
+
+        assert!(graph.get_attachment(AttachmentId::new()).is_none());
+        assert!(matches!(
+            graph.add_attachment(NodeId::new(), "q3.pdf", data, "application/pdf"),
+            Err(MindmapError::NodeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_attachment_size_limit() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("Video")).unwrap();
+
+        let oversized = vec![0u8; MAX_ATTACHMENT_SIZE as usize + 1];
+        let error = graph.add_attachment(node_id, "talk.mp4", oversized, "video/mp4").unwrap_err();
+        assert!(matches!(
+            error,
+            MindmapError::AttachmentTooLarge { size, limit } if size == MAX_ATTACHMENT_SIZE + 1 && limit == MAX_ATTACHMENT_SIZE
+        ));
+        assert!(error.to_string().contains("limit"));
+        assert!(graph.get_node(node_id).unwrap().attachments.is_empty());
+
+        // Exactly at the limit is accepted
+        let at_limit = vec![0u8; MAX_ATTACHMENT_SIZE as usize];
+        assert!(graph.add_attachment(node_id, "talk.mp4", at_limit, "video/mp4").is_ok());
+    }
+}
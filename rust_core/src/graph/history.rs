@@ -0,0 +1,310 @@
+//! Operation log for graph edits
+//!
+//! This module records graph mutations so they can be replayed for undo or
+//! synchronization. Retention is bounded by an estimated memory budget rather
+//! than a plain entry count, so long editing sessions cannot grow the log
+//! without limit.
+
+use crate::graph::Graph;
+use crate::models::{Edge, Node};
+use crate::types::MindmapResult;
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+/// A reversible graph edit, as recorded by an [`OperationLog`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoCommand {
+    /// A node was added
+    AddNode(Node),
+    /// A node was removed together with its incident edges
+    RemoveNode { node: Box<Node>, edges: Vec<Edge> },
+    /// A node was replaced with a new version, e.g. new text or position
+    UpdateNode { before: Box<Node>, after: Box<Node> },
+    /// An edge was added
+    AddEdge(Edge),
+    /// An edge was removed
+    RemoveEdge(Edge),
+    /// Several edits made as one, undone and redone together
+    Batch(Vec<UndoCommand>),
+}
+
+impl UndoCommand {
+    /// Perform the edit on the graph
+    pub fn apply(&self, graph: &mut Graph) -> MindmapResult<()> {
+        match self {
+            UndoCommand::AddNode(node) => graph.restore_node(node.clone()).map(|_| ()),
+            UndoCommand::RemoveNode { node, .. } => graph.remove_node(node.id).map(|_| ()),
+            UndoCommand::UpdateNode { after, .. } => graph.update_node(after.as_ref().clone()),
+            UndoCommand::AddEdge(edge) => graph.add_edge(edge.clone()).map(|_| ()),
+            UndoCommand::RemoveEdge(edge) => graph.remove_edge(edge.id).map(|_| ()),
+            UndoCommand::Batch(commands) => commands.iter().try_for_each(|command| command.apply(graph)),
+        }
+    }
+
+    /// Reverse the edit on the graph
+    pub fn revert(&self, graph: &mut Graph) -> MindmapResult<()> {
+        match self {
+            UndoCommand::AddNode(node) => graph.remove_node(node.id).map(|_| ()),
+            UndoCommand::RemoveNode { node, edges } => {
+                graph.restore_node(node.as_ref().clone())?;
+                for edge in edges {
+                    graph.add_edge(edge.clone())?;
+                }
+                Ok(())
+            }
+            UndoCommand::UpdateNode { before, .. } => graph.update_node(before.as_ref().clone()),
+            UndoCommand::AddEdge(edge) => graph.remove_edge(edge.id).map(|_| ()),
+            UndoCommand::RemoveEdge(edge) => graph.add_edge(edge.clone()).map(|_| ()),
+            UndoCommand::Batch(commands) => commands.iter().rev().try_for_each(|command| command.revert(graph)),
+        }
+    }
+
+    /// Estimate the heap and inline memory used by this command in bytes
+    pub fn estimated_size(&self) -> usize {
+        let payload = match self {
+            UndoCommand::AddNode(node) => estimate_node_size(node),
+            UndoCommand::RemoveNode { node, edges } => {
+                size_of::<Node>()
+                    + estimate_node_size(node)
+                    + edges.iter().map(|edge| size_of::<Edge>() + estimate_edge_size(edge)).sum::<usize>()
+            }
+            UndoCommand::UpdateNode { before, after } => {
+                2 * size_of::<Node>() + estimate_node_size(before) + estimate_node_size(after)
+            }
+            UndoCommand::AddEdge(edge) | UndoCommand::RemoveEdge(edge) => estimate_edge_size(edge),
+            UndoCommand::Batch(commands) => commands.iter().map(UndoCommand::estimated_size).sum(),
+        };
+
+        size_of::<UndoCommand>() + payload
+    }
+}
+
+/// Estimate the heap memory owned by a node
+fn estimate_node_size(node: &Node) -> usize {
+    let tags: usize = node.tags.iter().map(|t| size_of::<String>() + t.len()).sum();
+    let metadata: usize = node
+        .metadata
+        .iter()
+        .map(|(k, v)| 2 * size_of::<String>() + k.len() + v.len())
+        .sum();
+    let attachments: usize = node
+        .attachments
+        .iter()
+        .map(|a| {
+            size_of::<crate::models::Attachment>()
+                + a.id.len()
+                + a.filename.len()
+                + a.mime_type.len()
+                + a.path.len()
+        })
+        .sum();
+
+    node.text.len() + tags + metadata + attachments
+}
+
+/// Estimate the heap memory owned by an edge
+fn estimate_edge_size(edge: &Edge) -> usize {
+    edge.label.as_ref().map_or(0, |label| label.len())
+}
+
+/// Retention policy for an operation log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogRetention {
+    /// Maximum estimated memory in bytes the log may hold
+    pub max_bytes: usize,
+    /// Number of most recent entries that are always kept, even over budget
+    pub min_entries: usize,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_bytes: 8 * 1024 * 1024,
+            min_entries: 32,
+        }
+    }
+}
+
+/// Log of graph edits with memory-aware trimming
+///
+/// Backs the undo history of [`UndoStack`](crate::graph::UndoStack).
+#[derive(Debug, Clone, Default)]
+pub struct OperationLog {
+    /// Recorded operations with their estimated sizes, oldest first
+    entries: VecDeque<(UndoCommand, usize)>,
+    /// Sum of the estimated sizes of all entries
+    total_bytes: usize,
+    /// Retention policy applied after every push
+    retention: LogRetention,
+}
+
+impl OperationLog {
+    /// Create an empty log with the default retention policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty log with the given retention policy
+    pub fn with_retention(retention: LogRetention) -> Self {
+        Self {
+            retention,
+            ..Self::default()
+        }
+    }
+
+    /// Record an operation, evicting the oldest entries if over budget
+    pub fn push(&mut self, operation: UndoCommand) {
+        let size = operation.estimated_size();
+        self.total_bytes += size;
+        self.entries.push_back((operation, size));
+        self.trim();
+    }
+
+    /// Remove and return the most recent operation
+    pub fn pop(&mut self) -> Option<UndoCommand> {
+        let (operation, size) = self.entries.pop_back()?;
+        self.total_bytes -= size;
+        Some(operation)
+    }
+
+    /// Remove and return the oldest operation
+    pub fn pop_oldest(&mut self) -> Option<UndoCommand> {
+        let (operation, size) = self.entries.pop_front()?;
+        self.total_bytes -= size;
+        Some(operation)
+    }
+
+    /// Get the most recent operation without removing it
+    pub fn last(&self) -> Option<&UndoCommand> {
+        self.entries.back().map(|(operation, _)| operation)
+    }
+
+    /// Iterate over recorded operations, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &UndoCommand> {
+        self.entries.iter().map(|(operation, _)| operation)
+    }
+
+    /// Number of recorded operations
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the log is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Estimated memory held by the log in bytes
+    pub fn estimated_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    /// Get the current retention policy
+    pub fn retention(&self) -> LogRetention {
+        self.retention
+    }
+
+    /// Change the retention policy and trim immediately
+    pub fn set_retention(&mut self, retention: LogRetention) {
+        self.retention = retention;
+        self.trim();
+    }
+
+    /// Remove all recorded operations
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+
+    /// Evict oldest entries until the log fits its budget
+    fn trim(&mut self) {
+        while self.total_bytes > self.retention.max_bytes
+            && self.entries.len() > self.retention.min_entries
+        {
+            self.pop_oldest();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn large_node(index: usize) -> Node {
+        let mut node = Node::new(format!("{} {}", index, "x".repeat(1000)));
+        node.set_metadata("index", index.to_string());
+        node
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut log = OperationLog::new();
+        let node = Node::new("Test");
+
+        log.push(UndoCommand::AddNode(node.clone()));
+        assert_eq!(log.len(), 1);
+        assert!(log.estimated_bytes() > 0);
+
+        assert_eq!(log.pop(), Some(UndoCommand::AddNode(node)));
+        assert!(log.is_empty());
+        assert_eq!(log.estimated_bytes(), 0);
+    }
+
+    #[test]
+    fn test_trims_to_memory_budget() {
+        let retention = LogRetention {
+            max_bytes: 20 * 1024,
+            min_entries: 4,
+        };
+        let mut log = OperationLog::with_retention(retention);
+
+        for i in 0..500 {
+            log.push(UndoCommand::AddNode(large_node(i)));
+            assert!(log.estimated_bytes() <= retention.max_bytes);
+        }
+
+        assert!(log.len() < 500);
+        assert!(log.len() >= retention.min_entries);
+
+        // The retained entries must be the most recent ones, in order
+        let indices: Vec<usize> = log
+            .iter()
+            .filter_map(|op| match op {
+                UndoCommand::AddNode(node) => node.get_metadata("index")?.parse().ok(),
+                _ => None,
+            })
+            .collect();
+        let first = 500 - log.len();
+        assert_eq!(indices, (first..500).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_min_entries_kept_over_budget() {
+        let mut log = OperationLog::with_retention(LogRetention {
+            max_bytes: 1,
+            min_entries: 3,
+        });
+
+        for i in 0..10 {
+            log.push(UndoCommand::AddNode(large_node(i)));
+        }
+
+        assert_eq!(log.len(), 3);
+        assert!(log.estimated_bytes() > 1);
+    }
+
+    #[test]
+    fn test_set_retention_trims_immediately() {
+        let mut log = OperationLog::new();
+        for i in 0..50 {
+            log.push(UndoCommand::AddNode(large_node(i)));
+        }
+        assert_eq!(log.len(), 50);
+
+        log.set_retention(LogRetention {
+            max_bytes: 0,
+            min_entries: 5,
+        });
+        assert_eq!(log.len(), 5);
+    }
+}
@@ -6,7 +6,13 @@
 pub mod graph;
 pub mod traversal;
 pub mod operations;
+pub mod history;
+pub mod serialization;
+pub mod stats;
 
 pub use graph::*;
 pub use traversal::*;
-pub use operations::*;
\ No newline at end of file
+pub use operations::*;
+pub use history::*;
+pub use serialization::*;
+pub use stats::StatsCache;
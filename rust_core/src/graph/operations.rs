@@ -4,9 +4,14 @@
 //! including relationship management, validation, and batch operations.
 
 use crate::graph::Graph;
-use crate::models::{Node, Edge};
-use crate::types::{ids::{NodeId, EdgeId}, MindmapResult, MindmapError, Point};
-use std::collections::{HashMap, HashSet};
+use crate::graph::graph::sibling_key;
+use crate::graph::history::{LogRetention, OperationLog, UndoCommand};
+use crate::models::{Document, Node, NodePatch, Edge, MetadataMergePolicy};
+use crate::types::{ids::{AttachmentId, NodeId, EdgeId}, MindmapResult, MindmapError, Point};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 
 /// Batch operation result
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +22,150 @@ pub struct BatchResult<T> {
     pub failures: Vec<(T, String)>,
 }
 
+/// What a cascading delete would remove
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteImpact {
+    /// Number of nodes that would be removed, including the target
+    pub nodes_removed: usize,
+    /// Number of edges that would be removed
+    pub edges_removed: usize,
+    /// IDs of the nodes that would be removed, target first
+    pub node_ids: Vec<NodeId>,
+}
+
+/// A copied subtree, ready to be pasted elsewhere
+///
+/// Holds snapshots of the nodes, root first and every parent before its
+/// children, plus the edges that connect two nodes of the subtree and the
+/// contents of the nodes' attachments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtreeClipboard {
+    /// Copied nodes, root first
+    pub nodes: Vec<Node>,
+    /// Edges between copied nodes
+    pub edges: Vec<Edge>,
+    /// Contents of the copied nodes' attachments
+    #[serde(skip)]
+    pub attachment_data: HashMap<AttachmentId, Vec<u8>>,
+}
+
+impl SubtreeClipboard {
+    /// ID of the copied root node
+    pub fn root_id(&self) -> Option<NodeId> {
+        self.nodes.first().map(|node| node.id)
+    }
+
+    /// Number of copied nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether nothing was copied
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Recovery policy when a document's root node is missing from its graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MissingRootPolicy {
+    /// Fail with `NodeNotFound` for the missing root
+    #[default]
+    Error,
+    /// Use the oldest parentless node in the graph as the new root
+    PickAvailableRoot,
+    /// Add a fresh root node titled after the document
+    CreateRoot,
+    /// Leave the document without a root, see [`Document::clear_root_node`]
+    Clear,
+}
+
+/// Field used to sort sibling nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    /// Node text, compared case-insensitively
+    Text,
+    /// Creation time
+    CreatedAt,
+    /// Last modification time
+    UpdatedAt,
+    /// Value of a metadata key; nodes without the key always sort last
+    Metadata(String),
+}
+
+impl SortKey {
+    /// Compare two nodes by this key in ascending order
+    fn compare(&self, a: &Node, b: &Node) -> Ordering {
+        match self {
+            SortKey::Text => a.text.to_lowercase().cmp(&b.text.to_lowercase())
+                .then_with(|| a.text.cmp(&b.text)),
+            SortKey::CreatedAt => a.created_at.cmp(&b.created_at),
+            SortKey::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            SortKey::Metadata(key) => match (a.get_metadata(key), b.get_metadata(key)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+}
+
+/// Policy deciding when two nodes are considered the same node
+///
+/// Used by merge conflict detection and duplicate detection so importers can
+/// dedupe by content when node IDs differ.
+#[derive(Clone, Default)]
+pub enum NodeIdentity {
+    /// Nodes are the same if they share an ID
+    #[default]
+    Id,
+    /// Nodes are the same if their text matches after trimming, lowercasing
+    /// and collapsing whitespace
+    NormalizedText,
+    /// Nodes are the same if the given function produces the same key
+    Custom(Arc<dyn Fn(&Node) -> String + Send + Sync>),
+}
+
+impl NodeIdentity {
+    /// Create a custom identity policy from a key function
+    pub fn custom(key_fn: impl Fn(&Node) -> String + Send + Sync + 'static) -> Self {
+        NodeIdentity::Custom(Arc::new(key_fn))
+    }
+
+    /// Compute the identity key of a node under this policy
+    pub fn key(&self, node: &Node) -> String {
+        match self {
+            NodeIdentity::Id => node.id.to_string(),
+            NodeIdentity::NormalizedText => normalize_text(&node.text),
+            NodeIdentity::Custom(key_fn) => key_fn(node),
+        }
+    }
+
+    /// Check whether two nodes are the same under this policy
+    pub fn same(&self, a: &Node, b: &Node) -> bool {
+        self.key(a) == self.key(b)
+    }
+}
+
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeIdentity::Id => write!(f, "Id"),
+            NodeIdentity::NormalizedText => write!(f, "NormalizedText"),
+            NodeIdentity::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Normalize text for identity comparison
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Operations for node management
 impl Graph {
     /// Add a node with position and parent validation
@@ -60,6 +209,18 @@ impl Graph {
         self.update_node(node)
     }
 
+    /// Apply a set of field changes to a node
+    ///
+    /// The patched node is validated as a whole, so an invalid change (e.g.
+    /// empty text) leaves the node untouched.
+    pub fn patch_node(&mut self, node_id: NodeId, patch: NodePatch) -> MindmapResult<()> {
+        let mut node = self.get_node(node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?
+            .clone();
+        node.apply_patch(patch);
+        self.update_node(node)
+    }
+
     /// Delete a node and handle dependent relationships
     pub fn delete_node_with_cleanup(&mut self, node_id: NodeId) -> MindmapResult<Node> {
         // Check if node exists
@@ -67,36 +228,98 @@ impl Graph {
             return Err(MindmapError::NodeNotFound { id: node_id });
         }
 
-        // Get all children of this node
-        let children: Vec<_> = self.get_children(node_id).into_iter().map(|n| n.id).collect();
-
-        // Handle orphaned children by either removing them or reparenting
-        for child_id in children {
-            // Option 1: Remove child nodes (cascade delete)
-            self.delete_node_with_cleanup(child_id)?;
-
-            // Option 2: Reparent to grandparent (uncomment if preferred)
-            // if let Some(grandparent_id) = self.get_parent(node_id).map(|n| n.id) {
-            //     if let Some(mut child) = self.get_node_mut(child_id) {
-            //         child.parent_id = Some(grandparent_id);
-            //         child.updated_at = chrono::Utc::now();
-            //     }
-            // } else {
-            //     // Make orphaned children root nodes
-            //     if let Some(mut child) = self.get_node_mut(child_id) {
-            //         child.parent_id = None;
-            //         child.updated_at = chrono::Utc::now();
-            //     }
-            // }
+        // Children are removed along with the node (cascade delete).
+        // Descendants come in depth-first pre-order; removing them in reverse
+        // deletes every child before its parent without recursing
+        for descendant_id in self.get_descendants(node_id).into_iter().rev() {
+            self.remove_node(descendant_id)?;
         }
 
         // Remove the node (this also removes all connected edges)
         self.remove_node(node_id)
     }
 
+    /// Remove a node but keep its children, promoting them to its parent
+    ///
+    /// The children take the removed node's place among its siblings, in
+    /// their current order; if the node was a root they become roots. Edges
+    /// from the node to its children move to its parent, and its other edges
+    /// are removed with it. Returns the removed node.
+    pub fn remove_node_reparent_children(&mut self, node_id: NodeId) -> MindmapResult<Node> {
+        let node = self.get_node(node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?
+            .clone();
+        let children: Vec<Node> = self.get_children(node_id).into_iter().cloned().collect();
+
+        if let (Some(parent_id), Some(limit)) = (node.parent_id, self.max_children_per_node()) {
+            if self.get_children(parent_id).len() - 1 + children.len() > limit {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Node {} would exceed the maximum of {} children", parent_id, limit)
+                });
+            }
+        }
+
+        // Detach the node so it no longer counts against its parent's capacity
+        if node.parent_id.is_some() {
+            let mut detached = node.clone();
+            detached.parent_id = None;
+            self.update_node(detached)?;
+        }
+
+        // Make room for the children after the node's earlier siblings
+        if children.len() > 1 {
+            let shift = children.len() as i64 - 1;
+            let later_siblings: Vec<NodeId> = self.nodes()
+                .filter(|n| n.parent_id == node.parent_id && n.id != node_id && n.order > node.order)
+                .map(|n| n.id)
+                .collect();
+            for sibling_id in later_siblings {
+                if let Some(sibling) = self.get_node_mut(sibling_id) {
+                    sibling.set_order(sibling.order + shift);
+                }
+            }
+        }
+
+        for (index, child) in children.into_iter().enumerate() {
+            self.reparent(child, node.parent_id, node.order + index as i64)?;
+        }
+
+        self.remove_node(node_id)
+    }
+
+    /// Compute what `delete_node_with_cleanup` would remove without modifying the graph
+    pub fn delete_preview(&self, node_id: NodeId) -> MindmapResult<DeleteImpact> {
+        if !self.contains_node(node_id) {
+            return Err(MindmapError::NodeNotFound { id: node_id });
+        }
+
+        let mut node_ids = vec![node_id];
+        node_ids.extend(self.get_descendants(node_id));
+
+        let edges: HashSet<EdgeId> = node_ids.iter()
+            .flat_map(|&id| {
+                self.get_outgoing_edges(id)
+                    .into_iter()
+                    .chain(self.get_incoming_edges(id))
+                    .map(|edge| edge.id)
+            })
+            .collect();
+
+        Ok(DeleteImpact {
+            nodes_removed: node_ids.len(),
+            edges_removed: edges.len(),
+            node_ids,
+        })
+    }
+
     /// Move a node to a new parent
+    ///
+    /// Descendants keep pointing at their own parents, so only the moved node
+    /// changes, and its parent-child edge follows it. The node is appended
+    /// after the new parent's existing children; with `None` it becomes a
+    /// root. Moving a node under itself or one of its descendants is rejected.
     pub fn move_node(&mut self, node_id: NodeId, new_parent_id: Option<NodeId>) -> MindmapResult<()> {
-        let mut node = self.get_node(node_id)
+        let node = self.get_node(node_id)
             .ok_or(MindmapError::NodeNotFound { id: node_id })?
             .clone();
 
@@ -106,11 +329,300 @@ impl Graph {
             self.validate_no_circular_dependency(node_id, parent_id)?;
         }
 
-        // Update parent
-        node.parent_id = new_parent_id;
-        node.updated_at = chrono::Utc::now();
+        let order = match new_parent_id {
+            Some(parent_id) => self.get_children(parent_id).last().map(|last| last.order),
+            None => self.get_root_nodes().iter().map(|root| root.order).max(),
+        }
+        .map_or(0, |last| last + 1);
 
-        self.update_node(node)
+        self.reparent(node, new_parent_id, order)
+    }
+
+    /// Move a node and its whole subtree under a new parent
+    ///
+    /// Same as [`move_node`](Self::move_node), which already carries the
+    /// descendants along.
+    pub fn move_subtree(&mut self, node_id: NodeId, new_parent: Option<NodeId>) -> MindmapResult<()> {
+        self.move_node(node_id, new_parent)
+    }
+
+    /// Merge one node into another, e.g. when collapsing duplicates
+    ///
+    /// Metadata is combined under `policy` and tags are unioned. The merged
+    /// node's children and edges move to the kept node before it is removed.
+    /// Nothing changes if the policy rejects a metadata conflict or the kept
+    /// node would end up with more children than allowed.
+    pub fn merge_nodes(&mut self, keep_id: NodeId, merge_id: NodeId, policy: MetadataMergePolicy) -> MindmapResult<()> {
+        if keep_id == merge_id {
+            return Err(MindmapError::InvalidOperation {
+                message: "Cannot merge a node into itself".to_string()
+            });
+        }
+        let merged = self.get_node(merge_id)
+            .ok_or(MindmapError::NodeNotFound { id: merge_id })?
+            .clone();
+        let mut kept = self.get_node(keep_id)
+            .ok_or(MindmapError::NodeNotFound { id: keep_id })?
+            .clone();
+        if self.is_descendant(keep_id, merge_id) {
+            return Err(MindmapError::InvalidOperation {
+                message: "Cannot merge a node into one of its descendants".to_string()
+            });
+        }
+
+        // Check everything that could fail before changing anything
+        kept.merge_metadata_from(&merged, policy)
+            .map_err(|message| MindmapError::InvalidOperation { message })?;
+        let children: Vec<NodeId> = self.get_children(merge_id).iter().map(|child| child.id).collect();
+        if let Some(limit) = self.max_children_per_node() {
+            let kept_children = self.get_children(keep_id).iter().filter(|child| child.id != merge_id).count();
+            if kept_children + children.len() > limit {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Node {} would exceed the maximum of {} children", keep_id, limit)
+                });
+            }
+        }
+
+        for tag in &merged.tags {
+            kept.add_tag_with(tag.clone(), self.tag_dedupe());
+        }
+        self.update_node(kept)?;
+
+        // Children and connections of the merged node now belong to the kept node.
+        // Detach the merged node first so its slot under the kept node is free.
+        if merged.parent_id == Some(keep_id) {
+            self.move_subtree(merge_id, None)?;
+        }
+        for child_id in children {
+            self.move_subtree(child_id, Some(keep_id))?;
+        }
+
+        let edges: Vec<Edge> = self.get_outgoing_edges(merge_id)
+            .into_iter()
+            .chain(self.get_incoming_edges(merge_id))
+            .cloned()
+            .collect();
+        self.remove_node(merge_id)?;
+
+        for mut edge in edges {
+            if edge.from_node == merge_id {
+                edge.from_node = keep_id;
+            }
+            if edge.to_node == merge_id {
+                edge.to_node = keep_id;
+            }
+            if edge.from_node == edge.to_node
+                || self.contains_edge(edge.id)
+                || self.has_edge_between(edge.from_node, edge.to_node)
+            {
+                continue;
+            }
+            self.add_edge(edge)?;
+        }
+
+        Ok(())
+    }
+
+    /// Combine two sibling nodes into `a`
+    ///
+    /// Text and metadata are merged under the graph's sibling merge policy
+    /// and tags are unioned. `b`'s children move under `a`, its edges are
+    /// redirected to `a`, and `b` is removed. Nothing changes if the nodes
+    /// are not siblings, the metadata conflicts are rejected, or `a` would
+    /// end up with more children than allowed.
+    pub fn merge_siblings(&mut self, a: NodeId, b: NodeId) -> MindmapResult<()> {
+        if a == b {
+            return Err(MindmapError::InvalidOperation {
+                message: "Cannot merge a node into itself".to_string()
+            });
+        }
+        let first = self.get_node(a)
+            .ok_or(MindmapError::NodeNotFound { id: a })?
+            .clone();
+        let second = self.get_node(b)
+            .ok_or(MindmapError::NodeNotFound { id: b })?
+            .clone();
+        if first.parent_id != second.parent_id {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Nodes {} and {} are not siblings", a, b)
+            });
+        }
+
+        // Check everything that could fail before changing anything
+        let policy = self.sibling_merge_policy().clone();
+        policy.metadata.merge(&first.metadata, &second.metadata)
+            .map_err(|message| MindmapError::InvalidOperation { message })?;
+        if let Some(limit) = self.max_children_per_node() {
+            if self.get_children(a).len() + self.get_children(b).len() > limit {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Node {} would exceed the maximum of {} children", a, limit)
+                });
+            }
+        }
+
+        let text = policy.merge_text(&first.text, &second.text);
+        if text != first.text {
+            let mut survivor = first;
+            survivor.set_text(text);
+            self.update_node(survivor)?;
+        }
+
+        self.merge_nodes(a, b, policy.metadata)
+    }
+
+    /// Sort the children of a node and persist the new sibling order
+    ///
+    /// Children that compare equal keep their current relative order.
+    pub fn sort_children(&mut self, parent_id: NodeId, key: SortKey, ascending: bool) -> MindmapResult<()> {
+        if !self.contains_node(parent_id) {
+            return Err(MindmapError::NodeNotFound { id: parent_id });
+        }
+
+        let mut children: Vec<&Node> = self.get_children(parent_id);
+        children.sort_by(|a, b| {
+            let ordering = key.compare(a, b);
+            // Missing metadata stays last in both directions
+            let missing_a = matches!(&key, SortKey::Metadata(k) if a.get_metadata(k).is_none());
+            let missing_b = matches!(&key, SortKey::Metadata(k) if b.get_metadata(k).is_none());
+            if ascending || missing_a || missing_b {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+
+        let sorted: Vec<NodeId> = children.into_iter().map(|node| node.id).collect();
+        for (index, child_id) in sorted.into_iter().enumerate() {
+            if let Some(child) = self.get_node_mut(child_id) {
+                child.set_order(index as i64);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move a child to `new_index` among its siblings and persist the new order
+    ///
+    /// Indices past the last sibling move the child to the end.
+    pub fn reorder_child(&mut self, parent_id: NodeId, node_id: NodeId, new_index: usize) -> MindmapResult<()> {
+        if !self.contains_node(parent_id) {
+            return Err(MindmapError::NodeNotFound { id: parent_id });
+        }
+        let node = self.get_node(node_id).ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        if node.parent_id != Some(parent_id) {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Node {} is not a child of {}", node_id, parent_id)
+            });
+        }
+
+        let mut siblings: Vec<NodeId> = self.get_children(parent_id)
+            .into_iter()
+            .map(|child| child.id)
+            .filter(|&id| id != node_id)
+            .collect();
+        siblings.insert(new_index.min(siblings.len()), node_id);
+
+        for (index, child_id) in siblings.into_iter().enumerate() {
+            if let Some(child) = self.get_node_mut(child_id) {
+                child.set_order(index as i64);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make a node the last child of its previous sibling (outline "indent")
+    pub fn demote_node(&mut self, node_id: NodeId) -> MindmapResult<()> {
+        let node = self.get_node(node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?
+            .clone();
+
+        let siblings: Vec<NodeId> = match node.parent_id {
+            Some(parent_id) => self.get_children(parent_id).into_iter().map(|n| n.id).collect(),
+            None => {
+                let mut roots: Vec<&Node> = self.get_root_nodes();
+                roots.sort_by_key(|n| sibling_key(n));
+                roots.into_iter().map(|n| n.id).collect()
+            }
+        };
+
+        let index = siblings.iter().position(|&id| id == node_id).unwrap_or(0);
+        if index == 0 {
+            return Err(MindmapError::InvalidOperation {
+                message: "Node has no previous sibling to demote under".to_string()
+            });
+        }
+        let new_parent_id = siblings[index - 1];
+
+        let order = self.get_children(new_parent_id)
+            .last()
+            .map_or(0, |last| last.order + 1);
+        self.reparent(node, Some(new_parent_id), order)
+    }
+
+    /// Make a node the next sibling of its parent (outline "outdent")
+    pub fn promote_node(&mut self, node_id: NodeId) -> MindmapResult<()> {
+        let node = self.get_node(node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?
+            .clone();
+
+        let parent = node.parent_id
+            .and_then(|parent_id| self.get_node(parent_id))
+            .cloned()
+            .ok_or_else(|| MindmapError::InvalidOperation {
+                message: "Root node cannot be promoted".to_string()
+            })?;
+
+        // Siblings to shift are picked before the move, which may still be rejected
+        let order = parent.order + 1;
+        let later_siblings: Vec<Node> = self.nodes()
+            .filter(|n| n.parent_id == parent.parent_id && n.id != parent.id && n.order >= order)
+            .cloned()
+            .collect();
+        self.reparent(node, parent.parent_id, order)?;
+
+        // Make room directly after the former parent
+        for mut sibling in later_siblings {
+            sibling.set_order(sibling.order + 1);
+            self.update_node(sibling)?;
+        }
+
+        Ok(())
+    }
+
+    /// Make sure a document's root node exists in this graph
+    ///
+    /// If the root is missing (e.g. after corruption or a partial load) it is
+    /// repaired according to `policy`. Returns the ID of the valid root, or
+    /// `None` if the document has no root.
+    pub fn resolve_document_root(&mut self, document: &mut Document, policy: MissingRootPolicy) -> MindmapResult<Option<NodeId>> {
+        let root_id = document.get_root_node();
+        if self.contains_node(root_id) {
+            return Ok(Some(root_id));
+        }
+        if !document.has_root_node() {
+            return Ok(None);
+        }
+
+        let new_root = match policy {
+            MissingRootPolicy::Error => return Err(MindmapError::NodeNotFound { id: root_id }),
+            MissingRootPolicy::PickAvailableRoot => self.get_root_nodes()
+                .into_iter()
+                .min_by(|a, b| a.created_at.cmp(&b.created_at)
+                    .then_with(|| a.id.to_string().cmp(&b.id.to_string())))
+                .map(|node| node.id)
+                .ok_or(MindmapError::NodeNotFound { id: root_id })?,
+            MissingRootPolicy::CreateRoot => self.add_node(Node::new(document.title.clone()))?,
+            MissingRootPolicy::Clear => {
+                log::warn!("Document root {:#} is missing; clearing it", root_id);
+                document.clear_root_node();
+                return Ok(None);
+            }
+        };
+
+        log::warn!("Document root {:#} is missing; using {:#} instead", root_id, new_root);
+        document.set_root_node(new_root);
+        Ok(Some(new_root))
     }
 
     /// Batch add multiple nodes
@@ -129,6 +641,89 @@ impl Graph {
         BatchResult { successes, failures }
     }
 
+    /// Add many nodes at once, all or nothing
+    ///
+    /// The whole batch is validated up front: every node must be valid, IDs
+    /// must be new and every parent must exist either in the graph or in the
+    /// batch. Nodes are then inserted parents first, in any input order. If
+    /// anything is wrong nothing is added and the error names the offending
+    /// node. Returns the node IDs in insertion order.
+    pub fn add_nodes_bulk(&mut self, nodes: Vec<Node>) -> MindmapResult<Vec<NodeId>> {
+        let mut batch_index: HashMap<NodeId, usize> = HashMap::with_capacity(nodes.len());
+        for (index, node) in nodes.iter().enumerate() {
+            node.validate().map_err(|msg| MindmapError::InvalidOperation {
+                message: format!("Invalid node {}: {}", node.id, msg)
+            })?;
+            if self.contains_node(node.id) || batch_index.insert(node.id, index).is_some() {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Duplicate node ID {}", node.id)
+                });
+            }
+        }
+
+        // Group nodes under their parent within the batch
+        let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut ready: Vec<usize> = Vec::new();
+        for (index, node) in nodes.iter().enumerate() {
+            match node.parent_id {
+                Some(parent_id) => match batch_index.get(&parent_id) {
+                    Some(&parent_index) => children.entry(parent_index).or_default().push(index),
+                    None if self.contains_node(parent_id) => ready.push(index),
+                    None => {
+                        return Err(MindmapError::InvalidOperation {
+                            message: format!("Node {} references missing parent {}", node.id, parent_id)
+                        });
+                    }
+                },
+                None => ready.push(index),
+            }
+        }
+
+        if let Some(limit) = self.max_children_per_node() {
+            let mut child_counts: HashMap<NodeId, usize> = HashMap::new();
+            for parent_id in nodes.iter().filter_map(|node| node.parent_id) {
+                *child_counts.entry(parent_id).or_default() += 1;
+            }
+            for (parent_id, added) in child_counts {
+                if self.get_children(parent_id).len() + added > limit {
+                    return Err(MindmapError::InvalidOperation {
+                        message: format!("Node {} would exceed the maximum of {} children", parent_id, limit)
+                    });
+                }
+            }
+        }
+
+        // Breadth-first from nodes whose parents already exist
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut next = 0;
+        order.extend(ready);
+        while next < order.len() {
+            if let Some(child_indices) = children.remove(&order[next]) {
+                order.extend(child_indices);
+            }
+            next += 1;
+        }
+
+        // Anything left over hangs off a parent cycle inside the batch
+        if order.len() < nodes.len() {
+            let mut placed = vec![false; nodes.len()];
+            for &index in &order {
+                placed[index] = true;
+            }
+            let stuck = nodes.iter().zip(placed).find(|(_, placed)| !placed).map(|(node, _)| node.id);
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Node {} is part of a parent cycle", stuck.unwrap_or_default())
+            });
+        }
+
+        let mut slots: Vec<Option<Node>> = nodes.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|index| slots[index].take())
+            .map(|node| self.insert_validated_node(node))
+            .collect())
+    }
+
     /// Batch update multiple nodes
     pub fn update_nodes_batch(&mut self, nodes: Vec<Node>) -> BatchResult<NodeId> {
         let mut successes = Vec::new();
@@ -174,6 +769,40 @@ impl Graph {
         Ok(())
     }
 
+    /// Attach a node to a new parent at the given sibling order
+    ///
+    /// An edge linking the old parent to the node is moved to the new parent,
+    /// or dropped when the node becomes a root.
+    fn reparent(&mut self, mut node: Node, new_parent_id: Option<NodeId>, order: i64) -> MindmapResult<()> {
+        let node_id = node.id;
+        let old_parent_id = node.parent_id;
+
+        node.parent_id = new_parent_id;
+        node.order = order;
+        node.updated_at = chrono::Utc::now();
+        self.update_node(node)?;
+
+        let Some(old_parent_id) = old_parent_id else {
+            return Ok(());
+        };
+        let parent_edges: Vec<EdgeId> = self.get_incoming_edges(node_id)
+            .into_iter()
+            .filter(|edge| edge.from_node == old_parent_id)
+            .map(|edge| edge.id)
+            .collect();
+
+        for edge_id in parent_edges {
+            let mut edge = self.remove_edge(edge_id)?;
+            if let Some(parent_id) = new_parent_id {
+                edge.from_node = parent_id;
+                edge.updated_at = chrono::Utc::now();
+                self.add_edge(edge)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate position conflicts within same parent group
     fn validate_position_conflicts(&self, parent_id: NodeId, position: &Point) -> MindmapResult<()> {
         let children = self.get_children(parent_id);
@@ -273,6 +902,58 @@ impl Graph {
         BatchResult { successes, failures }
     }
 
+    /// Split an edge A→B by inserting `new_node` so that A→C→B
+    ///
+    /// The original edge is removed and replaced by A→C and C→B. Both keep its
+    /// style, the label stays on the first segment, and a weight is halved
+    /// between them so the path cost from A to B is unchanged. When the edge
+    /// mirrors a parent-child link, C also takes B's place in the hierarchy
+    /// and B becomes C's child.
+    ///
+    /// Returns the IDs of the A→C and C→B edges.
+    pub fn insert_on_edge(&mut self, edge_id: EdgeId, mut new_node: Node) -> MindmapResult<(EdgeId, EdgeId)> {
+        let edge = self.get_edge(edge_id)
+            .ok_or(MindmapError::EdgeNotFound { id: edge_id })?
+            .clone();
+        let new_id = new_node.id;
+        if self.contains_node(new_id) {
+            return Err(MindmapError::InvalidOperation {
+                message: "Node inserted on an edge must not already be in the graph".to_string(),
+            });
+        }
+
+        let target = self.get_node(edge.to_node)
+            .ok_or(MindmapError::NodeNotFound { id: edge.to_node })?;
+        let splits_parent_link = target.parent_id == Some(edge.from_node);
+        if splits_parent_link {
+            // Take the target's place among its siblings
+            new_node.parent_id = Some(edge.from_node);
+            new_node.order = target.order;
+            self.restore_node(new_node)?;
+        } else {
+            self.add_node(new_node)?;
+        }
+        if splits_parent_link {
+            if let Err(e) = self.move_node(edge.to_node, Some(new_id)) {
+                self.remove_node(new_id)?;
+                return Err(e);
+            }
+        }
+
+        self.remove_edge(edge_id)?;
+
+        let weight = edge.weight.map(|weight| weight / 2.0);
+        let mut incoming = Edge::new(edge.from_node, new_id);
+        incoming.label = edge.label;
+        incoming.style = edge.style.clone();
+        incoming.weight = weight;
+        let mut outgoing = Edge::new(new_id, edge.to_node);
+        outgoing.style = edge.style;
+        outgoing.weight = weight;
+
+        Ok((self.add_edge(incoming)?, self.add_edge(outgoing)?))
+    }
+
     /// Remove all edges between two nodes
     pub fn remove_edges_between(&mut self, node1: NodeId, node2: NodeId) -> MindmapResult<Vec<Edge>> {
         let mut removed_edges = Vec::new();
@@ -294,6 +975,46 @@ impl Graph {
     }
 }
 
+/// Copy of `edge` with a fresh ID between the nodes its endpoints were copied to
+///
+/// Returns `None` unless both endpoints appear in `id_mapping`.
+fn remap_edge(edge: &Edge, id_mapping: &HashMap<NodeId, NodeId>) -> Option<Edge> {
+    let mut new_edge = edge.clone();
+    new_edge.id = EdgeId::new();
+    new_edge.from_node = *id_mapping.get(&edge.from_node)?;
+    new_edge.to_node = *id_mapping.get(&edge.to_node)?;
+    Some(new_edge)
+}
+
+/// Give the attachments of a copied node fresh IDs
+///
+/// Attachments whose contents `contents` finds under their original ID get a
+/// new ID, and the contents are returned under it, so the copy never shares
+/// stored contents with the original. Attachments without contents only
+/// record a file path and are kept as they are.
+fn reassign_attachments<'a>(
+    node: &mut Node,
+    contents: impl Fn(AttachmentId) -> Option<&'a [u8]>,
+) -> Vec<(AttachmentId, Vec<u8>)> {
+    let mut copied = Vec::new();
+    for attachment in &mut node.attachments {
+        let Some(old_id) = attachment.attachment_id() else {
+            continue;
+        };
+        let Some(data) = contents(old_id) else {
+            continue;
+        };
+
+        let new_id = AttachmentId::new();
+        if attachment.path == format!("attachments/{}", old_id.as_uuid()) {
+            attachment.path = format!("attachments/{}", new_id.as_uuid());
+        }
+        attachment.id = new_id.as_uuid().to_string();
+        copied.push((new_id, data.to_vec()));
+    }
+    copied
+}
+
 /// Advanced graph operations
 impl Graph {
     /// Clone a subgraph starting from a node
@@ -302,82 +1023,143 @@ impl Graph {
         let mut visited = HashSet::new();
         let mut id_mapping = HashMap::new(); // old_id -> new_id
 
-        self.clone_subgraph_recursive(
-            root_id,
-            &mut new_graph,
-            &mut visited,
-            &mut id_mapping,
-            0,
-            max_depth,
-        )?;
+        // Depth-first with an explicit stack, so deep hierarchies don't overflow
+        let children_map = self.children_by_parent();
+        let mut stack = vec![(root_id, 0)];
+        while let Some((node_id, current_depth)) = stack.pop() {
+            if visited.contains(&node_id) {
+                continue;
+            }
 
-        // Add edges between cloned nodes
-        for edge in self.edges() {
-            if let (Some(&new_from), Some(&new_to)) = (
-                id_mapping.get(&edge.from_node),
-                id_mapping.get(&edge.to_node),
-            ) {
-                let mut new_edge = edge.clone();
-                new_edge.id = EdgeId::new();
-                new_edge.from_node = new_from;
-                new_edge.to_node = new_to;
-                new_graph.add_edge(new_edge)?;
+            if let Some(max) = max_depth {
+                if current_depth > max {
+                    continue;
+                }
             }
-        }
 
-        Ok(new_graph)
-    }
+            visited.insert(node_id);
 
-    /// Helper for recursive subgraph cloning
-    fn clone_subgraph_recursive(
-        &self,
-        node_id: NodeId,
-        new_graph: &mut Graph,
-        visited: &mut HashSet<NodeId>,
-        id_mapping: &mut HashMap<NodeId, NodeId>,
-        current_depth: usize,
-        max_depth: Option<usize>,
-    ) -> MindmapResult<()> {
-        if visited.contains(&node_id) {
-            return Ok(());
-        }
+            // Clone the node
+            if let Some(node) = self.get_node(node_id) {
+                let mut new_node = node.clone();
+                let new_id = NodeId::new();
+                new_node.id = new_id;
+
+                // Update parent reference if parent was already cloned
+                if let Some(parent_id) = new_node.parent_id {
+                    new_node.parent_id = id_mapping.get(&parent_id).copied();
+                }
 
-        if let Some(max) = max_depth {
-            if current_depth > max {
-                return Ok(());
+                let contents = reassign_attachments(&mut new_node, |id| self.get_attachment(id));
+                id_mapping.insert(node_id, new_id);
+                new_graph.add_node(new_node)?;
+                for (attachment_id, data) in contents {
+                    new_graph.insert_attachment_data(attachment_id, data);
+                }
+
+                // Clone children next, in order
+                if let Some(children) = children_map.get(&node_id) {
+                    stack.extend(children.iter().rev().map(|child| (child.id, current_depth + 1)));
+                }
             }
         }
 
-        visited.insert(node_id);
+        // Add edges between cloned nodes
+        for new_edge in self.edges().filter_map(|edge| remap_edge(edge, &id_mapping)) {
+            new_graph.add_edge(new_edge)?;
+        }
 
-        // Clone the node
-        if let Some(node) = self.get_node(node_id) {
-            let mut new_node = node.clone();
-            let new_id = NodeId::new();
-            new_node.id = new_id;
+        Ok(new_graph)
+    }
 
-            // Update parent reference if parent was already cloned
-            if let Some(parent_id) = new_node.parent_id {
-                new_node.parent_id = id_mapping.get(&parent_id).copied();
+    /// Copy a node and all of its descendants to a clipboard
+    pub fn copy_subtree(&self, node_id: NodeId) -> MindmapResult<SubtreeClipboard> {
+        let root = self.get_node(node_id)
+            .ok_or(MindmapError::NodeNotFound { id: node_id })?;
+
+        let mut nodes = vec![root.clone()];
+        nodes.extend(
+            self.get_descendants(node_id)
+                .into_iter()
+                .filter_map(|id| self.get_node(id).cloned())
+        );
+
+        let copied: HashSet<NodeId> = nodes.iter().map(|node| node.id).collect();
+        let edges = self.edges()
+            .filter(|edge| copied.contains(&edge.from_node) && copied.contains(&edge.to_node))
+            .cloned()
+            .collect();
+        let attachment_data = nodes.iter()
+            .flat_map(|node| node.attachments.iter().filter_map(|attachment| attachment.attachment_id()))
+            .filter_map(|id| self.get_attachment(id).map(|data| (id, data.to_vec())))
+            .collect();
+
+        Ok(SubtreeClipboard { nodes, edges, attachment_data })
+    }
+
+    /// Paste a copied subtree under `new_parent`, or as a new root
+    ///
+    /// Every node, edge and attachment gets a fresh ID, so the same clipboard
+    /// can be pasted any number of times, including under the copied node
+    /// itself.
+    /// Text, tags, styles and the internal structure are kept; the pasted
+    /// root goes after the parent's existing children. Returns the ID of the
+    /// pasted root.
+    pub fn paste_subtree(&mut self, clip: &SubtreeClipboard, new_parent: Option<NodeId>) -> MindmapResult<NodeId> {
+        let root_id = clip.root_id().ok_or_else(|| MindmapError::InvalidOperation {
+            message: "Cannot paste an empty clipboard".to_string()
+        })?;
+        if let Some(parent_id) = new_parent {
+            if !self.contains_node(parent_id) {
+                return Err(MindmapError::NodeNotFound { id: parent_id });
             }
+        }
 
-            id_mapping.insert(node_id, new_id);
-            new_graph.add_node(new_node)?;
+        let id_mapping: HashMap<NodeId, NodeId> = clip.nodes.iter()
+            .map(|node| (node.id, NodeId::new()))
+            .collect();
 
-            // Recursively clone children
-            for child in self.get_children(node_id) {
-                self.clone_subgraph_recursive(
-                    child.id,
-                    new_graph,
-                    visited,
-                    id_mapping,
-                    current_depth + 1,
-                    max_depth,
-                )?;
+        let now = chrono::Utc::now();
+        let root_order = match new_parent {
+            Some(parent_id) => self.get_children(parent_id).last().map_or(0, |last| last.order + 1),
+            None => 0,
+        };
+        let mut contents = Vec::new();
+        let nodes: Vec<Node> = clip.nodes.iter()
+            .map(|node| {
+                let mut new_node = node.clone();
+                new_node.id = id_mapping[&node.id];
+                contents.extend(reassign_attachments(&mut new_node, |id| {
+                    clip.attachment_data.get(&id).map(Vec::as_slice)
+                }));
+                new_node.created_at = now;
+                new_node.updated_at = now;
+                if node.id == root_id {
+                    new_node.parent_id = new_parent;
+                    new_node.order = root_order;
+                } else {
+                    new_node.parent_id = node.parent_id.and_then(|parent_id| id_mapping.get(&parent_id).copied());
+                }
+                new_node
+            })
+            .collect();
+        let pasted = self.add_nodes_bulk(nodes)?;
+
+        let added_edges = clip.edges.iter()
+            .filter_map(|edge| remap_edge(edge, &id_mapping))
+            .try_for_each(|new_edge| self.add_edge(new_edge).map(|_| ()));
+        if let Err(error) = added_edges {
+            // Leave the graph as it was; removing the nodes drops their edges too
+            for node_id in pasted.into_iter().rev() {
+                self.remove_node(node_id)?;
             }
+            return Err(error);
+        }
+        for (attachment_id, data) in contents {
+            self.insert_attachment_data(attachment_id, data);
         }
 
-        Ok(())
+        Ok(id_mapping[&root_id])
     }
 
     /// Merge another graph into this one
@@ -390,9 +1172,13 @@ impl Graph {
             let new_id = NodeId::new();
             new_node.id = new_id;
             new_node.parent_id = None; // Will be fixed in second pass
+            let contents = reassign_attachments(&mut new_node, |id| other.get_attachment(id));
 
             id_mapping.insert(node.id, new_id);
             self.add_node(new_node)?;
+            for (attachment_id, data) in contents {
+                self.insert_attachment_data(attachment_id, data);
+            }
         }
 
         // Second pass: fix parent relationships
@@ -407,24 +1193,118 @@ impl Graph {
         }
 
         // Third pass: add all edges with new IDs
-        for edge in other.edges() {
-            if let (Some(&new_from), Some(&new_to)) = (
-                id_mapping.get(&edge.from_node),
-                id_mapping.get(&edge.to_node),
-            ) {
-                let mut new_edge = edge.clone();
-                new_edge.id = EdgeId::new();
-                new_edge.from_node = new_from;
-                new_edge.to_node = new_to;
-                self.add_edge(new_edge)?;
+        for new_edge in other.edges().filter_map(|edge| remap_edge(edge, &id_mapping)) {
+            self.add_edge(new_edge)?;
+        }
+
+        Ok(id_mapping)
+    }
+
+    /// Find nodes in another graph that match nodes in this graph
+    ///
+    /// Returns `(existing, incoming)` pairs where the incoming node from `other`
+    /// is considered the same as an existing node under the identity policy.
+    pub fn find_merge_conflicts(&self, other: &Graph, identity: &NodeIdentity) -> Vec<(NodeId, NodeId)> {
+        let existing: HashMap<String, NodeId> = self.nodes()
+            .map(|node| (identity.key(node), node.id))
+            .collect();
+
+        other.nodes()
+            .filter_map(|node| existing.get(&identity.key(node)).map(|&id| (id, node.id)))
+            .collect()
+    }
+
+    /// Merge another graph, collapsing nodes that are the same under the identity policy
+    ///
+    /// Incoming nodes that match an existing node (or an earlier incoming node)
+    /// are mapped onto it instead of being added. All other nodes are added with
+    /// new IDs, as with [`Graph::merge_graph`]. Returns the mapping from the
+    /// other graph's node IDs to IDs in this graph.
+    pub fn merge_graph_with_identity(&mut self, other: &Graph, identity: &NodeIdentity) -> MindmapResult<HashMap<NodeId, NodeId>> {
+        let mut id_mapping = HashMap::new();
+        let mut added = HashSet::new();
+        let mut known: HashMap<String, NodeId> = self.nodes()
+            .map(|node| (identity.key(node), node.id))
+            .collect();
+
+        // First pass: map matching nodes, add the rest with new IDs
+        for node in other.nodes() {
+            let key = identity.key(node);
+            if let Some(&existing_id) = known.get(&key) {
+                id_mapping.insert(node.id, existing_id);
+                continue;
+            }
+
+            let mut new_node = node.clone();
+            let new_id = NodeId::new();
+            new_node.id = new_id;
+            new_node.parent_id = None; // Will be fixed in second pass
+            let contents = reassign_attachments(&mut new_node, |id| other.get_attachment(id));
+
+            known.insert(key, new_id);
+            id_mapping.insert(node.id, new_id);
+            added.insert(new_id);
+            self.add_node(new_node)?;
+            for (attachment_id, data) in contents {
+                self.insert_attachment_data(attachment_id, data);
             }
         }
 
+        // Second pass: fix parent relationships of newly added nodes
+        for node in other.nodes() {
+            let new_node_id = id_mapping[&node.id];
+            if !added.contains(&new_node_id) {
+                continue;
+            }
+
+            if let Some(&new_parent_id) = node.parent_id.and_then(|parent_id| id_mapping.get(&parent_id)) {
+                if new_parent_id != new_node_id {
+                    self.move_node(new_node_id, Some(new_parent_id))?;
+                }
+            }
+        }
+
+        // Third pass: add edges that don't already exist
+        for new_edge in other.edges().filter_map(|edge| remap_edge(edge, &id_mapping)) {
+            if new_edge.from_node == new_edge.to_node || self.has_edge_between(new_edge.from_node, new_edge.to_node) {
+                continue;
+            }
+            self.add_edge(new_edge)?;
+        }
+
         Ok(id_mapping)
     }
 
-    /// Get graph statistics
-    pub fn get_statistics(&self) -> GraphStatistics {
+    /// Find groups of nodes that are duplicates of each other under the identity policy
+    ///
+    /// Each returned group contains at least two nodes. Groups are ordered by
+    /// their identity key and nodes within a group by creation time.
+    pub fn find_duplicate_texts(&self, identity: &NodeIdentity) -> Vec<Vec<NodeId>> {
+        let mut groups: HashMap<String, Vec<&Node>> = HashMap::new();
+        for node in self.nodes() {
+            groups.entry(identity.key(node)).or_default().push(node);
+        }
+
+        let mut duplicates: Vec<(String, Vec<&Node>)> = groups
+            .into_iter()
+            .filter(|(_, nodes)| nodes.len() > 1)
+            .collect();
+        duplicates.sort_by(|a, b| a.0.cmp(&b.0));
+
+        duplicates
+            .into_iter()
+            .map(|(_, mut nodes)| {
+                nodes.sort_by_key(|node| node.created_at);
+                nodes.into_iter().map(|node| node.id).collect()
+            })
+            .collect()
+    }
+
+    /// Summary statistics for the whole graph
+    ///
+    /// Depth and branching follow the parent hierarchy; the branching factor
+    /// averages the child count over nodes that have at least one child.
+    pub fn statistics(&self) -> GraphStatistics {
         let node_count = self.node_count();
         let edge_count = self.edge_count();
         let root_count = self.get_root_nodes().len();
@@ -441,6 +1321,24 @@ impl Graph {
             0.0
         };
 
+        let mut child_counts: HashMap<NodeId, usize> = HashMap::new();
+        for parent_id in self.nodes().filter_map(|node| node.parent_id) {
+            if self.contains_node(parent_id) {
+                *child_counts.entry(parent_id).or_default() += 1;
+            }
+        }
+        let avg_branching_factor = if child_counts.is_empty() {
+            0.0
+        } else {
+            child_counts.values().sum::<usize>() as f64 / child_counts.len() as f64
+        };
+
+        let total_word_count = self.nodes().map(|node| node.word_count()).sum();
+        let mut tag_frequency = BTreeMap::new();
+        for tag in self.nodes().flat_map(|node| node.tags.iter()) {
+            *tag_frequency.entry(tag.clone()).or_default() += 1;
+        }
+
         GraphStatistics {
             node_count,
             edge_count,
@@ -448,12 +1346,15 @@ impl Graph {
             max_depth,
             avg_degree,
             has_cycles,
+            avg_branching_factor,
+            total_word_count,
+            tag_frequency,
         }
     }
 }
 
 /// Graph statistics
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphStatistics {
     pub node_count: usize,
     pub edge_count: usize,
@@ -461,14 +1362,428 @@ pub struct GraphStatistics {
     pub max_depth: usize,
     pub avg_degree: f64,
     pub has_cycles: bool,
+    /// Average number of children of nodes that have children
+    pub avg_branching_factor: f64,
+    /// Words across all node texts
+    pub total_word_count: usize,
+    /// Number of nodes carrying each tag
+    pub tag_frequency: BTreeMap<String, usize>,
+}
+
+/// Default number of commands kept by an [`UndoStack`]
+pub const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// Bounded undo/redo history of graph edits
+///
+/// Edits are made through the stack so each one is recorded with the state
+/// needed to reverse it. Making a new edit discards the redo history. The
+/// undo history is an [`OperationLog`], so besides the command limit it is
+/// trimmed to the log's memory budget.
+#[derive(Debug, Clone)]
+pub struct UndoStack {
+    /// Commands that can be undone, oldest first
+    undo: OperationLog,
+    /// Commands that can be redone, most recently undone last
+    redo: Vec<UndoCommand>,
+    /// Maximum number of undoable commands; zero means unlimited
+    limit: usize,
+}
+
+impl UndoStack {
+    /// Create an empty stack with the default history limit
+    pub fn new() -> Self {
+        Self::with_limit(DEFAULT_UNDO_LIMIT)
+    }
+
+    /// Create an empty stack keeping at most `limit` commands (zero for unlimited)
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            undo: OperationLog::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Get the memory retention policy of the undo history
+    pub fn retention(&self) -> LogRetention {
+        self.undo.retention()
+    }
+
+    /// Change the memory retention policy, dropping the oldest commands if needed
+    pub fn set_retention(&mut self, retention: LogRetention) {
+        self.undo.set_retention(retention);
+    }
+
+    /// Estimated memory held by the undo history in bytes
+    pub fn estimated_bytes(&self) -> usize {
+        self.undo.estimated_bytes()
+    }
+
+    /// Maximum number of undoable commands
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Change the history limit, dropping the oldest commands if needed
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    /// Check if there is an edit to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Check if there is an undone edit to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Number of edits that can be undone
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Number of edits that can be redone
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Forget all recorded edits
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Record an edit that has already been applied to the graph
+    pub fn push(&mut self, command: UndoCommand) {
+        self.redo.clear();
+        self.undo.push(command);
+        self.trim();
+    }
+
+    /// Reverse the most recent edit
+    pub fn undo(&mut self, graph: &mut Graph) -> MindmapResult<()> {
+        let command = self.undo.pop().ok_or_else(|| MindmapError::InvalidOperation {
+            message: "Nothing to undo".to_string(),
+        })?;
+
+        if let Err(error) = command.revert(graph) {
+            self.undo.push(command);
+            return Err(error);
+        }
+
+        self.redo.push(command);
+        Ok(())
+    }
+
+    /// Re-apply the most recently undone edit
+    pub fn redo(&mut self, graph: &mut Graph) -> MindmapResult<()> {
+        let command = self.redo.pop().ok_or_else(|| MindmapError::InvalidOperation {
+            message: "Nothing to redo".to_string(),
+        })?;
+
+        if let Err(error) = command.apply(graph) {
+            self.redo.push(command);
+            return Err(error);
+        }
+
+        self.undo.push(command);
+        self.trim();
+        Ok(())
+    }
+
+    /// Add a node and record the edit
+    pub fn add_node(&mut self, graph: &mut Graph, node: Node) -> MindmapResult<NodeId> {
+        let node_id = graph.add_node(node)?;
+        let added = graph.get_node(node_id).cloned().ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        self.push(UndoCommand::AddNode(added));
+        Ok(node_id)
+    }
+
+    /// Remove a node and its incident edges and record the edit
+    pub fn remove_node(&mut self, graph: &mut Graph, node_id: NodeId) -> MindmapResult<Node> {
+        let (node, command) = Self::remove_with_edges(graph, node_id)?;
+        self.push(command);
+        Ok(node)
+    }
+
+    /// Remove a node with all its descendants and record it as one edit
+    ///
+    /// Descendants go deepest first, so undoing restores every node after its
+    /// parent together with its edges. Returns the removed nodes in removal order.
+    pub fn remove_subtree(&mut self, graph: &mut Graph, node_id: NodeId) -> MindmapResult<Vec<Node>> {
+        if !graph.contains_node(node_id) {
+            return Err(MindmapError::NodeNotFound { id: node_id });
+        }
+
+        let mut subtree = graph.get_descendants(node_id);
+        subtree.reverse();
+        subtree.push(node_id);
+
+        let mut removed = Vec::with_capacity(subtree.len());
+        let mut commands = Vec::with_capacity(subtree.len());
+        for subtree_id in subtree {
+            let (node, command) = Self::remove_with_edges(graph, subtree_id)?;
+            removed.push(node);
+            commands.push(command);
+        }
+        self.push(UndoCommand::Batch(commands));
+        Ok(removed)
+    }
+
+    /// Remove a node, returning it with the command that puts it and its edges back
+    fn remove_with_edges(graph: &mut Graph, node_id: NodeId) -> MindmapResult<(Node, UndoCommand)> {
+        let mut edges: Vec<Edge> = graph
+            .get_outgoing_edges(node_id)
+            .into_iter()
+            .chain(graph.get_incoming_edges(node_id))
+            .cloned()
+            .collect();
+        let mut seen = HashSet::new();
+        edges.retain(|edge| seen.insert(edge.id));
+
+        let node = graph.remove_node(node_id)?;
+        let command = UndoCommand::RemoveNode { node: Box::new(node.clone()), edges };
+        Ok((node, command))
+    }
+
+    /// Change a node's text and record the edit
+    pub fn update_node_text(&mut self, graph: &mut Graph, node_id: NodeId, text: impl Into<String>) -> MindmapResult<()> {
+        self.update_node_with(graph, node_id, |node| node.set_text(text))
+    }
+
+    /// Move a node to a new position and record the edit
+    pub fn update_node_position(&mut self, graph: &mut Graph, node_id: NodeId, position: Point) -> MindmapResult<()> {
+        self.update_node_with(graph, node_id, |node| node.set_position(position))
+    }
+
+    /// Add an edge and record the edit
+    pub fn add_edge(&mut self, graph: &mut Graph, edge: Edge) -> MindmapResult<EdgeId> {
+        let edge_id = graph.add_edge(edge.clone())?;
+        self.push(UndoCommand::AddEdge(edge));
+        Ok(edge_id)
+    }
+
+    /// Remove an edge and record the edit
+    pub fn remove_edge(&mut self, graph: &mut Graph, edge_id: EdgeId) -> MindmapResult<Edge> {
+        let edge = graph.remove_edge(edge_id)?;
+        self.push(UndoCommand::RemoveEdge(edge.clone()));
+        Ok(edge)
+    }
+
+    /// Apply a change to a copy of a node, store it and record the edit
+    fn update_node_with<F>(&mut self, graph: &mut Graph, node_id: NodeId, change: F) -> MindmapResult<()>
+    where
+        F: FnOnce(&mut Node),
+    {
+        let before = graph.get_node(node_id).cloned().ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        let mut after = before.clone();
+        change(&mut after);
+
+        graph.update_node(after)?;
+        let after = graph.get_node(node_id).cloned().ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        self.push(UndoCommand::UpdateNode { before: Box::new(before), after: Box::new(after) });
+        Ok(())
+    }
+
+    /// Drop the oldest commands beyond the history limit
+    fn trim(&mut self) {
+        if self.limit == 0 {
+            return;
+        }
+        while self.undo.len() > self.limit {
+            self.undo.pop_oldest();
+        }
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::{Node, Edge};
+    use crate::graph::SiblingMergePolicy;
     use crate::types::Point;
 
+    #[test]
+    fn test_resolve_missing_document_root() {
+        let mut graph = Graph::new();
+        let root = Node::new("Existing root");
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+        graph.add_node(Node::new_child(root_id, "Child")).unwrap();
+
+        let missing_id = NodeId::new();
+        let document = Document::new("Recovered", missing_id);
+
+        // Error policy leaves the document untouched
+        let mut doc = document.clone();
+        let result = graph.resolve_document_root(&mut doc, MissingRootPolicy::Error);
+        assert!(matches!(result, Err(MindmapError::NodeNotFound { id }) if id == missing_id));
+        assert_eq!(doc.get_root_node(), missing_id);
+
+        // Picking an available root reuses the existing parentless node
+        let mut doc = document.clone();
+        let picked = graph.resolve_document_root(&mut doc, MissingRootPolicy::PickAvailableRoot).unwrap();
+        assert_eq!(picked, Some(root_id));
+        assert_eq!(doc.get_root_node(), root_id);
+        assert!(doc.is_dirty);
+
+        // Creating a root adds a new node named after the document
+        let mut doc = document.clone();
+        let created = graph.resolve_document_root(&mut doc, MissingRootPolicy::CreateRoot).unwrap().unwrap();
+        assert_eq!(doc.get_root_node(), created);
+        assert_eq!(graph.get_node(created).unwrap().text, "Recovered");
+
+        // A valid root is returned as-is
+        assert_eq!(graph.resolve_document_root(&mut doc, MissingRootPolicy::Error).unwrap(), Some(created));
+
+        // Clearing leaves the document without a root, which later checks accept
+        let mut doc = document.clone();
+        assert_eq!(graph.resolve_document_root(&mut doc, MissingRootPolicy::Clear).unwrap(), None);
+        assert!(!doc.has_root_node());
+        assert!(doc.is_dirty);
+        assert_eq!(graph.resolve_document_root(&mut doc, MissingRootPolicy::Error).unwrap(), None);
+    }
+
+    #[test]
+    fn test_sort_children() {
+        let mut graph = Graph::new();
+        let root = Node::new("Root");
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+
+        for text in ["banana", "Cherry", "apple", "date"] {
+            graph.add_node(Node::new_child(root_id, text)).unwrap();
+        }
+
+        let texts = |graph: &Graph| -> Vec<String> {
+            graph.get_children(root_id).iter().map(|n| n.text.clone()).collect()
+        };
+
+        graph.sort_children(root_id, SortKey::Text, true).unwrap();
+        assert_eq!(texts(&graph), vec!["apple", "banana", "Cherry", "date"]);
+
+        graph.sort_children(root_id, SortKey::Text, false).unwrap();
+        assert_eq!(texts(&graph), vec!["date", "Cherry", "banana", "apple"]);
+
+        assert!(graph.sort_children(NodeId::new(), SortKey::Text, true).is_err());
+    }
+
+    #[test]
+    fn test_sort_children_by_metadata() {
+        let mut graph = Graph::new();
+        let root = Node::new("Root");
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+
+        for (text, priority) in [("low", Some("3")), ("none", None), ("high", Some("1"))] {
+            let mut child = Node::new_child(root_id, text);
+            if let Some(priority) = priority {
+                child.set_metadata("priority", priority);
+            }
+            graph.add_node(child).unwrap();
+        }
+
+        let key = SortKey::Metadata("priority".to_string());
+        graph.sort_children(root_id, key.clone(), true).unwrap();
+        let texts: Vec<_> = graph.get_children(root_id).iter().map(|n| n.text.clone()).collect();
+        assert_eq!(texts, vec!["high", "low", "none"]);
+
+        graph.sort_children(root_id, key, false).unwrap();
+        let texts: Vec<_> = graph.get_children(root_id).iter().map(|n| n.text.clone()).collect();
+        assert_eq!(texts, vec!["low", "high", "none"]);
+    }
+
+    #[test]
+    fn test_reorder_child() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let ids: Vec<NodeId> = ["a", "b", "c", "d"].into_iter()
+            .map(|text| graph.add_node(Node::new_child(root_id, text)).unwrap())
+            .collect();
+
+        let texts = |graph: &Graph| -> Vec<String> {
+            graph.get_children(root_id).iter().map(|n| n.text.clone()).collect()
+        };
+        // New children append at the end, and the order is stable across calls
+        assert_eq!(texts(&graph), vec!["a", "b", "c", "d"]);
+        assert_eq!(texts(&graph), texts(&graph));
+
+        graph.reorder_child(root_id, ids[3], 0).unwrap();
+        assert_eq!(texts(&graph), vec!["d", "a", "b", "c"]);
+
+        graph.reorder_child(root_id, ids[0], 2).unwrap();
+        assert_eq!(texts(&graph), vec!["d", "b", "a", "c"]);
+
+        // Indices past the end move the child last
+        graph.reorder_child(root_id, ids[3], 10).unwrap();
+        assert_eq!(texts(&graph), vec!["b", "a", "c", "d"]);
+        let orders: Vec<i64> = graph.get_children(root_id).iter().map(|n| n.order).collect();
+        assert_eq!(orders, vec![0, 1, 2, 3]);
+
+        // A child added after reordering still goes last
+        graph.add_node(Node::new_child(root_id, "e")).unwrap();
+        assert_eq!(texts(&graph), vec!["b", "a", "c", "d", "e"]);
+
+        assert!(matches!(graph.reorder_child(ids[0], ids[1], 0), Err(MindmapError::InvalidOperation { .. })));
+        assert!(matches!(graph.reorder_child(root_id, NodeId::new(), 0), Err(MindmapError::NodeNotFound { .. })));
+        assert!(matches!(graph.reorder_child(NodeId::new(), ids[1], 0), Err(MindmapError::NodeNotFound { .. })));
+    }
+
+    #[test]
+    fn test_child_added_after_delete_goes_last() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let ids: Vec<NodeId> = ["a", "b", "c"].into_iter()
+            .map(|text| graph.add_node(Node::new_child(root_id, text)).unwrap())
+            .collect();
+        let texts = |graph: &Graph| -> Vec<String> {
+            graph.get_children(root_id).iter().map(|n| n.text.clone()).collect()
+        };
+
+        // Deleting the first child frees order 0, which a new child must not take
+        let removed = graph.remove_node(ids[0]).unwrap();
+        graph.add_node(Node::new_child(root_id, "d")).unwrap();
+        assert_eq!(texts(&graph), vec!["b", "c", "d"]);
+
+        // Undo puts a deleted child back in its old place
+        UndoCommand::RemoveNode { node: Box::new(removed), edges: Vec::new() }.revert(&mut graph).unwrap();
+        assert_eq!(texts(&graph), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_get_children_breaks_order_ties_deterministically() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let mut first = Node::new_child(root_id, "first");
+        first.order = 5;
+        graph.add_node(first).unwrap();
+        // Bulk inserts keep the given order, so these tie with `first`
+        let tied: Vec<Node> = (0..20).map(|i| {
+            let mut node = Node::new_child(root_id, format!("tied {}", i));
+            node.order = 5;
+            node
+        }).collect();
+        graph.add_nodes_bulk(tied).unwrap();
+
+        let ids = |graph: &Graph| -> Vec<NodeId> {
+            graph.get_children(root_id).iter().map(|n| n.id).collect()
+        };
+        let expected = ids(&graph);
+        for _ in 0..10 {
+            assert_eq!(ids(&graph), expected);
+        }
+        assert_eq!(graph.children_by_parent()[&root_id].iter().map(|n| n.id).collect::<Vec<_>>(), expected);
+    }
+
     #[test]
     fn test_add_node_with_validation() {
         let mut graph = Graph::new();
@@ -527,6 +1842,85 @@ mod tests {
         assert!(!graph.contains_node(child_id));
     }
 
+    #[test]
+    fn test_remove_node_reparent_children() {
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Root")).unwrap();
+        let mut first = Node::new_child(root, "First");
+        first.order = 0;
+        let first = graph.add_node(first).unwrap();
+        let mut middle = Node::new_child(root, "Middle");
+        middle.order = 1;
+        let middle = graph.add_node(middle).unwrap();
+        let mut last = Node::new_child(root, "Last");
+        last.order = 2;
+        let last = graph.add_node(last).unwrap();
+
+        let mut a = Node::new_child(middle, "A");
+        a.order = 0;
+        let a = graph.add_node(a).unwrap();
+        let mut b = Node::new_child(middle, "B");
+        b.order = 1;
+        let b = graph.add_node(b).unwrap();
+        let grandchild = graph.add_node(Node::new_child(a, "Under A")).unwrap();
+        graph.add_edge(Edge::new(root, middle)).unwrap();
+        graph.add_edge(Edge::new(middle, a)).unwrap();
+
+        let removed = graph.remove_node_reparent_children(middle).unwrap();
+        assert_eq!(removed.id, middle);
+        assert!(!graph.contains_node(middle));
+
+        // The children now hang off the grandparent, in the removed node's place
+        let children: Vec<NodeId> = graph.get_children(root).iter().map(|n| n.id).collect();
+        assert_eq!(children, vec![first, a, b, last]);
+        assert_eq!(graph.get_node(a).unwrap().parent_id, Some(root));
+        assert_eq!(graph.get_node(b).unwrap().parent_id, Some(root));
+        assert_eq!(graph.get_node(grandchild).unwrap().parent_id, Some(a));
+
+        // The link to the child moved up; the link to the removed node is gone
+        assert!(graph.has_edge_between(root, a));
+        assert_eq!(graph.edge_count(), 1);
+
+        // Without a parent, the children become roots
+        graph.remove_node_reparent_children(root).unwrap();
+        assert!(graph.get_node(a).unwrap().parent_id.is_none());
+        assert_eq!(graph.get_root_nodes().len(), 4);
+
+        assert!(graph.remove_node_reparent_children(middle).is_err());
+    }
+
+    #[test]
+    fn test_remove_node_reparent_children_up_to_child_limit() {
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Root")).unwrap();
+        let first = graph.add_node(Node::new_child(root, "First")).unwrap();
+        let middle = graph.add_node(Node::new_child(root, "Middle")).unwrap();
+        let last = graph.add_node(Node::new_child(root, "Last")).unwrap();
+        let only = graph.add_node(Node::new_child(middle, "Only")).unwrap();
+        graph.set_max_children_per_node(Some(3));
+
+        // A single child exactly replaces the removed node
+        graph.remove_node_reparent_children(middle).unwrap();
+        let children: Vec<NodeId> = graph.get_children(root).iter().map(|n| n.id).collect();
+        assert_eq!(children, vec![first, only, last]);
+
+        // Two children landing exactly on the limit
+        graph.remove_node_reparent_children(last).unwrap();
+        let a = graph.add_node(Node::new_child(only, "A")).unwrap();
+        let b = graph.add_node(Node::new_child(only, "B")).unwrap();
+        graph.remove_node_reparent_children(only).unwrap();
+        let children: Vec<NodeId> = graph.get_children(root).iter().map(|n| n.id).collect();
+        assert_eq!(children, vec![first, a, b]);
+
+        // One over the limit is refused without changing anything
+        let c = graph.add_node(Node::new_child(a, "C")).unwrap();
+        let d = graph.add_node(Node::new_child(a, "D")).unwrap();
+        assert!(graph.remove_node_reparent_children(a).is_err());
+        assert_eq!(graph.get_node(c).unwrap().parent_id, Some(a));
+        assert_eq!(graph.get_node(d).unwrap().parent_id, Some(a));
+        assert_eq!(graph.get_node(a).unwrap().parent_id, Some(root));
+    }
+
     #[test]
     fn test_move_node() {
         let mut graph = Graph::new();
@@ -604,6 +1998,85 @@ mod tests {
         assert!(graph.add_edge_with_validation(duplicate_edge).is_err());
     }
 
+    #[test]
+    fn test_insert_on_edge() {
+        let mut graph = Graph::new();
+        let a_id = graph.add_node(Node::new("A")).unwrap();
+        let b_id = graph.add_node(Node::new_child(a_id, "B")).unwrap();
+        let mut edge = Edge::new_with_label(a_id, b_id, "leads to");
+        edge.weight = Some(4.0);
+        let edge_id = graph.add_edge(edge).unwrap();
+
+        let c = Node::new("C");
+        let c_id = c.id;
+        let (first_id, second_id) = graph.insert_on_edge(edge_id, c).unwrap();
+
+        assert!(graph.get_edge(edge_id).is_none());
+        assert!(!graph.has_edge_between(a_id, b_id));
+        assert_eq!(graph.edge_count(), 2);
+
+        let first = graph.get_edge(first_id).unwrap();
+        assert_eq!((first.from_node, first.to_node), (a_id, c_id));
+        assert_eq!(first.label.as_deref(), Some("leads to"));
+        assert_eq!(first.weight, Some(2.0));
+        let second = graph.get_edge(second_id).unwrap();
+        assert_eq!((second.from_node, second.to_node), (c_id, b_id));
+        assert_eq!(second.label, None);
+        assert_eq!(second.weight, Some(2.0));
+
+        // The parent-child link is split too
+        assert_eq!(graph.get_node(c_id).unwrap().parent_id, Some(a_id));
+        assert_eq!(graph.get_node(b_id).unwrap().parent_id, Some(c_id));
+
+        assert!(matches!(
+            graph.insert_on_edge(edge_id, Node::new("D")),
+            Err(MindmapError::EdgeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delete_preview_matches_delete() {
+        let mut graph = Graph::new();
+
+        let root = Node::new("Root");
+        let root_id = root.id;
+        let branch = Node::new_child(root_id, "Branch");
+        let branch_id = branch.id;
+        let leaf1 = Node::new_child(branch_id, "Leaf 1");
+        let leaf1_id = leaf1.id;
+        let leaf2 = Node::new_child(branch_id, "Leaf 2");
+        let leaf2_id = leaf2.id;
+        let other = Node::new_child(root_id, "Other");
+        let other_id = other.id;
+
+        for node in [root, branch, leaf1, leaf2, other] {
+            graph.add_node(node).unwrap();
+        }
+
+        graph.add_edge(Edge::new(root_id, branch_id)).unwrap();
+        graph.add_edge(Edge::new(branch_id, leaf1_id)).unwrap();
+        graph.add_edge(Edge::new(leaf1_id, leaf2_id)).unwrap();
+        graph.add_edge(Edge::new(leaf2_id, other_id)).unwrap();
+        graph.add_edge(Edge::new(root_id, other_id)).unwrap();
+
+        let impact = graph.delete_preview(branch_id).unwrap();
+        assert_eq!(impact.nodes_removed, 3);
+        assert_eq!(impact.edges_removed, 4);
+        assert_eq!(impact.node_ids[0], branch_id);
+
+        // Preview must not modify the graph
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 5);
+
+        let nodes_before = graph.node_count();
+        let edges_before = graph.edge_count();
+        graph.delete_node_with_cleanup(branch_id).unwrap();
+        assert_eq!(nodes_before - graph.node_count(), impact.nodes_removed);
+        assert_eq!(edges_before - graph.edge_count(), impact.edges_removed);
+
+        assert!(graph.delete_preview(branch_id).is_err());
+    }
+
     #[test]
     fn test_clone_subgraph() {
         let mut graph = Graph::new();
@@ -632,6 +2105,137 @@ mod tests {
         assert_eq!(cloned.node_count(), 3); // root + 2 children, no grandchild
     }
 
+    #[test]
+    fn test_paste_subtree_under_its_own_descendant() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let mut topic = Node::new_child(root_id, "Topic");
+        topic.tags.push("important".to_string());
+        topic.style.background_color = 0xFF3366CC;
+        let topic_id = graph.add_node(topic).unwrap();
+        let detail_id = graph.add_node(Node::new_child(topic_id, "Detail")).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(detail_id, "Leaf")).unwrap();
+        graph.add_edge(Edge::new(leaf_id, topic_id)).unwrap();
+
+        let clip = graph.copy_subtree(topic_id).unwrap();
+        assert_eq!(clip.len(), 3);
+        assert_eq!(clip.root_id(), Some(topic_id));
+        assert_eq!(clip.edges.len(), 1);
+
+        let pasted_id = graph.paste_subtree(&clip, Some(leaf_id)).unwrap();
+        assert_eq!(graph.node_count(), 7);
+        assert!(!clip.nodes.iter().any(|node| node.id == pasted_id));
+
+        // Structure, tags and style carry over to fresh nodes
+        let pasted = graph.get_node(pasted_id).unwrap();
+        assert_eq!(pasted.parent_id, Some(leaf_id));
+        assert_eq!(pasted.text, "Topic");
+        assert_eq!(pasted.tags, vec!["important".to_string()]);
+        assert_eq!(pasted.style.background_color, 0xFF3366CC);
+        let pasted_detail = graph.get_children(pasted_id)[0].id;
+        assert_eq!(graph.get_node(pasted_detail).unwrap().text, "Detail");
+        let pasted_leaf = graph.get_children(pasted_detail)[0].id;
+        assert!(graph.edges().any(|edge| edge.from_node == pasted_leaf && edge.to_node == pasted_id));
+        assert!(graph.validate().is_ok());
+
+        // Editing or removing the original leaves the copy alone
+        graph.get_node_mut(topic_id).unwrap().text = "Renamed".to_string();
+        assert_eq!(graph.get_node(pasted_id).unwrap().text, "Topic");
+        graph.remove_node(detail_id).unwrap();
+        assert_eq!(graph.get_node(pasted_detail).unwrap().text, "Detail");
+        assert_eq!(graph.get_descendants(pasted_id).len(), 2);
+    }
+
+    #[test]
+    fn test_copied_attachments_get_fresh_ids_and_contents() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let data = b"%PDF-1.7 quarterly report".to_vec();
+        let original_id = graph.add_attachment(root_id, "q3.pdf", data.clone(), "application/pdf").unwrap();
+
+        let copied_attachment = |graph: &Graph, node_id: NodeId| {
+            let attachment = graph.get_node(node_id).unwrap().attachments[0].clone();
+            let attachment_id = attachment.attachment_id().unwrap();
+            assert_ne!(attachment_id, original_id);
+            assert_eq!(attachment.path, format!("attachments/{}", attachment_id.as_uuid()));
+            assert_eq!(graph.get_attachment(attachment_id), Some(data.as_slice()));
+            attachment_id
+        };
+
+        // Pasting twice, here and into another graph, gives every copy its own contents
+        let clip = graph.copy_subtree(root_id).unwrap();
+        let first = graph.paste_subtree(&clip, None).unwrap();
+        let second = graph.paste_subtree(&clip, None).unwrap();
+        assert_ne!(copied_attachment(&graph, first), copied_attachment(&graph, second));
+        let mut other = Graph::new();
+        let pasted = other.paste_subtree(&clip, None).unwrap();
+        copied_attachment(&other, pasted);
+
+        let cloned = graph.clone_subgraph(root_id, None).unwrap();
+        copied_attachment(&cloned, cloned.get_root_nodes()[0].id);
+
+        let mapping = other.merge_graph(&cloned).unwrap();
+        copied_attachment(&other, mapping[&cloned.get_root_nodes()[0].id]);
+        assert_eq!(graph.get_attachment(original_id), Some(data.as_slice()));
+    }
+
+    #[test]
+    fn test_paste_after_deleted_sibling_goes_last() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let first_id = graph.add_node(Node::new_child(root_id, "c1")).unwrap();
+        let second_id = graph.add_node(Node::new_child(root_id, "c2")).unwrap();
+        graph.add_node(Node::new_child(root_id, "c3")).unwrap();
+        graph.add_node(Node::new_child(root_id, "c4")).unwrap();
+        let other_id = graph.add_node(Node::new("Other")).unwrap();
+        graph.remove_node(first_id).unwrap();
+        graph.remove_node(second_id).unwrap();
+
+        let clip = graph.copy_subtree(other_id).unwrap();
+        graph.paste_subtree(&clip, Some(root_id)).unwrap();
+        let texts: Vec<&str> = graph.get_children(root_id).iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, vec!["c3", "c4", "Other"]);
+    }
+
+    #[test]
+    fn test_paste_subtree_errors() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        assert!(matches!(graph.copy_subtree(NodeId::new()), Err(MindmapError::NodeNotFound { .. })));
+
+        let clip = graph.copy_subtree(root_id).unwrap();
+        assert!(matches!(graph.paste_subtree(&clip, Some(NodeId::new())), Err(MindmapError::NodeNotFound { .. })));
+
+        let empty = SubtreeClipboard { nodes: Vec::new(), edges: Vec::new(), attachment_data: HashMap::new() };
+        assert!(graph.paste_subtree(&empty, None).is_err());
+        assert_eq!(graph.node_count(), 1);
+
+        // Pasting without a parent adds a second root
+        let pasted_id = graph.paste_subtree(&clip, None).unwrap();
+        assert_eq!(graph.get_node(pasted_id).unwrap().parent_id, None);
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_failed_paste_leaves_graph_unchanged() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let child_id = graph.add_node(Node::new_child(root_id, "Child")).unwrap();
+        let grandchild_id = graph.add_node(Node::new_child(child_id, "Grandchild")).unwrap();
+        graph.add_edge(Edge::new(child_id, grandchild_id)).unwrap();
+
+        // A corrupted clipboard whose last edge loops back onto its node
+        let mut clip = graph.copy_subtree(child_id).unwrap();
+        clip.edges.push(Edge::new(child_id, child_id));
+
+        let nodes_before = graph.node_count();
+        let edges_before = graph.edge_count();
+        assert!(graph.paste_subtree(&clip, Some(root_id)).is_err());
+        assert_eq!(graph.node_count(), nodes_before);
+        assert_eq!(graph.edge_count(), edges_before);
+        assert_eq!(graph.get_children(root_id).len(), 1);
+    }
+
     #[test]
     fn test_graph_statistics() {
         let mut graph = Graph::new();
@@ -642,13 +2246,51 @@ mod tests {
         graph.add_node_with_validation(root).unwrap();
         graph.add_node_with_validation(child).unwrap();
 
-        let stats = graph.get_statistics();
+        let stats = graph.statistics();
         assert_eq!(stats.node_count, 2);
         assert_eq!(stats.root_count, 1);
         assert_eq!(stats.max_depth, 1);
         assert!(!stats.has_cycles);
     }
 
+    #[test]
+    fn test_statistics_of_known_tree() {
+        // Root -> (A -> (A1, A2, A3), B -> B1), plus one cross-link
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Quarterly plan")).unwrap();
+        let mut a = Node::new_child(root_id, "Hire two engineers");
+        a.tags = vec!["team".to_string(), "urgent".to_string()];
+        let a_id = graph.add_node(a).unwrap();
+        let b_id = graph.add_node(Node::new_child(root_id, "Launch")).unwrap();
+        for text in ["Write job post", "Screen", "Interview candidates"] {
+            let mut child = Node::new_child(a_id, text);
+            child.tags = vec!["team".to_string()];
+            graph.add_node(child).unwrap();
+        }
+        let b1_id = graph.add_node(Node::new_child(b_id, "Announce on blog")).unwrap();
+        graph.add_edge(Edge::new(b1_id, a_id)).unwrap();
+
+        let stats = graph.statistics();
+        assert_eq!(stats.node_count, 7);
+        assert_eq!(stats.edge_count, 1);
+        assert_eq!(stats.root_count, 1);
+        assert_eq!(stats.max_depth, 2);
+        // Root has 2 children, A has 3 and B has 1
+        assert_eq!(stats.avg_branching_factor, 2.0);
+        assert_eq!(stats.total_word_count, 2 + 3 + 1 + 3 + 1 + 2 + 3);
+        assert_eq!(stats.tag_frequency.get("team"), Some(&4));
+        assert_eq!(stats.tag_frequency.get("urgent"), Some(&1));
+        assert_eq!(stats.tag_frequency.len(), 2);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["total_word_count"], 15);
+        assert_eq!(json["tag_frequency"]["team"], 4);
+
+        let empty = Graph::new().statistics();
+        assert_eq!(empty.avg_branching_factor, 0.0);
+        assert_eq!(empty.total_word_count, 0);
+    }
+
     #[test]
     fn test_position_validation() {
         let mut graph = Graph::new();
@@ -672,4 +2314,609 @@ mod tests {
         child3.position = Point::new(200.0, 200.0); // Safe distance
         assert!(graph.add_node_with_validation(child3).is_ok());
     }
-}
\ No newline at end of file
+
+    fn build_topic_graph(root_text: &str, child_texts: &[&str]) -> Graph {
+        let mut graph = Graph::new();
+        let root = Node::new(root_text);
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+        for text in child_texts {
+            graph.add_node(Node::new_child(root_id, *text)).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn test_merge_with_identity_by_normalized_text() {
+        let mut graph = build_topic_graph("Project", &["Design", "Testing"]);
+        let other = build_topic_graph("  project ", &["design", "Release"]);
+
+        let conflicts = graph.find_merge_conflicts(&other, &NodeIdentity::NormalizedText);
+        assert_eq!(conflicts.len(), 2);
+
+        let mapping = graph.merge_graph_with_identity(&other, &NodeIdentity::NormalizedText).unwrap();
+        assert_eq!(mapping.len(), 3);
+
+        // "Project" and "Design" collapse, only "Release" is new
+        assert_eq!(graph.node_count(), 4);
+        let roots = graph.get_root_nodes();
+        assert_eq!(roots.len(), 1);
+        let release = graph.nodes().find(|n| n.text == "Release").unwrap();
+        assert_eq!(release.parent_id, Some(roots[0].id));
+    }
+
+    #[test]
+    fn test_merge_with_identity_by_id() {
+        let mut graph = build_topic_graph("Project", &["Design", "Testing"]);
+        let other = build_topic_graph("Project", &["Design", "Release"]);
+
+        assert!(graph.find_merge_conflicts(&other, &NodeIdentity::Id).is_empty());
+
+        graph.merge_graph_with_identity(&other, &NodeIdentity::Id).unwrap();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.get_root_nodes().len(), 2);
+    }
+
+    #[test]
+    fn test_find_duplicate_texts() {
+        let graph = build_topic_graph("Root", &["Idea", "idea ", "Other", "IDEA"]);
+
+        assert!(graph.find_duplicate_texts(&NodeIdentity::Id).is_empty());
+
+        let duplicates = graph.find_duplicate_texts(&NodeIdentity::NormalizedText);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].len(), 3);
+
+        // Custom key: group by text length
+        let by_length = NodeIdentity::custom(|node| node.text.trim().len().to_string());
+        let duplicates = graph.find_duplicate_texts(&by_length);
+        assert_eq!(duplicates.len(), 1); // "Root", "Idea", "idea ", "IDEA" all have length 4
+        assert_eq!(duplicates[0].len(), 4);
+    }
+
+    #[test]
+    fn test_undo_stack_restores_snapshots() {
+        let mut graph = Graph::new();
+        let mut stack = UndoStack::new();
+        assert!(!stack.can_undo());
+        assert!(!stack.can_redo());
+
+        let root_id = stack.add_node(&mut graph, Node::new("Root")).unwrap();
+        let child_id = stack.add_node(&mut graph, Node::new_child(root_id, "Child")).unwrap();
+        let other_id = stack.add_node(&mut graph, Node::new_child(root_id, "Other")).unwrap();
+        let snapshot = graph.clone();
+
+        let edge = Edge::new_with_label(child_id, other_id, "relates");
+        let edge_id = stack.add_edge(&mut graph, edge).unwrap();
+        stack.add_edge(&mut graph, Edge::new(other_id, child_id)).unwrap();
+        stack.update_node_text(&mut graph, child_id, "Renamed").unwrap();
+        stack.update_node_position(&mut graph, child_id, Point::new(10.0, 20.0)).unwrap();
+        stack.remove_edge(&mut graph, edge_id).unwrap();
+        stack.remove_node(&mut graph, other_id).unwrap();
+        let edited = graph.clone();
+
+        assert!(!graph.contains_node(other_id));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(stack.undo_len(), 9);
+
+        // Undoing the node removal restores its incident edges
+        stack.undo(&mut graph).unwrap();
+        assert!(graph.contains_node(other_id));
+        assert_eq!(graph.edge_count(), 1);
+
+        for _ in 0..5 {
+            stack.undo(&mut graph).unwrap();
+        }
+        assert_eq!(graph, snapshot);
+        assert_eq!(stack.redo_len(), 6);
+
+        // Redoing everything returns to the edited state
+        while stack.can_redo() {
+            stack.redo(&mut graph).unwrap();
+        }
+        assert_eq!(graph, edited);
+
+        while stack.can_undo() {
+            stack.undo(&mut graph).unwrap();
+        }
+        assert!(graph.is_empty());
+        assert!(stack.undo(&mut graph).is_err());
+    }
+
+    #[test]
+    fn test_undo_batch_is_one_step() {
+        let mut graph = Graph::new();
+        let mut stack = UndoStack::new();
+        let root_id = stack.add_node(&mut graph, Node::new("Root")).unwrap();
+        let snapshot = graph.clone();
+
+        let added: Vec<UndoCommand> = ["A", "B", "C"].into_iter()
+            .map(|text| {
+                let node_id = graph.add_node(Node::new_child(root_id, text)).unwrap();
+                UndoCommand::AddNode(graph.get_node(node_id).unwrap().clone())
+            })
+            .collect();
+        stack.push(UndoCommand::Batch(added));
+        let edited = graph.clone();
+        assert_eq!(stack.undo_len(), 2);
+
+        stack.undo(&mut graph).unwrap();
+        assert_eq!(graph, snapshot);
+
+        stack.redo(&mut graph).unwrap();
+        assert_eq!(graph, edited);
+        let texts: Vec<String> = graph.get_children(root_id).iter().map(|n| n.text.clone()).collect();
+        assert_eq!(texts, vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_undo_subtree_removal_restores_nodes_and_edges() {
+        let mut graph = Graph::new();
+        let mut stack = UndoStack::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let outside_id = graph.add_node(Node::new_child(root_id, "Outside")).unwrap();
+        let child_id = graph.add_node(Node::new_child(root_id, "Child")).unwrap();
+        let first_id = graph.add_node(Node::new_child(child_id, "First")).unwrap();
+        let second_id = graph.add_node(Node::new_child(child_id, "Second")).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(first_id, "Leaf")).unwrap();
+        graph.add_edge(Edge::new(leaf_id, outside_id)).unwrap();
+        graph.add_edge(Edge::new(second_id, first_id)).unwrap();
+        let snapshot = graph.clone();
+
+        let removed: Vec<NodeId> = stack.remove_subtree(&mut graph, child_id).unwrap()
+            .iter().map(|node| node.id).collect();
+        assert_eq!(removed.len(), 4);
+        assert_eq!(removed.last(), Some(&child_id));
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+        let edited = graph.clone();
+
+        // The whole subtree comes back in one step
+        assert_eq!(stack.undo_len(), 1);
+        stack.undo(&mut graph).unwrap();
+        assert_eq!(graph, snapshot);
+        let children: Vec<NodeId> = graph.get_children(child_id).iter().map(|n| n.id).collect();
+        assert_eq!(children, vec![first_id, second_id]);
+
+        stack.redo(&mut graph).unwrap();
+        assert_eq!(graph, edited);
+
+        assert!(stack.remove_subtree(&mut graph, child_id).is_err());
+        assert_eq!(stack.undo_len(), 1);
+    }
+
+    #[test]
+    fn test_undo_stack_limit_and_redo_reset() {
+        let mut graph = Graph::new();
+        let mut stack = UndoStack::with_limit(2);
+
+        let root_id = stack.add_node(&mut graph, Node::new("Root")).unwrap();
+        stack.update_node_text(&mut graph, root_id, "One").unwrap();
+        stack.update_node_text(&mut graph, root_id, "Two").unwrap();
+        assert_eq!(stack.undo_len(), 2);
+
+        stack.undo(&mut graph).unwrap();
+        stack.undo(&mut graph).unwrap();
+        assert!(!stack.can_undo());
+        assert_eq!(graph.get_node(root_id).unwrap().text, "Root");
+
+        // A new edit discards the redo history
+        assert!(stack.can_redo());
+        stack.update_node_text(&mut graph, root_id, "Three").unwrap();
+        assert!(!stack.can_redo());
+        assert!(stack.redo(&mut graph).is_err());
+    }
+
+    #[test]
+    fn test_undo_stack_memory_budget() {
+        let mut graph = Graph::new();
+        let mut stack = UndoStack::with_limit(0);
+        stack.set_retention(LogRetention { max_bytes: 64 * 1024, min_entries: 2 });
+
+        let root_id = stack.add_node(&mut graph, Node::new("Root")).unwrap();
+        for i in 0..200 {
+            let text = format!("{} {}", i, "x".repeat(4096));
+            stack.add_node(&mut graph, Node::new_child(root_id, text)).unwrap();
+        }
+
+        // Large snapshots are dropped once the history exceeds its budget
+        assert!(stack.estimated_bytes() <= 64 * 1024);
+        assert!(stack.undo_len() >= 2 && stack.undo_len() < 200);
+        while stack.can_undo() {
+            stack.undo(&mut graph).unwrap();
+        }
+        assert!(graph.node_count() > 1);
+    }
+
+    #[test]
+    fn test_demote_and_promote_node() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let mut first = Node::new_child(root_id, "First");
+        first.set_order(0);
+        let mut second = Node::new_child(root_id, "Second");
+        second.set_order(1);
+        let mut third = Node::new_child(root_id, "Third");
+        third.set_order(2);
+        let (first_id, second_id, third_id) = (first.id, second.id, third.id);
+        graph.add_node(first).unwrap();
+        graph.add_node(second).unwrap();
+        graph.add_node(third).unwrap();
+        graph.add_edge(Edge::new(root_id, second_id)).unwrap();
+
+        // The first child has no previous sibling and a root has no parent
+        assert!(graph.demote_node(first_id).is_err());
+        assert!(graph.promote_node(root_id).is_err());
+
+        // Demoting moves the node under its previous sibling, edge included
+        graph.demote_node(second_id).unwrap();
+        assert_eq!(graph.get_node(second_id).unwrap().parent_id, Some(first_id));
+        assert!(graph.has_edge_between(first_id, second_id));
+        assert!(!graph.has_edge_between(root_id, second_id));
+        let root_children: Vec<NodeId> = graph.get_children(root_id).iter().map(|n| n.id).collect();
+        assert_eq!(root_children, vec![first_id, third_id]);
+
+        // Promoting makes it the next sibling of its former parent
+        graph.promote_node(second_id).unwrap();
+        assert_eq!(graph.get_node(second_id).unwrap().parent_id, Some(root_id));
+        assert!(graph.has_edge_between(root_id, second_id));
+        assert!(graph.get_children(first_id).is_empty());
+        let root_children: Vec<NodeId> = graph.get_children(root_id).iter().map(|n| n.id).collect();
+        assert_eq!(root_children, vec![first_id, second_id, third_id]);
+    }
+
+    #[test]
+    fn test_demote_root_picks_previous_root_deterministically() {
+        let start = chrono::Utc::now();
+        let mut graph = Graph::new();
+        let mut ids = Vec::new();
+        for (offset, text) in [(2, "C"), (0, "A"), (1, "B")] {
+            let mut root = Node::new(text);
+            root.created_at = start + chrono::Duration::seconds(offset);
+            ids.push(graph.add_node(root).unwrap());
+        }
+        let (c_id, a_id, b_id) = (ids[0], ids[1], ids[2]);
+
+        // All roots share order 0, so creation time decides which comes first
+        assert!(graph.demote_node(a_id).is_err());
+        graph.demote_node(c_id).unwrap();
+        assert_eq!(graph.get_node(c_id).unwrap().parent_id, Some(b_id));
+        graph.demote_node(b_id).unwrap();
+        assert_eq!(graph.get_node(b_id).unwrap().parent_id, Some(a_id));
+    }
+
+    #[test]
+    fn test_rejected_promotion_leaves_siblings_alone() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let first_id = graph.add_node(Node::new_child(root_id, "First")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Second")).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(first_id, "Leaf")).unwrap();
+        graph.set_max_children_per_node(Some(2));
+
+        let snapshot = graph.clone();
+        assert!(graph.promote_node(leaf_id).is_err());
+        assert_eq!(graph, snapshot);
+    }
+
+    #[test]
+    fn test_move_subtree() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let branch_id = graph.add_node(Node::new_child(root_id, "Branch")).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(branch_id, "Leaf")).unwrap();
+        let deep_id = graph.add_node(Node::new_child(leaf_id, "Deep")).unwrap();
+        let other_id = graph.add_node(Node::new_child(root_id, "Other")).unwrap();
+        graph.add_edge(Edge::new(root_id, branch_id)).unwrap();
+        graph.add_edge(Edge::new(branch_id, leaf_id)).unwrap();
+
+        // Moving a node under itself or its own descendant is a cycle
+        assert!(graph.move_subtree(branch_id, Some(branch_id)).is_err());
+        assert!(graph.move_subtree(branch_id, Some(deep_id)).is_err());
+        assert_eq!(graph.get_node(branch_id).unwrap().parent_id, Some(root_id));
+
+        // Descendants follow the moved node without being touched
+        graph.move_subtree(branch_id, Some(other_id)).unwrap();
+        assert_eq!(graph.get_node(branch_id).unwrap().parent_id, Some(other_id));
+        assert_eq!(graph.get_node(leaf_id).unwrap().parent_id, Some(branch_id));
+        assert_eq!(graph.get_node(deep_id).unwrap().parent_id, Some(leaf_id));
+        assert!(graph.has_edge_between(other_id, branch_id));
+        assert!(!graph.has_edge_between(root_id, branch_id));
+        assert!(graph.has_edge_between(branch_id, leaf_id));
+
+        // Without a new parent the node becomes a root and loses its parent edge
+        graph.move_subtree(branch_id, None).unwrap();
+        assert!(graph.get_node(branch_id).unwrap().is_root());
+        assert!(graph.get_incoming_edges(branch_id).is_empty());
+        assert_eq!(graph.get_root_nodes().len(), 2);
+        assert_eq!(graph.get_descendants(branch_id).len(), 2);
+    }
+
+    #[test]
+    fn test_max_children_per_node() {
+        let mut graph = Graph::new();
+        assert_eq!(graph.max_children_per_node(), None);
+        graph.set_max_children_per_node(Some(2));
+
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let first_id = graph.add_node(Node::new_child(root_id, "First")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Second")).unwrap();
+
+        // A third child is over the limit
+        let result = graph.add_node(Node::new_child(root_id, "Third"));
+        assert!(matches!(result, Err(MindmapError::InvalidOperation { .. })));
+        assert_eq!(graph.get_children(root_id).len(), 2);
+
+        // Updating an existing child does not count it twice
+        let mut first = graph.get_node(first_id).unwrap().clone();
+        first.set_text("First (edited)");
+        graph.update_node(first).unwrap();
+
+        // Moving a node under a full parent is rejected and leaves it in place
+        let loose_id = graph.add_node(Node::new("Loose")).unwrap();
+        let result = graph.move_subtree(loose_id, Some(root_id));
+        assert!(matches!(result, Err(MindmapError::InvalidOperation { .. })));
+        assert!(graph.get_node(loose_id).unwrap().is_root());
+
+        // Moving within capacity still works
+        graph.move_subtree(loose_id, Some(first_id)).unwrap();
+        assert_eq!(graph.get_node(loose_id).unwrap().parent_id, Some(first_id));
+
+        // Bulk inserts are checked against the limit as a whole
+        let batch = vec![Node::new_child(loose_id, "A"), Node::new_child(loose_id, "B"), Node::new_child(loose_id, "C")];
+        assert!(graph.add_nodes_bulk(batch).is_err());
+        assert!(graph.get_children(loose_id).is_empty());
+
+        graph.set_max_children_per_node(None);
+        graph.add_node(Node::new_child(root_id, "Third")).unwrap();
+        assert_eq!(graph.get_children(root_id).len(), 3);
+    }
+
+    #[test]
+    fn test_add_nodes_bulk_inserts_parents_first() {
+        let mut graph = Graph::new();
+        let existing_id = graph.add_node(Node::new("Existing")).unwrap();
+
+        // Build 10k nodes in a wide tree and hand them over children first
+        let root = Node::new("Root");
+        let mut nodes = vec![root.clone()];
+        let mut parents = vec![root.id];
+        while nodes.len() < 10_000 {
+            let parent_id = parents[nodes.len() / 8];
+            let node = Node::new_child(parent_id, format!("Node {}", nodes.len()));
+            parents.push(node.id);
+            nodes.push(node);
+        }
+        nodes.push(Node::new_child(existing_id, "Attached"));
+        nodes.reverse();
+
+        let started = std::time::Instant::now();
+        let ids = graph.add_nodes_bulk(nodes).unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(ids.len(), 10_001);
+        assert_eq!(graph.node_count(), 10_002);
+        assert!(graph.validate().is_ok());
+        assert!(elapsed < std::time::Duration::from_secs(5), "bulk insert took {:?}", elapsed);
+
+        // Every node comes after its parent in the returned order
+        let position: HashMap<NodeId, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        for id in &ids {
+            if let Some(parent_id) = graph.get_node(*id).unwrap().parent_id {
+                if let Some(parent_position) = position.get(&parent_id) {
+                    assert!(parent_position < &position[id]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_nodes_bulk_rolls_back_on_error() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new("Existing")).unwrap();
+        let snapshot = graph.clone();
+
+        let root = Node::new("Root");
+        let child = Node::new_child(root.id, "Child");
+        let orphan = Node::new_child(NodeId::new(), "Orphan");
+        let orphan_id = orphan.id;
+
+        let result = graph.add_nodes_bulk(vec![root.clone(), child.clone(), orphan]);
+        match result {
+            Err(MindmapError::InvalidOperation { message }) => assert!(message.contains(&orphan_id.to_string())),
+            other => panic!("expected missing parent error, got {:?}", other),
+        }
+        assert_eq!(graph, snapshot);
+
+        // Duplicates and cycles are rejected as well
+        assert!(graph.add_nodes_bulk(vec![root.clone(), root.clone()]).is_err());
+        let mut a = Node::new("A");
+        let mut b = Node::new("B");
+        a.parent_id = Some(b.id);
+        b.parent_id = Some(a.id);
+        assert!(graph.add_nodes_bulk(vec![root, child, a, b]).is_err());
+        assert_eq!(graph, snapshot);
+    }
+
+    #[test]
+    fn test_merge_nodes_metadata_policies() {
+        let build = || {
+            let mut graph = Graph::new();
+            let root_id = graph.add_node(Node::new("Root")).unwrap();
+            let mut left = Node::new_child(root_id, "Topic");
+            left.set_metadata("owner", "alice");
+            left.set_metadata("status", "draft");
+            left.tags = vec!["ai".to_string()];
+            let mut right = Node::new_child(root_id, "topic");
+            right.set_metadata("owner", "bob");
+            right.set_metadata("due", "friday");
+            right.tags = vec!["AI".to_string(), "ml".to_string()];
+            let left_id = graph.add_node(left).unwrap();
+            let right_id = graph.add_node(right).unwrap();
+            let child_id = graph.add_node(Node::new_child(right_id, "Detail")).unwrap();
+            graph.add_edge(Edge::new(root_id, right_id)).unwrap();
+            (graph, root_id, left_id, right_id, child_id)
+        };
+
+        let merged_metadata = |policy| {
+            let (mut graph, _, left_id, right_id, _) = build();
+            graph.merge_nodes(left_id, right_id, policy).map(|_| graph.get_node(left_id).unwrap().metadata.clone())
+        };
+
+        let union = merged_metadata(MetadataMergePolicy::Union).unwrap();
+        assert_eq!(union.len(), 3);
+        assert_eq!(union["owner"], "alice; bob");
+        assert_eq!(union["status"], "draft");
+        assert_eq!(union["due"], "friday");
+
+        let left = merged_metadata(MetadataMergePolicy::PreferLeft).unwrap();
+        assert_eq!(left["owner"], "alice");
+        assert_eq!(left["due"], "friday");
+
+        let right = merged_metadata(MetadataMergePolicy::PreferRight).unwrap();
+        assert_eq!(right["owner"], "bob");
+        assert_eq!(right["status"], "draft");
+
+        // A rejected conflict leaves the graph untouched
+        let (mut graph, _, left_id, right_id, _) = build();
+        let snapshot = graph.clone();
+        assert!(graph.merge_nodes(left_id, right_id, MetadataMergePolicy::ErrorOnConflict).is_err());
+        assert_eq!(graph, snapshot);
+
+        // Structure moves over to the kept node
+        let (mut graph, root_id, left_id, right_id, child_id) = build();
+        graph.merge_nodes(left_id, right_id, MetadataMergePolicy::default()).unwrap();
+        assert!(!graph.contains_node(right_id));
+        assert_eq!(graph.get_node(child_id).unwrap().parent_id, Some(left_id));
+        assert!(graph.has_edge_between(root_id, left_id));
+        assert_eq!(graph.get_node(left_id).unwrap().tags, vec!["ai".to_string(), "ml".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_nodes_over_child_limit_changes_nothing() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let keep_id = graph.add_node(Node::new_child(root_id, "Keep")).unwrap();
+        let mut merged = Node::new_child(root_id, "Merge");
+        merged.tags = vec!["extra".to_string()];
+        let merge_id = graph.add_node(merged).unwrap();
+        graph.add_node(Node::new_child(keep_id, "Kept child")).unwrap();
+        for text in ["First", "Second"] {
+            graph.add_node(Node::new_child(merge_id, text)).unwrap();
+        }
+        graph.set_max_children_per_node(Some(2));
+
+        let snapshot = graph.clone();
+        assert!(graph.merge_nodes(keep_id, merge_id, MetadataMergePolicy::default()).is_err());
+        assert_eq!(graph, snapshot);
+
+        // Merging a node into its own parent frees the slot it took
+        graph.set_max_children_per_node(Some(3));
+        graph.merge_nodes(root_id, merge_id, MetadataMergePolicy::default()).unwrap();
+        assert_eq!(graph.get_children(root_id).len(), 3);
+    }
+
+    #[test]
+    fn test_merge_siblings_combines_children() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let a = graph.add_node(Node::new_child(root_id, "Apples")).unwrap();
+        let b = graph.add_node(Node::new_child(root_id, "Pears")).unwrap();
+        let mut children = Vec::new();
+        for (parent_id, text) in [(a, "Red"), (a, "Green"), (b, "Conference"), (b, "Bosc")] {
+            let child_id = graph.add_node(Node::new_child(parent_id, text)).unwrap();
+            graph.add_edge(Edge::new(parent_id, child_id)).unwrap();
+            children.push(child_id);
+        }
+        graph.add_edge(Edge::new(root_id, a)).unwrap();
+        graph.add_edge(Edge::new(root_id, b)).unwrap();
+        graph.get_node_mut(a).unwrap().add_tag("fruit");
+        graph.get_node_mut(b).unwrap().add_tag("pome");
+
+        graph.merge_siblings(a, b).unwrap();
+
+        assert!(!graph.contains_node(b));
+        let survivor = graph.get_node(a).unwrap();
+        assert_eq!(survivor.text, "Apples Pears");
+        assert!(survivor.tags.contains(&"fruit".to_string()) && survivor.tags.contains(&"pome".to_string()));
+
+        let child_ids: HashSet<NodeId> = graph.get_children(a).iter().map(|child| child.id).collect();
+        assert_eq!(child_ids, children.iter().copied().collect());
+
+        // One edge from the root plus one per child, none pointing at the removed node
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 5);
+        assert!(graph.edges().all(|edge| edge.from_node != b && edge.to_node != b));
+        assert!(children.iter().all(|&child| graph.has_edge_between(a, child)));
+        assert!(graph.validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_siblings_rejects_non_siblings_and_conflicts() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let a = graph.add_node(Node::new_child(root_id, "A")).unwrap();
+        let b = graph.add_node(Node::new_child(root_id, "B")).unwrap();
+        let nested = graph.add_node(Node::new_child(a, "Nested")).unwrap();
+        assert!(graph.merge_siblings(a, nested).is_err());
+
+        graph.get_node_mut(a).unwrap().set_metadata("owner", "ann");
+        graph.get_node_mut(b).unwrap().set_metadata("owner", "bo");
+        graph.set_sibling_merge_policy(SiblingMergePolicy {
+            text_separator: " / ".to_string(),
+            metadata: MetadataMergePolicy::ErrorOnConflict,
+        });
+        assert!(graph.merge_siblings(a, b).is_err());
+        assert_eq!(graph.get_node(a).unwrap().text, "A");
+        assert!(graph.contains_node(b));
+
+        graph.set_sibling_merge_policy(SiblingMergePolicy {
+            text_separator: " / ".to_string(),
+            metadata: MetadataMergePolicy::PreferLeft,
+        });
+        graph.merge_siblings(a, b).unwrap();
+        let survivor = graph.get_node(a).unwrap();
+        assert_eq!(survivor.text, "A / B");
+        assert_eq!(survivor.metadata["owner"], "ann");
+    }
+
+    #[test]
+    fn test_mutation_paths_advance_updated_at() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("Draft")).unwrap();
+
+        // Each mutation must land on a later timestamp than the previous one
+        let mut last = graph.get_node(node_id).unwrap().updated_at;
+        let mut assert_advanced = |graph: &Graph, path: &str| {
+            let updated_at = graph.get_node(node_id).unwrap().updated_at;
+            assert!(updated_at > last, "{} did not advance updated_at", path);
+            last = updated_at;
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        };
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        let patch = NodePatch { text: Some("Final".to_string()), ..NodePatch::default() };
+        graph.patch_node(node_id, patch).unwrap();
+        assert_advanced(&graph, "patch_node");
+
+        graph.get_node_mut(node_id).unwrap().set_tags(vec!["review".to_string()]);
+        assert_advanced(&graph, "set_tags");
+
+        graph.get_node_mut(node_id).unwrap().set_collapsed(true);
+        assert_advanced(&graph, "set_collapsed");
+
+        graph.get_node_mut(node_id).unwrap().set_position(Point::new(40.0, 20.0));
+        assert_advanced(&graph, "set_position");
+
+        let mut history = UndoStack::new();
+        history.update_node_position(&mut graph, node_id, Point::new(80.0, 20.0)).unwrap();
+        assert_advanced(&graph, "UndoStack::update_node_position");
+
+        // Invalid patches leave the node, and its timestamp, alone
+        let empty = NodePatch { text: Some(String::new()), ..NodePatch::default() };
+        assert!(graph.patch_node(node_id, empty).is_err());
+        let node = graph.get_node(node_id).unwrap();
+        assert_eq!(node.text, "Final");
+        assert_eq!(node.updated_at, last);
+    }
+}
@@ -0,0 +1,290 @@
+//! Graph serialization
+//!
+//! This module converts graphs to and from a JSON representation. Node
+//! positions can optionally be stored as fixed-point integers, which keeps
+//! stored documents and exports considerably smaller than full `f64` output
+//! while staying precise enough for rendering.
+//!
+//! Every payload carries a format version. Payloads from older versions are
+//! migrated on load; payloads from newer versions are rejected with a
+//! [`MindmapError::ParseError`] instead of being misread.
+
+use super::Graph;
+use crate::types::{MindmapError, MindmapResult, PositionEncoding};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Format version written by [`Graph::to_json_string`]
+///
+/// Version history:
+/// - 0: the bare graph object, without an envelope
+/// - 1: envelope with `position_encoding` and `graph`, but no version field
+/// - 2: envelope with an explicit `format_version`
+pub const GRAPH_FORMAT_VERSION: u32 = 2;
+
+/// Options controlling how a graph is serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SerializationOptions {
+    /// Encoding used for node position coordinates
+    pub position_encoding: PositionEncoding,
+}
+
+impl SerializationOptions {
+    /// Store node positions with the given encoding
+    pub fn with_position_encoding(mut self, encoding: PositionEncoding) -> Self {
+        self.position_encoding = encoding;
+        self
+    }
+}
+
+/// Serialized graph together with the encoding needed to read it back
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedGraph {
+    /// Format version of this payload (payloads without one are version 1)
+    #[serde(default = "legacy_format_version")]
+    format_version: u32,
+    /// Encoding used for the node positions in `graph`
+    #[serde(default)]
+    position_encoding: PositionEncoding,
+    /// Graph data
+    graph: Value,
+}
+
+impl Graph {
+    /// Serialize the graph to a JSON string
+    pub fn to_json_string(&self, options: &SerializationOptions) -> MindmapResult<String> {
+        let mut graph = serde_json::to_value(self).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Failed to serialize graph: {}", e),
+        })?;
+
+        let encoding = options.position_encoding;
+        if encoding.is_fixed_point() {
+            for_each_position(&mut graph, |coordinate| {
+                coordinate.as_f64().map(|value| Value::from(encoding.encode(value)))
+            });
+        }
+
+        let serialized = SerializedGraph {
+            format_version: GRAPH_FORMAT_VERSION,
+            position_encoding: encoding,
+            graph,
+        };
+
+        serde_json::to_string(&serialized).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Failed to serialize graph: {}", e),
+        })
+    }
+
+    /// Deserialize a graph produced by [`Graph::to_json_string`]
+    ///
+    /// Older format versions are migrated; newer ones are rejected.
+    pub fn from_json_string(data: &str) -> MindmapResult<Graph> {
+        let payload: Value = serde_json::from_str(data).map_err(|e| MindmapError::ParseError {
+            message: format!("Invalid graph data: {}", e),
+        })?;
+        let mut serialized = migrate_payload(payload)?;
+
+        let encoding = serialized.position_encoding;
+        if encoding.is_fixed_point() {
+            for_each_position(&mut serialized.graph, |coordinate| {
+                coordinate.as_i64().map(|value| Value::from(encoding.decode(value)))
+            });
+        }
+
+        serde_json::from_value(serialized.graph).map_err(|e| MindmapError::ParseError {
+            message: format!("Invalid graph data: {}", e),
+        })
+    }
+
+    /// Serialize the graph to bytes (UTF-8 encoded JSON, see [`Graph::to_json_string`])
+    pub fn to_bytes(&self, options: &SerializationOptions) -> MindmapResult<Vec<u8>> {
+        self.to_json_string(options).map(String::into_bytes)
+    }
+
+    /// Deserialize a graph produced by [`Graph::to_bytes`]
+    pub fn from_bytes(data: &[u8]) -> MindmapResult<Graph> {
+        let data = std::str::from_utf8(data).map_err(|e| MindmapError::ParseError {
+            message: format!("Invalid graph data: {}", e),
+        })?;
+        Self::from_json_string(data)
+    }
+}
+
+/// Version assumed for envelopes written before the version field existed
+fn legacy_format_version() -> u32 {
+    1
+}
+
+/// Detect the format version of a payload and upgrade it to the current envelope
+fn migrate_payload(payload: Value) -> MindmapResult<SerializedGraph> {
+    let is_envelope = payload.get("graph").is_some();
+    let version = if is_envelope {
+        payload.get("format_version").map_or(Some(legacy_format_version()), |version| {
+            version.as_u64().and_then(|version| u32::try_from(version).ok())
+        })
+        .ok_or_else(|| MindmapError::ParseError {
+            message: "Invalid graph data: format_version must be a non-negative integer".to_string(),
+        })?
+    } else {
+        0
+    };
+
+    if version > GRAPH_FORMAT_VERSION {
+        return Err(MindmapError::ParseError {
+            message: format!(
+                "Unsupported graph format version {} (newest supported is {})",
+                version, GRAPH_FORMAT_VERSION
+            ),
+        });
+    }
+
+    let mut serialized = if is_envelope {
+        serde_json::from_value(payload).map_err(|e| MindmapError::ParseError {
+            message: format!("Invalid graph data: {}", e),
+        })?
+    } else {
+        // Version 0: the bare graph with floating point positions
+        SerializedGraph {
+            format_version: 0,
+            position_encoding: PositionEncoding::default(),
+            graph: payload,
+        }
+    };
+
+    // Versions 0 and 1 only differ from the current format in the envelope,
+    // which is normalized above
+    serialized.format_version = GRAPH_FORMAT_VERSION;
+    Ok(serialized)
+}
+
+/// Rewrite the `x`/`y` coordinates of every node position in a serialized graph
+fn for_each_position<F>(graph: &mut Value, convert: F)
+where
+    F: Fn(&Value) -> Option<Value>,
+{
+    let Some(nodes) = graph.get_mut("nodes").and_then(Value::as_object_mut) else {
+        return;
+    };
+
+    for node in nodes.values_mut() {
+        let Some(position) = node.get_mut("position").and_then(Value::as_object_mut) else {
+            continue;
+        };
+
+        for axis in ["x", "y"] {
+            if let Some(coordinate) = position.get_mut(axis) {
+                if let Some(converted) = convert(coordinate) {
+                    *coordinate = converted;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Node;
+    use crate::types::Point;
+
+    fn build_positioned_graph() -> Graph {
+        let mut graph = Graph::new();
+        let mut root = Node::new("Root");
+        root.set_position(Point::new(0.123456789, -10.987654321));
+        let root_id = graph.add_node(root).unwrap();
+
+        for i in 0..20 {
+            let mut child = Node::new_child(root_id, format!("Child {}", i));
+            child.position.x = 100.0 / 3.0 * i as f64;
+            child.position.y = -200.0 / 7.0 * i as f64 + 0.000123;
+            graph.add_node(child).unwrap();
+        }
+
+        graph
+    }
+
+    #[test]
+    fn test_float_round_trip() {
+        let graph = build_positioned_graph();
+        let data = graph.to_json_string(&SerializationOptions::default()).unwrap();
+        let restored = Graph::from_json_string(&data).unwrap();
+
+        assert_eq!(restored.node_count(), graph.node_count());
+        for node in graph.nodes() {
+            let reloaded = restored.get_node(node.id).unwrap();
+            assert_eq!(reloaded.parent_id, node.parent_id);
+            assert!((reloaded.position.x - node.position.x).abs() < 1e-9);
+            assert!((reloaded.position.y - node.position.y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_positions_shrink_output() {
+        let graph = build_positioned_graph();
+        let float_data = graph.to_json_string(&SerializationOptions::default()).unwrap();
+
+        for encoding in [PositionEncoding::integer(), PositionEncoding::fixed_point(2)] {
+            let options = SerializationOptions::default().with_position_encoding(encoding);
+            let data = graph.to_json_string(&options).unwrap();
+            assert!(data.len() < float_data.len());
+
+            let restored = Graph::from_json_string(&data).unwrap();
+            assert_eq!(restored.node_count(), graph.node_count());
+
+            for node in graph.nodes() {
+                let reloaded = restored.get_node(node.id).unwrap();
+                assert!((reloaded.position.x - node.position.x).abs() <= encoding.precision());
+                assert!((reloaded.position.y - node.position.y).abs() <= encoding.precision());
+                assert_eq!(reloaded.text, node.text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_data_is_parse_error() {
+        let result = Graph::from_json_string("not json");
+        assert!(matches!(result, Err(MindmapError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_current_version_payload_loads() {
+        let graph = build_positioned_graph();
+        let data = graph.to_bytes(&SerializationOptions::default()).unwrap();
+
+        let payload: Value = serde_json::from_slice(&data).unwrap();
+        assert_eq!(payload["format_version"], GRAPH_FORMAT_VERSION);
+
+        let restored = Graph::from_bytes(&data).unwrap();
+        assert_eq!(restored.node_count(), graph.node_count());
+    }
+
+    #[test]
+    fn test_future_version_is_rejected() {
+        let graph = build_positioned_graph();
+        let mut payload: Value = serde_json::from_str(&graph.to_json_string(&SerializationOptions::default()).unwrap()).unwrap();
+        payload["format_version"] = Value::from(GRAPH_FORMAT_VERSION + 1);
+
+        match Graph::from_json_string(&payload.to_string()) {
+            Err(MindmapError::ParseError { message }) => {
+                assert!(message.contains("Unsupported graph format version"), "{}", message);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_older_versions_are_migrated() {
+        let graph = build_positioned_graph();
+
+        // Version 1: envelope without a version field
+        let mut payload: Value = serde_json::from_str(&graph.to_json_string(&SerializationOptions::default()).unwrap()).unwrap();
+        payload.as_object_mut().unwrap().remove("format_version");
+        let restored = Graph::from_json_string(&payload.to_string()).unwrap();
+        assert_eq!(restored.node_count(), graph.node_count());
+
+        // Version 0: the bare graph
+        let bare = serde_json::to_string(&graph).unwrap();
+        let restored = Graph::from_json_string(&bare).unwrap();
+        assert_eq!(restored.node_count(), graph.node_count());
+    }
+}
@@ -0,0 +1,213 @@
+//! Incrementally maintained graph statistics
+//!
+//! Subtree sizes feed UI badges such as "12 hidden nodes" on collapsed
+//! branches, and recomputing them on every frame is costly for large maps.
+//! `StatsCache` computes them once and then adjusts only the ancestors of
+//! nodes that are added, removed or moved.
+
+use crate::models::Node;
+use crate::types::ids::NodeId;
+use std::collections::{HashMap, HashSet};
+
+/// Cached per-node subtree sizes, kept in sync by the owning graph
+///
+/// Sizes follow parent links and count the node itself. Children whose
+/// parent is missing keep their own sizes and are counted again once the
+/// parent is re-added, e.g. by undo.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsCache {
+    /// Number of nodes in each node's subtree, including the node itself
+    subtree_sizes: HashMap<NodeId, usize>,
+    /// Parent each node is currently counted under
+    parents: HashMap<NodeId, NodeId>,
+    /// Children counted under each parent, including parents that are missing
+    children: HashMap<NodeId, HashSet<NodeId>>,
+    /// Nodes handed out through `Graph::get_node_mut` whose parent may have changed
+    stale: HashSet<NodeId>,
+    /// How many times the sizes were recomputed from scratch
+    full_recomputes: usize,
+}
+
+impl StatsCache {
+    /// Build the cache from scratch for the given nodes
+    pub(crate) fn build<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Self {
+        let mut cache = Self::default();
+        cache.recompute(nodes);
+        cache
+    }
+
+    /// Throw away the cached sizes and compute them again from the nodes
+    pub(crate) fn recompute<'a>(&mut self, nodes: impl IntoIterator<Item = &'a Node>) {
+        let nodes: Vec<&Node> = nodes.into_iter().collect();
+        self.parents = nodes.iter()
+            .filter_map(|node| node.parent_id.map(|parent_id| (node.id, parent_id)))
+            .collect();
+        self.children.clear();
+        for (&child_id, &parent_id) in &self.parents {
+            self.children.entry(parent_id).or_default().insert(child_id);
+        }
+        self.subtree_sizes = subtree_sizes(nodes.iter().map(|node| node.id), &self.children);
+        self.stale.clear();
+        self.full_recomputes += 1;
+    }
+
+    /// Number of nodes in the subtree rooted at `node_id`, including itself
+    pub fn subtree_size(&self, node_id: NodeId) -> Option<usize> {
+        self.subtree_sizes.get(&node_id).copied()
+    }
+
+    /// Number of descendants of `node_id`
+    pub fn descendant_count(&self, node_id: NodeId) -> Option<usize> {
+        self.subtree_size(node_id).map(|size| size - 1)
+    }
+
+    /// Number of nodes the cache knows about
+    pub fn node_count(&self) -> usize {
+        self.subtree_sizes.len()
+    }
+
+    /// How many times the sizes were recomputed from scratch
+    ///
+    /// Only enabling the cache and `Graph::clear` recompute; every other
+    /// mutation adjusts the affected ancestors.
+    pub fn full_recomputes(&self) -> usize {
+        self.full_recomputes
+    }
+
+    /// Nodes whose parent may have changed since the cache last saw them
+    pub fn stale_nodes(&self) -> &HashSet<NodeId> {
+        &self.stale
+    }
+
+    /// Record a node that was added or replaced, counted under `parent_id`
+    pub(crate) fn insert(&mut self, node_id: NodeId, parent_id: Option<NodeId>) {
+        if self.subtree_sizes.contains_key(&node_id) {
+            self.set_parent(node_id, parent_id);
+            return;
+        }
+
+        // Children left behind when the node was removed count again
+        let size = 1 + self.children.get(&node_id)
+            .map(|children| children.iter().filter_map(|child| self.subtree_sizes.get(child)).sum::<usize>())
+            .unwrap_or(0);
+        self.subtree_sizes.insert(node_id, size);
+        self.attach(node_id, parent_id);
+    }
+
+    /// Forget a removed node; its children keep their own subtrees
+    pub(crate) fn remove(&mut self, node_id: NodeId) {
+        if !self.subtree_sizes.contains_key(&node_id) {
+            return;
+        }
+        self.detach(node_id);
+        self.subtree_sizes.remove(&node_id);
+        self.stale.remove(&node_id);
+    }
+
+    /// Move a node, with its subtree, under a new parent
+    pub(crate) fn set_parent(&mut self, node_id: NodeId, parent_id: Option<NodeId>) {
+        if self.parents.get(&node_id).copied() == parent_id {
+            return;
+        }
+        self.detach(node_id);
+        self.attach(node_id, parent_id);
+    }
+
+    /// Remember that a node's parent may be changed behind the cache's back
+    pub(crate) fn mark_stale(&mut self, node_id: NodeId) {
+        self.stale.insert(node_id);
+    }
+
+    /// Take the nodes marked stale so their parents can be checked again
+    pub(crate) fn take_stale(&mut self) -> HashSet<NodeId> {
+        std::mem::take(&mut self.stale)
+    }
+
+    /// Stop counting a node under its current parent
+    fn detach(&mut self, node_id: NodeId) {
+        let Some(parent_id) = self.parents.remove(&node_id) else {
+            return;
+        };
+        if let Some(siblings) = self.children.get_mut(&parent_id) {
+            siblings.remove(&node_id);
+            if siblings.is_empty() {
+                self.children.remove(&parent_id);
+            }
+        }
+        let size = self.subtree_sizes[&node_id];
+        self.adjust_ancestors(node_id, parent_id, |total| total - size);
+    }
+
+    /// Start counting a node under `parent_id`
+    fn attach(&mut self, node_id: NodeId, parent_id: Option<NodeId>) {
+        let Some(parent_id) = parent_id else {
+            return;
+        };
+        self.parents.insert(node_id, parent_id);
+        self.children.entry(parent_id).or_default().insert(node_id);
+        let size = self.subtree_sizes[&node_id];
+        self.adjust_ancestors(node_id, parent_id, |total| total + size);
+    }
+
+    /// Apply `adjust` to `parent_id` and every ancestor above it
+    ///
+    /// Stops at a missing parent and at `node_id` itself, so a parent cycle
+    /// can't loop forever.
+    fn adjust_ancestors(&mut self, node_id: NodeId, parent_id: NodeId, adjust: impl Fn(usize) -> usize) {
+        let mut visited = HashSet::from([node_id]);
+        let mut current = Some(parent_id);
+        while let Some(ancestor_id) = current {
+            if !visited.insert(ancestor_id) {
+                break;
+            }
+            let Some(total) = self.subtree_sizes.get_mut(&ancestor_id) else {
+                break;
+            };
+            *total = adjust(*total);
+            current = self.parents.get(&ancestor_id).copied();
+        }
+    }
+}
+
+/// Subtree sizes for `node_ids` computed from a child index
+///
+/// Each node is counted once per ancestor; a child on a parent cycle is not
+/// counted again under itself.
+pub(crate) fn subtree_sizes(
+    node_ids: impl IntoIterator<Item = NodeId>,
+    children: &HashMap<NodeId, HashSet<NodeId>>,
+) -> HashMap<NodeId, usize> {
+    let node_ids: HashSet<NodeId> = node_ids.into_iter().collect();
+    let mut sizes: HashMap<NodeId, usize> = HashMap::with_capacity(node_ids.len());
+
+    for &start in &node_ids {
+        if sizes.contains_key(&start) {
+            continue;
+        }
+
+        // Post-order walk so every child is sized before its parent
+        let mut on_path = HashSet::from([start]);
+        let mut stack = vec![(start, false)];
+        while let Some((node_id, expanded)) = stack.pop() {
+            let child_ids = children.get(&node_id).into_iter().flatten()
+                .filter(|child_id| node_ids.contains(child_id));
+            if expanded {
+                let size = 1 + child_ids
+                    .filter_map(|child_id| sizes.get(child_id))
+                    .sum::<usize>();
+                sizes.insert(node_id, size);
+                on_path.remove(&node_id);
+                continue;
+            }
+
+            stack.push((node_id, true));
+            for &child_id in child_ids {
+                if !sizes.contains_key(&child_id) && on_path.insert(child_id) {
+                    stack.push((child_id, false));
+                }
+            }
+        }
+    }
+
+    sizes
+}
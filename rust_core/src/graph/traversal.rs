@@ -4,8 +4,10 @@
 //! depth-first search, breadth-first search, and path finding.
 
 use crate::graph::Graph;
+use crate::models::Node;
 use crate::types::ids::NodeId;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
 /// Result of a traversal operation
 #[derive(Debug, Clone, PartialEq)]
@@ -27,6 +29,30 @@ pub enum TraversalOrder {
     BreadthFirst,
 }
 
+/// Frontier entry for Dijkstra, ordered so the cheapest node pops first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WeightedVisit {
+    cost: f64,
+    node: NodeId,
+}
+
+impl Eq for WeightedVisit {}
+
+impl Ord for WeightedVisit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node.as_uuid().cmp(&self.node.as_uuid()))
+    }
+}
+
+impl PartialOrd for WeightedVisit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Graph {
     /// Perform a traversal starting from a given node
     pub fn traverse(&self, start: NodeId, order: TraversalOrder) -> Option<TraversalResult> {
@@ -162,11 +188,78 @@ impl Graph {
     }
 
     /// Find the shortest path between two nodes
+    ///
+    /// Edges are treated as undirected and unweighted. The path includes both
+    /// endpoints; `None` means the nodes are not connected.
     pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
         // For unweighted graphs, BFS gives the shortest path
         self.find_path(from, to)
     }
 
+    /// Number of edges on the shortest path between two nodes
+    pub fn path_length(&self, from: NodeId, to: NodeId) -> Option<usize> {
+        self.shortest_path(from, to).map(|path| path.len() - 1)
+    }
+
+    /// Find the cheapest path between two nodes using Dijkstra's algorithm
+    ///
+    /// Edges are treated as undirected and cost their `weight`, or 1.0 when no
+    /// weight is set. Negative weights are rejected when edges are added, and
+    /// any that slip through are skipped. Returns the path including both
+    /// endpoints together with its total cost.
+    pub fn weighted_shortest_path(&self, from: NodeId, to: NodeId) -> Option<(Vec<NodeId>, f64)> {
+        if !self.contains_node(from) || !self.contains_node(to) {
+            return None;
+        }
+        if from == to {
+            return Some((vec![from], 0.0));
+        }
+
+        let mut costs: HashMap<NodeId, f64> = HashMap::new();
+        let mut parents: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut settled = HashSet::new();
+        let mut frontier = BinaryHeap::new();
+
+        costs.insert(from, 0.0);
+        frontier.push(WeightedVisit { cost: 0.0, node: from });
+
+        while let Some(WeightedVisit { cost, node }) = frontier.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while let Some(&parent) = parents.get(&current) {
+                    path.push(parent);
+                    current = parent;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+
+            let outgoing = self.get_outgoing_edges(node).into_iter().map(|edge| (edge.to_node, edge));
+            let incoming = self.get_incoming_edges(node).into_iter().map(|edge| (edge.from_node, edge));
+
+            for (neighbor, edge) in outgoing.chain(incoming) {
+                let weight = edge.effective_weight();
+                if settled.contains(&neighbor) || !weight.is_finite() || weight < 0.0 {
+                    continue;
+                }
+
+                let next_cost = cost + weight;
+                if costs.get(&neighbor).is_none_or(|&known| next_cost < known) {
+                    costs.insert(neighbor, next_cost);
+                    parents.insert(neighbor, node);
+                    frontier.push(WeightedVisit { cost: next_cost, node: neighbor });
+                }
+            }
+        }
+
+        None
+    }
+
     /// Get all ancestors of a node (following parent relationships)
     pub fn get_ancestors(&self, node_id: NodeId) -> Vec<NodeId> {
         let mut ancestors = Vec::new();
@@ -180,8 +273,35 @@ impl Graph {
         ancestors
     }
 
+    /// Get the hierarchical path from the containing root down to a node
+    ///
+    /// The path follows `parent_id` links and includes both the root and the
+    /// node itself. Returns an empty path if the node does not exist.
+    pub fn root_path(&self, node_id: NodeId) -> Vec<NodeId> {
+        if !self.contains_node(node_id) {
+            return Vec::new();
+        }
+
+        let mut path = vec![node_id];
+        let mut visited: HashSet<NodeId> = path.iter().copied().collect();
+        let mut current = node_id;
+
+        while let Some(parent) = self.get_parent(current) {
+            // Stop on a corrupted parent chain rather than looping forever
+            if !visited.insert(parent.id) {
+                break;
+            }
+            path.push(parent.id);
+            current = parent.id;
+        }
+
+        path.reverse();
+        path
+    }
+
     /// Get all descendants of a node (following child relationships)
     pub fn get_descendants(&self, node_id: NodeId) -> Vec<NodeId> {
+        let children_map = self.children_by_parent();
         let mut descendants = Vec::new();
         let mut stack = vec![node_id];
         let mut visited = HashSet::new();
@@ -192,7 +312,7 @@ impl Graph {
             }
             visited.insert(current);
 
-            for child in self.get_children(current) {
+            for child in children_map.get(&current).into_iter().flatten() {
                 descendants.push(child.id);
                 stack.push(child.id);
             }
@@ -201,6 +321,35 @@ impl Graph {
         descendants
     }
 
+    /// Get the nodes shown in the view
+    ///
+    /// Descendants of a collapsed node are left out; the collapsed node
+    /// itself stays visible. Hidden nodes remain in the graph.
+    pub fn visible_nodes(&self) -> Vec<&Node> {
+        let hidden = self.hidden_node_ids();
+        self.nodes().filter(|node| !hidden.contains(&node.id)).collect()
+    }
+
+    /// IDs of the nodes below at least one collapsed ancestor
+    pub(crate) fn hidden_node_ids(&self) -> HashSet<NodeId> {
+        let children_map = self.children_by_parent();
+        let mut hidden = HashSet::new();
+        let mut stack: Vec<NodeId> = self.nodes()
+            .filter(|node| node.collapsed)
+            .map(|node| node.id)
+            .collect();
+
+        while let Some(current) = stack.pop() {
+            for child in children_map.get(&current).into_iter().flatten() {
+                if hidden.insert(child.id) {
+                    stack.push(child.id);
+                }
+            }
+        }
+
+        hidden
+    }
+
     /// Check if one node is an ancestor of another
     pub fn is_ancestor(&self, ancestor_id: NodeId, descendant_id: NodeId) -> bool {
         let ancestors = self.get_ancestors(descendant_id);
@@ -289,28 +438,46 @@ impl Graph {
     }
 
     /// Helper function for cycle detection using DFS
+    ///
+    /// Keeps its own stack of (node, remaining neighbors) frames rather than
+    /// recursing, so long edge chains don't overflow the call stack.
     fn has_cycle_dfs(
         &self,
-        node_id: NodeId,
+        start: NodeId,
         visited: &mut HashSet<NodeId>,
         recursion_stack: &mut HashSet<NodeId>,
     ) -> bool {
-        visited.insert(node_id);
-        recursion_stack.insert(node_id);
-
-        for edge in self.get_outgoing_edges(node_id) {
-            let neighbor = edge.to_node;
-
-            if !visited.contains(&neighbor) {
-                if self.has_cycle_dfs(neighbor, visited, recursion_stack) {
-                    return true;
+        let neighbors = |node_id: NodeId| -> std::vec::IntoIter<NodeId> {
+            self.get_outgoing_edges(node_id)
+                .into_iter()
+                .map(|edge| edge.to_node)
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        visited.insert(start);
+        recursion_stack.insert(start);
+        let mut frames = vec![(start, neighbors(start))];
+
+        while let Some((node_id, remaining)) = frames.last_mut() {
+            match remaining.next() {
+                Some(neighbor) if !visited.contains(&neighbor) => {
+                    visited.insert(neighbor);
+                    recursion_stack.insert(neighbor);
+                    frames.push((neighbor, neighbors(neighbor)));
+                }
+                Some(neighbor) => {
+                    if recursion_stack.contains(&neighbor) {
+                        return true;
+                    }
+                }
+                None => {
+                    recursion_stack.remove(node_id);
+                    frames.pop();
                 }
-            } else if recursion_stack.contains(&neighbor) {
-                return true;
             }
         }
 
-        recursion_stack.remove(&node_id);
         false
     }
 }
@@ -318,7 +485,7 @@ impl Graph {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Node, Edge};
+    use crate::models::Edge;
 
     fn create_test_graph() -> (Graph, NodeId, NodeId, NodeId, NodeId) {
         let mut graph = Graph::new();
@@ -413,6 +580,35 @@ mod tests {
         assert!(path.is_none());
     }
 
+    #[test]
+    fn test_shortest_path_sequence() {
+        let mut graph = Graph::new();
+        let ids: Vec<NodeId> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|text| graph.add_node(Node::new(*text)).unwrap())
+            .collect();
+        graph.add_edge(Edge::new(ids[0], ids[1])).unwrap();
+        // Direction doesn't matter
+        graph.add_edge(Edge::new(ids[2], ids[1])).unwrap();
+        graph.add_edge(Edge::new(ids[2], ids[3])).unwrap();
+
+        assert_eq!(graph.shortest_path(ids[0], ids[3]), Some(ids.clone()));
+        assert_eq!(graph.path_length(ids[0], ids[3]), Some(3));
+        assert_eq!(graph.shortest_path(ids[2], ids[2]), Some(vec![ids[2]]));
+        assert_eq!(graph.path_length(ids[2], ids[2]), Some(0));
+
+        // A shortcut is preferred over the long way round
+        graph.add_edge(Edge::new(ids[0], ids[3])).unwrap();
+        assert_eq!(graph.shortest_path(ids[0], ids[3]), Some(vec![ids[0], ids[3]]));
+
+        // Separate components are not connected
+        let island = graph.add_node(Node::new("Island")).unwrap();
+        let other = graph.add_node(Node::new("Other")).unwrap();
+        graph.add_edge(Edge::new(island, other)).unwrap();
+        assert_eq!(graph.shortest_path(ids[0], other), None);
+        assert_eq!(graph.path_length(other, ids[1]), None);
+    }
+
     #[test]
     fn test_ancestor_descendant_relationships() {
         let mut graph = Graph::new();
@@ -447,6 +643,33 @@ mod tests {
         assert!(!graph.is_ancestor(grandchild_id, root_id));
     }
 
+    #[test]
+    fn test_root_path() {
+        let mut graph = Graph::new();
+        let root = Node::new("Root");
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+
+        let level1 = Node::new_child(root_id, "Level 1");
+        let level1_id = level1.id;
+        graph.add_node(level1).unwrap();
+
+        let level2 = Node::new_child(level1_id, "Level 2");
+        let level2_id = level2.id;
+        graph.add_node(level2).unwrap();
+
+        let level3 = Node::new_child(level2_id, "Level 3");
+        let level3_id = level3.id;
+        graph.add_node(level3).unwrap();
+
+        let sibling = Node::new_child(root_id, "Sibling");
+        graph.add_node(sibling).unwrap();
+
+        assert_eq!(graph.root_path(level3_id), vec![root_id, level1_id, level2_id, level3_id]);
+        assert_eq!(graph.root_path(root_id), vec![root_id]);
+        assert!(graph.root_path(NodeId::new()).is_empty());
+    }
+
     #[test]
     fn test_node_depth() {
         let mut graph = Graph::new();
@@ -532,4 +755,81 @@ mod tests {
         graph.add_edge(Edge::new(id3, id1)).unwrap();
         assert!(graph.has_cycles());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_weighted_vs_unweighted_path() {
+        let mut graph = Graph::new();
+        let ids: Vec<NodeId> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|text| graph.add_node(Node::new(*text)).unwrap())
+            .collect();
+        let (a, b, c, d) = (ids[0], ids[1], ids[2], ids[3]);
+
+        // Direct route A -> D is one hop but expensive; A -> B -> C -> D is cheap
+        let mut direct = Edge::new(a, d);
+        direct.weight = Some(10.0);
+        graph.add_edge(direct).unwrap();
+        for (from, to) in [(a, b), (b, c), (c, d)] {
+            let mut edge = Edge::new(from, to);
+            edge.weight = Some(1.5);
+            graph.add_edge(edge).unwrap();
+        }
+
+        assert_eq!(graph.shortest_path(a, d), Some(vec![a, d]));
+
+        let (path, cost) = graph.weighted_shortest_path(a, d).unwrap();
+        assert_eq!(path, vec![a, b, c, d]);
+        assert!((cost - 4.5).abs() < f64::EPSILON);
+
+        // Unset weights count as 1.0, matching the unweighted route
+        let (graph, id1, _, id3, _) = create_test_graph();
+        let (path, cost) = graph.weighted_shortest_path(id1, id3).unwrap();
+        assert_eq!(Some(path), graph.shortest_path(id1, id3));
+        assert_eq!(cost, 2.0);
+        assert_eq!(graph.weighted_shortest_path(id1, id1), Some((vec![id1], 0.0)));
+    }
+
+    #[test]
+    fn test_weighted_path_rejects_negative_weights() {
+        let (mut graph, id1, _, id3, _) = create_test_graph();
+
+        let mut edge = Edge::new(id1, id3);
+        edge.weight = Some(-2.0);
+        assert!(matches!(
+            graph.add_edge(edge),
+            Err(crate::types::MindmapError::InvalidOperation { .. })
+        ));
+
+        let isolated = graph.add_node(Node::new("Isolated")).unwrap();
+        assert!(graph.weighted_shortest_path(id1, isolated).is_none());
+    }
+
+    #[test]
+    fn test_collapsing_hides_descendants_from_view_only() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let branch_id = graph.add_node(Node::new_child(root_id, "Branch")).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(branch_id, "Leaf")).unwrap();
+        let deep_id = graph.add_node(Node::new_child(leaf_id, "Deep")).unwrap();
+        let other_id = graph.add_node(Node::new_child(root_id, "Other")).unwrap();
+        assert_eq!(graph.visible_nodes().len(), 5);
+
+        graph.get_node_mut(branch_id).unwrap().set_collapsed(true);
+        let mut visible: Vec<NodeId> = graph.visible_nodes().iter().map(|node| node.id).collect();
+        visible.sort_by_key(|id| id.as_uuid());
+        let mut expected = vec![root_id, branch_id, other_id];
+        expected.sort_by_key(|id| id.as_uuid());
+        assert_eq!(visible, expected);
+
+        // Hidden nodes stay in the graph, and a collapsed node inside a
+        // collapsed subtree changes nothing
+        assert_eq!(graph.node_count(), 5);
+        assert!(graph.contains_node(leaf_id) && graph.contains_node(deep_id));
+        graph.get_node_mut(leaf_id).unwrap().set_collapsed(true);
+        assert_eq!(graph.visible_nodes().len(), 3);
+
+        graph.get_node_mut(branch_id).unwrap().set_collapsed(false);
+        assert_eq!(graph.visible_nodes().len(), 4);
+        assert!(graph.visible_nodes().iter().all(|node| node.id != deep_id));
+    }
+}
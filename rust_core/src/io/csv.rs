@@ -0,0 +1,490 @@
+//! CSV (comma-separated values) import/export handler
+//!
+//! This module flattens a mindmap into one row per node for spreadsheets and
+//! analysis tools. Rows carry the node ID, parent ID, text, depth and tags,
+//! optionally followed by timestamps and metadata columns. Fields are quoted
+//! as described in RFC 4180. On import, the `parent_id` column, when present,
+//! rebuilds the hierarchy.
+
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use super::utils::{self, LinkResolver};
+use crate::models::document::Document;
+use crate::models::edge::Edge;
+use crate::models::node::Node;
+use crate::types::{ids::NodeId, MindmapResult, MindmapError};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Prefix of columns holding node metadata, e.g. `metadata.owner`
+const METADATA_PREFIX: &str = "metadata.";
+
+/// Separator between tags in the tags column
+///
+/// A separator or backslash inside a tag is escaped with a backslash.
+const TAG_SEPARATOR: char = ';';
+
+/// Join tags into one tags column value, escaping separators inside tags
+fn join_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| tag.replace('\\', "\\\\").replace(TAG_SEPARATOR, "\\;"))
+        .collect::<Vec<_>>()
+        .join(&TAG_SEPARATOR.to_string())
+}
+
+/// Split a tags column value written by [`join_tags`]
+fn split_tags(field: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut tag = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => tag.extend(chars.next()),
+            TAG_SEPARATOR => tags.push(std::mem::take(&mut tag)),
+            _ => tag.push(c),
+        }
+    }
+    tags.push(tag);
+    tags
+}
+
+/// Columns always written, in order
+const BASE_COLUMNS: [&str; 5] = ["id", "parent_id", "text", "depth", "tags"];
+
+/// CSV format handler
+#[derive(Default)]
+pub struct CsvHandler {
+    /// Metadata keys exported as columns; every key in use when `None`
+    metadata_columns: Option<Vec<String>>,
+}
+
+impl CsvHandler {
+    /// Create a CSV handler exporting every metadata key in use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a CSV handler exporting only the given metadata keys, in order
+    pub fn with_metadata_columns<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            metadata_columns: Some(keys.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Metadata keys to export for the given nodes
+    fn metadata_keys(&self, nodes: &[Node]) -> Vec<String> {
+        match &self.metadata_columns {
+            Some(keys) => keys.clone(),
+            None => nodes
+                .iter()
+                .flat_map(|node| node.metadata.keys().cloned())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Nodes in outline order (depth first, children by order) with their depth
+    ///
+    /// Nodes not reachable from a root, e.g. on a parent cycle, follow in their
+    /// original order with the depth of their parent chain.
+    fn outline_order<'a>(&self, nodes: &'a [Node], root_id: NodeId) -> Vec<(&'a Node, usize)> {
+        let by_id: HashMap<NodeId, &Node> = nodes.iter().map(|node| (node.id, node)).collect();
+        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        let mut roots = Vec::new();
+        for node in nodes {
+            match node.parent_id.filter(|parent_id| by_id.contains_key(parent_id)) {
+                Some(parent_id) => children_map.entry(parent_id).or_default().push(node),
+                None if node.id == root_id => roots.insert(0, node),
+                None => roots.push(node),
+            }
+        }
+        for children in children_map.values_mut() {
+            children.sort_by_key(|child| child.order);
+        }
+
+        let mut ordered = Vec::with_capacity(nodes.len());
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(&Node, usize)> = roots.into_iter().rev().map(|node| (node, 0)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            if !visited.insert(node.id) {
+                continue;
+            }
+            ordered.push((node, depth));
+            if let Some(children) = children_map.get(&node.id) {
+                stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+
+        for node in nodes.iter().filter(|node| !visited.contains(&node.id)) {
+            ordered.push((node, self.parent_chain_depth(node, &by_id)));
+        }
+        ordered
+    }
+
+    /// Number of ancestors of a node, stopping at a repeated node
+    fn parent_chain_depth(&self, node: &Node, by_id: &HashMap<NodeId, &Node>) -> usize {
+        let mut seen = HashSet::from([node.id]);
+        let mut depth = 0;
+        let mut current = node.parent_id;
+        while let Some(parent_id) = current {
+            let Some(parent) = by_id.get(&parent_id) else { break };
+            if !seen.insert(parent_id) {
+                break;
+            }
+            depth += 1;
+            current = parent.parent_id;
+        }
+        depth
+    }
+
+    /// Split CSV content into records of fields
+    fn parse_records(&self, content: &str) -> MindmapResult<Vec<Vec<String>>> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        field.push('"');
+                        chars.next();
+                    }
+                    '"' => in_quotes = false,
+                    _ => field.push(c),
+                }
+                continue;
+            }
+
+            match c {
+                '"' if field.is_empty() => in_quotes = true,
+                ',' => record.push(std::mem::take(&mut field)),
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' | '\r' => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(c),
+            }
+        }
+
+        if in_quotes {
+            return Err(MindmapError::ParseError {
+                message: "Unterminated quoted field in CSV".to_string(),
+            });
+        }
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+
+        // Blank lines carry no rows
+        records.retain(|record| !(record.len() == 1 && record[0].is_empty()));
+        Ok(records)
+    }
+
+    /// Parse the header row, mapping column names to their positions
+    fn parse_header(&self, header: &[String]) -> HashMap<String, usize> {
+        header
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.trim().to_string(), index))
+            .collect()
+    }
+}
+
+impl FormatHandler for CsvHandler {
+    fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+        let mut records = self.parse_records(content)?.into_iter();
+        let header = records.next().ok_or_else(|| MindmapError::ParseError {
+            message: "CSV content is empty".to_string(),
+        })?;
+        let columns = self.parse_header(&header);
+        let text_column = *columns.get("text").ok_or_else(|| MindmapError::ParseError {
+            message: "CSV header has no 'text' column".to_string(),
+        })?;
+        let id_column = columns.get("id").copied();
+        let parent_column = columns.get("parent_id").copied();
+
+        let mut warnings = Vec::new();
+        let mut links = LinkResolver::new();
+        let mut nodes = Vec::new();
+        let mut parent_anchors = Vec::new();
+
+        for (row, record) in records.enumerate() {
+            let field = |index: Option<usize>| index.and_then(|index| record.get(index)).map(|value| value.trim()).filter(|value| !value.is_empty());
+
+            let mut node = Node::new(record.get(text_column).cloned().unwrap_or_default());
+            node.id = links.node_id(field(id_column), options.preserve_ids);
+            parent_anchors.push(field(parent_column).map(str::to_string));
+
+            if let Some(tags) = field(columns.get("tags").copied()) {
+                for tag in split_tags(tags).iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+                    node.add_tag(tag);
+                }
+            }
+
+            if options.include_timestamps {
+                if let Some(created_at) = field(columns.get("created_at").copied()) {
+                    match options.parse_timestamp(created_at) {
+                        Some(timestamp) => node.created_at = timestamp,
+                        None => warnings.push(format!("Row {}: invalid created_at '{}'", row + 1, created_at)),
+                    }
+                }
+                if let Some(updated_at) = field(columns.get("updated_at").copied()) {
+                    match options.parse_timestamp(updated_at) {
+                        Some(timestamp) => node.updated_at = timestamp,
+                        None => warnings.push(format!("Row {}: invalid updated_at '{}'", row + 1, updated_at)),
+                    }
+                }
+            }
+
+            if options.include_metadata {
+                for (name, &index) in &columns {
+                    if let (Some(key), Some(value)) = (name.strip_prefix(METADATA_PREFIX), field(Some(index))) {
+                        node.metadata.insert(key.to_string(), value.to_string());
+                    }
+                }
+            }
+
+            nodes.push(node);
+        }
+
+        // Parents are resolved once every row has its ID
+        let mut sibling_counts: HashMap<Option<NodeId>, i64> = HashMap::new();
+        for (node, parent_anchor) in nodes.iter_mut().zip(parent_anchors) {
+            node.parent_id = parent_anchor.and_then(|anchor| match links.resolve_anchor(&anchor) {
+                Some(parent_id) if parent_id != node.id => Some(parent_id),
+                _ => {
+                    warnings.push(format!("Parent '{}' of '{}' not found; imported as a root", anchor, node.text));
+                    None
+                }
+            });
+            let order = sibling_counts.entry(node.parent_id).or_default();
+            node.order = *order;
+            *order += 1;
+        }
+
+        // Parents first, root first; rows on a parent cycle become roots
+        let root = nodes.iter().find(|node| node.parent_id.is_none()).ok_or_else(|| MindmapError::ParseError {
+            message: "CSV has no root row".to_string(),
+        })?;
+        let (root_id, title) = (root.id, root.text.clone());
+        let flattened: Vec<(&Node, usize)> = self.outline_order(&nodes, root_id);
+        let reachable: HashSet<NodeId> = {
+            let mut reachable = HashSet::new();
+            for (node, _) in &flattened {
+                if node.parent_id.is_none() || node.parent_id.is_some_and(|parent_id| reachable.contains(&parent_id)) {
+                    reachable.insert(node.id);
+                }
+            }
+            reachable
+        };
+        let order: Vec<NodeId> = flattened.iter().map(|(node, _)| node.id).collect();
+        let mut by_id: HashMap<NodeId, Node> = nodes.into_iter().map(|node| (node.id, node)).collect();
+        let nodes: Vec<Node> = order
+            .into_iter()
+            .filter_map(|id| by_id.remove(&id))
+            .map(|mut node| {
+                if !reachable.contains(&node.id) {
+                    warnings.push(format!("'{}' is part of a parent cycle; imported as a root", node.text));
+                    node.parent_id = None;
+                }
+                node
+            })
+            .collect();
+
+        let document = Document::new(&title, root_id);
+        let edge_count = nodes.iter().filter(|node| node.parent_id.is_some()).count();
+
+        Ok(ImportResult {
+            document,
+            node_count: nodes.len(),
+            edge_count,
+            nodes,
+            edges: Vec::new(),
+            warnings,
+        })
+    }
+
+    fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut warnings = Vec::new();
+        let nodes = utils::truncate_to_depth(nodes, document.get_root_node(), options.max_depth, &mut warnings);
+        let nodes: Vec<Node> = nodes
+            .into_iter()
+            .filter(|node| options.include_empty_nodes || !node.text.trim().is_empty() || node.id == document.get_root_node())
+            .collect();
+
+        let metadata_keys = if options.include_metadata { self.metadata_keys(&nodes) } else { Vec::new() };
+
+        let mut header: Vec<String> = BASE_COLUMNS.iter().map(|column| column.to_string()).collect();
+        if options.include_timestamps {
+            header.extend(["created_at".to_string(), "updated_at".to_string()]);
+        }
+        header.extend(metadata_keys.iter().map(|key| format!("{}{}", METADATA_PREFIX, key)));
+
+        let mut csv = String::new();
+        let write_row = |csv: &mut String, fields: &[String]| {
+            let escaped: Vec<String> = fields.iter().map(|field| utils::escape_csv(field)).collect();
+            csv.push_str(&escaped.join(","));
+            csv.push_str("\r\n");
+        };
+        write_row(&mut csv, &header);
+
+        let rows = self.outline_order(&nodes, document.get_root_node());
+        for (node, depth) in &rows {
+            let mut fields = vec![
+                node.id.as_uuid().to_string(),
+                node.parent_id.map(|parent_id| parent_id.as_uuid().to_string()).unwrap_or_default(),
+                node.text.clone(),
+                depth.to_string(),
+                join_tags(&node.tags),
+            ];
+            if options.include_timestamps {
+                fields.push(options.format_timestamp(&node.created_at));
+                fields.push(options.format_timestamp(&node.updated_at));
+            }
+            fields.extend(metadata_keys.iter().map(|key| node.metadata.get(key).cloned().unwrap_or_default()));
+            write_row(&mut csv, &fields);
+        }
+
+        Ok(ExportResult {
+            content: csv,
+            node_count: rows.len(),
+            edge_count: rows.iter().filter(|(node, _)| node.parent_id.is_some()).count(),
+            format: FileFormat::Csv,
+            warnings,
+        })
+    }
+
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], _edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        self.export(document, nodes, options)
+    }
+
+    fn format(&self) -> FileFormat {
+        FileFormat::Csv
+    }
+
+    fn validate(&self, content: &str) -> MindmapResult<bool> {
+        let Some(first_line) = content.trim_start_matches('\u{feff}').lines().next() else {
+            return Ok(false);
+        };
+        let header = self.parse_records(first_line).unwrap_or_default();
+        let columns = header.first().map(|header| self.parse_header(header)).unwrap_or_default();
+        Ok(columns.contains_key("id") && columns.contains_key("text"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_document() -> (Document, Vec<Node>) {
+        let root = Node::new("Budget, 2024");
+        let mut child = Node::new_child(root.id, "Say \"hello\"\nto finance");
+        child.add_tag("q1");
+        child.add_tag("review");
+        child.set_metadata("owner", "alice");
+        child.set_metadata("cost", "1,200");
+        let mut second = Node::new_child(root.id, "Travel");
+        second.order = 1;
+        let grandchild = Node::new_child(child.id, "Slides");
+
+        let document = Document::new("Budget", root.id);
+        (document, vec![root, second, child, grandchild])
+    }
+
+    #[test]
+    fn test_export_rows_and_escaping() {
+        let (document, nodes) = create_test_document();
+        let options = ImportExportOptions { include_timestamps: false, ..ImportExportOptions::default() };
+        let handler = CsvHandler::with_metadata_columns(["owner"]);
+        let result = handler.export(&document, &nodes, &options).unwrap();
+        let lines: Vec<&str> = result.content.split("\r\n").collect();
+
+        assert_eq!(lines[0], "id,parent_id,text,depth,tags,metadata.owner");
+        assert_eq!(lines[1], format!("{},,\"Budget, 2024\",0,,", nodes[0].id.as_uuid()));
+        assert_eq!(
+            lines[2],
+            format!("{},{},\"Say \"\"hello\"\"\nto finance\",1,q1;review,alice", nodes[2].id.as_uuid(), nodes[0].id.as_uuid()),
+        );
+        assert!(lines[3].contains(",Slides,2,"));
+        assert!(lines[4].contains(",Travel,1,"));
+        assert_eq!(result.node_count, 4);
+        assert!(handler.validate(&result.content).unwrap());
+
+        let without_metadata = ImportExportOptions { include_metadata: false, ..options };
+        let result = handler.export(&document, &nodes, &without_metadata).unwrap();
+        assert!(result.content.starts_with("id,parent_id,text,depth,tags\r\n"));
+    }
+
+    #[test]
+    fn test_round_trip_preserves_tree() {
+        let (document, nodes) = create_test_document();
+        let handler = CsvHandler::new();
+        let exported = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+
+        let preserving = ImportExportOptions { preserve_ids: true, ..ImportExportOptions::default() };
+        let imported = handler.import(&exported.content, &preserving).unwrap();
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+        assert_eq!(imported.node_count, 4);
+        assert_eq!(imported.edge_count, 3);
+        assert_eq!(imported.document.get_root_node(), nodes[0].id);
+
+        for original in &nodes {
+            let node = imported.nodes.iter().find(|node| node.id == original.id).unwrap();
+            assert_eq!(node.text, original.text);
+            assert_eq!(node.parent_id, original.parent_id);
+            assert_eq!(node.tags, original.tags);
+            assert_eq!(node.metadata, original.metadata);
+            assert_eq!(
+                node.created_at.timestamp_millis(),
+                original.created_at.timestamp_millis(),
+            );
+        }
+        assert!(imported.to_graph().is_ok());
+
+        // Without preserve_ids the tree is rebuilt with fresh IDs
+        let fresh = handler.import(&exported.content, &ImportExportOptions::default()).unwrap();
+        assert!(fresh.nodes.iter().all(|node| nodes.iter().all(|original| original.id != node.id)));
+        let graph = fresh.to_graph().unwrap();
+        let root_children = graph.get_children(fresh.document.get_root_node());
+        let texts: Vec<&str> = root_children.iter().map(|node| node.text.as_str()).collect();
+        assert_eq!(texts, vec!["Say \"hello\"\nto finance", "Travel"]);
+    }
+
+    #[test]
+    fn test_tags_with_separators_round_trip() {
+        let root = Node::new("Root");
+        let mut child = Node::new_child(root.id, "Child");
+        for tag in ["plain", "a;b", "back\\slash", "both\\;"] {
+            child.add_tag(tag);
+        }
+        let document = Document::new("Tags", root.id);
+        let handler = CsvHandler::new();
+
+        let exported = handler.export(&document, &[root, child.clone()], &ImportExportOptions::default()).unwrap();
+        assert!(exported.content.contains("plain;a\\;b;back\\\\slash;both\\\\\\;"));
+
+        let imported = handler.import(&exported.content, &ImportExportOptions::default()).unwrap();
+        let node = imported.nodes.iter().find(|node| node.text == "Child").unwrap();
+        assert_eq!(node.tags, child.tags);
+    }
+
+    #[test]
+    fn test_import_without_parent_column_is_flat() {
+        let content = "text,tags\nFirst,a;b\n\"Second, with comma\",\n";
+        let imported = CsvHandler::new().import(content, &ImportExportOptions::default()).unwrap();
+        assert_eq!(imported.node_count, 2);
+        assert!(imported.nodes.iter().all(|node| node.parent_id.is_none()));
+        assert_eq!(imported.nodes[1].text, "Second, with comma");
+        assert_eq!(imported.nodes[0].tags, vec!["a", "b"]);
+
+        assert!(CsvHandler::new().import("id,parent_id\n1,\n", &ImportExportOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! GraphViz DOT export handler
+//!
+//! This module writes mindmaps as a DOT `digraph` for rendering with GraphViz
+//! in documentation pipelines. Parent-child links become solid edges and
+//! cross-links become dashed edges; node colors and shapes follow each node's
+//! style. DOT is export-only, since arbitrary graphs don't map back to an
+//! outline.
+
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use super::utils;
+use crate::models::document::Document;
+use crate::models::edge::{Edge, LineStyle};
+use crate::models::node::{FontWeight, Node, NodeShape, NodeStyle};
+use crate::types::{ids::NodeId, utils::color_to_rgba, Color, MindmapResult, MindmapError};
+use std::collections::{HashMap, HashSet};
+
+/// DOT format handler
+pub struct DotHandler;
+
+impl DotHandler {
+    /// Create a new DOT handler
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// DOT identifiers for the exported nodes
+    ///
+    /// Short IDs keep the output readable; if two of them collide, every node
+    /// falls back to its full ID so the graph stays correct.
+    fn node_names(&self, nodes: &[Node]) -> HashMap<NodeId, String> {
+        let short: HashMap<NodeId, String> = nodes.iter().map(|node| (node.id, format!("n{}", node.id.short()))).collect();
+        let distinct: HashSet<&String> = short.values().collect();
+        if distinct.len() == short.len() {
+            short
+        } else {
+            nodes.iter().map(|node| (node.id, format!("n{}", node.id.as_uuid().simple()))).collect()
+        }
+    }
+
+    /// Render a color as a DOT `#RRGGBB` or `#RRGGBBAA` string
+    fn dot_color(&self, color: Color) -> String {
+        let (r, g, b, a) = color_to_rgba(color);
+        if a == 255 {
+            format!("\"#{:02x}{:02x}{:02x}\"", r, g, b)
+        } else {
+            format!("\"#{:02x}{:02x}{:02x}{:02x}\"", r, g, b, a)
+        }
+    }
+
+    /// DOT attributes describing a node's style
+    fn style_attributes(&self, style: &NodeStyle) -> Vec<String> {
+        let (shape, rounded) = match style.shape {
+            NodeShape::Rectangle => ("box", false),
+            NodeShape::RoundedRectangle => ("box", true),
+            NodeShape::Circle => ("circle", false),
+            NodeShape::Ellipse => ("ellipse", false),
+        };
+
+        let mut styles = vec!["filled"];
+        if rounded {
+            styles.insert(0, "rounded");
+        }
+        if style.font_weight == FontWeight::Bold {
+            styles.push("bold");
+        }
+
+        vec![
+            format!("shape={}", shape),
+            format!("style=\"{}\"", styles.join(",")),
+            format!("color={}", self.dot_color(style.border_color)),
+            format!("fillcolor={}", self.dot_color(style.background_color)),
+            format!("fontcolor={}", self.dot_color(style.text_color)),
+        ]
+    }
+
+    /// Write a node statement and its subtree, children in order
+    fn write_subtree(
+        &self,
+        node: &Node,
+        children_map: &HashMap<NodeId, Vec<&Node>>,
+        names: &HashMap<NodeId, String>,
+        nodes_out: &mut String,
+        edges_out: &mut String,
+    ) {
+        let mut attributes = vec![format!("label=\"{}\"", utils::escape_dot(&node.text))];
+        attributes.extend(self.style_attributes(&node.style));
+        nodes_out.push_str(&format!("  {} [{}];\n", names[&node.id], attributes.join(", ")));
+
+        if let Some(children) = children_map.get(&node.id) {
+            for child in children {
+                edges_out.push_str(&format!("  {} -> {};\n", names[&node.id], names[&child.id]));
+                self.write_subtree(child, children_map, names, nodes_out, edges_out);
+            }
+        }
+    }
+
+    /// Write a cross-link edge statement
+    fn write_cross_link(&self, edge: &Edge, names: &HashMap<NodeId, String>, edges_out: &mut String) {
+        let line_style = match edge.style.style {
+            LineStyle::Dotted => "dotted",
+            _ => "dashed",
+        };
+        let mut attributes = vec![
+            format!("style={}", line_style),
+            format!("color={}", self.dot_color(edge.style.color)),
+        ];
+        if let Some(label) = edge.label.as_deref().filter(|label| !label.is_empty()) {
+            attributes.push(format!("label=\"{}\"", utils::escape_dot(label)));
+        }
+        edges_out.push_str(&format!(
+            "  {} -> {} [{}];\n",
+            names[&edge.from_node],
+            names[&edge.to_node],
+            attributes.join(", "),
+        ));
+    }
+}
+
+impl FormatHandler for DotHandler {
+    fn import(&self, _content: &str, _options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+        Err(MindmapError::InvalidOperation {
+            message: "Importing GraphViz DOT files is not supported; DOT is an export-only format".to_string(),
+        })
+    }
+
+    fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        self.export_with_edges(document, nodes, &[], options)
+    }
+
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut warnings = Vec::new();
+        let nodes = &utils::truncate_to_depth(nodes, document.get_root_node(), options.max_depth, &mut warnings);
+
+        let root = nodes
+            .iter()
+            .find(|node| node.id == document.get_root_node())
+            .ok_or(MindmapError::NodeNotFound { id: document.get_root_node() })?;
+
+        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent_id) = node.parent_id {
+                children_map.entry(parent_id).or_default().push(node);
+            }
+        }
+        for children in children_map.values_mut() {
+            children.sort_by_key(|child| child.order);
+        }
+
+        let names = self.node_names(nodes);
+        let mut node_lines = String::new();
+        let mut edge_lines = String::new();
+        self.write_subtree(root, &children_map, &names, &mut node_lines, &mut edge_lines);
+
+        let cross_links = utils::cross_links(nodes, edges);
+        for edge in &cross_links {
+            self.write_cross_link(edge, &names, &mut edge_lines);
+        }
+
+        // Nodes unreachable from the root are not written, nor are their tree edges
+        let node_count = node_lines.lines().count();
+        let edge_count = edge_lines.lines().count();
+
+        let mut dot = format!("digraph \"{}\" {{\n", utils::escape_dot(&document.title));
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str(&node_lines);
+        dot.push_str(&edge_lines);
+        dot.push_str("}\n");
+
+        Ok(ExportResult {
+            content: dot,
+            node_count,
+            edge_count,
+            format: FileFormat::Dot,
+            warnings,
+        })
+    }
+
+    fn format(&self) -> FileFormat {
+        FileFormat::Dot
+    }
+
+    fn validate(&self, content: &str) -> MindmapResult<bool> {
+        let trimmed = content.trim_start();
+        Ok(trimmed.starts_with("digraph") || trimmed.starts_with("strict digraph"))
+    }
+}
+
+impl Default for DotHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::utils::rgb_to_color;
+
+    fn create_small_tree() -> (Document, Vec<Node>, Vec<Edge>) {
+        let mut root = Node::new("Plan \"Q3\"");
+        root.style.background_color = rgb_to_color(255, 204, 0);
+        let first = Node::new_child(root.id, "Research\nphase");
+        let mut second = Node::new_child(root.id, "Build");
+        second.order = 1;
+        let grandchild = Node::new_child(first.id, "Interviews");
+        let link = Edge::new(grandchild.id, second.id);
+
+        let document = Document::new("Roadmap", root.id);
+        (document, vec![root, first, second, grandchild], vec![link])
+    }
+
+    #[test]
+    fn test_export_small_tree_as_digraph() {
+        let (document, nodes, edges) = create_small_tree();
+        let handler = DotHandler::new();
+        let result = handler
+            .export_with_edges(&document, &nodes, &edges, &ImportExportOptions::default())
+            .unwrap();
+        let dot = &result.content;
+
+        assert!(dot.starts_with("digraph \"Roadmap\" {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(handler.validate(dot).unwrap());
+
+        // Three parent-child edges and one dashed cross-link
+        let edge_lines: Vec<&str> = dot.lines().filter(|line| line.contains(" -> ")).collect();
+        assert_eq!(edge_lines.len(), 4);
+        assert_eq!(edge_lines.iter().filter(|line| line.contains("style=dashed")).count(), 1);
+        assert_eq!(result.edge_count, 4);
+
+        // One statement per node, labels escaped and styles mapped
+        assert_eq!(dot.lines().filter(|line| line.contains("[label=")).count(), 4);
+        assert!(dot.contains(&format!("n{} [label=\"Plan \\\"Q3\\\"\"", nodes[0].id.short())));
+        assert!(dot.contains("label=\"Research\\nphase\""));
+        assert!(dot.contains("fillcolor=\"#ffcc00\""));
+        assert!(dot.contains("style=\"rounded,filled\""));
+    }
+
+    #[test]
+    fn test_bold_nodes_and_unreachable_nodes() {
+        let (document, mut nodes, edges) = create_small_tree();
+        nodes[1].style.font_weight = FontWeight::Bold;
+        nodes[1].style.shape = NodeShape::Rectangle;
+        // An orphan whose parent is missing from the export
+        let orphan = Node::new_child(NodeId::new(), "Orphan");
+        let orphan_child = Node::new_child(orphan.id, "Orphan child");
+        nodes.extend([orphan, orphan_child]);
+
+        let result = DotHandler::new()
+            .export_with_edges(&document, &nodes, &edges, &ImportExportOptions::default())
+            .unwrap();
+        let dot = &result.content;
+
+        assert!(dot.contains("style=\"filled,bold\""));
+        assert!(!dot.contains("fontname"));
+        assert!(!dot.contains("Orphan"));
+        assert_eq!(result.node_count, 4);
+        assert_eq!(result.edge_count, dot.lines().filter(|line| line.contains(" -> ")).count());
+        assert_eq!(result.edge_count, 4);
+    }
+
+    #[test]
+    fn test_import_is_not_supported() {
+        let error = DotHandler::new()
+            .import("digraph G { a -> b; }", &ImportExportOptions::default())
+            .unwrap_err();
+        assert!(matches!(error, MindmapError::InvalidOperation { message } if message.contains("not supported")));
+    }
+}
@@ -0,0 +1,671 @@
+//! FreeMind/Freeplane (.mm) import/export handler
+//!
+//! This module reads and writes the XML mind map format used by FreeMind and
+//! Freeplane. Node hierarchy, folding, side placement, timestamps, custom
+//! attributes and arrow links between nodes are preserved.
+
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use super::utils::{self, LinkResolver};
+use crate::models::document::Document;
+use crate::models::edge::Edge;
+use crate::models::node::Node;
+use crate::types::{ids::NodeId, MindmapResult, MindmapError, Point, Timestamp};
+use std::collections::HashMap;
+
+/// FreeMind file format version written on export
+const FREEMIND_VERSION: &str = "1.0.1";
+
+/// Metadata key storing whether a node was folded in FreeMind
+const FOLDED_KEY: &str = "folded";
+
+/// Metadata key storing which side of the root a node was placed on
+const POSITION_KEY: &str = "position";
+
+/// Metadata key storing a node's note
+const NOTE_KEY: &str = "note";
+
+/// Text used for nodes without any content
+const EMPTY_NODE_TEXT: &str = "Empty Node";
+
+/// FreeMind format handler
+pub struct FreemindHandler;
+
+impl FreemindHandler {
+    /// Create a new FreeMind handler
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse FreeMind content into its top-level nodes
+    fn parse_freemind_content(&self, content: &str) -> MindmapResult<Vec<FreemindNode>> {
+        if !self.has_map_root(content) {
+            return Err(MindmapError::ParseError {
+                message: "Content does not appear to be a FreeMind map".to_string(),
+            });
+        }
+
+        let mut roots = Vec::new();
+        let mut stack: Vec<FreemindNode> = Vec::new();
+        let mut pos = 0;
+
+        while let Some(offset) = content[pos..].find('<') {
+            let start = pos + offset;
+            let rest = &content[start..];
+
+            // Comments and processing instructions carry no data
+            if rest.starts_with("<!--") {
+                pos = start + rest.find("-->").map_or(rest.len(), |end| end + 3);
+                continue;
+            }
+            if rest.starts_with("<?") {
+                pos = start + rest.find("?>").map_or(rest.len(), |end| end + 2);
+                continue;
+            }
+
+            let end = self.find_tag_end(rest).ok_or_else(|| MindmapError::ParseError {
+                message: "Unterminated tag in FreeMind map".to_string(),
+            })?;
+            let tag = &rest[..=end];
+            pos = start + end + 1;
+
+            let name = self.tag_name(tag);
+            let self_closing = tag.ends_with("/>");
+
+            match name {
+                "node" => {
+                    let node = FreemindNode {
+                        attributes: self.parse_attributes(tag),
+                        ..FreemindNode::default()
+                    };
+                    if self_closing {
+                        Self::attach(node, &mut stack, &mut roots);
+                    } else {
+                        stack.push(node);
+                    }
+                }
+                "/node" => {
+                    let node = stack.pop().ok_or_else(|| MindmapError::ParseError {
+                        message: "Unbalanced </node> in FreeMind map".to_string(),
+                    })?;
+                    Self::attach(node, &mut stack, &mut roots);
+                }
+                "attribute" => {
+                    if let Some(current) = stack.last_mut() {
+                        let attributes = self.parse_attributes(tag);
+                        if let Some(name) = attributes.get("NAME") {
+                            let value = attributes.get("VALUE").cloned().unwrap_or_default();
+                            current.metadata.push((name.clone(), value));
+                        }
+                    }
+                }
+                "arrowlink" => {
+                    if let (Some(current), Some(destination)) = (stack.last_mut(), self.parse_attributes(tag).remove("DESTINATION")) {
+                        current.links.push(destination);
+                    }
+                }
+                "richcontent" if !self_closing => {
+                    let kind = self.parse_attributes(tag).remove("TYPE").unwrap_or_default();
+                    let close = content[pos..].find("</richcontent>").ok_or_else(|| MindmapError::ParseError {
+                        message: "Unterminated <richcontent> in FreeMind map".to_string(),
+                    })?;
+                    let text = self.strip_markup(&content[pos..pos + close]);
+                    pos += close + "</richcontent>".len();
+
+                    if let Some(current) = stack.last_mut() {
+                        match kind.as_str() {
+                            "NODE" => current.rich_text = Some(text),
+                            "NOTE" => current.note = Some(text),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !stack.is_empty() {
+            return Err(MindmapError::ParseError {
+                message: "Unclosed <node> in FreeMind map".to_string(),
+            });
+        }
+
+        Ok(roots)
+    }
+
+    /// Add a completed node to its parent, or to the top level
+    fn attach(node: FreemindNode, stack: &mut [FreemindNode], roots: &mut Vec<FreemindNode>) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    /// Check whether the content's root element is a FreeMind `<map version=...>`
+    fn has_map_root(&self, content: &str) -> bool {
+        let mut rest = content.trim_start();
+
+        // Skip the XML declaration and any leading comments
+        loop {
+            if rest.starts_with("<?") {
+                rest = rest.find("?>").map_or("", |end| rest[end + 2..].trim_start());
+            } else if rest.starts_with("<!--") {
+                rest = rest.find("-->").map_or("", |end| rest[end + 3..].trim_start());
+            } else {
+                break;
+            }
+        }
+
+        rest.starts_with("<map")
+            && rest.find('>').is_some_and(|end| rest[..end].contains("version="))
+    }
+
+    /// Find the index of the `>` closing a tag, skipping quoted attribute values
+    fn find_tag_end(&self, tag: &str) -> Option<usize> {
+        let mut quote = None;
+        for (index, c) in tag.char_indices() {
+            match (quote, c) {
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(open), _) if c == open => quote = None,
+                (None, '>') => return Some(index),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Get the element name of a tag, with a leading `/` for closing tags
+    fn tag_name<'a>(&self, tag: &'a str) -> &'a str {
+        let inner = tag.trim_start_matches('<');
+        let end = inner
+            .find(|c: char| c.is_whitespace() || c == '>' || (c == '/' && !inner.starts_with('/')))
+            .unwrap_or(inner.len());
+        &inner[..end]
+    }
+
+    /// Parse the attributes of a tag into a map
+    fn parse_attributes(&self, tag: &str) -> HashMap<String, String> {
+        let mut attributes = HashMap::new();
+        let mut rest = tag
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .trim_end_matches('/');
+        rest = rest.trim_start_matches(|c: char| !c.is_whitespace());
+
+        while let Some(eq) = rest.find('=') {
+            let name = rest[..eq].trim();
+            let value_part = rest[eq + 1..].trim_start();
+            let Some(quote) = value_part.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                break;
+            };
+            let Some(close) = value_part[1..].find(quote) else {
+                break;
+            };
+
+            attributes.insert(name.to_string(), self.unescape_xml(&value_part[1..close + 1]));
+            rest = &value_part[close + 2..];
+        }
+
+        attributes
+    }
+
+    /// Reduce HTML rich content to plain text
+    fn strip_markup(&self, html: &str) -> String {
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in html.chars() {
+            match c {
+                '<' => {
+                    in_tag = true;
+                    text.push(' ');
+                }
+                '>' => in_tag = false,
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+
+        self.unescape_xml(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Parse a FreeMind millisecond timestamp
+    fn parse_timestamp(&self, value: Option<&String>) -> Option<Timestamp> {
+        value?.parse::<i64>().ok().and_then(chrono::DateTime::from_timestamp_millis)
+    }
+
+    /// Convert parsed FreeMind nodes to mindmap nodes, parents before children
+    #[allow(clippy::too_many_arguments)]
+    fn freemind_nodes_to_nodes(
+        &self,
+        items: &[FreemindNode],
+        parent_id: Option<NodeId>,
+        depth: usize,
+        side: f64,
+        y_offset: &mut f64,
+        links: &mut LinkResolver,
+        options: &ImportExportOptions,
+        nodes: &mut Vec<Node>,
+        warnings: &mut Vec<String>,
+    ) {
+        for item in items {
+            let anchor = item.attributes.get("ID").map(|id| id.strip_prefix("ID_").unwrap_or(id));
+            let node_id = links.node_id(anchor, options.preserve_ids);
+            for destination in &item.links {
+                links.add_link(node_id, destination.strip_prefix("ID_").unwrap_or(destination), None);
+            }
+
+            let text = item
+                .attributes
+                .get("TEXT")
+                .cloned()
+                .or_else(|| item.rich_text.clone())
+                .filter(|text| !text.trim().is_empty())
+                .unwrap_or_else(|| {
+                    warnings.push(format!("FreeMind node without text imported as '{}'", EMPTY_NODE_TEXT));
+                    EMPTY_NODE_TEXT.to_string()
+                });
+
+            let mut node = Node::new(text);
+            node.id = node_id;
+            node.parent_id = parent_id;
+
+            // First-level nodes choose the side of the root their subtree grows on
+            let side = match item.attributes.get("POSITION").map(String::as_str) {
+                Some("left") => -1.0,
+                Some("right") => 1.0,
+                _ => side,
+            };
+            node.position = Point::new(side * depth as f64 * 200.0, *y_offset);
+            *y_offset += 100.0;
+
+            if let Some(folded) = item.attributes.get("FOLDED") {
+                node.set_metadata(FOLDED_KEY, folded.clone());
+                node.collapsed = folded == "true";
+            }
+            if let Some(position) = item.attributes.get("POSITION") {
+                node.set_metadata(POSITION_KEY, position.clone());
+            }
+            if let Some(ref note) = item.note {
+                node.set_metadata(NOTE_KEY, note.clone());
+            }
+            if options.include_metadata {
+                for (key, value) in &item.metadata {
+                    node.set_metadata(key.clone(), value.clone());
+                }
+            }
+
+            if let Some(created_at) = self.parse_timestamp(item.attributes.get("CREATED")) {
+                node.created_at = created_at;
+            }
+            node.updated_at = self
+                .parse_timestamp(item.attributes.get("MODIFIED"))
+                .unwrap_or(node.created_at);
+
+            nodes.push(node);
+            self.freemind_nodes_to_nodes(&item.children, Some(node_id), depth + 1, side, y_offset, links, options, nodes, warnings);
+        }
+    }
+
+    /// Write a node and its descendants as FreeMind XML
+    #[allow(clippy::too_many_arguments)]
+    fn node_to_xml(
+        &self,
+        node: &Node,
+        root: &Node,
+        children_map: &HashMap<NodeId, Vec<&Node>>,
+        link_targets: &HashMap<NodeId, Vec<NodeId>>,
+        options: &ImportExportOptions,
+        depth: usize,
+        xml: &mut String,
+    ) {
+        let indent = "  ".repeat(depth + 1);
+        xml.push_str(&format!("{}<node ID=\"ID_{}\" TEXT=\"{}\"", indent, node.id, self.escape_xml(&node.text)));
+
+        if options.include_timestamps {
+            xml.push_str(&format!(
+                " CREATED=\"{}\" MODIFIED=\"{}\"",
+                node.created_at.timestamp_millis(),
+                node.updated_at.timestamp_millis()
+            ));
+        }
+
+        let children: &[&Node] = children_map.get(&node.id).map_or(&[], Vec::as_slice);
+        let include_children = options.max_depth < 0 || depth < options.max_depth as usize;
+
+        if node.collapsed && !children.is_empty() {
+            xml.push_str(" FOLDED=\"true\"");
+        }
+
+        if depth == 1 {
+            let side = node.get_metadata(POSITION_KEY).map(String::as_str).unwrap_or(
+                if node.position.x < root.position.x { "left" } else { "right" },
+            );
+            xml.push_str(&format!(" POSITION=\"{}\"", self.escape_xml(side)));
+        }
+
+        xml.push_str(">\n");
+
+        if let Some(note) = node.get_metadata(NOTE_KEY) {
+            xml.push_str(&format!(
+                "{}  <richcontent TYPE=\"NOTE\"><html><head></head><body><p>{}</p></body></html></richcontent>\n",
+                indent,
+                self.escape_xml(note)
+            ));
+        }
+
+        if options.include_metadata {
+            let mut metadata: Vec<(&String, &String)> = node
+                .metadata
+                .iter()
+                .filter(|(key, _)| ![FOLDED_KEY, POSITION_KEY, NOTE_KEY].contains(&key.as_str()))
+                .collect();
+            metadata.sort();
+
+            for (key, value) in metadata {
+                xml.push_str(&format!(
+                    "{}  <attribute NAME=\"{}\" VALUE=\"{}\"/>\n",
+                    indent,
+                    self.escape_xml(key),
+                    self.escape_xml(value)
+                ));
+            }
+        }
+
+        if let Some(targets) = link_targets.get(&node.id) {
+            for target in targets {
+                xml.push_str(&format!("{}  <arrowlink DESTINATION=\"ID_{}\"/>\n", indent, target));
+            }
+        }
+
+        if include_children {
+            for child in children {
+                self.node_to_xml(child, root, children_map, link_targets, options, depth + 1, xml);
+            }
+        }
+
+        xml.push_str(&format!("{}</node>\n", indent));
+    }
+
+    /// Escape XML special characters, keeping line breaks in attribute values
+    fn escape_xml(&self, text: &str) -> String {
+        utils::escape_xml(text).replace('\n', "&#xa;")
+    }
+
+    /// Unescape XML entities, including numeric character references
+    fn unescape_xml(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(amp) = rest.find('&') {
+            result.push_str(&rest[..amp]);
+            rest = &rest[amp..];
+
+            let Some(semi) = rest.find(';') else {
+                break;
+            };
+            let entity = &rest[1..semi];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+
+            match decoded {
+                Some(c) => {
+                    result.push(c);
+                    rest = &rest[semi + 1..];
+                }
+                None => {
+                    result.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        result.push_str(rest);
+        result
+    }
+}
+
+impl FormatHandler for FreemindHandler {
+    fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+        let mut roots = self.parse_freemind_content(content)?.into_iter();
+        let mut root = roots.next().ok_or_else(|| MindmapError::ParseError {
+            message: "FreeMind map does not contain a root node".to_string(),
+        })?;
+
+        let mut warnings = Vec::new();
+        let extra_roots: Vec<FreemindNode> = roots.collect();
+        if !extra_roots.is_empty() {
+            warnings.push(format!("{} additional top-level nodes attached to the root", extra_roots.len()));
+            root.children.extend(extra_roots);
+        }
+
+        let mut links = LinkResolver::new();
+        let mut nodes = Vec::new();
+        let mut y_offset = 0.0;
+        self.freemind_nodes_to_nodes(
+            std::slice::from_ref(&root),
+            None,
+            0,
+            1.0,
+            &mut y_offset,
+            &mut links,
+            options,
+            &mut nodes,
+            &mut warnings,
+        );
+        let edges = links.into_edges(&mut warnings);
+
+        let root_node = &nodes[0];
+        let document = Document::new(&root_node.text, root_node.id);
+
+        Ok(ImportResult {
+            document,
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1) + edges.len(),
+            nodes,
+            edges,
+            warnings,
+        })
+    }
+
+    fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        self.export_with_edges(document, nodes, &[], options)
+    }
+
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut warnings = Vec::new();
+        let nodes = &utils::truncate_to_depth(nodes, document.get_root_node(), options.max_depth, &mut warnings);
+
+        let root = nodes
+            .iter()
+            .find(|node| node.id == document.get_root_node())
+            .ok_or(MindmapError::NodeNotFound { id: document.get_root_node() })?;
+
+        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent_id) = node.parent_id {
+                children_map.entry(parent_id).or_default().push(node);
+            }
+        }
+        for children in children_map.values_mut() {
+            children.sort_by_key(|child| child.order);
+        }
+
+        let cross_links = utils::cross_links(nodes, edges);
+        let mut link_targets: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for edge in &cross_links {
+            link_targets.entry(edge.from_node).or_default().push(edge.to_node);
+        }
+
+        let mut xml = format!("<map version=\"{}\">\n", FREEMIND_VERSION);
+        xml.push_str("<!-- To view this file, download free mind mapping software FreeMind from http://freemind.sourceforge.net -->\n");
+        self.node_to_xml(root, root, &children_map, &link_targets, options, 0, &mut xml);
+        xml.push_str("</map>\n");
+
+        Ok(ExportResult {
+            content: xml,
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1) + cross_links.len(),
+            format: FileFormat::Freemind,
+            warnings,
+        })
+    }
+
+    fn format(&self) -> FileFormat {
+        FileFormat::Freemind
+    }
+
+    fn validate(&self, content: &str) -> MindmapResult<bool> {
+        Ok(self.has_map_root(content))
+    }
+}
+
+impl Default for FreemindHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A `<node>` element parsed from a FreeMind map
+#[derive(Debug, Clone, Default)]
+struct FreemindNode {
+    /// Attributes of the `<node>` tag
+    attributes: HashMap<String, String>,
+    /// Custom `<attribute>` name/value pairs
+    metadata: Vec<(String, String)>,
+    /// Destinations of `<arrowlink>` elements
+    links: Vec<String>,
+    /// Plain text of a `NODE` rich content block
+    rich_text: Option<String>,
+    /// Plain text of a `NOTE` rich content block
+    note: Option<String>,
+    children: Vec<FreemindNode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_map() -> String {
+        r#"<map version="1.0.1">
+<!-- To view this file, download free mind mapping software FreeMind from http://freemind.sourceforge.net -->
+<node CREATED="1700000000000" ID="ID_1" MODIFIED="1700000500000" TEXT="Project &amp; Plan">
+<node CREATED="1700000000000" FOLDED="true" ID="ID_2" MODIFIED="1700000000000" POSITION="right" TEXT="Research">
+<attribute NAME="owner" VALUE="alice"/>
+<arrowlink DESTINATION="ID_4" ENDARROW="Default"/>
+<node ID="ID_3" TEXT="Read&#xa;papers"/>
+</node>
+<node ID="ID_4" POSITION="left" TEXT="Build">
+<richcontent TYPE="NOTE"><html><head></head><body><p>Start &lt;soon&gt;</p></body></html></richcontent>
+</node>
+</node>
+</map>"#.to_string()
+    }
+
+    #[test]
+    fn test_freemind_validation() {
+        let handler = FreemindHandler::new();
+
+        assert!(handler.validate(&create_test_map()).unwrap());
+        assert!(handler.validate("<?xml version=\"1.0\"?>\n<map version=\"freeplane 1.9.13\"><node TEXT=\"A\"/></map>").unwrap());
+        assert!(!handler.validate("<opml version=\"2.0\"><body></body></opml>").unwrap());
+        assert!(!handler.validate("# Markdown").unwrap());
+    }
+
+    #[test]
+    fn test_freemind_import() {
+        let handler = FreemindHandler::new();
+        let result = handler.import(&create_test_map(), &ImportExportOptions::default()).unwrap();
+
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.node_count, 4);
+        assert_eq!(result.document.title, "Project & Plan");
+        assert_eq!(result.nodes[0].id, result.document.get_root_node());
+
+        let find = |text: &str| result.nodes.iter().find(|n| n.text == text).unwrap();
+        let research = find("Research");
+        assert_eq!(research.parent_id, Some(result.nodes[0].id));
+        assert_eq!(research.get_metadata("folded"), Some(&"true".to_string()));
+        assert_eq!(research.get_metadata("position"), Some(&"right".to_string()));
+        assert_eq!(research.get_metadata("owner"), Some(&"alice".to_string()));
+        assert!(research.collapsed);
+        assert_eq!(research.created_at.timestamp_millis(), 1_700_000_000_000);
+
+        let papers = find("Read\npapers");
+        assert_eq!(papers.parent_id, Some(research.id));
+
+        let build = find("Build");
+        assert_eq!(build.get_metadata("note"), Some(&"Start <soon>".to_string()));
+        assert!(build.position.x < 0.0);
+
+        assert_eq!(result.edges.len(), 1);
+        assert_eq!(result.edges[0].from_node, research.id);
+        assert_eq!(result.edges[0].to_node, build.id);
+        assert_eq!(result.edge_count, 4);
+    }
+
+    #[test]
+    fn test_freemind_round_trip() {
+        let handler = FreemindHandler::new();
+        let options = ImportExportOptions::default();
+        let imported = handler.import(&create_test_map(), &options).unwrap();
+
+        let exported = handler
+            .export_with_edges(&imported.document, &imported.nodes, &imported.edges, &options)
+            .unwrap();
+        assert_eq!(exported.format, FileFormat::Freemind);
+        assert!(exported.content.starts_with("<map version=\"1.0.1\">"));
+        assert!(exported.content.contains("TEXT=\"Project &amp; Plan\""));
+        assert!(exported.content.contains("FOLDED=\"true\""));
+        assert!(exported.content.contains("POSITION=\"left\""));
+        assert!(exported.content.contains("TEXT=\"Read&#xa;papers\""));
+
+        let reimported = handler.import(&exported.content, &options).unwrap();
+        assert_eq!(reimported.node_count, imported.node_count);
+        assert_eq!(reimported.edges.len(), 1);
+
+        for original in &imported.nodes {
+            let node = reimported.nodes.iter().find(|n| n.text == original.text).unwrap();
+            assert_eq!(node.metadata, original.metadata);
+            assert_eq!(node.collapsed, original.collapsed);
+            assert_eq!(node.created_at.timestamp_millis(), original.created_at.timestamp_millis());
+        }
+    }
+
+    #[test]
+    fn test_freemind_preserves_ids() {
+        let handler = FreemindHandler::new();
+        let root = Node::new("Root");
+        let child = Node::new_child(root.id, "Child");
+        let document = Document::new("Root", root.id);
+        let nodes = vec![root, child];
+
+        let exported = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+        let options = ImportExportOptions { preserve_ids: true, ..ImportExportOptions::default() };
+        let imported = handler.import(&exported.content, &options).unwrap();
+
+        assert_eq!(imported.nodes[0].id, nodes[0].id);
+        assert_eq!(imported.nodes[1].id, nodes[1].id);
+        assert_eq!(imported.nodes[1].parent_id, Some(nodes[0].id));
+    }
+
+    #[test]
+    fn test_malformed_map_is_rejected() {
+        let handler = FreemindHandler::new();
+        let options = ImportExportOptions::default();
+
+        assert!(handler.import("<map version=\"1.0.1\"><node TEXT=\"A\"></map>", &options).is_err());
+        assert!(handler.import("<map version=\"1.0.1\"></map>", &options).is_err());
+        assert!(handler.import("<opml></opml>", &options).is_err());
+    }
+}
@@ -0,0 +1,539 @@
+//! HTML import handler
+//!
+//! This module turns HTML, typically pasted from a web page, into a node
+//! hierarchy. Headings open nested sections, `<ul>`/`<ol>` list items become
+//! children of the enclosing section or list item, and paragraphs become leaf
+//! nodes. Inline markup, styling and attributes are dropped, so node text is
+//! plain. HTML is import-only; the parser is deliberately forgiving and never
+//! rejects malformed markup.
+
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use crate::models::document::Document;
+use crate::models::node::Node;
+use crate::types::{ids::NodeId, MindmapResult, MindmapError, Point};
+
+/// Elements that never have content or an end tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Elements whose content is not document text
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "template", "noscript"];
+
+/// Elements that implicitly close an open paragraph
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p", "div", "ul", "ol", "li", "h1", "h2", "h3", "h4", "h5", "h6", "section", "article", "table", "blockquote", "pre",
+];
+
+/// HTML format handler
+pub struct HtmlHandler;
+
+impl HtmlHandler {
+    /// Create a new HTML handler
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse HTML into a tree of elements and text
+    ///
+    /// End tags without a matching open element are ignored, and open `<li>`
+    /// and `<p>` elements are closed where HTML implies it.
+    fn parse_html(&self, content: &str) -> HtmlElement {
+        let mut stack = vec![HtmlElement::new("#root")];
+        let mut rest = content;
+
+        while !rest.is_empty() {
+            let Some(start) = rest.find('<') else {
+                push_text(&mut stack, rest);
+                break;
+            };
+            push_text(&mut stack, &rest[..start]);
+            rest = &rest[start..];
+
+            // Comments, doctypes and processing instructions
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+                continue;
+            }
+            if rest.starts_with("<!") || rest.starts_with("<?") {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+                continue;
+            }
+
+            let Some((tag, after)) = split_tag(rest) else {
+                // A lone '<' is text
+                push_text(&mut stack, "<");
+                rest = &rest[1..];
+                continue;
+            };
+            rest = after;
+
+            match tag {
+                Tag::End(name) => {
+                    if let Some(index) = stack.iter().rposition(|element| element.name == name) {
+                        close_to(&mut stack, index);
+                    }
+                }
+                Tag::Start(name, self_closing) => {
+                    if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+                        // Keep the title, drop everything else up to the end tag
+                        let end_tag = format!("</{}", name);
+                        let end = find_ignore_case(rest, &end_tag).unwrap_or(rest.len());
+                        if name == "head" {
+                            let mut head = self.parse_html(&rest[..end]);
+                            head.name = name;
+                            stack.last_mut().unwrap().children.push(HtmlContent::Element(head));
+                        }
+                        rest = &rest[end..];
+                        continue;
+                    }
+
+                    if BLOCK_ELEMENTS.contains(&name.as_str()) && stack.last().is_some_and(|element| element.name == "p") {
+                        let paragraph = stack.len() - 1;
+                        close_to(&mut stack, paragraph);
+                    }
+                    if name == "li" {
+                        // A new item closes the previous one in the same list
+                        let open_item = stack.iter().rposition(|element| element.name == "li");
+                        let open_list = stack.iter().rposition(|element| element.name == "ul" || element.name == "ol");
+                        if let (Some(item), Some(list)) = (open_item, open_list) {
+                            if item > list {
+                                close_to(&mut stack, item);
+                            }
+                        }
+                    }
+
+                    if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                        stack.last_mut().unwrap().children.push(HtmlContent::Element(HtmlElement::new(&name)));
+                    } else {
+                        stack.push(HtmlElement::new(&name));
+                    }
+                }
+            }
+        }
+
+        close_to(&mut stack, 1);
+        stack.pop().unwrap()
+    }
+
+    /// Convert the block structure under `element` into nodes
+    ///
+    /// Headings nest by level under `sections`; list items and paragraphs go
+    /// under `list_parent` when inside a list item, otherwise under the
+    /// current section.
+    fn collect_nodes(&self, element: &HtmlElement, list_parent: Option<NodeId>, builder: &mut NodeBuilder) {
+        for child in &element.children {
+            let HtmlContent::Element(child) = child else {
+                continue;
+            };
+
+            match child.name.as_str() {
+                "head" => {}
+                name if heading_level(name).is_some() => {
+                    let level = heading_level(name).unwrap_or(1);
+                    let text = inline_text(child);
+                    if text.is_empty() {
+                        continue;
+                    }
+                    while builder.sections.last().is_some_and(|&(open, _)| open >= level) {
+                        builder.sections.pop();
+                    }
+                    let parent = list_parent.unwrap_or_else(|| builder.section());
+                    let node_id = builder.add(&text, parent, "header", Some(level));
+                    builder.sections.push((level, node_id));
+                }
+                "ul" | "ol" => {
+                    let item_type = if child.name == "ol" { "numbered_item" } else { "list_item" };
+                    let parent = list_parent.unwrap_or_else(|| builder.section());
+                    for item in &child.children {
+                        match item {
+                            HtmlContent::Element(item) if item.name == "li" => {
+                                let text = inline_text(item);
+                                let item_id = if text.is_empty() {
+                                    parent
+                                } else {
+                                    builder.add(&text, parent, item_type, None)
+                                };
+                                self.collect_nodes(item, Some(item_id), builder);
+                            }
+                            // Stray content directly in the list
+                            HtmlContent::Element(other) => self.collect_nodes(other, Some(parent), builder),
+                            HtmlContent::Text(_) => {}
+                        }
+                    }
+                }
+                "p" => {
+                    let text = inline_text(child);
+                    if !text.is_empty() && list_parent.is_none() {
+                        let parent = builder.section();
+                        builder.add(&text, parent, "paragraph", None);
+                    }
+                }
+                _ => self.collect_nodes(child, list_parent, builder),
+            }
+        }
+    }
+}
+
+impl FormatHandler for HtmlHandler {
+    fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+        let tree = self.parse_html(content);
+        let title = tree
+            .find("title")
+            .map(inline_text)
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "HTML Document".to_string());
+
+        let mut root = Node::new(&title);
+        root.position = Point::new(0.0, 0.0);
+        let root_id = root.id;
+
+        let mut builder = NodeBuilder {
+            root_id,
+            nodes: vec![root],
+            sections: Vec::new(),
+            y_offset: 100.0,
+            preserve_metadata: options.include_metadata,
+        };
+        self.collect_nodes(&tree, None, &mut builder);
+
+        let mut warnings = Vec::new();
+        if builder.nodes.len() == 1 {
+            warnings.push("No headings, lists or paragraphs found in HTML".to_string());
+        }
+
+        let nodes = builder.nodes;
+        Ok(ImportResult {
+            document: Document::new(&title, root_id),
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1),
+            nodes,
+            edges: Vec::new(),
+            warnings,
+        })
+    }
+
+    fn export(&self, _document: &Document, _nodes: &[Node], _options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        Err(MindmapError::InvalidOperation {
+            message: "Exporting HTML is not supported; HTML is an import-only format".to_string(),
+        })
+    }
+
+    fn format(&self) -> FileFormat {
+        FileFormat::Html
+    }
+
+    fn validate(&self, content: &str) -> MindmapResult<bool> {
+        let start = content.trim_start().get(..64).unwrap_or(content.trim_start()).to_lowercase();
+        Ok(start.starts_with("<!doctype html") || start.starts_with("<html")
+            || ["<ul", "<ol", "<h1", "<h2", "<body"].iter().any(|tag| start.starts_with(tag)))
+    }
+}
+
+impl Default for HtmlHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An HTML element with its children; attributes are not kept
+#[derive(Debug, Clone)]
+struct HtmlElement {
+    name: String,
+    children: Vec<HtmlContent>,
+}
+
+/// Content of an HTML element
+#[derive(Debug, Clone)]
+enum HtmlContent {
+    Element(HtmlElement),
+    Text(String),
+}
+
+impl HtmlElement {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            children: Vec::new(),
+        }
+    }
+
+    /// First descendant element with the given name
+    fn find(&self, name: &str) -> Option<&HtmlElement> {
+        self.children.iter().find_map(|child| match child {
+            HtmlContent::Element(element) if element.name == name => Some(element),
+            HtmlContent::Element(element) => element.find(name),
+            HtmlContent::Text(_) => None,
+        })
+    }
+}
+
+/// A start or end tag, with the name lowercased
+enum Tag {
+    Start(String, bool),
+    End(String),
+}
+
+/// Nodes created so far and the open heading sections
+struct NodeBuilder {
+    root_id: NodeId,
+    nodes: Vec<Node>,
+    /// Open headings as (level, node), outermost first
+    sections: Vec<(usize, NodeId)>,
+    y_offset: f64,
+    preserve_metadata: bool,
+}
+
+impl NodeBuilder {
+    /// The innermost open section, or the root
+    fn section(&self) -> NodeId {
+        self.sections.last().map_or(self.root_id, |&(_, node_id)| node_id)
+    }
+
+    /// Add a node under `parent`, returning its ID
+    fn add(&mut self, text: &str, parent: NodeId, item_type: &str, level: Option<usize>) -> NodeId {
+        let depth = std::iter::successors(Some(parent), |id| {
+            self.nodes.iter().find(|node| node.id == *id).and_then(|node| node.parent_id)
+        })
+        .count();
+
+        let mut node = Node::new_child(parent, text);
+        node.position = Point::new(depth as f64 * 200.0, self.y_offset);
+        if self.preserve_metadata {
+            node.set_metadata("type", item_type);
+            if let Some(level) = level {
+                node.set_metadata("level", level.to_string());
+            }
+        }
+        self.y_offset += 80.0;
+
+        let node_id = node.id;
+        self.nodes.push(node);
+        node_id
+    }
+}
+
+/// Append text to the innermost open element
+fn push_text(stack: &mut [HtmlElement], text: &str) {
+    if !text.is_empty() {
+        if let Some(element) = stack.last_mut() {
+            element.children.push(HtmlContent::Text(decode_entities(text)));
+        }
+    }
+}
+
+/// Close the element at `index` and everything opened after it
+///
+/// The root at index 0 is never closed.
+fn close_to(stack: &mut Vec<HtmlElement>, index: usize) {
+    while stack.len() > index.max(1) {
+        let element = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(HtmlContent::Element(element));
+    }
+}
+
+/// Split a tag off the start of `input`, returning it and the remaining input
+fn split_tag(input: &str) -> Option<(Tag, &str)> {
+    let body = input.strip_prefix('<')?;
+    let (is_end, body) = match body.strip_prefix('/') {
+        Some(body) => (true, body),
+        None => (false, body),
+    };
+    if !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    // Find the closing '>' outside quoted attribute values
+    let mut quote = None;
+    let end = body.char_indices().find_map(|(index, c)| {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+        None
+    })?;
+
+    let inner = &body[..end];
+    let name: String = inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let rest = &body[end + 1..];
+
+    if is_end {
+        Some((Tag::End(name), rest))
+    } else {
+        Some((Tag::Start(name, inner.trim_end().ends_with('/')), rest))
+    }
+}
+
+/// Byte offset of `needle` in `haystack`, ignoring ASCII case
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(&needle.to_ascii_lowercase())
+}
+
+/// Level of a heading element name such as `h2`
+fn heading_level(name: &str) -> Option<usize> {
+    match name.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some((level - b'0') as usize),
+        _ => None,
+    }
+}
+
+/// Plain text of an element, without nested lists, whitespace collapsed
+fn inline_text(element: &HtmlElement) -> String {
+    fn collect(element: &HtmlElement, out: &mut String) {
+        for child in &element.children {
+            match child {
+                HtmlContent::Text(text) => out.push_str(text),
+                HtmlContent::Element(child) if child.name == "ul" || child.name == "ol" => {}
+                HtmlContent::Element(child) => {
+                    // Line breaks and block boundaries separate words
+                    if child.name == "br" || BLOCK_ELEMENTS.contains(&child.name.as_str()) {
+                        out.push(' ');
+                    }
+                    collect(child, out);
+                }
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(element, &mut text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode the character references in a piece of text
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..].find(';').filter(|&end| end <= 10).map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let value = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(value)
+            }
+        });
+
+        match (character, entity) {
+            (Some(character), Some(entity)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_html() -> String {
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Trip &amp; Plans</title><style>h1 { color: red; }</style></head>
+<body>
+  <h1 style="color: blue">Summer <em>trip</em></h1>
+  <p>Two weeks in <a href="https://example.com">Portugal</a>.</p>
+  <h2>Packing</h2>
+  <ul>
+    <li><b>Clothes</b>
+      <ul>
+        <li>Shirts</li>
+        <li>Shoes<br>and socks</li>
+      </ul>
+    </li>
+    <li class="item">Books &amp; <span style="font-weight:bold">maps</span>
+  </ul>
+  <h2>Route</h2>
+  <ol><li>Lisbon<li>Porto</ol>
+</body>
+</html>"#.to_string()
+    }
+
+    #[test]
+    fn test_nested_lists_under_headings() {
+        let handler = HtmlHandler::new();
+        let html = create_test_html();
+        assert!(handler.validate(&html).unwrap());
+
+        let result = handler.import(&html, &ImportExportOptions::default()).unwrap();
+        assert_eq!(result.document.title, "Trip & Plans");
+        assert!(result.warnings.is_empty());
+
+        let by_text: HashMap<&str, &Node> = result.nodes.iter().map(|node| (node.text.as_str(), node)).collect();
+        let parent_text = |text: &str| {
+            let parent_id = by_text[text].parent_id.unwrap();
+            result.nodes.iter().find(|node| node.id == parent_id).unwrap().text.as_str()
+        };
+
+        // Inline tags and styling are stripped from titles
+        assert_eq!(result.nodes.len(), 11);
+        assert!(by_text.contains_key("Summer trip"));
+        assert!(by_text.contains_key("Two weeks in Portugal."));
+        assert!(by_text.contains_key("Shoes and socks"));
+        assert!(by_text.contains_key("Books & maps"));
+        assert!(result.nodes.iter().all(|node| !node.text.contains('<')));
+
+        // Headings nest by level; list items nest under their section and item
+        assert_eq!(parent_text("Summer trip"), "Trip & Plans");
+        assert_eq!(parent_text("Two weeks in Portugal."), "Summer trip");
+        assert_eq!(parent_text("Packing"), "Summer trip");
+        assert_eq!(parent_text("Clothes"), "Packing");
+        assert_eq!(parent_text("Shirts"), "Clothes");
+        assert_eq!(parent_text("Shoes and socks"), "Clothes");
+        assert_eq!(parent_text("Books & maps"), "Packing");
+        assert_eq!(parent_text("Route"), "Summer trip");
+        assert_eq!(parent_text("Lisbon"), "Route");
+        assert_eq!(parent_text("Porto"), "Route");
+
+        assert_eq!(by_text["Packing"].get_metadata("level").map(String::as_str), Some("2"));
+        assert_eq!(by_text["Porto"].get_metadata("type").map(String::as_str), Some("numbered_item"));
+        assert!(result.to_graph().is_ok());
+    }
+
+    #[test]
+    fn test_fragment_and_entities() {
+        let handler = HtmlHandler::new();
+        let result = handler
+            .import("<ul><li>caf&#233; &lt;3 &#x1F600;</li><li>R&amp;D</ul>", &ImportExportOptions::default())
+            .unwrap();
+
+        assert_eq!(result.document.title, "HTML Document");
+        let texts: Vec<&str> = result.nodes.iter().skip(1).map(|node| node.text.as_str()).collect();
+        assert_eq!(texts, vec!["café <3 😀", "R&D"]);
+        assert!(result.nodes.iter().skip(1).all(|node| node.parent_id == Some(result.document.root_node)));
+
+        let empty = handler.import("<div></div>", &ImportExportOptions::default()).unwrap();
+        assert_eq!(empty.node_count, 1);
+        assert_eq!(empty.warnings.len(), 1);
+    }
+}
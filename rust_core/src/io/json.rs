@@ -0,0 +1,361 @@
+//! JSON (native mindmap format) import/export handler
+//!
+//! This module serializes a document together with all of its nodes and edges
+//! into a stable JSON schema, so a mindmap can be saved and reloaded without
+//! losing styling, tags, metadata, positions or cross-links.
+
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use crate::models::document::Document;
+use crate::models::edge::Edge;
+use crate::models::node::Node;
+use crate::types::{ids::{DocumentId, EdgeId, NodeId}, MindmapResult, MindmapError};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Current version of the JSON schema
+pub const JSON_FORMAT_VERSION: &str = "1.0";
+
+/// On-disk representation of a mindmap document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JsonDocument {
+    /// Schema version
+    version: String,
+    /// Document properties
+    document: Document,
+    /// All nodes of the document, parents before children
+    nodes: Vec<Node>,
+    /// Edges between nodes of the document
+    #[serde(default)]
+    edges: Vec<Edge>,
+}
+
+/// JSON format handler
+pub struct JsonHandler;
+
+impl JsonHandler {
+    /// Create a new JSON handler
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse JSON content, checking that the schema version is supported
+    fn parse_json_content(&self, content: &str) -> MindmapResult<JsonDocument> {
+        let json_doc: JsonDocument = serde_json::from_str(content).map_err(|e| MindmapError::ParseError {
+            message: format!("Invalid mindmap JSON: {}", e),
+        })?;
+
+        let major = json_doc.version.split('.').next().unwrap_or_default();
+        let supported_major = JSON_FORMAT_VERSION.split('.').next().unwrap_or_default();
+        if major != supported_major {
+            return Err(MindmapError::ParseError {
+                message: format!("Unsupported mindmap JSON version: {}", json_doc.version),
+            });
+        }
+
+        Ok(json_doc)
+    }
+
+    /// Order nodes so that every parent comes before its children
+    ///
+    /// Nodes are visited breadth-first from the root, followed by any other
+    /// parentless nodes and finally nodes whose parent is not in the set.
+    fn order_parents_first(&self, nodes: Vec<Node>, root_id: NodeId) -> Vec<Node> {
+        let mut children: HashMap<NodeId, Vec<usize>> = HashMap::new();
+        let ids: HashSet<NodeId> = nodes.iter().map(|node| node.id).collect();
+        let mut starts = Vec::new();
+
+        for (index, node) in nodes.iter().enumerate() {
+            match node.parent_id {
+                Some(parent_id) if ids.contains(&parent_id) => {
+                    children.entry(parent_id).or_default().push(index);
+                }
+                _ if node.id == root_id => starts.insert(0, index),
+                _ => starts.push(index),
+            }
+        }
+
+        let mut ordered_indices = Vec::with_capacity(nodes.len());
+        let mut queue: VecDeque<usize> = starts.into_iter().collect();
+        while let Some(index) = queue.pop_front() {
+            ordered_indices.push(index);
+            if let Some(child_indices) = children.get(&nodes[index].id) {
+                queue.extend(child_indices.iter().copied());
+            }
+        }
+
+        let mut slots: Vec<Option<Node>> = nodes.into_iter().map(Some).collect();
+        ordered_indices
+            .into_iter()
+            .filter_map(|index| slots[index].take())
+            .collect()
+    }
+
+    /// Give the document, all nodes and all edges fresh IDs, keeping their relationships
+    fn regenerate_ids(&self, document: &mut Document, nodes: &mut [Node], edges: &mut [Edge]) {
+        let id_map: HashMap<NodeId, NodeId> = nodes
+            .iter()
+            .map(|node| (node.id, NodeId::new()))
+            .collect();
+
+        for node in nodes.iter_mut() {
+            node.id = id_map[&node.id];
+            node.parent_id = node.parent_id.and_then(|parent_id| id_map.get(&parent_id).copied());
+        }
+
+        for edge in edges.iter_mut() {
+            edge.id = EdgeId::new();
+            edge.from_node = id_map[&edge.from_node];
+            edge.to_node = id_map[&edge.to_node];
+        }
+
+        document.id = DocumentId::new();
+        if let Some(root_id) = id_map.get(&document.root_node) {
+            document.root_node = *root_id;
+        }
+    }
+}
+
+impl FormatHandler for JsonHandler {
+    fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+        let JsonDocument { mut document, nodes, edges, .. } = self.parse_json_content(content)?;
+        let mut warnings = Vec::new();
+
+        let ids: HashSet<NodeId> = nodes.iter().map(|node| node.id).collect();
+        let mut nodes: Vec<Node> = nodes
+            .into_iter()
+            .map(|mut node| {
+                if let Some(parent_id) = node.parent_id.filter(|parent_id| !ids.contains(parent_id)) {
+                    warnings.push(format!("Node {} references missing parent {}; imported as a root", node.id, parent_id));
+                    node.parent_id = None;
+                }
+                node
+            })
+            .collect();
+
+        let mut edges: Vec<Edge> = edges
+            .into_iter()
+            .filter(|edge| {
+                let connected = ids.contains(&edge.from_node) && ids.contains(&edge.to_node);
+                if !connected {
+                    warnings.push(format!("Edge {} references a missing node; edge dropped", edge.id));
+                }
+                connected
+            })
+            .collect();
+
+        if !ids.contains(&document.root_node) {
+            warnings.push(format!("Document root {} is not among the imported nodes", document.root_node));
+        }
+
+        if !options.preserve_ids {
+            self.regenerate_ids(&mut document, &mut nodes, &mut edges);
+        }
+
+        let nodes = self.order_parents_first(nodes, document.root_node);
+        let edge_count = nodes.iter().filter(|node| node.parent_id.is_some()).count() + edges.len();
+
+        Ok(ImportResult {
+            document,
+            node_count: nodes.len(),
+            edge_count,
+            nodes,
+            edges,
+            warnings,
+        })
+    }
+
+    fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        self.export_with_edges(document, nodes, &[], options)
+    }
+
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut exported: Vec<Node> = nodes
+            .iter()
+            .filter(|node| options.include_empty_nodes || !node.text.trim().is_empty() || node.id == document.root_node)
+            .cloned()
+            .collect();
+
+        if !options.include_metadata {
+            for node in &mut exported {
+                node.metadata.clear();
+            }
+        }
+
+        let encoding = options.position_encoding;
+        if encoding.is_fixed_point() {
+            for node in &mut exported {
+                node.position.x = encoding.quantize(node.position.x);
+                node.position.y = encoding.quantize(node.position.y);
+            }
+        }
+
+        let exported = self.order_parents_first(exported, document.root_node);
+        let exported_ids: HashSet<NodeId> = exported.iter().map(|node| node.id).collect();
+        let exported_edges: Vec<Edge> = edges
+            .iter()
+            .filter(|edge| exported_ids.contains(&edge.from_node) && exported_ids.contains(&edge.to_node))
+            .cloned()
+            .collect();
+        let edge_count = exported.iter().filter(|node| node.parent_id.is_some()).count() + exported_edges.len();
+
+        let json_doc = JsonDocument {
+            version: JSON_FORMAT_VERSION.to_string(),
+            document: document.clone(),
+            nodes: exported,
+            edges: exported_edges,
+        };
+
+        let content = serde_json::to_string_pretty(&json_doc).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Failed to serialize document: {}", e),
+        })?;
+
+        Ok(ExportResult {
+            content,
+            node_count: json_doc.nodes.len(),
+            edge_count,
+            format: FileFormat::Json,
+            warnings: Vec::new(),
+        })
+    }
+
+    fn format(&self) -> FileFormat {
+        FileFormat::Json
+    }
+
+    fn validate(&self, content: &str) -> MindmapResult<bool> {
+        Ok(self.parse_json_content(content).is_ok())
+    }
+}
+
+impl Default for JsonHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Point, PositionEncoding};
+
+    fn create_test_document() -> (Document, Vec<Node>) {
+        let mut root = Node::new("Project");
+        root.set_position(Point::new(0.0, 0.0));
+        let root_id = root.id;
+
+        let mut child = Node::new_child(root_id, "Research");
+        child.add_tag("important");
+        child.set_metadata("owner", "alice");
+        child.set_position(Point::new(120.5, -40.25));
+
+        let mut grandchild = Node::new_child(child.id, "Read papers");
+        grandchild.add_tag("reading");
+        grandchild.set_position(Point::new(240.0, -80.0));
+
+        let document = Document::new("Project", root_id);
+        // Deliberately put children first to exercise ordering
+        (document, vec![grandchild, child, root])
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let handler = JsonHandler::new();
+        let (document, nodes) = create_test_document();
+
+        let exported = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+        assert_eq!(exported.format, FileFormat::Json);
+        assert_eq!(exported.node_count, 3);
+        assert_eq!(exported.edge_count, 2);
+
+        let options = ImportExportOptions { preserve_ids: true, ..ImportExportOptions::default() };
+        let imported = handler.import(&exported.content, &options).unwrap();
+
+        assert!(imported.warnings.is_empty());
+        assert_eq!(imported.document.id, document.id);
+        assert_eq!(imported.nodes[0].id, document.get_root_node());
+
+        for original in &nodes {
+            let restored = imported.nodes.iter().find(|n| n.id == original.id).unwrap();
+            assert_eq!(restored.text, original.text);
+            assert_eq!(restored.tags, original.tags);
+            assert_eq!(restored.metadata, original.metadata);
+            assert_eq!(restored.position, original.position);
+            assert_eq!(restored.parent_id, original.parent_id);
+        }
+    }
+
+    #[test]
+    fn test_import_regenerates_ids() {
+        let handler = JsonHandler::new();
+        let (document, nodes) = create_test_document();
+        let exported = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+
+        let imported = handler.import(&exported.content, &ImportExportOptions::default()).unwrap();
+        let original_ids: HashSet<NodeId> = nodes.iter().map(|n| n.id).collect();
+
+        assert_ne!(imported.document.id, document.id);
+        assert!(imported.nodes.iter().all(|n| !original_ids.contains(&n.id)));
+
+        // Relationships survive the remapping
+        let root = &imported.nodes[0];
+        assert_eq!(root.id, imported.document.get_root_node());
+        let research = imported.nodes.iter().find(|n| n.text == "Research").unwrap();
+        let papers = imported.nodes.iter().find(|n| n.text == "Read papers").unwrap();
+        assert_eq!(research.parent_id, Some(root.id));
+        assert_eq!(papers.parent_id, Some(research.id));
+    }
+
+    #[test]
+    fn test_json_round_trip_with_edges() {
+        let handler = JsonHandler::new();
+        let (document, nodes) = create_test_document();
+        let edges = vec![Edge::new_with_label(nodes[0].id, nodes[2].id, "see also")];
+
+        let exported = handler.export_with_edges(&document, &nodes, &edges, &ImportExportOptions::default()).unwrap();
+        assert_eq!(exported.edge_count, 3);
+
+        let imported = handler.import(&exported.content, &ImportExportOptions::default()).unwrap();
+        assert_eq!(imported.edges.len(), 1);
+        assert_eq!(imported.edge_count, 3);
+
+        let find = |text: &str| imported.nodes.iter().find(|n| n.text == text).unwrap().id;
+        assert_eq!(imported.edges[0].from_node, find("Read papers"));
+        assert_eq!(imported.edges[0].to_node, find("Project"));
+        assert_eq!(imported.edges[0].label.as_deref(), Some("see also"));
+    }
+
+    #[test]
+    fn test_fixed_point_positions_shrink_export() {
+        let handler = JsonHandler::new();
+        let (document, mut nodes) = create_test_document();
+        nodes[0].set_position(Point::new(100.0 / 3.0, -200.0 / 7.0));
+
+        let full = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+        let encoding = PositionEncoding::fixed_point(2);
+        let options = ImportExportOptions { position_encoding: encoding, preserve_ids: true, ..ImportExportOptions::default() };
+        let compact = handler.export(&document, &nodes, &options).unwrap();
+        assert!(compact.content.len() < full.content.len());
+
+        let imported = handler.import(&compact.content, &options).unwrap();
+        for original in &nodes {
+            let restored = imported.nodes.iter().find(|n| n.id == original.id).unwrap();
+            assert!((restored.position.x - original.position.x).abs() <= encoding.precision());
+            assert!((restored.position.y - original.position.y).abs() <= encoding.precision());
+        }
+    }
+
+    #[test]
+    fn test_json_validation() {
+        let handler = JsonHandler::new();
+        let (document, nodes) = create_test_document();
+        let exported = handler.export(&document, &nodes, &ImportExportOptions::default()).unwrap();
+
+        assert!(handler.validate(&exported.content).unwrap());
+        assert!(!handler.validate("{}").unwrap());
+        assert!(!handler.validate("{\"nodes\": []}").unwrap());
+        assert!(!handler.validate("not json").unwrap());
+
+        let future = exported.content.replacen("\"1.0\"", "\"2.0\"", 1);
+        assert!(handler.import(&future, &ImportExportOptions::default()).is_err());
+    }
+}
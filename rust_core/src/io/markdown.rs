@@ -3,12 +3,21 @@
 //! This module provides functionality to import and export mindmap documents
 //! to/from Markdown outline format, preserving the hierarchical structure using
 //! headers and list items.
+//!
+//! Cross-links between nodes are written as reference-style link definitions
+//! after the outline. Linked items carry a `{#id}` anchor, and each link is a
+//! line of the form `[source->target]: #target "label"`.
+//!
+//! Node tags are written after the item text as `[tags: a, b]`.
 
-use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult, ExportStats};
+use super::utils::{self, LinkResolver};
 use crate::models::document::Document;
+use crate::models::edge::Edge;
 use crate::models::node::Node;
-use crate::types::{ids::NodeId, MindmapResult, Point};
-use std::collections::HashMap;
+use crate::types::{ids::NodeId, MindmapResult, MindmapError, Point};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Write};
 
 /// Markdown format handler
 pub struct MarkdownHandler;
@@ -29,7 +38,8 @@ impl MarkdownHandler {
         for line in &lines {
             let trimmed = line.trim();
             if trimmed.starts_with('#') {
-                title = trimmed.trim_start_matches('#').trim().to_string();
+                let (text, _) = self.split_anchor(trimmed.trim_start_matches('#').trim());
+                title = self.split_tags(&text).0;
                 if title.is_empty() {
                     title = "Untitled".to_string();
                 }
@@ -48,9 +58,64 @@ impl MarkdownHandler {
             outline_items = self.parse_simple_list(&lines)?;
         }
 
+        let links = lines.iter().filter_map(|line| self.parse_link_definition(line)).collect();
+
         Ok(MarkdownDocument {
             title,
             outline_items,
+            links,
+        })
+    }
+
+    /// Split a trailing `[tags: a, b]` list off item text
+    fn split_tags(&self, text: &str) -> (String, Vec<String>) {
+        if let Some(stripped) = text.strip_suffix(']') {
+            if let Some(start) = stripped.rfind("[tags:") {
+                let tags = stripped[start + "[tags:".len()..]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                return (stripped[..start].trim_end().to_string(), tags);
+            }
+        }
+
+        (text.to_string(), Vec::new())
+    }
+
+    /// Split a trailing `{#anchor}` off item text
+    fn split_anchor(&self, text: &str) -> (String, Option<String>) {
+        if let Some(stripped) = text.strip_suffix('}') {
+            if let Some(start) = stripped.rfind("{#") {
+                let anchor = &stripped[start + 2..];
+                if !anchor.is_empty() && !anchor.contains(char::is_whitespace) {
+                    return (stripped[..start].trim_end().to_string(), Some(anchor.to_string()));
+                }
+            }
+        }
+
+        (text.to_string(), None)
+    }
+
+    /// Parse a cross-link definition line: `[source->target]: #target "label"`
+    fn parse_link_definition(&self, line: &str) -> Option<MarkdownLink> {
+        let rest = line.trim().strip_prefix('[')?;
+        let (key, rest) = rest.split_once("]: #")?;
+        let (source, _) = key.split_once("->")?;
+
+        let (target, label) = match rest.split_once(' ') {
+            Some((target, label)) => {
+                let label = label.trim().strip_prefix('"')?.strip_suffix('"')?;
+                (target, Some(label.replace("\\\"", "\"")))
+            }
+            None => (rest, None),
+        };
+
+        Some(MarkdownLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            label,
         })
     }
 
@@ -77,6 +142,8 @@ impl MarkdownHandler {
                         text,
                         level,
                         item_type: MarkdownItemType::Header,
+                        anchor: None,
+                        tags: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -89,6 +156,8 @@ impl MarkdownHandler {
                         text,
                         level,
                         item_type: MarkdownItemType::ListItem,
+                        anchor: None,
+                        tags: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -101,6 +170,8 @@ impl MarkdownHandler {
                         text,
                         level,
                         item_type: MarkdownItemType::NumberedItem,
+                        anchor: None,
+                        tags: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -120,7 +191,7 @@ impl MarkdownHandler {
         for line in lines {
             let trimmed = line.trim();
 
-            if trimmed.is_empty() {
+            if trimmed.is_empty() || self.parse_link_definition(trimmed).is_some() {
                 continue;
             }
 
@@ -132,6 +203,8 @@ impl MarkdownHandler {
                         text,
                         level,
                         item_type: MarkdownItemType::ListItem,
+                        anchor: None,
+                        tags: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -142,6 +215,8 @@ impl MarkdownHandler {
                         text,
                         level,
                         item_type: MarkdownItemType::NumberedItem,
+                        anchor: None,
+                        tags: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -151,6 +226,8 @@ impl MarkdownHandler {
                     text: trimmed.to_string(),
                     level: 1,
                     item_type: MarkdownItemType::Text,
+                    anchor: None,
+                    tags: Vec::new(),
                     children: Vec::new(),
                 });
             }
@@ -209,7 +286,13 @@ impl MarkdownHandler {
         let mut result = Vec::new();
         let mut stack: Vec<(usize, usize)> = Vec::new(); // (level, index in result)
 
-        for item in items {
+        for mut item in items {
+            let (text, anchor) = self.split_anchor(&item.text);
+            let (text, tags) = self.split_tags(&text);
+            item.text = text;
+            item.anchor = anchor;
+            item.tags = tags;
+
             // Find the appropriate parent level
             while let Some(&(parent_level, _)) = stack.last() {
                 if parent_level < item.level {
@@ -240,11 +323,11 @@ impl MarkdownHandler {
     }
 
     /// Convert markdown items to mindmap nodes
-    fn markdown_items_to_nodes(&self, items: &[MarkdownItem], parent_id: Option<NodeId>, x_offset: f64, y_offset: &mut f64) -> Vec<Node> {
+    fn markdown_items_to_nodes(&self, items: &[MarkdownItem], parent_id: Option<NodeId>, x_offset: f64, y_offset: &mut f64, links: &mut LinkResolver, options: &ImportExportOptions) -> Vec<Node> {
         let mut nodes = Vec::new();
 
         for item in items {
-            let node_id = NodeId::new();
+            let node_id = links.node_id(item.anchor.as_deref(), options.preserve_ids);
             let mut node = Node::new(&item.text);
             node.id = node_id;
             node.parent_id = parent_id;
@@ -267,6 +350,12 @@ impl MarkdownHandler {
                 }
             }
 
+            if options.tags_enabled() {
+                for tag in &item.tags {
+                    node.add_tag(tag.as_str());
+                }
+            }
+
             *y_offset += 80.0; // Space nodes vertically
             nodes.push(node);
 
@@ -276,7 +365,9 @@ impl MarkdownHandler {
                     &item.children,
                     Some(node_id),
                     x_offset + 200.0, // Indent children
-                    y_offset
+                    y_offset,
+                    links,
+                    options,
                 );
                 nodes.append(&mut child_nodes);
             }
@@ -285,33 +376,8 @@ impl MarkdownHandler {
         nodes
     }
 
-    /// Convert nodes to markdown items
-    fn nodes_to_markdown_items(&self, nodes: &[Node], root_node_id: NodeId, options: &ImportExportOptions) -> Vec<MarkdownItem> {
-        let mut items = Vec::new();
-
-        // Build parent-child relationships
-        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
-        for node in nodes {
-            if let Some(parent_id) = node.parent_id {
-                children_map.entry(parent_id).or_default().push(node);
-            }
-        }
-
-        // Start from root and build items recursively
-        if let Some(_root_node) = nodes.iter().find(|n| n.id == root_node_id) {
-            if let Some(child_nodes) = children_map.get(&root_node_id) {
-                for child_node in child_nodes {
-                    let item = self.node_to_markdown_item(child_node, &children_map, options, 1);
-                    items.push(item);
-                }
-            }
-        }
-
-        items
-    }
-
-    /// Convert a single node to markdown item
-    fn node_to_markdown_item(&self, node: &Node, children_map: &HashMap<NodeId, Vec<&Node>>, options: &ImportExportOptions, level: usize) -> MarkdownItem {
+    /// Convert a single node to a markdown item, without its children
+    fn node_to_markdown_item(&self, node: &Node, anchors: &HashSet<NodeId>, options: &ImportExportOptions, level: usize) -> MarkdownItem {
         let text = if options.include_empty_nodes || !node.text.trim().is_empty() {
             node.text.clone()
         } else {
@@ -330,69 +396,148 @@ impl MarkdownHandler {
             MarkdownItemType::ListItem
         };
 
-        let mut children = Vec::new();
-        if let Some(child_nodes) = children_map.get(&node.id) {
-            for child_node in child_nodes {
-                if options.max_depth < 0 || level < options.max_depth as usize {
-                    children.push(self.node_to_markdown_item(child_node, children_map, options, level + 1));
-                }
-            }
-        }
-
         MarkdownItem {
             text,
             level,
             item_type,
-            children,
+            anchor: anchors.contains(&node.id).then(|| node.id.to_string()),
+            tags: if options.tags_enabled() { node.tags.clone() } else { Vec::new() },
+            children: Vec::new(),
         }
     }
 
-    /// Generate markdown text from items
-    fn generate_markdown(&self, doc: &MarkdownDocument) -> String {
-        let mut markdown = String::new();
+    /// Write a complete markdown outline for the given nodes
+    fn write_markdown(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        let mut warnings = Vec::new();
+        let root_node_id = document.get_root_node();
+        let nodes = &utils::truncate_to_depth(nodes, root_node_id, options.max_depth, &mut warnings);
+
+        let cross_links = utils::cross_links(nodes, edges);
+        let anchors: HashSet<NodeId> = cross_links
+            .iter()
+            .flat_map(|edge| [edge.from_node, edge.to_node])
+            .collect();
+
+        // Build parent-child relationships
+        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent_id) = node.parent_id {
+                children_map.entry(parent_id).or_default().push(node);
+            }
+        }
+
+        let mut out = utils::CountingWriter::new(BufWriter::new(writer));
 
         // Add title as main header
-        markdown.push_str(&format!("# {}\n\n", doc.title));
+        let title_anchor = anchors.contains(&root_node_id).then(|| root_node_id.to_string());
+        write!(out, "# {}{}\n\n", document.title, self.anchor_suffix(&title_anchor)).map_err(utils::write_error)?;
+
+        // Add outline items, starting below the root
+        if nodes.iter().any(|n| n.id == root_node_id) {
+            for child_node in children_map.get(&root_node_id).into_iter().flatten() {
+                self.write_markdown_item(child_node, &children_map, &anchors, options, 1, 0, &mut out)
+                    .map_err(utils::write_error)?;
+            }
+        }
+
+        // Add cross-links as reference definitions
+        if !cross_links.is_empty() {
+            out.write_all(b"\n").map_err(utils::write_error)?;
+            for edge in &cross_links {
+                let link = MarkdownLink {
+                    source: edge.from_node.to_string(),
+                    target: edge.to_node.to_string(),
+                    label: edge.label.clone(),
+                };
+                self.write_link(&link, &mut out).map_err(utils::write_error)?;
+            }
+        }
+        out.flush().map_err(utils::write_error)?;
+
+        Ok(ExportStats {
+            bytes_written: out.bytes_written(),
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1) + cross_links.len(),
+            format: FileFormat::Markdown,
+            warnings,
+        })
+    }
 
-        // Add outline items
-        for item in &doc.outline_items {
-            markdown.push_str(&self.markdown_item_to_text(item, 0));
+    /// Write a cross-link as a reference definition line
+    fn write_link(&self, link: &MarkdownLink, out: &mut dyn Write) -> std::io::Result<()> {
+        write!(out, "[{}->{}]: #{}", link.source, link.target, link.target)?;
+        if let Some(ref label) = link.label {
+            write!(out, " \"{}\"", label.replace('"', "\\\""))?;
         }
+        out.write_all(b"\n")
+    }
 
-        markdown
+    /// Format an optional anchor as a ` {#anchor}` suffix
+    fn anchor_suffix(&self, anchor: &Option<String>) -> String {
+        anchor.as_ref().map(|anchor| format!(" {{#{}}}", anchor)).unwrap_or_default()
     }
 
-    /// Convert markdown item to text representation
-    fn markdown_item_to_text(&self, item: &MarkdownItem, base_level: usize) -> String {
-        let mut text = String::new();
+    /// Write a node and its subtree as markdown lines
+    #[allow(clippy::too_many_arguments)]
+    fn write_markdown_item(&self, node: &Node, children_map: &HashMap<NodeId, Vec<&Node>>, anchors: &HashSet<NodeId>, options: &ImportExportOptions, level: usize, base_level: usize, out: &mut dyn Write) -> std::io::Result<()> {
+        let item = self.node_to_markdown_item(node, anchors, options, level);
         let indent = "  ".repeat(base_level);
+        let mut anchor = self.anchor_suffix(&item.anchor);
+        if !item.tags.is_empty() {
+            anchor.insert_str(0, &format!(" [tags: {}]", item.tags.join(", ")));
+        }
 
-        match item.item_type {
+        // Notes are indented to line up with the item's text
+        let body_indent = match item.item_type {
             MarkdownItemType::Header => {
                 let level = (item.level + base_level).min(6); // Markdown supports up to 6 header levels
-                text.push_str(&format!("{} {}\n", "#".repeat(level), item.text));
+                writeln!(out, "{} {}{}", "#".repeat(level), item.text, anchor)?;
+                String::new()
             }
             MarkdownItemType::ListItem => {
-                text.push_str(&format!("{}* {}\n", indent, item.text));
+                writeln!(out, "{}* {}{}", indent, item.text, anchor)?;
+                format!("{}  ", indent)
             }
             MarkdownItemType::NumberedItem => {
-                text.push_str(&format!("{}1. {}\n", indent, item.text));
+                writeln!(out, "{}1. {}{}", indent, item.text, anchor)?;
+                format!("{}   ", indent)
             }
             MarkdownItemType::Text => {
                 if base_level == 0 {
-                    text.push_str(&format!("{}\n", item.text));
+                    writeln!(out, "{}{}", item.text, anchor)?;
+                    String::new()
                 } else {
-                    text.push_str(&format!("{}* {}\n", indent, item.text));
+                    writeln!(out, "{}* {}{}", indent, item.text, anchor)?;
+                    format!("{}  ", indent)
                 }
             }
+        };
+
+        if let Some(note) = node.note.as_deref() {
+            self.write_note(note, &body_indent, out)?;
         }
 
         // Add children
-        for child in &item.children {
-            text.push_str(&self.markdown_item_to_text(child, base_level + 1));
+        if options.max_depth < 0 || level < options.max_depth as usize {
+            for child_node in children_map.get(&node.id).into_iter().flatten() {
+                self.write_markdown_item(child_node, children_map, anchors, options, level + 1, base_level + 1, out)?;
+            }
         }
 
-        text
+        Ok(())
+    }
+
+    /// Write a note as body text under its item, separated by blank lines
+    fn write_note(&self, note: &str, indent: &str, out: &mut dyn Write) -> std::io::Result<()> {
+        out.write_all(b"\n")?;
+        for line in note.trim_end().lines() {
+            if line.trim().is_empty() {
+                out.write_all(b"\n")?;
+            } else {
+                writeln!(out, "{}{}", indent, line)?;
+            }
+        }
+        out.write_all(b"\n")
     }
 
     /// Escape markdown special characters
@@ -412,7 +557,7 @@ impl MarkdownHandler {
 }
 
 impl FormatHandler for MarkdownHandler {
-    fn import(&self, content: &str, _options: &ImportExportOptions) -> MindmapResult<ImportResult> {
+    fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
         let markdown_doc = self.parse_markdown_content(content)?;
 
         // Create root node
@@ -423,39 +568,58 @@ impl FormatHandler for MarkdownHandler {
 
         // Convert markdown items to nodes
         let mut y_offset = 100.0;
+        let mut links = LinkResolver::new();
         let mut nodes = vec![root_node];
-        let mut markdown_nodes = self.markdown_items_to_nodes(&markdown_doc.outline_items, Some(root_node_id), 200.0, &mut y_offset);
+        let mut markdown_nodes = self.markdown_items_to_nodes(&markdown_doc.outline_items, Some(root_node_id), 200.0, &mut y_offset, &mut links, options);
         nodes.append(&mut markdown_nodes);
 
+        // Resolve cross-links now that every anchored node exists
+        let mut warnings = Vec::new();
+        for link in markdown_doc.links {
+            match links.resolve_anchor(&link.source) {
+                Some(from) => links.add_link(from, link.target, link.label),
+                None => warnings.push(format!("Link source '{}' not found; link dropped", link.source)),
+            }
+        }
+        let edges = links.into_edges(&mut warnings);
+
         // Create document
         let document = Document::new(&markdown_doc.title, root_node_id);
 
         Ok(ImportResult {
             document,
             node_count: nodes.len(),
-            edge_count: nodes.len().saturating_sub(1),
-            warnings: Vec::new(),
+            edge_count: nodes.len().saturating_sub(1) + edges.len(),
+            nodes,
+            edges,
+            warnings,
         })
     }
 
     fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
-        let markdown_items = self.nodes_to_markdown_items(nodes, document.get_root_node(), options);
-
-        let markdown_doc = MarkdownDocument {
-            title: document.title.clone(),
-            outline_items: markdown_items,
-        };
+        self.export_with_edges(document, nodes, &[], options)
+    }
 
-        let content = self.generate_markdown(&markdown_doc);
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut buffer = Vec::new();
+        let stats = self.write_markdown(document, nodes, edges, options, &mut buffer)?;
+        let content = String::from_utf8(buffer).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Generated Markdown is not valid UTF-8: {}", e),
+        })?;
 
         Ok(ExportResult {
             content,
-            node_count: nodes.len(),
-            edge_count: nodes.len().saturating_sub(1),
-            format: FileFormat::Markdown,
+            node_count: stats.node_count,
+            edge_count: stats.edge_count,
+            format: stats.format,
+            warnings: stats.warnings,
         })
     }
 
+    fn export_to_writer(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        self.write_markdown(document, nodes, edges, options, writer)
+    }
+
     fn format(&self) -> FileFormat {
         FileFormat::Markdown
     }
@@ -487,6 +651,7 @@ impl Default for MarkdownHandler {
 struct MarkdownDocument {
     title: String,
     outline_items: Vec<MarkdownItem>,
+    links: Vec<MarkdownLink>,
 }
 
 /// Represents an item in markdown outline
@@ -495,9 +660,20 @@ struct MarkdownItem {
     text: String,
     level: usize,
     item_type: MarkdownItemType,
+    /// Anchor identifying this item as a cross-link endpoint
+    anchor: Option<String>,
+    tags: Vec<String>,
     children: Vec<MarkdownItem>,
 }
 
+/// Represents a cross-link between two anchored items
+#[derive(Debug, Clone)]
+struct MarkdownLink {
+    source: String,
+    target: String,
+    label: Option<String>,
+}
+
 /// Types of markdown items
 #[derive(Debug, Clone, PartialEq)]
 enum MarkdownItemType {
@@ -616,4 +792,127 @@ Some plain text content"#.to_string()
         assert_eq!(doc.title, "Markdown Document");
         assert!(!doc.outline_items.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cross_link_round_trip() {
+        let handler = MarkdownHandler::new();
+        let options = ImportExportOptions::default();
+
+        let root = Node::new("Project");
+        let design = Node::new_child(root.id, "Design");
+        let build = Node::new_child(root.id, "Build");
+        let document = Document::new("Project", root.id);
+        let edges = vec![Edge::new_with_label(build.id, design.id, "depends on \"v2\"")];
+        let nodes = vec![root, design, build];
+
+        let exported = handler.export_with_edges(&document, &nodes, &edges, &options).unwrap();
+        assert!(exported.content.contains(&format!("* Design {{#{}}}", nodes[1].id)));
+        assert_eq!(exported.edge_count, 3);
+
+        let imported = handler.import(&exported.content, &options).unwrap();
+        assert!(imported.warnings.is_empty());
+        assert_eq!(imported.edges.len(), 1);
+        assert!(imported.nodes.iter().all(|n| !n.text.contains("{#")));
+
+        let find = |text: &str| imported.nodes.iter().find(|n| n.text == text).unwrap().id;
+        let edge = &imported.edges[0];
+        assert_eq!(edge.from_node, find("Build"));
+        assert_eq!(edge.to_node, find("Design"));
+        assert_eq!(edge.label.as_deref(), Some("depends on \"v2\""));
+    }
+
+    #[test]
+    fn test_anchor_and_link_parsing() {
+        let handler = MarkdownHandler::new();
+
+        assert_eq!(handler.split_anchor("Item {#a1}"), ("Item".to_string(), Some("a1".to_string())));
+        assert_eq!(handler.split_anchor("Set {a, b}"), ("Set {a, b}".to_string(), None));
+
+        let link = handler.parse_link_definition("[a1->b2]: #b2").unwrap();
+        assert_eq!((link.source.as_str(), link.target.as_str(), link.label), ("a1", "b2", None));
+        assert!(handler.parse_link_definition("[text](url)").is_none());
+    }
+
+    #[test]
+    fn test_tags_round_trip() {
+        let handler = MarkdownHandler::new();
+        let options = ImportExportOptions::default();
+
+        let root = Node::new("Project");
+        let mut topic = Node::new_child(root.id, "Topic");
+        topic.tags = vec!["ai".to_string(), "ml".to_string()];
+        let document = Document::new("Project", root.id);
+        let nodes = vec![root, topic];
+
+        let exported = handler.export(&document, &nodes, &options).unwrap();
+        assert!(exported.content.contains("* Topic [tags: ai, ml]"));
+
+        let imported = handler.import(&exported.content, &options).unwrap();
+        let topic = imported.nodes.iter().find(|n| n.text == "Topic").unwrap();
+        assert_eq!(topic.tags, vec!["ai".to_string(), "ml".to_string()]);
+
+        // Tags are skipped when metadata is excluded
+        let without_metadata = ImportExportOptions { include_metadata: false, ..ImportExportOptions::default() };
+        let exported = handler.export(&document, &nodes, &without_metadata).unwrap();
+        assert!(!exported.content.contains("[tags:"));
+    }
+
+    #[test]
+    fn test_max_depth_truncation() {
+        let handler = MarkdownHandler::new();
+
+        let root = Node::new("Project");
+        let design = Node::new_child(root.id, "Design");
+        let build = Node::new_child(root.id, "Build");
+        let mockups = Node::new_child(design.id, "Mockups");
+        let testing = Node::new_child(build.id, "Testing");
+        let document = Document::new("Project", root.id);
+        let nodes = vec![root, design, build, mockups, testing];
+
+        let export = |max_depth| {
+            let options = ImportExportOptions { max_depth, ..ImportExportOptions::default() };
+            handler.export(&document, &nodes, &options).unwrap()
+        };
+
+        let depth_0 = export(0);
+        assert!(!depth_0.content.contains("Design"));
+        assert_eq!(depth_0.node_count, 1);
+        assert_eq!(depth_0.warnings.len(), 1);
+
+        let depth_1 = export(1);
+        assert!(depth_1.content.contains("* Design"));
+        assert!(depth_1.content.contains("* Build"));
+        assert!(!depth_1.content.contains("Mockups"));
+        assert!(!depth_1.content.contains("Testing"));
+        assert_eq!(depth_1.node_count, 3);
+        assert_eq!(depth_1.warnings.len(), 1);
+
+        let depth_2 = export(2);
+        assert!(depth_2.content.contains("  * Mockups"));
+        assert!(depth_2.content.contains("  * Testing"));
+        assert_eq!(depth_2.node_count, 5);
+        assert!(depth_2.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_note_rendered_under_its_bullet() {
+        let handler = MarkdownHandler::new();
+        let options = ImportExportOptions::default();
+
+        let root = Node::new("Project");
+        let mut topic = Node::new_child(root.id, "Topic");
+        topic.set_note(Some("First *paragraph*\n\nSecond paragraph".to_string()));
+        let mut detail = Node::new_child(topic.id, "Detail");
+        detail.set_note(Some("Detail note".to_string()));
+        let document = Document::new("Project", root.id);
+        let nodes = vec![root, topic, detail];
+
+        let exported = handler.export(&document, &nodes, &options).unwrap();
+        let expected = "* Topic\n\n  First *paragraph*\n\n  Second paragraph\n\n  * Detail\n\n    Detail note\n\n";
+        assert!(exported.content.contains(expected), "{}", exported.content);
+
+        // Note lines are body text, not outline items, when read back
+        let imported = handler.import(&exported.content, &options).unwrap();
+        assert!(imported.nodes.iter().all(|n| !n.text.contains("paragraph") && !n.text.contains("note")));
+    }
+}
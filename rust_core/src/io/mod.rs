@@ -1,15 +1,26 @@
 //! File format handlers for mindmap documents
 //!
 //! This module provides import and export functionality for various file formats
-//! including OPML and Markdown, with format detection and validation utilities.
+//! including OPML, Markdown, JSON, FreeMind, CSV, GraphViz DOT and HTML, with format detection and validation utilities.
 
 pub mod opml;
 pub mod markdown;
-
+pub mod json;
+pub mod freemind;
+pub mod dot;
+pub mod csv;
+pub mod html;
+
+use crate::events::EngineEvent;
+use crate::graph::Graph;
 use crate::models::document::Document;
+use crate::models::edge::Edge;
 use crate::models::node::Node;
-use crate::types::{MindmapResult, MindmapError};
+use crate::types::{MindmapResult, MindmapError, PositionEncoding, Timestamp};
+use crate::types::clock::Instant;
+use chrono::{FixedOffset, SecondsFormat, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 use std::path::Path;
 
 /// Supported file formats for import/export
@@ -23,6 +34,14 @@ pub enum FileFormat {
     Json,
     /// Plain text format
     Text,
+    /// FreeMind/Freeplane mind map format
+    Freemind,
+    /// GraphViz DOT graph description (export only)
+    Dot,
+    /// Comma-separated values, one row per node
+    Csv,
+    /// HTML headings and lists (import only)
+    Html,
 }
 
 impl FileFormat {
@@ -33,6 +52,10 @@ impl FileFormat {
             FileFormat::Markdown => "md",
             FileFormat::Json => "json",
             FileFormat::Text => "txt",
+            FileFormat::Freemind => "mm",
+            FileFormat::Dot => "dot",
+            FileFormat::Csv => "csv",
+            FileFormat::Html => "html",
         }
     }
 
@@ -43,6 +66,10 @@ impl FileFormat {
             FileFormat::Markdown => "text/markdown",
             FileFormat::Json => "application/json",
             FileFormat::Text => "text/plain",
+            FileFormat::Freemind => "application/x-freemind",
+            FileFormat::Dot => "text/vnd.graphviz",
+            FileFormat::Csv => "text/csv",
+            FileFormat::Html => "text/html",
         }
     }
 
@@ -53,10 +80,26 @@ impl FileFormat {
             FileFormat::Markdown => "Markdown Outline",
             FileFormat::Json => "JSON (Native Mindmap Format)",
             FileFormat::Text => "Plain Text",
+            FileFormat::Freemind => "FreeMind Mind Map",
+            FileFormat::Dot => "GraphViz DOT Graph",
+            FileFormat::Csv => "CSV (Comma-Separated Values)",
+            FileFormat::Html => "HTML Outline",
         }
     }
 }
 
+/// How node timestamps are written by formats that store them as text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimestampFormat {
+    /// RFC 3339 with millisecond precision and a timezone, e.g. `2024-05-01T12:30:00.250+02:00`
+    #[default]
+    Rfc3339,
+    /// Whole seconds since the Unix epoch
+    UnixSeconds,
+    /// Milliseconds since the Unix epoch
+    UnixMillis,
+}
+
 /// Import/export options for file operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportExportOptions {
@@ -64,14 +107,28 @@ pub struct ImportExportOptions {
     pub preserve_ids: bool,
     /// Whether to include metadata in export (default: true)
     pub include_metadata: bool,
+    /// Whether to include node tags in formats without native tag support (default: true)
+    ///
+    /// Only takes effect together with `include_metadata`.
+    #[serde(default = "default_include_tags")]
+    pub include_tags: bool,
     /// Whether to include timestamps in export (default: true)
     pub include_timestamps: bool,
+    /// How node timestamps are written (default: RFC 3339)
+    #[serde(default)]
+    pub timestamp_format: TimestampFormat,
+    /// Offset from UTC in seconds used for RFC 3339 timestamps (default: None - UTC)
+    #[serde(default)]
+    pub timestamp_offset: Option<i32>,
     /// Maximum depth for export (-1 for unlimited, default: -1)
     pub max_depth: i32,
     /// Whether to include empty nodes (default: false)
     pub include_empty_nodes: bool,
     /// Text encoding for export (default: UTF-8)
     pub encoding: String,
+    /// Precision of node positions written by the JSON format (default: full precision)
+    #[serde(default)]
+    pub position_encoding: PositionEncoding,
 }
 
 impl Default for ImportExportOptions {
@@ -79,22 +136,84 @@ impl Default for ImportExportOptions {
         Self {
             preserve_ids: false,
             include_metadata: true,
+            include_tags: true,
             include_timestamps: true,
+            timestamp_format: TimestampFormat::Rfc3339,
+            timestamp_offset: None,
             max_depth: -1,
             include_empty_nodes: false,
             encoding: "UTF-8".to_string(),
+            position_encoding: PositionEncoding::Float,
+        }
+    }
+}
+
+impl ImportResult {
+    /// Build a graph from the imported nodes and cross-link edges
+    ///
+    /// Nodes are inserted with a single bulk validation pass, so large imports
+    /// don't pay for per-node parent checks.
+    pub fn to_graph(&self) -> MindmapResult<Graph> {
+        let mut graph = Graph::new();
+        graph.add_nodes_bulk(self.nodes.clone())?;
+        for edge in &self.edges {
+            graph.add_edge(edge.clone())?;
         }
+        Ok(graph)
     }
 }
 
+impl ImportExportOptions {
+    /// Check whether node tags should be written and read
+    pub fn tags_enabled(&self) -> bool {
+        self.include_metadata && self.include_tags
+    }
+
+    /// Render a timestamp in the configured format and timezone
+    ///
+    /// Offsets of a day or more are out of range and fall back to UTC.
+    pub fn format_timestamp(&self, timestamp: &Timestamp) -> String {
+        match self.timestamp_format {
+            TimestampFormat::Rfc3339 => match self.timestamp_offset.and_then(FixedOffset::east_opt) {
+                Some(offset) => timestamp.with_timezone(&offset).to_rfc3339_opts(SecondsFormat::Millis, false),
+                None => timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            },
+            TimestampFormat::UnixSeconds => timestamp.timestamp().to_string(),
+            TimestampFormat::UnixMillis => timestamp.timestamp_millis().to_string(),
+        }
+    }
+
+    /// Parse a timestamp written with the configured format
+    ///
+    /// RFC 3339 values may carry any offset; they are converted to UTC.
+    pub fn parse_timestamp(&self, value: &str) -> Option<Timestamp> {
+        let value = value.trim();
+        match self.timestamp_format {
+            TimestampFormat::Rfc3339 => chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|timestamp| timestamp.with_timezone(&Utc)),
+            TimestampFormat::UnixSeconds => value.parse::<i64>().ok().and_then(|seconds| Utc.timestamp_opt(seconds, 0).single()),
+            TimestampFormat::UnixMillis => value.parse::<i64>().ok().and_then(chrono::DateTime::from_timestamp_millis),
+        }
+    }
+}
+
+fn default_include_tags() -> bool {
+    true
+}
+
 /// Result of an import operation
 #[derive(Debug, Clone)]
 pub struct ImportResult {
     /// The imported document
     pub document: Document,
+    /// The imported nodes, root first
+    pub nodes: Vec<Node>,
+    /// Imported cross-link edges between nodes outside the parent-child tree
+    pub edges: Vec<Edge>,
     /// Number of nodes imported
     pub node_count: usize,
-    /// Number of edges imported
+    /// Number of edges imported, counting parent-child links and cross-links
     pub edge_count: usize,
     /// Any warnings encountered during import
     pub warnings: Vec<String>,
@@ -111,6 +230,23 @@ pub struct ExportResult {
     pub edge_count: usize,
     /// File format used for export
     pub format: FileFormat,
+    /// Any warnings encountered during export
+    pub warnings: Vec<String>,
+}
+
+/// Result of an export written to a writer
+#[derive(Debug, Clone)]
+pub struct ExportStats {
+    /// Number of bytes written
+    pub bytes_written: u64,
+    /// Number of nodes exported
+    pub node_count: usize,
+    /// Number of edges exported
+    pub edge_count: usize,
+    /// File format used for export
+    pub format: FileFormat,
+    /// Any warnings encountered during export
+    pub warnings: Vec<String>,
 }
 
 /// Trait for file format handlers
@@ -121,6 +257,32 @@ pub trait FormatHandler {
     /// Export a document to the format's string representation
     fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult>;
 
+    /// Export a document including cross-link edges between its nodes
+    ///
+    /// Formats that cannot represent edges ignore them by default.
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], _edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        self.export(document, nodes, options)
+    }
+
+    /// Export a document including cross-link edges directly to a writer,
+    /// e.g. a file or socket
+    ///
+    /// The default builds the whole export with
+    /// [`export_with_edges`](Self::export_with_edges) and then writes it.
+    /// Handlers that can write incrementally override this so large
+    /// documents are never held in memory as a single string.
+    fn export_to_writer(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        let result = self.export_with_edges(document, nodes, edges, options)?;
+        writer.write_all(result.content.as_bytes()).map_err(utils::write_error)?;
+        Ok(ExportStats {
+            bytes_written: result.content.len() as u64,
+            node_count: result.node_count,
+            edge_count: result.edge_count,
+            format: result.format,
+            warnings: result.warnings,
+        })
+    }
+
     /// Get the file format this handler supports
     fn format(&self) -> FileFormat;
 
@@ -143,6 +305,11 @@ impl FormatManager {
         // Register default handlers
         manager.register_handler(Box::new(opml::OpmlHandler::new()));
         manager.register_handler(Box::new(markdown::MarkdownHandler::new()));
+        manager.register_handler(Box::new(json::JsonHandler::new()));
+        manager.register_handler(Box::new(freemind::FreemindHandler::new()));
+        manager.register_handler(Box::new(dot::DotHandler::new()));
+        manager.register_handler(Box::new(csv::CsvHandler::new()));
+        manager.register_handler(Box::new(html::HtmlHandler::new()));
 
         manager
     }
@@ -159,7 +326,16 @@ impl FormatManager {
                 message: format!("No handler registered for format: {:?}", format),
             })?;
 
-        handler.import(content, options)
+        let started = Instant::now();
+        let result = handler.import(content, options)?;
+        crate::events::emit(EngineEvent::ImportFinished {
+            format,
+            document_id: result.document.id,
+            node_count: result.node_count,
+            warning_count: result.warnings.len(),
+            duration: started.elapsed(),
+        });
+        Ok(result)
     }
 
     /// Export a document to a specific format
@@ -172,6 +348,38 @@ impl FormatManager {
         handler.export(document, nodes, options)
     }
 
+    /// Export a document with its cross-link edges to a specific format
+    pub fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], format: FileFormat, options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let handler = self.handlers.get(&format)
+            .ok_or_else(|| MindmapError::InvalidOperation {
+                message: format!("No handler registered for format: {:?}", format),
+            })?;
+
+        handler.export_with_edges(document, nodes, edges, options)
+    }
+
+    /// Export a document with its cross-link edges in a specific format directly to a writer
+    pub fn export_to_writer(&self, document: &Document, nodes: &[Node], edges: &[Edge], format: FileFormat, options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        let handler = self.handlers.get(&format)
+            .ok_or_else(|| MindmapError::InvalidOperation {
+                message: format!("No handler registered for format: {:?}", format),
+            })?;
+
+        handler.export_to_writer(document, nodes, edges, options, writer)
+    }
+
+    /// Export only the nodes matching a predicate
+    ///
+    /// Ancestors of matching nodes and the document root are kept as well, so
+    /// the exported outline stays connected.
+    pub fn export_filtered<F>(&self, document: &Document, nodes: &[Node], format: FileFormat, options: &ImportExportOptions, predicate: F) -> MindmapResult<ExportResult>
+    where
+        F: Fn(&Node) -> bool,
+    {
+        let filtered = utils::filter_with_ancestors(nodes, document.get_root_node(), predicate);
+        self.export(document, &filtered, format, options)
+    }
+
     /// Detect the file format from file extension
     pub fn detect_format_from_path(&self, path: &Path) -> Option<FileFormat> {
         let extension = path.extension()?.to_str()?.to_lowercase();
@@ -181,6 +389,10 @@ impl FormatManager {
             "md" | "markdown" => Some(FileFormat::Markdown),
             "json" => Some(FileFormat::Json),
             "txt" | "text" => Some(FileFormat::Text),
+            "mm" => Some(FileFormat::Freemind),
+            "dot" | "gv" => Some(FileFormat::Dot),
+            "csv" => Some(FileFormat::Csv),
+            "html" | "htm" => Some(FileFormat::Html),
             _ => None,
         }
     }
@@ -198,6 +410,8 @@ impl FormatManager {
         let trimmed = content.trim();
         if trimmed.starts_with("<?xml") && trimmed.contains("<opml") {
             Some(FileFormat::Opml)
+        } else if trimmed.contains("<map version=") {
+            Some(FileFormat::Freemind)
         } else if trimmed.starts_with('#') || trimmed.contains("- ") || trimmed.contains("* ") {
             Some(FileFormat::Markdown)
         } else if (trimmed.starts_with('{') && trimmed.ends_with('}')) ||
@@ -208,6 +422,29 @@ impl FormatManager {
         }
     }
 
+    /// Detect the file format of a file, trying its extension first and then its content
+    ///
+    /// An extension is only trusted when a handler is registered for it, so that
+    /// e.g. a `.txt` file containing OPML is still recognized by its content.
+    pub fn detect_format(&self, path: &Path, content: &str) -> Option<FileFormat> {
+        self.detect_format_from_path(path)
+            .filter(|format| self.handlers.contains_key(format))
+            .or_else(|| self.detect_format_from_content(content))
+    }
+
+    /// Import a document from a file, detecting its format automatically
+    pub fn import_file(&self, path: &Path, options: &ImportExportOptions) -> MindmapResult<(FileFormat, ImportResult)> {
+        let content = utils::read_file_with_encoding(path)?;
+
+        let format = self.detect_format(path, &content)
+            .ok_or_else(|| MindmapError::InvalidOperation {
+                message: format!("Could not detect format of {}", path.display()),
+            })?;
+
+        let result = self.import(&content, format, options)?;
+        Ok((format, result))
+    }
+
     /// Get list of supported formats
     pub fn supported_formats(&self) -> Vec<FileFormat> {
         self.handlers.keys().copied().collect()
@@ -233,6 +470,50 @@ impl Default for FormatManager {
 /// Utility functions for file operations
 pub mod utils {
     use super::*;
+    use crate::types::NodeId;
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    /// Writer that counts the bytes written through it
+    pub struct CountingWriter<W> {
+        inner: W,
+        bytes_written: u64,
+    }
+
+    impl<W: Write> CountingWriter<W> {
+        /// Wrap a writer
+        pub fn new(inner: W) -> Self {
+            Self { inner, bytes_written: 0 }
+        }
+
+        /// Number of bytes written so far
+        pub fn bytes_written(&self) -> u64 {
+            self.bytes_written
+        }
+
+        /// Unwrap the inner writer
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+
+    impl<W: Write> Write for CountingWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let written = self.inner.write(buf)?;
+            self.bytes_written += written as u64;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    /// Convert a failed write of export output into a mindmap error
+    pub fn write_error(error: std::io::Error) -> MindmapError {
+        MindmapError::IoError {
+            message: format!("Failed to write export: {}", error),
+        }
+    }
 
     /// Read file content and detect encoding
     pub fn read_file_with_encoding(path: &Path) -> MindmapResult<String> {
@@ -268,6 +549,156 @@ pub mod utils {
         }
     }
 
+    /// Select nodes matching a predicate together with their ancestors
+    ///
+    /// The root node is always kept. Nodes are returned in their original order.
+    pub fn filter_with_ancestors<F>(nodes: &[Node], root_id: NodeId, predicate: F) -> Vec<Node>
+    where
+        F: Fn(&Node) -> bool,
+    {
+        let by_id: HashMap<NodeId, &Node> = nodes.iter().map(|node| (node.id, node)).collect();
+        let mut keep: HashSet<NodeId> = HashSet::new();
+        keep.insert(root_id);
+
+        for node in nodes.iter().filter(|node| predicate(node)) {
+            let mut current = Some(node);
+            while let Some(current_node) = current {
+                // Stop once we reach a chain that is already kept
+                if !keep.insert(current_node.id) && current_node.id != node.id {
+                    break;
+                }
+                current = current_node.parent_id.and_then(|parent_id| by_id.get(&parent_id).copied());
+            }
+        }
+
+        nodes.iter().filter(|node| keep.contains(&node.id)).cloned().collect()
+    }
+
+    /// Drop nodes nested deeper than `max_depth` below the root
+    ///
+    /// The root is at depth 0 and a negative `max_depth` keeps every node. Nodes
+    /// that are not connected to the root are left untouched. A warning is
+    /// recorded when anything was omitted.
+    pub fn truncate_to_depth(nodes: &[Node], root_id: NodeId, max_depth: i32, warnings: &mut Vec<String>) -> Vec<Node> {
+        if max_depth < 0 {
+            return nodes.to_vec();
+        }
+
+        let mut children_map: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent_id) = node.parent_id {
+                children_map.entry(parent_id).or_default().push(node.id);
+            }
+        }
+
+        // Collect everything below the depth limit
+        let mut omitted: HashSet<NodeId> = HashSet::new();
+        let mut queue = VecDeque::from([(root_id, 0usize)]);
+        while let Some((node_id, depth)) = queue.pop_front() {
+            if depth > max_depth as usize && !omitted.insert(node_id) {
+                continue;
+            }
+            for child_id in children_map.get(&node_id).into_iter().flatten() {
+                queue.push_back((*child_id, depth + 1));
+            }
+        }
+
+        if !omitted.is_empty() {
+            warnings.push(format!(
+                "Omitted {} node(s) nested deeper than the maximum depth of {}",
+                omitted.len(),
+                max_depth
+            ));
+        }
+
+        nodes.iter().filter(|node| !omitted.contains(&node.id)).cloned().collect()
+    }
+
+    /// Select edges that need to be exported as cross-links between the given nodes
+    ///
+    /// Edges with an endpoint outside `nodes` and edges duplicating a parent-child
+    /// link are skipped, since the outline structure already encodes the latter.
+    pub fn cross_links<'a>(nodes: &[Node], edges: &'a [Edge]) -> Vec<&'a Edge> {
+        let parents: HashMap<NodeId, Option<NodeId>> = nodes.iter().map(|node| (node.id, node.parent_id)).collect();
+
+        edges
+            .iter()
+            .filter(|edge| {
+                let (Some(from_parent), Some(to_parent)) = (parents.get(&edge.from_node), parents.get(&edge.to_node)) else {
+                    return false;
+                };
+                *to_parent != Some(edge.from_node) && *from_parent != Some(edge.to_node)
+            })
+            .collect()
+    }
+
+    /// Resolves cross-link references found while importing an outline
+    ///
+    /// Formats identify link endpoints with textual anchors. Nodes register
+    /// their anchor as they are created and links are turned into edges once
+    /// every node is known, so links may point forward in the document.
+    #[derive(Debug, Default)]
+    pub struct LinkResolver {
+        /// Node created for each anchor
+        anchors: HashMap<String, NodeId>,
+        /// Links waiting for their target: (source, target anchor, label)
+        pending: Vec<(NodeId, String, Option<String>)>,
+    }
+
+    impl LinkResolver {
+        /// Create an empty resolver
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Pick the ID for an imported node and register its anchor
+        ///
+        /// When IDs are preserved and the anchor is a valid node ID it is reused,
+        /// otherwise a fresh ID is generated.
+        pub fn node_id(&mut self, anchor: Option<&str>, preserve_ids: bool) -> NodeId {
+            let node_id = anchor
+                .filter(|_| preserve_ids)
+                .and_then(|anchor| anchor.parse::<NodeId>().ok())
+                .filter(|id| !self.anchors.values().any(|existing| existing == id))
+                .unwrap_or_default();
+
+            if let Some(anchor) = anchor {
+                self.anchors.entry(anchor.to_string()).or_insert(node_id);
+            }
+            node_id
+        }
+
+        /// Look up the node registered for an anchor
+        pub fn resolve_anchor(&self, anchor: &str) -> Option<NodeId> {
+            self.anchors.get(anchor).copied()
+        }
+
+        /// Record a link from a node to the node carrying `target_anchor`
+        pub fn add_link(&mut self, from: NodeId, target_anchor: impl Into<String>, label: Option<String>) {
+            self.pending.push((from, target_anchor.into(), label));
+        }
+
+        /// Turn the recorded links into edges
+        ///
+        /// Links to unknown anchors are dropped with a warning.
+        pub fn into_edges(self, warnings: &mut Vec<String>) -> Vec<Edge> {
+            let mut edges = Vec::with_capacity(self.pending.len());
+
+            for (from, target_anchor, label) in self.pending {
+                match self.anchors.get(&target_anchor) {
+                    Some(&to) => {
+                        let mut edge = Edge::new(from, to);
+                        edge.label = label;
+                        edges.push(edge);
+                    }
+                    None => warnings.push(format!("Link target '{}' not found; link dropped", target_anchor)),
+                }
+            }
+
+            edges
+        }
+    }
+
     /// Sanitize text for file output
     pub fn sanitize_text(text: &str) -> String {
         text.chars()
@@ -283,7 +714,9 @@ pub mod utils {
     /// Escape special characters for specific formats
     pub fn escape_for_format(text: &str, format: FileFormat) -> String {
         match format {
-            FileFormat::Opml => escape_xml(text),
+            FileFormat::Opml | FileFormat::Freemind | FileFormat::Html => escape_xml(text),
+            FileFormat::Dot => escape_dot(text),
+            FileFormat::Csv => escape_csv(text),
             FileFormat::Markdown => escape_markdown(text),
             FileFormat::Json => serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text)),
             FileFormat::Text => text.to_string(),
@@ -299,6 +732,23 @@ pub mod utils {
             .replace('\'', "&apos;")
     }
 
+    /// Escape text for a quoted DOT string
+    pub fn escape_dot(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace("\r\n", "\\n")
+            .replace(['\n', '\r'], "\\n")
+    }
+
+    /// Quote a CSV field if needed, as described in RFC 4180
+    pub fn escape_csv(text: &str) -> String {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    }
+
     /// Escape text for Markdown
     pub fn escape_markdown(text: &str) -> String {
         text.replace('\\', "\\\\")
@@ -325,9 +775,15 @@ mod tests {
         assert_eq!(FileFormat::Markdown.extension(), "md");
         assert_eq!(FileFormat::Json.extension(), "json");
         assert_eq!(FileFormat::Text.extension(), "txt");
+        assert_eq!(FileFormat::Freemind.extension(), "mm");
 
         assert_eq!(FileFormat::Opml.mime_type(), "text/x-opml");
         assert_eq!(FileFormat::Markdown.mime_type(), "text/markdown");
+        assert_eq!(FileFormat::Freemind.mime_type(), "application/x-freemind");
+        assert_eq!(FileFormat::Dot.extension(), "dot");
+        assert_eq!(FileFormat::Dot.mime_type(), "text/vnd.graphviz");
+        assert_eq!(FileFormat::Csv.extension(), "csv");
+        assert_eq!(FileFormat::Csv.mime_type(), "text/csv");
     }
 
     #[test]
@@ -335,6 +791,7 @@ mod tests {
         let options = ImportExportOptions::default();
         assert!(!options.preserve_ids);
         assert!(options.include_metadata);
+        assert!(options.include_tags);
         assert!(options.include_timestamps);
         assert_eq!(options.max_depth, -1);
         assert!(!options.include_empty_nodes);
@@ -348,7 +805,12 @@ mod tests {
 
         assert!(formats.contains(&FileFormat::Opml));
         assert!(formats.contains(&FileFormat::Markdown));
-        assert_eq!(formats.len(), 2);
+        assert!(formats.contains(&FileFormat::Json));
+        assert!(formats.contains(&FileFormat::Freemind));
+        assert!(formats.contains(&FileFormat::Dot));
+        assert!(formats.contains(&FileFormat::Csv));
+        assert!(formats.contains(&FileFormat::Html));
+        assert_eq!(formats.len(), 7);
     }
 
     #[test]
@@ -360,6 +822,10 @@ mod tests {
         assert_eq!(manager.detect_format_from_path(Path::new("test.markdown")), Some(FileFormat::Markdown));
         assert_eq!(manager.detect_format_from_path(Path::new("test.json")), Some(FileFormat::Json));
         assert_eq!(manager.detect_format_from_path(Path::new("test.txt")), Some(FileFormat::Text));
+        assert_eq!(manager.detect_format_from_path(Path::new("test.mm")), Some(FileFormat::Freemind));
+        assert_eq!(manager.detect_format_from_path(Path::new("test.gv")), Some(FileFormat::Dot));
+        assert_eq!(manager.detect_format_from_path(Path::new("test.csv")), Some(FileFormat::Csv));
+        assert_eq!(manager.detect_format_from_path(Path::new("page.htm")), Some(FileFormat::Html));
         assert_eq!(manager.detect_format_from_path(Path::new("test.unknown")), None);
     }
 
@@ -375,6 +841,10 @@ mod tests {
 </opml>"#;
         assert_eq!(manager.detect_format_from_content(opml_content), Some(FileFormat::Opml));
 
+        // FreeMind content
+        let freemind_content = "<map version=\"1.0.1\">\n<node TEXT=\"Root\">\n<node TEXT=\"Child\"/>\n</node>\n</map>";
+        assert_eq!(manager.detect_format_from_content(freemind_content), Some(FileFormat::Freemind));
+
         // Markdown content
         assert_eq!(manager.detect_format_from_content("# Title\n- Item 1"), Some(FileFormat::Markdown));
         assert_eq!(manager.detect_format_from_content("* Item 1\n* Item 2"), Some(FileFormat::Markdown));
@@ -387,6 +857,113 @@ mod tests {
         assert_eq!(manager.detect_format_from_content("plain text"), Some(FileFormat::Text));
     }
 
+    #[test]
+    fn test_import_file_detects_format() {
+        let manager = FormatManager::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let opml_path = dir.path().join("outline.opml");
+        std::fs::write(&opml_path, r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+<head><title>Plan</title></head>
+<body>
+<outline text="First" />
+<outline text="Second" />
+</body>
+</opml>"#).unwrap();
+
+        let (format, result) = manager.import_file(&opml_path, &ImportExportOptions::default()).unwrap();
+        assert_eq!(format, FileFormat::Opml);
+        assert_eq!(result.node_count, 3);
+        assert_eq!(result.nodes.len(), 3);
+
+        let md_path = dir.path().join("notes.md");
+        std::fs::write(&md_path, "- One\n  - Two\n- Three\n").unwrap();
+
+        let (format, result) = manager.import_file(&md_path, &ImportExportOptions::default()).unwrap();
+        assert_eq!(format, FileFormat::Markdown);
+        assert_eq!(result.node_count, 4);
+        assert_eq!(result.nodes[0].id, result.document.root_node);
+    }
+
+    #[test]
+    fn test_detect_format_falls_back_to_content() {
+        let manager = FormatManager::new();
+        let opml = "<opml version=\"2.0\"><head><title>T</title></head><body></body></opml>";
+
+        assert_eq!(manager.detect_format(Path::new("export.txt"), opml), Some(FileFormat::Opml));
+        assert_eq!(manager.detect_format(Path::new("export"), "# Title\n- Item"), Some(FileFormat::Markdown));
+    }
+
+    #[test]
+    fn test_export_filtered_keeps_matches_and_ancestors() {
+        let manager = FormatManager::new();
+
+        let root = Node::new("Project");
+        let root_id = root.id;
+        let mut planning = Node::new_child(root_id, "Planning");
+        planning.add_tag("keep");
+        let draft = Node::new_child(planning.id, "Draft notes");
+        let build = Node::new_child(root_id, "Build");
+        let mut release = Node::new_child(build.id, "Release checklist");
+        release.add_tag("keep");
+        let archive = Node::new_child(root_id, "Archive");
+        let old = Node::new_child(archive.id, "Old ideas");
+
+        let nodes = vec![root, planning, draft, build, release, archive, old];
+        let document = Document::new("Project", root_id);
+
+        let result = manager
+            .export_filtered(&document, &nodes, FileFormat::Markdown, &ImportExportOptions::default(), |node| {
+                node.tags.iter().any(|tag| tag == "keep")
+            })
+            .unwrap();
+
+        assert!(result.content.contains("Planning"));
+        assert!(result.content.contains("Release checklist"));
+        assert!(result.content.contains("Build"));
+        assert!(!result.content.contains("Draft notes"));
+        assert!(!result.content.contains("Archive"));
+        assert!(!result.content.contains("Old ideas"));
+    }
+
+    #[test]
+    fn test_export_to_writer_matches_export() {
+        let manager = FormatManager::new();
+
+        let mut root = Node::new("Project");
+        root.set_metadata("note", "Top & level");
+        let root_id = root.id;
+        let mut planning = Node::new_child(root_id, "Planning <draft>");
+        planning.add_tag("keep");
+        let draft = Node::new_child(planning.id, "Draft notes");
+        let build = Node::new_child(root_id, "Build");
+        let edges = vec![Edge::new_with_label(draft.id, build.id, "feeds")];
+        let nodes = vec![root, planning, draft, build];
+        let document = Document::new("Project", root_id);
+        let options = ImportExportOptions::default();
+
+        // Streaming handlers and the buffered default all match `export_with_edges`
+        for format in [FileFormat::Opml, FileFormat::Markdown, FileFormat::Json, FileFormat::Csv] {
+            let exported = manager.export_with_edges(&document, &nodes, &edges, format, &options).unwrap();
+            let mut buffer = Vec::new();
+            let stats = manager.export_to_writer(&document, &nodes, &edges, format, &options, &mut buffer).unwrap();
+
+            assert_eq!(String::from_utf8(buffer).unwrap(), exported.content, "{:?}", format);
+            assert_eq!(stats.bytes_written, exported.content.len() as u64);
+            assert_eq!(stats.node_count, exported.node_count);
+            assert_eq!(stats.edge_count, exported.edge_count);
+            assert_eq!(stats.format, format);
+        }
+
+        // Cross-links survive streaming in the formats that carry them
+        for format in [FileFormat::Opml, FileFormat::Markdown] {
+            let tree_only = manager.export(&document, &nodes, format, &options).unwrap();
+            let stats = manager.export_to_writer(&document, &nodes, &edges, format, &options, &mut Vec::new()).unwrap();
+            assert_eq!(stats.edge_count, tree_only.edge_count + 1, "{:?}", format);
+        }
+    }
+
     #[test]
     fn test_utils_sanitize_text() {
         assert_eq!(utils::sanitize_text("Hello\x00World\x1F"), "Hello World");
@@ -2,12 +2,24 @@
 //!
 //! This module provides functionality to import and export mindmap documents
 //! to/from OPML format, preserving the hierarchical structure of nodes.
+//!
+//! Cross-links between nodes are stored as outline attributes: endpoints carry
+//! an `_id` attribute and the source lists its targets in `_edgeTarget`,
+//! separated by spaces. Edge labels are not preserved.
+//!
+//! Node tags are written to the `category` attribute as a comma-separated list.
 
-use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult};
+use super::{FormatHandler, FileFormat, ImportExportOptions, ImportResult, ExportResult, ExportStats};
+use super::utils::{self, LinkResolver};
 use crate::models::document::Document;
+use crate::models::edge::Edge;
 use crate::models::node::Node;
 use crate::types::{ids::NodeId, MindmapResult, MindmapError, Point};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Write};
+
+/// Deepest chain of nested `type="include"` outlines that will be followed
+pub const MAX_INCLUDE_DEPTH: usize = 32;
 
 /// OPML format handler
 pub struct OpmlHandler;
@@ -92,6 +104,12 @@ impl OpmlHandler {
                 text,
                 note,
                 depth,
+                id: self.extract_outline_attribute(tag_content, "_id"),
+                edge_targets: self.extract_edge_targets(tag_content),
+                categories: self.extract_categories(tag_content),
+                created: self.extract_outline_attribute(tag_content, "created"),
+                modified: self.extract_outline_attribute(tag_content, "modified"),
+                include_url: self.extract_include_url(tag_content),
                 children: Vec::new(),
             };
 
@@ -145,6 +163,12 @@ impl OpmlHandler {
                     text,
                     note,
                     depth,
+                    id: self.extract_outline_attribute(tag_content, "_id"),
+                    edge_targets: self.extract_edge_targets(tag_content),
+                    categories: self.extract_categories(tag_content),
+                    created: self.extract_outline_attribute(tag_content, "created"),
+                    modified: self.extract_outline_attribute(tag_content, "modified"),
+                    include_url: self.extract_include_url(tag_content),
                     children: Vec::new(),
                 });
 
@@ -166,18 +190,139 @@ impl OpmlHandler {
         Some(self.unescape_xml(&tag_content[start_pos..end_pos]))
     }
 
+    /// Extract the space-separated cross-link targets of an outline tag
+    fn extract_edge_targets(&self, tag_content: &str) -> Vec<String> {
+        self.extract_outline_attribute(tag_content, "_edgeTarget")
+            .map(|targets| targets.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Extract the comma-separated categories of an outline tag
+    fn extract_categories(&self, tag_content: &str) -> Vec<String> {
+        self.extract_outline_attribute(tag_content, "category")
+            .map(|categories| {
+                categories
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|category| !category.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Extract the referenced outline URL of a `type="include"` outline tag
+    fn extract_include_url(&self, tag_content: &str) -> Option<String> {
+        if self.extract_outline_attribute(tag_content, "type").as_deref() != Some("include") {
+            return None;
+        }
+        self.extract_outline_attribute(tag_content, "url")
+    }
+
+    /// Import OPML, expanding `type="include"` outlines through `resolver`
+    ///
+    /// The resolver maps an include URL to OPML content; the core never
+    /// fetches anything itself. The top-level outlines of each included
+    /// document become children of the including outline. An include that
+    /// (directly or indirectly) includes itself is an error, as is nesting
+    /// deeper than [`MAX_INCLUDE_DEPTH`].
+    pub fn import_with_includes<F>(&self, content: &str, options: &ImportExportOptions, mut resolver: F) -> MindmapResult<ImportResult>
+    where
+        F: FnMut(&str) -> MindmapResult<String>,
+    {
+        let mut opml_doc = self.parse_opml_content(content)?;
+        let mut chain = Vec::new();
+        self.resolve_includes(&mut opml_doc.outline_items, &mut resolver, &mut chain)?;
+        Ok(self.import_document(opml_doc, options, Vec::new()))
+    }
+
+    /// Expand include outlines in place, tracking the chain of URLs being resolved
+    fn resolve_includes<F>(&self, items: &mut [OutlineItem], resolver: &mut F, chain: &mut Vec<String>) -> MindmapResult<()>
+    where
+        F: FnMut(&str) -> MindmapResult<String>,
+    {
+        for item in items.iter_mut() {
+            if let Some(url) = item.include_url.take() {
+                if chain.contains(&url) {
+                    chain.push(url);
+                    return Err(MindmapError::InvalidOperation {
+                        message: format!("Circular OPML include: {}", chain.join(" -> ")),
+                    });
+                }
+                if chain.len() >= MAX_INCLUDE_DEPTH {
+                    return Err(MindmapError::InvalidOperation {
+                        message: format!("OPML includes nested deeper than {} levels at {}", MAX_INCLUDE_DEPTH, url),
+                    });
+                }
+
+                let included = self.parse_opml_content(&resolver(&url)?)?;
+                let mut included_items = included.outline_items;
+                shift_depth(&mut included_items, item.depth + 1);
+                if item.text.is_empty() {
+                    item.text = included.title;
+                }
+
+                chain.push(url);
+                self.resolve_includes(&mut included_items, resolver, chain)?;
+                chain.pop();
+                item.children.extend(included_items);
+            }
+
+            self.resolve_includes(&mut item.children, resolver, chain)?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the import result for a parsed OPML document
+    fn import_document(&self, opml_doc: OpmlDocument, options: &ImportExportOptions, mut warnings: Vec<String>) -> ImportResult {
+        // Create root node
+        let root_node_id = NodeId::new();
+        let mut root_node = Node::new(&opml_doc.title);
+        root_node.id = root_node_id;
+        root_node.position = Point::new(0.0, 0.0);
+
+        // Convert outline items to nodes
+        let mut links = LinkResolver::new();
+        let mut nodes = vec![root_node];
+        let mut outline_nodes = self.outline_items_to_nodes(&opml_doc.outline_items, Some(root_node_id), options, &mut links);
+        nodes.append(&mut outline_nodes);
+
+        let edges = links.into_edges(&mut warnings);
+
+        // Create document
+        let mut document = Document::new(&opml_doc.title, root_node_id);
+
+        // Set metadata if available
+        if let Some(ref date_created) = opml_doc.date_created {
+            document.set_custom_metadata("opml_date_created", date_created);
+        }
+
+        if let Some(ref date_modified) = opml_doc.date_modified {
+            document.set_custom_metadata("opml_date_modified", date_modified);
+        }
+
+        ImportResult {
+            document,
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1) + edges.len(), // Parent-child edges and cross-links
+            nodes,
+            edges,
+            warnings,
+        }
+    }
+
     /// Convert outline items to mindmap nodes
-    fn outline_items_to_nodes(&self, items: &[OutlineItem], parent_id: Option<NodeId>, options: &ImportExportOptions) -> Vec<Node> {
+    fn outline_items_to_nodes(&self, items: &[OutlineItem], parent_id: Option<NodeId>, options: &ImportExportOptions, links: &mut LinkResolver) -> Vec<Node> {
         let mut nodes = Vec::new();
         let mut y_offset = 0.0;
 
         for item in items {
-            let node_id = if options.preserve_ids {
-                // For now, generate new IDs since OPML doesn't typically have node IDs
-                NodeId::new()
-            } else {
-                NodeId::new()
-            };
+            // OPML outlines only carry IDs for cross-link endpoints
+            let node_id = links.node_id(item.id.as_deref(), options.preserve_ids);
+            for target in &item.edge_targets {
+                links.add_link(node_id, target.as_str(), None);
+            }
 
             let mut node = Node::new(&item.text);
             node.id = node_id;
@@ -194,13 +339,27 @@ impl OpmlHandler {
                 }
             }
 
+            if options.tags_enabled() {
+                for category in &item.categories {
+                    node.add_tag(category.as_str());
+                }
+            }
+
+            if let Some(created_at) = item.created.as_deref().and_then(|value| options.parse_timestamp(value)) {
+                node.created_at = created_at;
+                node.updated_at = created_at;
+            }
+            if let Some(updated_at) = item.modified.as_deref().and_then(|value| options.parse_timestamp(value)) {
+                node.updated_at = updated_at;
+            }
+
             y_offset += 100.0; // Space nodes vertically
 
             nodes.push(node);
 
             // Process children recursively
             if !item.children.is_empty() {
-                let mut child_nodes = self.outline_items_to_nodes(&item.children, Some(node_id), options);
+                let mut child_nodes = self.outline_items_to_nodes(&item.children, Some(node_id), options, links);
                 nodes.append(&mut child_nodes);
             }
         }
@@ -208,29 +367,21 @@ impl OpmlHandler {
         nodes
     }
 
-    /// Convert nodes to OPML outline items
-    fn nodes_to_outline_items(&self, nodes: &[Node], root_node_id: NodeId, options: &ImportExportOptions) -> Vec<OutlineItem> {
-        let mut items = Vec::new();
-
-        // Build parent-child relationships
-        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
-        for node in nodes {
-            if let Some(parent_id) = node.parent_id {
-                children_map.entry(parent_id).or_default().push(node);
-            }
-        }
-
-        // Find root node
-        if let Some(root_node) = nodes.iter().find(|n| n.id == root_node_id) {
-            let root_item = self.node_to_outline_item(root_node, &children_map, options, 0);
-            items.push(root_item);
+    /// Cross-link endpoints and targets needed to write outline attributes
+    fn outline_links(&self, edges: &[&Edge]) -> OutlineLinks {
+        // Cross-link endpoints need an ID so targets can be referenced
+        let mut edge_targets: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        let mut endpoints: HashSet<NodeId> = HashSet::new();
+        for edge in edges {
+            edge_targets.entry(edge.from_node).or_default().push(edge.to_node);
+            endpoints.insert(edge.from_node);
+            endpoints.insert(edge.to_node);
         }
-
-        items
+        OutlineLinks { edge_targets, endpoints }
     }
 
-    /// Convert a single node to an outline item
-    fn node_to_outline_item(&self, node: &Node, children_map: &HashMap<NodeId, Vec<&Node>>, options: &ImportExportOptions, depth: usize) -> OutlineItem {
+    /// Convert a single node to an outline item, without its children
+    fn node_to_outline_item(&self, node: &Node, links: &OutlineLinks, options: &ImportExportOptions, depth: usize) -> OutlineItem {
         let text = if options.include_empty_nodes || !node.text.trim().is_empty() {
             node.text.clone()
         } else {
@@ -243,92 +394,136 @@ impl OpmlHandler {
             None
         };
 
-        let mut children = Vec::new();
-        if let Some(child_nodes) = children_map.get(&node.id) {
-            for child_node in child_nodes {
-                if options.max_depth < 0 || depth < options.max_depth as usize {
-                    children.push(self.node_to_outline_item(child_node, children_map, options, depth + 1));
-                }
-            }
-        }
-
         OutlineItem {
             text,
             note,
             depth,
-            children,
+            id: links.endpoints.contains(&node.id).then(|| node.id.to_string()),
+            categories: if options.tags_enabled() { node.tags.clone() } else { Vec::new() },
+            edge_targets: links
+                .edge_targets
+                .get(&node.id)
+                .map(|targets| targets.iter().map(|id| id.to_string()).collect())
+                .unwrap_or_default(),
+            created: options.include_timestamps.then(|| options.format_timestamp(&node.created_at)),
+            modified: options.include_timestamps.then(|| options.format_timestamp(&node.updated_at)),
+            include_url: None,
+            children: Vec::new(),
         }
     }
 
-    /// Generate OPML XML from outline items
-    fn generate_opml_xml(&self, doc: &OpmlDocument) -> String {
-        let mut xml = String::new();
+    /// Write a complete OPML document for the given nodes
+    fn write_opml(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        let mut warnings = Vec::new();
+        let nodes = &utils::truncate_to_depth(nodes, document.get_root_node(), options.max_depth, &mut warnings);
+
+        let cross_links = utils::cross_links(nodes, edges);
+        let links = self.outline_links(&cross_links);
 
+        // Build parent-child relationships
+        let mut children_map: HashMap<NodeId, Vec<&Node>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent_id) = node.parent_id {
+                children_map.entry(parent_id).or_default().push(node);
+            }
+        }
+
+        let date_created = document.get_custom_metadata("opml_date_created").cloned();
+        let date_modified = options.include_timestamps
+            .then(|| document.updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+
+        let mut out = utils::CountingWriter::new(BufWriter::new(writer));
+        self.write_opml_head(&document.title, date_created.as_deref(), date_modified.as_deref(), &mut out)
+            .map_err(utils::write_error)?;
+        if let Some(root_node) = nodes.iter().find(|n| n.id == document.get_root_node()) {
+            self.write_outline(root_node, &children_map, &links, options, 0, &mut out)
+                .map_err(utils::write_error)?;
+        }
+        out.write_all(b"  </body>\n</opml>\n").map_err(utils::write_error)?;
+        out.flush().map_err(utils::write_error)?;
+
+        Ok(ExportStats {
+            bytes_written: out.bytes_written(),
+            node_count: nodes.len(),
+            edge_count: nodes.len().saturating_sub(1) + cross_links.len(),
+            format: FileFormat::Opml,
+            warnings,
+        })
+    }
+
+    /// Write the XML declaration, head section and opening body tag
+    fn write_opml_head(&self, title: &str, date_created: Option<&str>, date_modified: Option<&str>, out: &mut dyn Write) -> std::io::Result<()> {
         // XML declaration
-        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
-        xml.push_str("<opml version=\"2.0\">\n");
+        out.write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")?;
+        out.write_all(b"<opml version=\"2.0\">\n")?;
 
         // Head section
-        xml.push_str("  <head>\n");
-        xml.push_str(&format!("    <title>{}</title>\n", self.escape_xml(&doc.title)));
+        out.write_all(b"  <head>\n")?;
+        writeln!(out, "    <title>{}</title>", self.escape_xml(title))?;
 
-        if let Some(ref date_created) = doc.date_created {
-            xml.push_str(&format!("    <dateCreated>{}</dateCreated>\n", self.escape_xml(date_created)));
+        if let Some(date_created) = date_created {
+            writeln!(out, "    <dateCreated>{}</dateCreated>", self.escape_xml(date_created))?;
         }
 
-        if let Some(ref date_modified) = doc.date_modified {
-            xml.push_str(&format!("    <dateModified>{}</dateModified>\n", self.escape_xml(date_modified)));
+        if let Some(date_modified) = date_modified {
+            writeln!(out, "    <dateModified>{}</dateModified>", self.escape_xml(date_modified))?;
         }
 
-        xml.push_str("  </head>\n");
+        out.write_all(b"  </head>\n")?;
+        out.write_all(b"  <body>\n")
+    }
+
+    /// Write a node and its subtree as nested outline elements
+    fn write_outline(&self, node: &Node, children_map: &HashMap<NodeId, Vec<&Node>>, links: &OutlineLinks, options: &ImportExportOptions, depth: usize, out: &mut dyn Write) -> std::io::Result<()> {
+        let item = self.node_to_outline_item(node, links, options, depth);
+        let indent = "  ".repeat(depth + 2);
 
-        // Body section
-        xml.push_str("  <body>\n");
+        write!(out, "{}<outline text=\"{}\"", indent, self.escape_xml(&item.text))?;
 
-        for item in &doc.outline_items {
-            xml.push_str(&self.outline_item_to_xml(item, 2));
+        if let Some(ref note) = item.note {
+            write!(out, " _note=\"{}\"", self.escape_xml(note))?;
         }
 
-        xml.push_str("  </body>\n");
-        xml.push_str("</opml>\n");
+        if !item.categories.is_empty() {
+            write!(out, " category=\"{}\"", self.escape_xml(&item.categories.join(",")))?;
+        }
 
-        xml
-    }
+        if let Some(ref id) = item.id {
+            write!(out, " _id=\"{}\"", self.escape_xml(id))?;
+        }
 
-    /// Convert outline item to XML
-    fn outline_item_to_xml(&self, item: &OutlineItem, indent_level: usize) -> String {
-        let indent = "  ".repeat(indent_level);
-        let mut xml = String::new();
+        if !item.edge_targets.is_empty() {
+            write!(out, " _edgeTarget=\"{}\"", self.escape_xml(&item.edge_targets.join(" ")))?;
+        }
 
-        if item.children.is_empty() {
-            // Self-closing tag
-            xml.push_str(&format!("{}< outline text=\"{}\"", indent, self.escape_xml(&item.text)));
+        if let Some(ref created) = item.created {
+            write!(out, " created=\"{}\"", self.escape_xml(created))?;
+        }
 
-            if let Some(ref note) = item.note {
-                xml.push_str(&format!(" _note=\"{}\"", self.escape_xml(note)));
-            }
+        if let Some(ref modified) = item.modified {
+            write!(out, " modified=\"{}\"", self.escape_xml(modified))?;
+        }
 
-            xml.push_str(" />\n");
-        } else {
-            // Opening tag
-            xml.push_str(&format!("{}< outline text=\"{}\"", indent, self.escape_xml(&item.text)));
+        let children = children_map
+            .get(&node.id)
+            .filter(|_| options.max_depth < 0 || depth < options.max_depth as usize)
+            .filter(|children| !children.is_empty());
 
-            if let Some(ref note) = item.note {
-                xml.push_str(&format!(" _note=\"{}\"", self.escape_xml(note)));
-            }
+        match children {
+            Some(children) => {
+                out.write_all(b">\n")?;
 
-            xml.push_str(">\n");
+                // Children
+                for child in children {
+                    self.write_outline(child, children_map, links, options, depth + 1, out)?;
+                }
 
-            // Children
-            for child in &item.children {
-                xml.push_str(&self.outline_item_to_xml(child, indent_level + 1));
+                // Closing tag
+                writeln!(out, "{}</outline>", indent)
             }
-
-            // Closing tag
-            xml.push_str(&format!("{}</outline>\n", indent));
+            // Self-closing tag
+            None => out.write_all(b" />\n"),
         }
-
-        xml
     }
 
     /// Escape XML special characters
@@ -354,61 +549,37 @@ impl FormatHandler for OpmlHandler {
     fn import(&self, content: &str, options: &ImportExportOptions) -> MindmapResult<ImportResult> {
         let opml_doc = self.parse_opml_content(content)?;
 
-        // Create root node
-        let root_node_id = NodeId::new();
-        let mut root_node = Node::new(&opml_doc.title);
-        root_node.id = root_node_id;
-        root_node.position = Point::new(0.0, 0.0);
-
-        // Convert outline items to nodes
-        let mut nodes = vec![root_node];
-        let mut outline_nodes = self.outline_items_to_nodes(&opml_doc.outline_items, Some(root_node_id), options);
-        nodes.append(&mut outline_nodes);
+        // Includes need a resolver; keep the outline itself and say so
+        let mut warnings = Vec::new();
+        collect_unresolved_includes(&opml_doc.outline_items, &mut warnings);
 
-        // Create document
-        let mut document = Document::new(&opml_doc.title, root_node_id);
-
-        // Set metadata if available
-        if let Some(ref date_created) = opml_doc.date_created {
-            document.set_custom_metadata("opml_date_created", date_created);
-        }
-
-        if let Some(ref date_modified) = opml_doc.date_modified {
-            document.set_custom_metadata("opml_date_modified", date_modified);
-        }
-
-        Ok(ImportResult {
-            document,
-            node_count: nodes.len(),
-            edge_count: nodes.len().saturating_sub(1), // Parent-child edges
-            warnings: Vec::new(),
-        })
+        Ok(self.import_document(opml_doc, options, warnings))
     }
 
     fn export(&self, document: &Document, nodes: &[Node], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
-        let outline_items = self.nodes_to_outline_items(nodes, document.get_root_node(), options);
-
-        let opml_doc = OpmlDocument {
-            title: document.title.clone(),
-            date_created: document.get_custom_metadata("opml_date_created").cloned(),
-            date_modified: if options.include_timestamps {
-                Some(document.updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
-            } else {
-                None
-            },
-            outline_items,
-        };
+        self.export_with_edges(document, nodes, &[], options)
+    }
 
-        let content = self.generate_opml_xml(&opml_doc);
+    fn export_with_edges(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions) -> MindmapResult<ExportResult> {
+        let mut buffer = Vec::new();
+        let stats = self.write_opml(document, nodes, edges, options, &mut buffer)?;
+        let content = String::from_utf8(buffer).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Generated OPML is not valid UTF-8: {}", e),
+        })?;
 
         Ok(ExportResult {
             content,
-            node_count: nodes.len(),
-            edge_count: nodes.len().saturating_sub(1),
-            format: FileFormat::Opml,
+            node_count: stats.node_count,
+            edge_count: stats.edge_count,
+            format: stats.format,
+            warnings: stats.warnings,
         })
     }
 
+    fn export_to_writer(&self, document: &Document, nodes: &[Node], edges: &[Edge], options: &ImportExportOptions, writer: &mut dyn Write) -> MindmapResult<ExportStats> {
+        self.write_opml(document, nodes, edges, options, writer)
+    }
+
     fn format(&self) -> FileFormat {
         FileFormat::Opml
     }
@@ -447,9 +618,47 @@ struct OutlineItem {
     text: String,
     note: Option<String>,
     depth: usize,
+    /// Anchor identifying this item as a cross-link endpoint
+    id: Option<String>,
+    /// Anchors of the items this item links to
+    edge_targets: Vec<String>,
+    /// Categories of the item, mapped to node tags
+    categories: Vec<String>,
+    /// Creation time, written with `ImportExportOptions::format_timestamp`
+    created: Option<String>,
+    /// Last modification time, written with `ImportExportOptions::format_timestamp`
+    modified: Option<String>,
+    /// URL of the outline referenced by a `type="include"` item
+    include_url: Option<String>,
     children: Vec<OutlineItem>,
 }
 
+/// Set the depth of included outline items, starting at `depth`
+fn shift_depth(items: &mut [OutlineItem], depth: usize) {
+    for item in items {
+        item.depth = depth;
+        shift_depth(&mut item.children, depth + 1);
+    }
+}
+
+/// Warn about include outlines that a plain import leaves unexpanded
+fn collect_unresolved_includes(items: &[OutlineItem], warnings: &mut Vec<String>) {
+    for item in items {
+        if let Some(url) = &item.include_url {
+            warnings.push(format!("Include of {} was not resolved; use import_with_includes to expand it", url));
+        }
+        collect_unresolved_includes(&item.children, warnings);
+    }
+}
+
+/// Cross-links to annotate while exporting outline items
+struct OutlineLinks {
+    /// Link targets for each source node
+    edge_targets: HashMap<NodeId, Vec<NodeId>>,
+    /// Nodes at either end of a link
+    endpoints: HashSet<NodeId>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -535,4 +744,250 @@ mod tests {
         assert_eq!(text, Some("Sample Text".to_string()));
         assert_eq!(note, Some("Sample Note".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cross_link_round_trip() {
+        let handler = OpmlHandler::new();
+        let options = ImportExportOptions::default();
+
+        let root = Node::new("Project");
+        let design = Node::new_child(root.id, "Design");
+        let build = Node::new_child(root.id, "Build");
+        let document = Document::new("Project", root.id);
+        let edges = vec![
+            Edge::new_with_label(build.id, design.id, "depends on"),
+            // Duplicates the tree structure and is not exported as a cross-link
+            Edge::new(root.id, design.id),
+        ];
+        let nodes = vec![root, design, build];
+
+        let exported = handler.export_with_edges(&document, &nodes, &edges, &options).unwrap();
+        assert!(exported.content.contains("_edgeTarget"));
+        assert_eq!(exported.edge_count, 3);
+
+        let imported = handler.import(&exported.content, &options).unwrap();
+        assert!(imported.warnings.is_empty());
+        assert_eq!(imported.edges.len(), 1);
+        assert_eq!(imported.edge_count, imported.nodes.len() - 1 + 1);
+
+        let find = |text: &str| imported.nodes.iter().find(|n| n.text == text).unwrap().id;
+        let edge = &imported.edges[0];
+        assert_eq!(edge.from_node, find("Build"));
+        assert_eq!(edge.to_node, find("Design"));
+
+        // Anchors are reused as node IDs when requested
+        let preserving = ImportExportOptions { preserve_ids: true, ..ImportExportOptions::default() };
+        let imported = handler.import(&exported.content, &preserving).unwrap();
+        assert_eq!(imported.edges[0].from_node, nodes[2].id);
+        assert_eq!(imported.edges[0].to_node, nodes[1].id);
+    }
+
+    #[test]
+    fn test_unknown_edge_target_warns() {
+        let handler = OpmlHandler::new();
+        let opml = r#"<opml version="2.0"><head><title>T</title></head><body>
+    <outline text="A" _edgeTarget="missing" />
+  </body></opml>"#;
+
+        let imported = handler.import(opml, &ImportExportOptions::default()).unwrap();
+        assert!(imported.edges.is_empty());
+        assert_eq!(imported.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_tags_round_trip_as_categories() {
+        let handler = OpmlHandler::new();
+        let options = ImportExportOptions::default();
+
+        let root = Node::new("Project");
+        let mut topic = Node::new_child(root.id, "Topic");
+        topic.tags = vec!["ai".to_string(), "ml".to_string()];
+        let document = Document::new("Project", root.id);
+        let nodes = vec![root, topic];
+
+        let exported = handler.export(&document, &nodes, &options).unwrap();
+        assert!(exported.content.contains("category=\"ai,ml\""));
+
+        let imported = handler.import(&exported.content, &options).unwrap();
+        let topic = imported.nodes.iter().find(|n| n.text == "Topic").unwrap();
+        assert_eq!(topic.tags, vec!["ai".to_string(), "ml".to_string()]);
+
+        // Tags are skipped when disabled
+        let without_tags = ImportExportOptions { include_tags: false, ..ImportExportOptions::default() };
+        let exported = handler.export(&document, &nodes, &without_tags).unwrap();
+        assert!(!exported.content.contains("category="));
+    }
+
+    #[test]
+    fn test_max_depth_truncation() {
+        let handler = OpmlHandler::new();
+
+        let root = Node::new("Project");
+        let design = Node::new_child(root.id, "Design");
+        let build = Node::new_child(root.id, "Build");
+        let mockups = Node::new_child(design.id, "Mockups");
+        let testing = Node::new_child(build.id, "Testing");
+        let document = Document::new("Project", root.id);
+        let nodes = vec![root, design, build, mockups, testing];
+
+        let export = |max_depth| {
+            let options = ImportExportOptions { max_depth, ..ImportExportOptions::default() };
+            handler.export(&document, &nodes, &options).unwrap()
+        };
+
+        let depth_0 = export(0);
+        assert!(depth_0.content.contains("text=\"Project\""));
+        assert!(!depth_0.content.contains("Design"));
+        assert_eq!(depth_0.node_count, 1);
+        assert_eq!(depth_0.warnings.len(), 1);
+
+        let depth_1 = export(1);
+        assert!(depth_1.content.contains("text=\"Design\""));
+        assert!(depth_1.content.contains("text=\"Build\""));
+        assert!(!depth_1.content.contains("Mockups"));
+        assert!(!depth_1.content.contains("Testing"));
+        assert_eq!(depth_1.node_count, 3);
+        assert_eq!(depth_1.warnings.len(), 1);
+
+        let depth_2 = export(2);
+        assert!(depth_2.content.contains("text=\"Mockups\""));
+        assert!(depth_2.content.contains("text=\"Testing\""));
+        assert_eq!(depth_2.node_count, 5);
+        assert!(depth_2.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_large_import_builds_graph() {
+        let handler = OpmlHandler::new();
+        let mut body = String::new();
+        for i in 0..1000 {
+            body.push_str(&format!("<outline text=\"Topic {}\">", i));
+            for j in 0..4 {
+                body.push_str(&format!("<outline text=\"Item {}.{}\" />", i, j));
+            }
+            body.push_str("</outline>\n");
+        }
+        let content = format!(
+            "<?xml version=\"1.0\"?>\n<opml version=\"2.0\">\n<head><title>Large</title></head>\n<body>\n{}</body>\n</opml>",
+            body
+        );
+
+        let imported = handler.import(&content, &ImportExportOptions::default()).unwrap();
+        assert_eq!(imported.node_count, 5001);
+
+        let graph = imported.to_graph().unwrap();
+        assert_eq!(graph.node_count(), 5001);
+        assert_eq!(graph.get_children(imported.document.get_root_node()).len(), 1000);
+        assert!(graph.validate().is_ok());
+    }
+
+    fn opml_with_body(title: &str, body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>{}</title></head>
+  <body>
+{}
+  </body>
+</opml>"#,
+            title, body
+        )
+    }
+
+    #[test]
+    fn test_include_resolved_with_stub_resolver() {
+        let handler = OpmlHandler::new();
+        let main = opml_with_body("Main", r#"    <outline text="Shared" type="include" url="shared.opml" />
+    <outline text="Local" />"#);
+        let shared = opml_with_body("Shared Outline", r#"    <outline text="Included A" />
+    <outline text="Included B" />"#);
+
+        let mut requested = Vec::new();
+        let result = handler
+            .import_with_includes(&main, &ImportExportOptions::default(), |url| {
+                requested.push(url.to_string());
+                match url {
+                    "shared.opml" => Ok(shared.clone()),
+                    other => Err(MindmapError::IoError { message: format!("unknown {}", other) }),
+                }
+            })
+            .unwrap();
+        assert_eq!(requested, vec!["shared.opml".to_string()]);
+
+        let shared_node = result.nodes.iter().find(|n| n.text == "Shared").unwrap();
+        let included: Vec<&str> = result.nodes.iter()
+            .filter(|n| n.parent_id == Some(shared_node.id))
+            .map(|n| n.text.as_str())
+            .collect();
+        assert_eq!(included, vec!["Included A", "Included B"]);
+        assert_eq!(result.node_count, 5);
+
+        // A plain import keeps the include outline and warns
+        let plain = handler.import(&main, &ImportExportOptions::default()).unwrap();
+        assert_eq!(plain.node_count, 3);
+        assert!(plain.warnings.iter().any(|w| w.contains("shared.opml")));
+    }
+
+    #[test]
+    fn test_circular_include_is_detected() {
+        let handler = OpmlHandler::new();
+        let looping = opml_with_body("Loop", r#"    <outline text="Again" type="include" url="loop.opml" />"#);
+
+        let mut calls = 0;
+        let result = handler.import_with_includes(&looping, &ImportExportOptions::default(), |_| {
+            calls += 1;
+            Ok(looping.clone())
+        });
+
+        match result {
+            Err(MindmapError::InvalidOperation { message }) => {
+                assert!(message.contains("Circular OPML include"), "{}", message);
+                assert!(message.contains("loop.opml -> loop.opml"), "{}", message);
+            }
+            other => panic!("expected a circular include error, got {:?}", other.map(|r| r.node_count)),
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_rfc3339_timestamps_round_trip() {
+        let handler = OpmlHandler::new();
+        let mut root = Node::new("Root");
+        root.created_at = chrono::DateTime::from_timestamp_millis(1_714_566_600_250).unwrap();
+        root.updated_at = chrono::DateTime::from_timestamp_millis(1_714_570_200_999).unwrap();
+        let document = Document::new("Timestamps", root.id);
+
+        for offset in [None, Some(2 * 3600), Some(-(5 * 3600 + 30 * 60))] {
+            let options = ImportExportOptions { timestamp_offset: offset, ..ImportExportOptions::default() };
+            let exported = handler.export(&document, std::slice::from_ref(&root), &options).unwrap();
+
+            let created = handler.extract_outline_attribute(&exported.content, "created").unwrap();
+            let parsed = chrono::DateTime::parse_from_rfc3339(&created).unwrap();
+            assert_eq!(parsed.timestamp_millis(), root.created_at.timestamp_millis());
+            assert_eq!(parsed.offset().local_minus_utc(), offset.unwrap_or(0));
+            if offset.is_none() {
+                assert!(created.ends_with('Z'), "{}", created);
+            }
+
+            let imported = handler.import(&exported.content, &options).unwrap();
+            let node = imported.nodes.iter().find(|n| n.text == "Root" && n.parent_id.is_some()).unwrap();
+            assert_eq!(node.created_at, root.created_at);
+            assert_eq!(node.updated_at, root.updated_at);
+        }
+    }
+
+    #[test]
+    fn test_unix_timestamp_formats() {
+        use crate::io::TimestampFormat;
+
+        let timestamp = chrono::DateTime::from_timestamp_millis(1_714_566_600_250).unwrap();
+        let seconds = ImportExportOptions { timestamp_format: TimestampFormat::UnixSeconds, ..ImportExportOptions::default() };
+        let millis = ImportExportOptions { timestamp_format: TimestampFormat::UnixMillis, ..ImportExportOptions::default() };
+
+        assert_eq!(seconds.format_timestamp(&timestamp), "1714566600");
+        assert_eq!(millis.format_timestamp(&timestamp), "1714566600250");
+        assert_eq!(millis.parse_timestamp("1714566600250"), Some(timestamp));
+        assert_eq!(seconds.parse_timestamp("1714566600").unwrap().timestamp(), timestamp.timestamp());
+        assert_eq!(seconds.parse_timestamp("not a number"), None);
+    }
+}
@@ -5,8 +5,31 @@
 
 use super::*;
 use crate::graph::Graph;
-use crate::types::{ids::NodeId, Point, MindmapResult, MindmapError};
-use std::collections::HashMap;
+use crate::models::Node;
+use crate::types::{global_seed, ids::NodeId, Point, MindmapResult, MindmapError, RngSource};
+use std::collections::{HashMap, HashSet};
+
+/// Seed used for initial positions when neither the engine nor the global seed is set
+const DEFAULT_RANDOM_SEED: u64 = 12345;
+
+/// Depth at which the quadtree stops subdividing, so coincident nodes share a leaf
+const MAX_QUADTREE_DEPTH: usize = 24;
+
+/// Repulsive force on a node from a mass `dx`, `dy` away from it
+///
+/// Coincident points have no direction to push along, and dividing by their
+/// zero distance would turn the force into NaN, so they exert none.
+fn repulsion(dx: f64, dy: f64, strength: f64) -> Option<Point> {
+    let distance_sq = dx * dx + dy * dy;
+    if distance_sq == 0.0 {
+        return None;
+    }
+    let distance = distance_sq.sqrt().max(1.0); // Minimum distance to avoid singularity
+
+    // Coulomb's law: F = k / r^2
+    let repulsion_force = strength / distance_sq;
+    Some(Point::new(-(dx / distance) * repulsion_force, -(dy / distance) * repulsion_force))
+}
 
 /// Force simulation parameters
 #[derive(Debug, Clone)]
@@ -27,6 +50,30 @@ pub struct ForceParameters {
     pub max_iterations: u32,
     /// Convergence threshold (total energy)
     pub convergence_threshold: f64,
+    /// Gravity wells attracting nearby nodes toward fixed points
+    pub gravity_wells: Vec<GravityWell>,
+    /// Barnes-Hut opening angle; 0.0 computes repulsion exactly, ~0.5 is typical
+    pub theta: f64,
+    /// Node count above which repulsion is approximated with a quadtree
+    pub barnes_hut_threshold: usize,
+}
+
+/// An attractive region that pulls nodes within its radius toward its center
+#[derive(Debug, Clone, PartialEq)]
+pub struct GravityWell {
+    /// Center of the well
+    pub center: Point,
+    /// Attraction strength (same scale as `center_strength`)
+    pub strength: f64,
+    /// Radius within which nodes are attracted
+    pub radius: f64,
+}
+
+impl GravityWell {
+    /// Create a new gravity well
+    pub fn new(center: Point, strength: f64, radius: f64) -> Self {
+        Self { center, strength, radius }
+    }
 }
 
 /// Node state during force simulation
@@ -45,6 +92,8 @@ pub struct ForceLayoutEngine {
     /// Whether to use adaptive time stepping
     pub adaptive_timestep: bool,
     /// Random seed for initial positioning
+    ///
+    /// Falls back to the global seed, then to a fixed default seed.
     pub random_seed: Option<u64>,
 }
 
@@ -59,6 +108,9 @@ impl Default for ForceParameters {
             time_step: 0.1,
             max_iterations: 1000,
             convergence_threshold: 0.01,
+            gravity_wells: Vec::new(),
+            theta: 0.5,
+            barnes_hut_threshold: 200,
         }
     }
 }
@@ -122,6 +174,13 @@ impl ForceLayoutEngine {
             convergence_threshold: config.parameters.get("convergence_threshold")
                 .copied()
                 .unwrap_or(self.parameters.convergence_threshold),
+            gravity_wells: self.parameters.gravity_wells.clone(),
+            theta: config.parameters.get(params::THETA)
+                .copied()
+                .unwrap_or(self.parameters.theta),
+            barnes_hut_threshold: config.parameters.get(params::BARNES_HUT_THRESHOLD)
+                .copied()
+                .unwrap_or(self.parameters.barnes_hut_threshold as f64) as usize,
         }
     }
 
@@ -133,23 +192,33 @@ impl ForceLayoutEngine {
     ) -> HashMap<NodeId, NodeState> {
         let mut states = HashMap::new();
 
-        // Use simple linear congruential generator for reproducible randomness
-        let mut rng_state = self.random_seed.unwrap_or(12345);
+        let mut rng = self.rng();
 
-        for node in graph.nodes() {
-            let position = if config.preserve_positions {
+        // Visit nodes in ID order so positions don't depend on map iteration order
+        let mut nodes: Vec<&Node> = graph.nodes().collect();
+        nodes.sort_by_key(|node| node.id.as_uuid());
+
+        let mut taken = HashSet::new();
+        for node in nodes {
+            // Nodes stacked on an already used spot would never separate, so they start afresh
+            let spot_free = taken.insert((node.position.x.to_bits(), node.position.y.to_bits()));
+            let position = if config.preserve_positions && (node.pinned || spot_free) {
                 node.position
             } else {
                 // Generate random position within canvas bounds
-                let x = self.random_f64(&mut rng_state) * config.canvas_width;
-                let y = self.random_f64(&mut rng_state) * config.canvas_height;
+                let x = rng.next_f64() * config.canvas_width;
+                let y = rng.next_f64() * config.canvas_height;
                 Point::new(x, y)
             };
 
             // Calculate node mass based on connectivity
             let connections = graph.get_children(node.id).len() +
                              if graph.get_parent(node.id).is_some() { 1 } else { 0 };
-            let mass = 1.0 + connections as f64 * 0.1; // Nodes with more connections have more mass
+            let mass = if config.preserve_positions && node.pinned {
+                f64::INFINITY // Pinned nodes are immovable anchors
+            } else {
+                1.0 + connections as f64 * 0.1 // Nodes with more connections have more mass
+            };
 
             states.insert(node.id, NodeState {
                 position,
@@ -162,10 +231,9 @@ impl ForceLayoutEngine {
         states
     }
 
-    /// Simple linear congruential generator for reproducible randomness
-    fn random_f64(&self, state: &mut u64) -> f64 {
-        *state = state.wrapping_mul(1103515245).wrapping_add(12345);
-        (*state % 1000000) as f64 / 1000000.0
+    /// Create the random number generator for initial positioning
+    fn rng(&self) -> RngSource {
+        RngSource::new(self.random_seed.or_else(global_seed).unwrap_or(DEFAULT_RANDOM_SEED))
     }
 
     /// Calculate spring forces between connected nodes
@@ -175,7 +243,11 @@ impl ForceLayoutEngine {
         states: &mut HashMap<NodeId, NodeState>,
         parameters: &ForceParameters,
     ) {
-        for edge in graph.edges() {
+        // Accumulate in edge ID order so floating-point results are reproducible
+        let mut edges: Vec<_> = graph.edges().collect();
+        edges.sort_by_key(|edge| edge.id.as_uuid());
+
+        for edge in edges {
             if let (Some(from_state), Some(to_state)) = (
                 states.get(&edge.from_node),
                 states.get(&edge.to_node),
@@ -203,13 +275,29 @@ impl ForceLayoutEngine {
         }
     }
 
-    /// Calculate repulsion forces between all pairs of nodes
+    /// Calculate repulsion forces, approximating distant clusters on large graphs
     fn calculate_repulsion_forces(
         &self,
         states: &mut HashMap<NodeId, NodeState>,
         parameters: &ForceParameters,
     ) {
-        let node_ids: Vec<NodeId> = states.keys().copied().collect();
+        if parameters.theta > 0.0 && states.len() > parameters.barnes_hut_threshold {
+            self.calculate_approximate_repulsion_forces(states, parameters);
+        } else {
+            self.calculate_exact_repulsion_forces(states, parameters);
+        };
+    }
+
+    /// Calculate repulsion forces between all pairs of nodes, returning the pairs evaluated
+    fn calculate_exact_repulsion_forces(
+        &self,
+        states: &mut HashMap<NodeId, NodeState>,
+        parameters: &ForceParameters,
+    ) -> usize {
+        let mut node_ids: Vec<NodeId> = states.keys().copied().collect();
+        node_ids.sort_by_key(|id| id.as_uuid());
+
+        let mut interactions = 0;
 
         for i in 0..node_ids.len() {
             for j in i + 1..node_ids.len() {
@@ -219,14 +307,10 @@ impl ForceLayoutEngine {
                 if let (Some(state1), Some(state2)) = (states.get(&node1), states.get(&node2)) {
                     let dx = state2.position.x - state1.position.x;
                     let dy = state2.position.y - state1.position.y;
-                    let distance_sq = dx * dx + dy * dy;
-                    let distance = distance_sq.sqrt().max(1.0); // Minimum distance to avoid singularity
-
-                    // Coulomb's law: F = k / r^2
-                    let repulsion_force = parameters.repulsion_strength / distance_sq;
-
-                    let fx = (dx / distance) * repulsion_force;
-                    let fy = (dy / distance) * repulsion_force;
+                    let Some(push) = repulsion(dx, dy, parameters.repulsion_strength) else {
+                        continue;
+                    };
+                    let (fx, fy) = (-push.x, -push.y);
 
                     // Apply forces to both nodes
                     if let Some(state1) = states.get_mut(&node1) {
@@ -237,9 +321,41 @@ impl ForceLayoutEngine {
                         state2.force.x += fx;
                         state2.force.y += fy;
                     }
+                    interactions += 1;
                 }
             }
         }
+        interactions
+    }
+
+    /// Calculate repulsion forces with the Barnes-Hut approximation
+    ///
+    /// Builds a quadtree over the current positions and treats any cell that
+    /// looks smaller than `theta` from a node as a single body at its center
+    /// of mass, which takes O(n log n) per iteration instead of O(n²).
+    fn calculate_approximate_repulsion_forces(
+        &self,
+        states: &mut HashMap<NodeId, NodeState>,
+        parameters: &ForceParameters,
+    ) -> usize {
+        // Insert in ID order so the cell averages are reproducible
+        let mut node_ids: Vec<NodeId> = states.keys().copied().collect();
+        node_ids.sort_by_key(|id| id.as_uuid());
+        let points: Vec<Point> = node_ids.iter().map(|id| states[id].position).collect();
+        let tree = QuadTree::build(&points);
+
+        let mut interactions = 0;
+        for state in states.values_mut() {
+            let force = tree.repulsion_at(
+                state.position,
+                parameters.repulsion_strength,
+                parameters.theta,
+                &mut interactions,
+            );
+            state.force.x += force.x;
+            state.force.y += force.y;
+        }
+        interactions
     }
 
     /// Calculate center attraction forces to prevent nodes from drifting away
@@ -258,6 +374,25 @@ impl ForceLayoutEngine {
         }
     }
 
+    /// Calculate attraction toward gravity wells for nodes within their radius
+    fn calculate_gravity_well_forces(
+        &self,
+        states: &mut HashMap<NodeId, NodeState>,
+        parameters: &ForceParameters,
+    ) {
+        for well in &parameters.gravity_wells {
+            for state in states.values_mut() {
+                let dx = well.center.x - state.position.x;
+                let dy = well.center.y - state.position.y;
+
+                if dx * dx + dy * dy <= well.radius * well.radius {
+                    state.force.x += dx * well.strength;
+                    state.force.y += dy * well.strength;
+                }
+            }
+        }
+    }
+
     /// Update node positions using Verlet integration
     fn integrate_forces(
         &self,
@@ -267,6 +402,11 @@ impl ForceLayoutEngine {
         let mut total_energy = 0.0;
 
         for state in states.values_mut() {
+            if state.mass.is_infinite() {
+                state.force = Point::new(0.0, 0.0);
+                continue;
+            }
+
             // Apply damping to velocity
             state.velocity.x *= parameters.damping;
             state.velocity.y *= parameters.damping;
@@ -306,7 +446,7 @@ impl ForceLayoutEngine {
         let max_x = config.canvas_width - margin;
         let max_y = config.canvas_height - margin;
 
-        for state in states.values_mut() {
+        for state in states.values_mut().filter(|state| state.mass.is_finite()) {
             // Constrain position
             if state.position.x < min_x {
                 state.position.x = min_x;
@@ -343,6 +483,7 @@ impl ForceLayoutEngine {
             self.calculate_spring_forces(graph, &mut states, parameters);
             self.calculate_repulsion_forces(&mut states, parameters);
             self.calculate_center_forces(&mut states, &config.center, parameters);
+            self.calculate_gravity_well_forces(&mut states, parameters);
 
             // Integrate forces and update positions
             let energy = self.integrate_forces(&mut states, parameters);
@@ -366,10 +507,9 @@ impl ForceLayoutEngine {
 
         Ok((positions, converged, iteration, final_energy))
     }
-}
 
-impl LayoutEngine for ForceLayoutEngine {
-    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+    /// Position every node; timed by [`LayoutEngine::calculate_layout`]
+    fn compute_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
         self.validate_config(config)?;
 
         if graph.node_count() == 0 {
@@ -379,6 +519,7 @@ impl LayoutEngine for ForceLayoutEngine {
                 converged: true,
                 iterations: 0,
                 energy: 0.0,
+                node_bounds: None,
             });
         }
 
@@ -393,14 +534,158 @@ impl LayoutEngine for ForceLayoutEngine {
         let all_points: Vec<Point> = positions.values().copied().collect();
         let bounds = LayoutBounds::from_points(&all_points);
 
+        let node_bounds = config.node_bounds(&positions);
         Ok(LayoutResult {
             positions,
             bounds,
             converged,
             iterations,
             energy,
+            node_bounds,
         })
     }
+}
+
+/// Cell of the Barnes-Hut quadtree
+#[derive(Debug, Clone)]
+struct QuadCell {
+    center: Point,
+    half_size: f64,
+    mass_center: Point,
+    count: usize,
+    /// Position of the only body in a leaf, if it holds exactly one
+    body: Option<Point>,
+    /// Index of the first of four consecutive child cells
+    children: Option<usize>,
+}
+
+impl QuadCell {
+    fn new(center: Point, half_size: f64) -> Self {
+        Self {
+            center,
+            half_size,
+            mass_center: Point::new(0.0, 0.0),
+            count: 0,
+            body: None,
+            children: None,
+        }
+    }
+
+    fn quadrant(&self, point: Point) -> usize {
+        (if point.x >= self.center.x { 1 } else { 0 }) + (if point.y >= self.center.y { 2 } else { 0 })
+    }
+}
+
+/// Quadtree over node positions, stored as a flat arena of cells
+#[derive(Debug, Clone)]
+struct QuadTree {
+    cells: Vec<QuadCell>,
+}
+
+impl QuadTree {
+    fn build(points: &[Point]) -> Self {
+        let bounds = LayoutBounds::from_points(points);
+        let half_size = (bounds.width().max(bounds.height()) / 2.0).max(1.0);
+        let center = Point::new(
+            (bounds.min_x + bounds.max_x) / 2.0,
+            (bounds.min_y + bounds.max_y) / 2.0,
+        );
+
+        let mut tree = Self { cells: vec![QuadCell::new(center, half_size)] };
+        for &point in points {
+            tree.insert(point);
+        }
+        tree
+    }
+
+    fn insert(&mut self, point: Point) {
+        let mut index = 0;
+        let mut depth = 0;
+        loop {
+            let cell = &mut self.cells[index];
+            let count = cell.count as f64;
+            cell.mass_center.x = (cell.mass_center.x * count + point.x) / (count + 1.0);
+            cell.mass_center.y = (cell.mass_center.y * count + point.y) / (count + 1.0);
+            cell.count += 1;
+
+            if let Some(first_child) = cell.children {
+                index = first_child + cell.quadrant(point);
+            } else if cell.count == 1 {
+                cell.body = Some(point);
+                return;
+            } else if depth >= MAX_QUADTREE_DEPTH {
+                // Too deep to separate; keep the bodies aggregated in this leaf
+                cell.body = None;
+                return;
+            } else {
+                // Split the leaf and push its existing body down one level
+                let existing = cell.body.take();
+                let first_child = self.subdivide(index);
+                if let Some(existing) = existing {
+                    let quadrant = self.cells[index].quadrant(existing);
+                    let child = &mut self.cells[first_child + quadrant];
+                    child.mass_center = existing;
+                    child.count = 1;
+                    child.body = Some(existing);
+                }
+                index = first_child + self.cells[index].quadrant(point);
+            }
+            depth += 1;
+        }
+    }
+
+    fn subdivide(&mut self, index: usize) -> usize {
+        let first_child = self.cells.len();
+        let center = self.cells[index].center;
+        let quarter = self.cells[index].half_size / 2.0;
+        for quadrant in 0..4 {
+            let dx = if quadrant & 1 == 1 { quarter } else { -quarter };
+            let dy = if quadrant & 2 == 2 { quarter } else { -quarter };
+            self.cells.push(QuadCell::new(Point::new(center.x + dx, center.y + dy), quarter));
+        }
+        self.cells[index].children = Some(first_child);
+        first_child
+    }
+
+    /// Repulsion felt at `point`, counting evaluated cell interactions
+    fn repulsion_at(&self, point: Point, strength: f64, theta: f64, interactions: &mut usize) -> Point {
+        let mut force = Point::new(0.0, 0.0);
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.count == 0 {
+                continue;
+            }
+
+            let dx = cell.mass_center.x - point.x;
+            let dy = cell.mass_center.y - point.y;
+            let distance_sq = dx * dx + dy * dy;
+
+            match cell.children {
+                Some(first_child) if cell.half_size * 2.0 >= theta * distance_sq.sqrt() => {
+                    stack.extend(first_child..first_child + 4);
+                }
+                _ => {
+                    // Skips the node itself (and any node sharing its exact position)
+                    let Some(push) = repulsion(dx, dy, strength * cell.count as f64) else {
+                        continue;
+                    };
+                    force.x += push.x;
+                    force.y += push.y;
+                    *interactions += 1;
+                }
+            }
+        }
+
+        force
+    }
+}
+
+impl LayoutEngine for ForceLayoutEngine {
+    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        timed_layout!("force", self.compute_layout(graph, config))
+    }
 
     fn layout_type(&self) -> LayoutType {
         LayoutType::Force
@@ -461,6 +746,14 @@ impl LayoutEngine for ForceLayoutEngine {
             }
         }
 
+        if let Some(&theta) = config.parameters.get(params::THETA) {
+            if theta < 0.0 {
+                return Err(MindmapError::InvalidOperation {
+                    message: "Theta must be non-negative".to_string(),
+                });
+            }
+        }
+
         if let Some(&max_iterations) = config.parameters.get("max_iterations") {
             if max_iterations < 1.0 {
                 return Err(MindmapError::InvalidOperation {
@@ -561,6 +854,9 @@ mod tests {
             time_step: 0.05,
             max_iterations: 500,
             convergence_threshold: 0.005,
+            gravity_wells: Vec::new(),
+            theta: 0.5,
+            barnes_hut_threshold: 200,
         };
 
         let engine = ForceLayoutEngine::new(custom_params);
@@ -624,6 +920,57 @@ mod tests {
         assert!(layout.converged || layout.iterations == 10);
     }
 
+    #[test]
+    fn test_gravity_well_pulls_centroid() {
+        let graph = create_test_graph();
+        let config = LayoutConfig::default();
+        let well_center = Point::new(100.0, 100.0);
+
+        let centroid_distance = |layout: &LayoutResult| {
+            let count = layout.positions.len() as f64;
+            let (sum_x, sum_y) = layout.positions.values()
+                .fold((0.0, 0.0), |(x, y), p| (x + p.x, y + p.y));
+            utils::distance(&Point::new(sum_x / count, sum_y / count), &well_center)
+        };
+
+        let plain = ForceLayoutEngine::default().with_seed(7);
+        let without_well = plain.calculate_layout(&graph, &config).unwrap();
+
+        let parameters = ForceParameters {
+            gravity_wells: vec![GravityWell::new(well_center, 0.05, 2000.0)],
+            ..ForceParameters::default()
+        };
+        let with_well = ForceLayoutEngine::new(parameters).with_seed(7)
+            .calculate_layout(&graph, &config)
+            .unwrap();
+
+        assert!(centroid_distance(&with_well) + 100.0 < centroid_distance(&without_well));
+    }
+
+    #[test]
+    fn test_gravity_well_ignores_nodes_outside_radius() {
+        let graph = create_test_graph();
+        let config = LayoutConfig::default();
+
+        let far_away = ForceParameters {
+            gravity_wells: vec![GravityWell::new(Point::new(-10000.0, -10000.0), 1.0, 1.0)],
+            ..ForceParameters::default()
+        };
+        let with_well = ForceLayoutEngine::new(far_away).with_seed(7)
+            .calculate_layout(&graph, &config)
+            .unwrap();
+        let without_well = ForceLayoutEngine::default().with_seed(7)
+            .calculate_layout(&graph, &config)
+            .unwrap();
+
+        // Summation order over hash maps may differ between runs, so allow rounding noise
+        assert_eq!(with_well.positions.len(), without_well.positions.len());
+        for (node_id, position) in &with_well.positions {
+            let other = without_well.positions[node_id];
+            assert!(position.distance_to(&other) < 1e-6);
+        }
+    }
+
     #[test]
     fn test_layout_type() {
         let engine = ForceLayoutEngine::default();
@@ -703,6 +1050,29 @@ mod tests {
         assert!((pos2.y - 200.0).abs() < 50.0);
     }
 
+    #[test]
+    fn test_coincident_nodes_stay_finite() {
+        let mut graph = Graph::new();
+        let ids: Vec<NodeId> = (0..4)
+            .map(|i| {
+                let mut node = Node::new(format!("Stacked {}", i));
+                node.position = Point::new(300.0, 300.0);
+                graph.add_node(node).unwrap()
+            })
+            .collect();
+
+        let config = LayoutConfig { preserve_positions: true, ..LayoutConfig::default() };
+        let layout = ForceLayoutEngine::default().with_seed(7).calculate_layout(&graph, &config).unwrap();
+
+        for id in &ids {
+            let position = layout.positions[id];
+            assert!(position.x.is_finite() && position.y.is_finite(), "{:?}", position);
+        }
+        // Stacked nodes are spread out rather than left on one spot
+        assert!(layout.positions[&ids[0]].distance_to(&layout.positions[&ids[1]]) > 1.0);
+        assert!(repulsion(0.0, 0.0, 1000.0).is_none());
+    }
+
     #[test]
     fn test_force_parameters_default() {
         let params = ForceParameters::default();
@@ -717,16 +1087,16 @@ mod tests {
 
     #[test]
     fn test_random_generator() {
-        let engine = ForceLayoutEngine::default();
-        let mut state1 = 12345u64;
-        let mut state2 = 12345u64;
+        let engine = ForceLayoutEngine::default().with_seed(12345);
+        let mut rng1 = engine.rng();
+        let mut rng2 = engine.rng();
 
         // Same seed should produce same sequence
-        let r1a = engine.random_f64(&mut state1);
-        let r1b = engine.random_f64(&mut state1);
+        let r1a = rng1.next_f64();
+        let r1b = rng1.next_f64();
 
-        let r2a = engine.random_f64(&mut state2);
-        let r2b = engine.random_f64(&mut state2);
+        let r2a = rng2.next_f64();
+        let r2b = rng2.next_f64();
 
         assert_eq!(r1a, r2a);
         assert_eq!(r1b, r2b);
@@ -762,4 +1132,83 @@ mod tests {
         assert!(min_mass >= 1.0); // All nodes have at least base mass
         assert!(max_mass >= min_mass); // Some variation is expected
     }
-}
\ No newline at end of file
+
+    fn scattered_states(count: usize, seed: u64) -> HashMap<NodeId, NodeState> {
+        let mut rng = RngSource::new(seed);
+        (0..count)
+            .map(|_| {
+                let position = Point::new(rng.next_f64() * 1000.0, rng.next_f64() * 800.0);
+                (NodeId::new(), NodeState {
+                    position,
+                    velocity: Point::new(0.0, 0.0),
+                    force: Point::new(0.0, 0.0),
+                    mass: 1.0,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_barnes_hut_matches_exact_repulsion() {
+        let engine = ForceLayoutEngine::default();
+        let parameters = ForceParameters { theta: 0.3, ..ForceParameters::default() };
+
+        let mut exact = scattered_states(300, 3);
+        let mut approximate = exact.clone();
+        engine.calculate_exact_repulsion_forces(&mut exact, &parameters);
+        engine.calculate_approximate_repulsion_forces(&mut approximate, &parameters);
+
+        let (mut error, mut magnitude) = (0.0, 0.0);
+        for (id, state) in &exact {
+            let other = approximate[id].force;
+            error += ((state.force.x - other.x).powi(2) + (state.force.y - other.y).powi(2)).sqrt();
+            magnitude += (state.force.x.powi(2) + state.force.y.powi(2)).sqrt();
+        }
+        assert!(error / magnitude < 0.05, "relative error {}", error / magnitude);
+    }
+
+    #[test]
+    fn test_barnes_hut_interactions_scale_sub_quadratically() {
+        let engine = ForceLayoutEngine::default();
+        let parameters = ForceParameters::default();
+
+        let interactions = |count: usize| {
+            let mut states = scattered_states(count, 11);
+            engine.calculate_approximate_repulsion_forces(&mut states, &parameters)
+        };
+        let small = interactions(250);
+        let large = interactions(1000);
+        let exact = engine.calculate_exact_repulsion_forces(&mut scattered_states(1000, 11), &parameters);
+
+        // Exact repulsion evaluates every pair, 16x more for 4x the nodes
+        assert_eq!(exact, 1000 * 999 / 2);
+        assert!(large < exact / 2, "{} interactions at 1000 nodes", large);
+        assert!((large as f64) / (small as f64) < 8.0, "{} vs {}", large, small);
+    }
+
+    #[test]
+    fn test_small_graphs_stay_exact() {
+        let graph = create_test_graph();
+        let config = LayoutConfig::default();
+
+        let exact = ForceParameters { theta: 0.0, ..ForceParameters::default() };
+        let with_exact = ForceLayoutEngine::new(exact).with_seed(5)
+            .calculate_layout(&graph, &config)
+            .unwrap();
+        let with_default = ForceLayoutEngine::default().with_seed(5)
+            .calculate_layout(&graph, &config)
+            .unwrap();
+
+        for (node_id, position) in &with_exact.positions {
+            assert!(position.distance_to(&with_default.positions[node_id]) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_negative_theta_is_rejected() {
+        let engine = ForceLayoutEngine::default();
+        let mut config = LayoutConfig::default();
+        config.parameters.insert(params::THETA.to_string(), -0.5);
+        assert!(engine.validate_config(&config).is_err());
+    }
+}
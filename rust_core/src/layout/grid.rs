@@ -0,0 +1,261 @@
+//! Grid layout algorithm implementation
+//!
+//! Arranges nodes in evenly spaced rows and columns, in breadth-first order
+//! from the root nodes. Suited to flat lists of ideas where the hierarchy
+//! matters less than a tidy overview.
+
+use super::*;
+use crate::graph::Graph;
+use crate::types::{ids::NodeId, Point, MindmapResult, MindmapError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Grid layout engine implementation
+#[derive(Default)]
+pub struct GridLayoutEngine {
+    /// Number of columns; derived from the canvas width when `None`
+    pub columns: Option<usize>,
+    /// Number of rows, used to derive the columns when they are not set
+    pub rows: Option<usize>,
+}
+
+impl GridLayoutEngine {
+    /// Create a grid layout engine with a fixed number of columns
+    pub fn with_columns(columns: usize) -> Self {
+        Self {
+            columns: Some(columns),
+            rows: None,
+        }
+    }
+
+    /// Distance between the centers of neighboring cells
+    ///
+    /// At least `min_distance`, and wide enough for the largest label box
+    /// when node sizes are set.
+    fn cell_spacing(&self, config: &LayoutConfig) -> f64 {
+        let label_spacing = if config.node_sizes.is_empty() {
+            0.0
+        } else {
+            config.max_node_extent() + config.label_gap()
+        };
+        config.min_distance.max(label_spacing)
+    }
+
+    /// Number of columns for `count` nodes, from the config, the engine or the canvas width
+    fn column_count(&self, config: &LayoutConfig, count: usize, spacing: f64) -> usize {
+        let columns = config.parameters.get(params::COLUMNS).map(|&columns| columns as usize)
+            .or(self.columns);
+        let rows = config.parameters.get(params::ROWS).map(|&rows| rows as usize)
+            .or(self.rows);
+
+        let columns = match (columns, rows) {
+            (Some(columns), _) => columns,
+            (None, Some(rows)) => count.div_ceil(rows.max(1)),
+            (None, None) => (config.canvas_width / spacing).floor() as usize,
+        };
+        columns.clamp(1, count.max(1))
+    }
+
+    /// Node IDs in breadth-first order from the roots
+    ///
+    /// Roots and siblings are visited by their order, then by ID. Nodes not
+    /// reachable from a root, e.g. on a parent cycle, come last in ID order.
+    fn breadth_first_order(&self, graph: &Graph) -> Vec<NodeId> {
+        let sorted = |mut nodes: Vec<&crate::models::Node>| {
+            nodes.sort_by_key(|node| (node.order, node.id.as_uuid()));
+            nodes.into_iter().map(|node| node.id).collect::<Vec<_>>()
+        };
+
+        let mut order = Vec::with_capacity(graph.node_count());
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<NodeId> = sorted(graph.get_root_nodes()).into();
+        visited.extend(queue.iter().copied());
+
+        while let Some(node_id) = queue.pop_front() {
+            order.push(node_id);
+            for child_id in sorted(graph.get_children(node_id)) {
+                if visited.insert(child_id) {
+                    queue.push_back(child_id);
+                }
+            }
+        }
+
+        let mut unreachable: Vec<NodeId> = graph.nodes()
+            .map(|node| node.id)
+            .filter(|id| !visited.contains(id))
+            .collect();
+        unreachable.sort_by_key(|id| id.as_uuid());
+        order.extend(unreachable);
+        order
+    }
+
+    /// Position every node; timed by [`LayoutEngine::calculate_layout`]
+    fn compute_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        self.validate_config(config)?;
+
+        if graph.node_count() == 0 {
+            return Ok(LayoutResult {
+                positions: HashMap::new(),
+                bounds: LayoutBounds::new(0.0, 0.0, 0.0, 0.0),
+                converged: true,
+                iterations: 0,
+                energy: 0.0,
+                node_bounds: None,
+            });
+        }
+
+        let order = self.breadth_first_order(graph);
+        let spacing = self.cell_spacing(config);
+        let columns = self.column_count(config, order.len(), spacing);
+        let rows = order.len().div_ceil(columns);
+
+        // Center the occupied grid on the layout center
+        let origin = Point::new(
+            config.center.x - (columns - 1) as f64 * spacing / 2.0,
+            config.center.y - (rows - 1) as f64 * spacing / 2.0,
+        );
+
+        let mut positions: HashMap<NodeId, Point> = order
+            .iter()
+            .enumerate()
+            .map(|(index, &node_id)| {
+                let (row, column) = (index / columns, index % columns);
+                let position = Point::new(
+                    origin.x + column as f64 * spacing,
+                    origin.y + row as f64 * spacing,
+                );
+                (node_id, position)
+            })
+            .collect();
+
+        // Pinned nodes keep their coordinates and take their subtrees along
+        utils::anchor_pinned(graph, &mut positions, &config.pinned_positions(graph));
+
+        let all_points: Vec<Point> = positions.values().copied().collect();
+        let bounds = LayoutBounds::from_points(&all_points);
+
+        // Calculate energy as sum of edge lengths (lower is better)
+        let mut total_energy = 0.0;
+        for edge in graph.edges() {
+            if let (Some(&pos1), Some(&pos2)) = (positions.get(&edge.from_node), positions.get(&edge.to_node)) {
+                total_energy += utils::distance(&pos1, &pos2);
+            }
+        }
+
+        let node_bounds = config.node_bounds(&positions);
+        Ok(LayoutResult {
+            positions,
+            bounds,
+            converged: true,
+            iterations: 1,
+            energy: total_energy,
+            node_bounds,
+        })
+    }
+}
+
+impl LayoutEngine for GridLayoutEngine {
+    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        timed_layout!("grid", self.compute_layout(graph, config))
+    }
+
+    fn layout_type(&self) -> LayoutType {
+        LayoutType::Grid
+    }
+
+    fn validate_config(&self, config: &LayoutConfig) -> MindmapResult<()> {
+        config.validate()?;
+
+        if self.cell_spacing(config) <= 0.0 {
+            return Err(MindmapError::InvalidOperation {
+                message: "Grid spacing must be positive; set a minimum distance".to_string(),
+            });
+        }
+
+        for key in [params::COLUMNS, params::ROWS] {
+            if let Some(&count) = config.parameters.get(key) {
+                if count < 1.0 || count.fract() != 0.0 {
+                    return Err(MindmapError::InvalidOperation {
+                        message: format!("Grid {} must be a positive whole number", key),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Node;
+
+    fn create_flat_list(count: usize) -> (Graph, NodeId) {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Ideas")).unwrap();
+        for i in 0..count {
+            graph.add_node(Node::new_child(root_id, format!("Idea {}", i))).unwrap();
+        }
+        (graph, root_id)
+    }
+
+    #[test]
+    fn test_nodes_land_on_distinct_evenly_spaced_cells() {
+        let (graph, root_id) = create_flat_list(10);
+        let config = LayoutConfig::builder()
+            .min_distance(80.0)
+            .parameter(params::COLUMNS, 4.0)
+            .build()
+            .unwrap();
+
+        let result = GridLayoutEngine::default().calculate_layout(&graph, &config).unwrap();
+        assert_eq!(result.positions.len(), 11);
+        assert!(result.validate_for_graph(&graph).is_ok());
+
+        // Every position sits on the 80px lattice, in its own cell
+        let origin = result.bounds;
+        let mut cells = HashSet::new();
+        for position in result.positions.values() {
+            let column = (position.x - origin.min_x) / 80.0;
+            let row = (position.y - origin.min_y) / 80.0;
+            assert!((column - column.round()).abs() < 1e-9 && (row - row.round()).abs() < 1e-9);
+            assert!(column.round() < 4.0);
+            assert!(cells.insert((column.round() as i64, row.round() as i64)));
+        }
+        assert_eq!(origin.width(), 3.0 * 80.0);
+        assert_eq!(origin.height(), 2.0 * 80.0);
+        assert_eq!(origin.center(), config.center);
+
+        // Breadth-first order puts the root in the first cell
+        assert_eq!(result.positions[&root_id], Point::new(origin.min_x, origin.min_y));
+    }
+
+    #[test]
+    fn test_columns_follow_canvas_width_and_rows() {
+        let (graph, _) = create_flat_list(29);
+        let engine = GridLayoutEngine::default();
+
+        // 1000px canvas with 100px spacing fits 10 columns
+        let result = engine.calculate_layout(&graph, &LayoutConfig::default()).unwrap();
+        assert_eq!(result.bounds.width(), 900.0);
+        assert_eq!(result.bounds.height(), 200.0);
+
+        // Fixing the rows derives the columns instead
+        let config = LayoutConfig::builder().parameter(params::ROWS, 5.0).build().unwrap();
+        let result = engine.calculate_layout(&graph, &config).unwrap();
+        assert_eq!(result.bounds.width(), 500.0);
+        assert_eq!(result.bounds.height(), 400.0);
+    }
+
+    #[test]
+    fn test_invalid_grid_parameters() {
+        let (graph, _) = create_flat_list(3);
+        let engine = GridLayoutEngine::default();
+
+        let config = LayoutConfig::builder().parameter(params::COLUMNS, 0.0).build().unwrap();
+        assert!(engine.calculate_layout(&graph, &config).is_err());
+
+        let config = LayoutConfig::builder().min_distance(0.0).build().unwrap();
+        assert!(engine.calculate_layout(&graph, &config).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+//! Hyperbolic (cone tree) layout algorithm implementation
+//!
+//! Places a focus node at the center of a Poincaré disk and spreads the rest of
+//! the hierarchy around it. Every level is the same hyperbolic distance further
+//! out, so deep branches shrink towards the boundary of the disk instead of
+//! running off the canvas.
+
+use super::*;
+use crate::graph::Graph;
+use crate::types::{ids::NodeId, Point, MindmapResult, MindmapError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+
+/// Hyperbolic layout engine implementation
+pub struct HyperbolicLayoutEngine {
+    /// Node placed at the center; the first root node when `None`
+    pub focus_node: Option<NodeId>,
+    /// Hyperbolic distance between consecutive levels
+    pub level_distance: f64,
+    /// Fraction of the canvas half-size used as the disk radius
+    pub disk_fill: f64,
+}
+
+impl Default for HyperbolicLayoutEngine {
+    fn default() -> Self {
+        Self {
+            focus_node: None,
+            level_distance: 0.8,
+            disk_fill: 0.9,
+        }
+    }
+}
+
+impl HyperbolicLayoutEngine {
+    /// Create a hyperbolic layout engine with custom parameters
+    pub fn new(level_distance: f64, disk_fill: f64) -> Self {
+        Self {
+            focus_node: None,
+            level_distance,
+            disk_fill,
+        }
+    }
+
+    /// Set the node placed at the center of the disk
+    pub fn with_focus_node(mut self, focus_node: NodeId) -> Self {
+        self.focus_node = Some(focus_node);
+        self
+    }
+
+    /// Resolve the focus node from the config, the engine, or the first root
+    fn find_focus_node(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<NodeId> {
+        if let Some(focus_node) = config.focus_node.or(self.focus_node) {
+            if !graph.contains_node(focus_node) {
+                return Err(MindmapError::NodeNotFound { id: focus_node });
+            }
+            return Ok(focus_node);
+        }
+
+        let mut roots: Vec<NodeId> = graph.get_root_nodes().iter().map(|node| node.id).collect();
+        roots.sort_by_key(|id| id.as_uuid());
+        roots
+            .first()
+            .copied()
+            .or_else(|| sorted_node_ids(graph).first().copied())
+            .ok_or_else(|| MindmapError::InvalidOperation {
+                message: "No suitable focus node found for hyperbolic layout".to_string(),
+            })
+    }
+
+    /// Build the tree seen from the focus node
+    ///
+    /// The hierarchy is walked in both directions, so the focus node's parent
+    /// becomes one of its branches. Nodes in other components hang off the
+    /// focus node so that every node is placed.
+    fn build_tree(&self, graph: &Graph, focus_id: NodeId) -> HashMap<NodeId, Vec<NodeId>> {
+        let mut tree: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        let mut roots: Vec<NodeId> = graph.get_root_nodes().iter().map(|node| node.id).collect();
+        roots.sort_by_key(|id| id.as_uuid());
+        let mut all_ids = sorted_node_ids(graph);
+        // Cycles in the hierarchy leave nodes without a root; reach them last
+        all_ids.retain(|id| !roots.contains(id));
+        let detached = roots.into_iter().chain(all_ids);
+
+        visited.insert(focus_id);
+        queue.push_back(focus_id);
+        let mut pending = detached.into_iter();
+
+        loop {
+            while let Some(node_id) = queue.pop_front() {
+                let mut neighbors: Vec<NodeId> = graph.get_children(node_id).iter().map(|child| child.id).collect();
+                neighbors.sort_by_key(|id| id.as_uuid());
+                if let Some(parent) = graph.get_parent(node_id) {
+                    neighbors.push(parent.id);
+                }
+
+                let branches = tree.entry(node_id).or_default();
+                for neighbor in neighbors {
+                    if visited.insert(neighbor) {
+                        branches.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            match pending.find(|id| !visited.contains(id)) {
+                Some(next) => {
+                    visited.insert(next);
+                    tree.entry(focus_id).or_default().push(next);
+                    queue.push_back(next);
+                }
+                None => break,
+            }
+        }
+
+        tree
+    }
+
+    /// Count the leaves below each node, used to size angular wedges
+    fn count_leaves(&self, tree: &HashMap<NodeId, Vec<NodeId>>, focus_id: NodeId) -> HashMap<NodeId, usize> {
+        let mut order = vec![focus_id];
+        let mut index = 0;
+        while index < order.len() {
+            if let Some(branches) = tree.get(&order[index]) {
+                order.extend(branches.iter().copied());
+            }
+            index += 1;
+        }
+
+        let mut leaves = HashMap::new();
+        for &node_id in order.iter().rev() {
+            let count = tree
+                .get(&node_id)
+                .filter(|branches| !branches.is_empty())
+                .map_or(1, |branches| branches.iter().map(|id| leaves[id]).sum());
+            leaves.insert(node_id, count);
+        }
+        leaves
+    }
+
+    /// Get layout parameters from config
+    fn extract_parameters(&self, config: &LayoutConfig) -> (f64, f64) {
+        let level_distance = config.parameters.get(params::LEVEL_DISTANCE)
+            .copied()
+            .unwrap_or(self.level_distance);
+
+        let disk_fill = config.parameters.get(params::DISK_FILL)
+            .copied()
+            .unwrap_or(self.disk_fill);
+
+        (level_distance, disk_fill)
+    }
+
+    /// Position every node; timed by [`LayoutEngine::calculate_layout`]
+    fn compute_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        self.validate_config(config)?;
+
+        if graph.node_count() == 0 {
+            return Ok(LayoutResult {
+                positions: HashMap::new(),
+                bounds: LayoutBounds::new(0.0, 0.0, 0.0, 0.0),
+                converged: true,
+                iterations: 0,
+                energy: 0.0,
+                node_bounds: None,
+            });
+        }
+
+        let focus_id = self.find_focus_node(graph, config)?;
+        let tree = self.build_tree(graph, focus_id);
+        let leaves = self.count_leaves(&tree, focus_id);
+
+        let (level_distance, disk_fill) = self.extract_parameters(config);
+        let disk_radius = config.canvas_width.min(config.canvas_height) / 2.0 * disk_fill;
+
+        let mut positions = HashMap::new();
+        positions.insert(focus_id, config.center);
+
+        // Each node owns an angular wedge, split between its branches by leaf count
+        let mut queue = VecDeque::new();
+        queue.push_back((focus_id, 0usize, 0.0, 2.0 * PI));
+
+        while let Some((node_id, depth, wedge_start, wedge_size)) = queue.pop_front() {
+            let Some(branches) = tree.get(&node_id) else {
+                continue;
+            };
+
+            let total_leaves: usize = branches.iter().map(|id| leaves[id]).sum();
+            let child_depth = depth + 1;
+            // Distance d from the center of the Poincaré disk maps to radius tanh(d / 2)
+            let radius = disk_radius * (level_distance * child_depth as f64 / 2.0).tanh();
+
+            let mut start = wedge_start;
+            for &branch_id in branches {
+                let size = wedge_size * leaves[&branch_id] as f64 / total_leaves as f64;
+                let angle = start + size / 2.0;
+                positions.insert(branch_id, utils::polar_to_cartesian(radius, angle, &config.center));
+                queue.push_back((branch_id, child_depth, start, size));
+                start += size;
+            }
+        }
+
+        // Pinned nodes keep their coordinates and take their subtrees along
+        utils::anchor_pinned(graph, &mut positions, &config.pinned_positions(graph));
+
+        let all_points: Vec<Point> = positions.values().copied().collect();
+        let bounds = LayoutBounds::from_points(&all_points);
+
+        // Calculate energy as sum of edge lengths (lower is better)
+        let mut total_energy = 0.0;
+        for edge in graph.edges() {
+            if let (Some(&pos1), Some(&pos2)) = (positions.get(&edge.from_node), positions.get(&edge.to_node)) {
+                total_energy += utils::distance(&pos1, &pos2);
+            }
+        }
+
+        let node_bounds = config.node_bounds(&positions);
+        Ok(LayoutResult {
+            positions,
+            bounds,
+            converged: true,
+            iterations: 1,
+            energy: total_energy,
+            node_bounds,
+        })
+    }
+}
+
+/// Node IDs of the graph in ID order
+fn sorted_node_ids(graph: &Graph) -> Vec<NodeId> {
+    let mut ids: Vec<NodeId> = graph.nodes().map(|node| node.id).collect();
+    ids.sort_by_key(|id| id.as_uuid());
+    ids
+}
+
+impl LayoutEngine for HyperbolicLayoutEngine {
+    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        timed_layout!("hyperbolic", self.compute_layout(graph, config))
+    }
+
+    fn layout_type(&self) -> LayoutType {
+        LayoutType::Hyperbolic
+    }
+
+    fn validate_config(&self, config: &LayoutConfig) -> MindmapResult<()> {
+        config.validate()?;
+
+        let (level_distance, disk_fill) = self.extract_parameters(config);
+        if level_distance <= 0.0 {
+            return Err(MindmapError::InvalidOperation {
+                message: "Level distance must be positive".to_string(),
+            });
+        }
+
+        if disk_fill <= 0.0 || disk_fill > 1.0 {
+            return Err(MindmapError::InvalidOperation {
+                message: "Disk fill must be between 0.0 and 1.0".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Node;
+
+    fn create_deep_tree() -> (Graph, Vec<NodeId>) {
+        let mut graph = Graph::new();
+        let mut chain = vec![graph.add_node(Node::new("Root")).unwrap()];
+        for depth in 1..=6 {
+            let parent = *chain.last().unwrap();
+            graph.add_node(Node::new_child(parent, format!("Side {}", depth))).unwrap();
+            chain.push(graph.add_node(Node::new_child(parent, format!("Level {}", depth))).unwrap());
+        }
+        (graph, chain)
+    }
+
+    #[test]
+    fn test_focus_node_at_center() {
+        let (graph, chain) = create_deep_tree();
+        let focus = chain[3];
+        let config = LayoutConfig::builder().focus_node(focus).build().unwrap();
+
+        let engine = HyperbolicLayoutEngine::default();
+        let result = engine.calculate_layout(&graph, &config).unwrap();
+        assert_eq!(result.positions[&focus], config.center);
+        assert!(result.validate_for_graph(&graph).is_ok());
+
+        // The engine setting gives the same layout
+        let by_engine = HyperbolicLayoutEngine::default().with_focus_node(focus);
+        let same = by_engine.calculate_layout(&graph, &LayoutConfig::default()).unwrap();
+        assert_eq!(same.positions, result.positions);
+
+        // Without a focus the root is centered
+        let rooted = engine.calculate_layout(&graph, &LayoutConfig::default()).unwrap();
+        assert_eq!(rooted.positions[&chain[0]], config.center);
+    }
+
+    #[test]
+    fn test_deep_levels_stay_inside_disk() {
+        let (graph, chain) = create_deep_tree();
+        let config = LayoutConfig::default();
+        let result = HyperbolicLayoutEngine::default().calculate_layout(&graph, &config).unwrap();
+
+        let disk_radius = config.canvas_width.min(config.canvas_height) / 2.0 * 0.9;
+        let radii: Vec<f64> = chain
+            .iter()
+            .map(|id| utils::distance(&result.positions[id], &config.center))
+            .collect();
+        assert!(radii.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(radii.iter().all(|&radius| radius < disk_radius));
+
+        // Each level adds less radius than the one before
+        let steps: Vec<f64> = radii.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!(steps.windows(2).all(|pair| pair[1] < pair[0]));
+    }
+
+    #[test]
+    fn test_invalid_focus_node() {
+        let (graph, _) = create_deep_tree();
+        let config = LayoutConfig::builder().focus_node(NodeId::new()).build().unwrap();
+        assert!(matches!(
+            HyperbolicLayoutEngine::default().calculate_layout(&graph, &config),
+            Err(MindmapError::NodeNotFound { .. })
+        ));
+
+        let missing = HyperbolicLayoutEngine::default().with_focus_node(NodeId::new());
+        assert!(missing.calculate_layout(&graph, &LayoutConfig::default()).is_err());
+    }
+}
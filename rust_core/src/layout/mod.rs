@@ -3,18 +3,41 @@
 //! This module provides various layout algorithms for automatically
 //! positioning nodes in a mindmap for optimal visualization.
 
+/// Evaluate a layout calculation, timing it as `MetricId::layout(name)`
+///
+/// Without the `metrics` feature this is just the calculation.
+macro_rules! timed_layout {
+    ($name:expr, $calculation:expr) => {{
+        #[cfg(feature = "metrics")]
+        let result = {
+            use crate::metrics::{MetricCategory, MetricId};
+            crate::time_operation!(MetricCategory::Layout, $name, $calculation)
+        };
+        #[cfg(not(feature = "metrics"))]
+        let result = $calculation;
+        result
+    }};
+}
+
 pub mod radial;
 pub mod tree;
 pub mod force;
+pub mod hyperbolic;
+pub mod grid;
 
 pub use radial::*;
 pub use tree::*;
 pub use force::*;
+pub use hyperbolic::*;
+pub use grid::*;
 
+use crate::events::EngineEvent;
 use crate::graph::Graph;
-use crate::types::{ids::NodeId, Point, MindmapResult, MindmapError};
+use crate::models::Node;
+use crate::types::{ids::NodeId, Point, MindmapResult, MindmapError, RngSource};
+use crate::types::clock::Instant;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Layout configuration options
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -28,11 +51,80 @@ pub struct LayoutConfig {
     /// Animation duration in milliseconds
     pub animation_duration: u32,
     /// Whether to respect existing node positions
+    ///
+    /// Pinned nodes then keep their coordinates and the rest of the layout
+    /// is arranged around them.
     pub preserve_positions: bool,
     /// Minimum distance between nodes
     pub min_distance: f64,
-    /// Layout-specific parameters
+    /// Layout-specific parameters, keyed by the names in [`params`]
     pub parameters: HashMap<String, f64>,
+    /// Label box size (width, height) of individual nodes
+    ///
+    /// Engines that support it space nodes so these boxes don't overlap.
+    #[serde(default)]
+    pub node_sizes: HashMap<NodeId, (f64, f64)>,
+    /// Hyperbolic: node placed at the center of the disk
+    #[serde(default)]
+    pub focus_node: Option<NodeId>,
+}
+
+/// Keys of `LayoutConfig::parameters` read by the layout engines
+///
+/// Keys an engine does not know are ignored. Missing keys fall back to the
+/// engine's own settings.
+pub mod params {
+    /// Radial: radius of the first ring around the root
+    pub const BASE_RADIUS: &str = "base_radius";
+    /// Radial: distance added per additional ring
+    pub const RADIUS_INCREMENT: &str = "radius_increment";
+    /// Radial: smallest angle between siblings, in radians (0 to π)
+    pub const MIN_ANGLE: &str = "min_angle";
+    /// Radial: angle of the first child, in radians
+    pub const START_ANGLE: &str = "start_angle";
+    /// Radial: values above 0.0 push overlapping nodes apart after placement
+    pub const RESOLVE_COLLISIONS: &str = "resolve_collisions";
+    /// Radial and tree: approximate node size used for spacing
+    pub const NODE_SIZE: &str = "node_size";
+    /// Tree: 0 top-down, 1 bottom-up, 2 left-right, 3 right-left
+    pub const ORIENTATION: &str = "orientation";
+    /// Tree: gap between siblings
+    pub const HORIZONTAL_SPACING: &str = "horizontal_spacing";
+    /// Tree: gap between levels
+    pub const VERTICAL_SPACING: &str = "vertical_spacing";
+    /// Force: pull of edges towards their rest length
+    pub const SPRING_STRENGTH: &str = "spring_strength";
+    /// Force: rest length of edges
+    pub const SPRING_LENGTH: &str = "spring_length";
+    /// Force: push between every pair of nodes
+    pub const REPULSION_STRENGTH: &str = "repulsion_strength";
+    /// Force: velocity kept per step (0.0 to 1.0)
+    pub const DAMPING: &str = "damping";
+    /// Force: pull towards the layout center
+    pub const CENTER_STRENGTH: &str = "center_strength";
+    /// Force: simulation step size (0.0 to 1.0]
+    pub const TIME_STEP: &str = "time_step";
+    /// Force: iteration cap
+    pub const MAX_ITERATIONS: &str = "max_iterations";
+    /// Force: energy below which the simulation stops
+    pub const CONVERGENCE_THRESHOLD: &str = "convergence_threshold";
+    /// Force: Barnes-Hut opening angle, 0.0 for exact repulsion
+    pub const THETA: &str = "theta";
+    /// Force: node count above which repulsion uses the Barnes-Hut quadtree
+    pub const BARNES_HUT_THRESHOLD: &str = "barnes_hut_threshold";
+    /// Radial and tree: smallest gap kept between label boxes from `LayoutConfig::node_sizes`
+    pub const LABEL_GAP: &str = "label_gap";
+    /// Hyperbolic: hyperbolic distance between levels
+    pub const LEVEL_DISTANCE: &str = "level_distance";
+    /// Hyperbolic: share of the canvas half-size used by the disk (0.0 to 1.0]
+    pub const DISK_FILL: &str = "disk_fill";
+    /// Grid: number of columns; derived from the canvas width when missing
+    pub const COLUMNS: &str = "columns";
+    /// Grid: number of rows, used to derive the columns when those are missing
+    pub const ROWS: &str = "rows";
+    /// `LayoutEngineImpl`: values above 0.0 lay out only nodes outside collapsed
+    /// subtrees; hidden nodes take the position of their collapsed ancestor
+    pub const RESPECT_COLLAPSED: &str = "respect_collapsed";
 }
 
 /// Result of a layout calculation
@@ -48,6 +140,9 @@ pub struct LayoutResult {
     pub iterations: u32,
     /// Total energy/cost of the layout
     pub energy: f64,
+    /// Label box of each sized node, when `LayoutConfig::node_sizes` is set
+    #[serde(default)]
+    pub node_bounds: Option<HashMap<NodeId, LayoutBounds>>,
 }
 
 /// Layout bounds information
@@ -68,6 +163,10 @@ pub enum LayoutType {
     Tree,
     /// Force-directed layout
     Force,
+    /// Hyperbolic cone tree around a focus node
+    Hyperbolic,
+    /// Evenly spaced rows and columns
+    Grid,
 }
 
 /// Core trait for layout algorithms
@@ -117,16 +216,354 @@ impl Default for LayoutConfig {
             preserve_positions: false,
             min_distance: 100.0,
             parameters: HashMap::new(),
+            node_sizes: HashMap::new(),
+            focus_node: None,
         }
     }
 }
 
+impl LayoutConfig {
+    /// Start building a configuration from the defaults
+    pub fn builder() -> LayoutConfigBuilder {
+        LayoutConfigBuilder::default()
+    }
+
+    /// Preset for the radial engine
+    pub fn for_radial() -> Self {
+        Self::builder()
+            .canvas_size(1200.0, 1200.0)
+            .min_distance(60.0)
+            .parameter(params::BASE_RADIUS, 150.0)
+            .parameter(params::RADIUS_INCREMENT, 100.0)
+            .parameter(params::MIN_ANGLE, std::f64::consts::PI / 12.0)
+            .parameter(params::START_ANGLE, 0.0)
+            .parameter(params::NODE_SIZE, 40.0)
+            .parameter(params::RESOLVE_COLLISIONS, 1.0)
+            .into_config()
+    }
+
+    /// Preset for the tree engine, laid out top-down
+    pub fn for_tree() -> Self {
+        Self::builder()
+            .canvas_size(1600.0, 900.0)
+            .min_distance(40.0)
+            .parameter(params::ORIENTATION, 0.0)
+            .parameter(params::HORIZONTAL_SPACING, 80.0)
+            .parameter(params::VERTICAL_SPACING, 120.0)
+            .parameter(params::NODE_SIZE, 60.0)
+            .into_config()
+    }
+
+    /// Preset for the force-directed engine
+    pub fn for_force() -> Self {
+        Self::builder()
+            .canvas_size(1000.0, 800.0)
+            .min_distance(50.0)
+            .parameter(params::SPRING_STRENGTH, 0.1)
+            .parameter(params::SPRING_LENGTH, 100.0)
+            .parameter(params::REPULSION_STRENGTH, 1000.0)
+            .parameter(params::DAMPING, 0.95)
+            .parameter(params::CENTER_STRENGTH, 0.001)
+            .parameter(params::TIME_STEP, 0.1)
+            .parameter(params::MAX_ITERATIONS, 1000.0)
+            .parameter(params::CONVERGENCE_THRESHOLD, 0.01)
+            .parameter(params::THETA, 0.5)
+            .into_config()
+    }
+
+    /// Preset for the hyperbolic engine, centered on the first root node
+    pub fn for_hyperbolic() -> Self {
+        Self::builder()
+            .canvas_size(1000.0, 1000.0)
+            .min_distance(20.0)
+            .parameter(params::LEVEL_DISTANCE, 0.8)
+            .parameter(params::DISK_FILL, 0.9)
+            .into_config()
+    }
+
+    /// Preset for the grid engine, with columns fitted to the canvas width
+    pub fn for_grid() -> Self {
+        Self::builder()
+            .canvas_size(1000.0, 800.0)
+            .min_distance(120.0)
+            .into_config()
+    }
+
+    /// Preset for the given layout type
+    pub fn for_layout(layout_type: LayoutType) -> Self {
+        match layout_type {
+            LayoutType::Radial => Self::for_radial(),
+            LayoutType::Tree => Self::for_tree(),
+            LayoutType::Force => Self::for_force(),
+            LayoutType::Hyperbolic => Self::for_hyperbolic(),
+            LayoutType::Grid => Self::for_grid(),
+        }
+    }
+
+    /// Check the settings shared by all engines
+    ///
+    /// Engines validate their own parameters in `LayoutEngine::validate_config`.
+    pub fn validate(&self) -> MindmapResult<()> {
+        let valid_size = |size: f64| size > 0.0 && size.is_finite();
+        if !valid_size(self.canvas_width) || !valid_size(self.canvas_height) {
+            return Err(MindmapError::InvalidOperation {
+                message: "Canvas dimensions must be positive".to_string(),
+            });
+        }
+
+        if self.min_distance < 0.0 || !self.min_distance.is_finite() {
+            return Err(MindmapError::InvalidOperation {
+                message: "Minimum distance cannot be negative".to_string(),
+            });
+        }
+
+        if !self.center.x.is_finite() || !self.center.y.is_finite() {
+            return Err(MindmapError::InvalidOperation {
+                message: "Layout center must be finite".to_string(),
+            });
+        }
+
+        if let Some((key, _)) = self.parameters.iter().find(|(_, value)| !value.is_finite()) {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Layout parameter '{}' must be finite", key),
+            });
+        }
+
+        let valid_extent = |extent: f64| extent >= 0.0 && extent.is_finite();
+        if let Some(node_id) = self
+            .node_sizes
+            .iter()
+            .find(|(_, &(width, height))| !valid_extent(width) || !valid_extent(height))
+            .map(|(node_id, _)| node_id)
+        {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Size of node {} must be non-negative and finite", node_id),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Gap kept between label boxes, from [`params::LABEL_GAP`]
+    pub fn label_gap(&self) -> f64 {
+        self.parameters.get(params::LABEL_GAP).copied().unwrap_or(10.0)
+    }
+
+    /// Largest width or height in `node_sizes`, 0.0 when no sizes are set
+    pub fn max_node_extent(&self) -> f64 {
+        self.node_sizes
+            .values()
+            .map(|&(width, height)| width.max(height))
+            .fold(0.0, f64::max)
+    }
+
+    /// Current positions of pinned nodes, empty unless `preserve_positions` is set
+    pub fn pinned_positions(&self, graph: &Graph) -> HashMap<NodeId, Point> {
+        if !self.preserve_positions {
+            return HashMap::new();
+        }
+        graph.nodes()
+            .filter(|node| node.pinned)
+            .map(|node| (node.id, node.position))
+            .collect()
+    }
+
+    /// Label boxes around the given positions, if any node sizes are set
+    pub fn node_bounds(&self, positions: &HashMap<NodeId, Point>) -> Option<HashMap<NodeId, LayoutBounds>> {
+        if self.node_sizes.is_empty() {
+            return None;
+        }
+
+        Some(
+            positions
+                .iter()
+                .map(|(&node_id, position)| {
+                    let (width, height) = self.node_sizes.get(&node_id).copied().unwrap_or((0.0, 0.0));
+                    (node_id, LayoutBounds::around(position, width, height))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Builder for [`LayoutConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct LayoutConfigBuilder {
+    config: LayoutConfig,
+    center_set: bool,
+}
+
+impl LayoutConfigBuilder {
+    /// Set the canvas size; the center follows unless set explicitly
+    pub fn canvas_size(mut self, width: f64, height: f64) -> Self {
+        self.config.canvas_width = width;
+        self.config.canvas_height = height;
+        if !self.center_set {
+            self.config.center = Point::new(width / 2.0, height / 2.0);
+        }
+        self
+    }
+
+    /// Set the center point of the layout
+    pub fn center(mut self, center: Point) -> Self {
+        self.config.center = center;
+        self.center_set = true;
+        self
+    }
+
+    /// Set the animation duration in milliseconds
+    pub fn animation_duration(mut self, duration_ms: u32) -> Self {
+        self.config.animation_duration = duration_ms;
+        self
+    }
+
+    /// Keep existing node positions where the engine supports it
+    pub fn preserve_positions(mut self, preserve: bool) -> Self {
+        self.config.preserve_positions = preserve;
+        self
+    }
+
+    /// Set the minimum distance between nodes
+    pub fn min_distance(mut self, distance: f64) -> Self {
+        self.config.min_distance = distance;
+        self
+    }
+
+    /// Set the node the hyperbolic layout places at the center
+    pub fn focus_node(mut self, node_id: NodeId) -> Self {
+        self.config.focus_node = Some(node_id);
+        self
+    }
+
+    /// Set the label box size of a node
+    pub fn node_size(mut self, node_id: NodeId, width: f64, height: f64) -> Self {
+        self.config.node_sizes.insert(node_id, (width, height));
+        self
+    }
+
+    /// Set the label box size of every node in the graph with a measuring function
+    pub fn measure_nodes<F>(mut self, graph: &Graph, measure: F) -> Self
+    where
+        F: Fn(&Node) -> (f64, f64),
+    {
+        self.config.node_sizes.extend(graph.nodes().map(|node| (node.id, measure(node))));
+        self
+    }
+
+    /// Set an engine parameter, see [`params`] for the keys
+    pub fn parameter(mut self, key: impl Into<String>, value: f64) -> Self {
+        self.config.parameters.insert(key.into(), value);
+        self
+    }
+
+    /// Validate and return the configuration
+    pub fn build(self) -> MindmapResult<LayoutConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    /// Return the configuration without validation, for known-good presets
+    fn into_config(self) -> LayoutConfig {
+        self.config
+    }
+}
+
+impl LayoutResult {
+    /// Tolerance used when checking that bounds encompass all positions
+    const BOUNDS_EPSILON: f64 = 1e-6;
+
+    /// Validate that the result is renderable
+    ///
+    /// Checks that all positions are finite and that the bounds are finite,
+    /// valid, and encompass every position.
+    pub fn validate(&self) -> MindmapResult<()> {
+        let bounds = &self.bounds;
+        let bounds_finite = bounds.min_x.is_finite() && bounds.min_y.is_finite()
+            && bounds.max_x.is_finite() && bounds.max_y.is_finite();
+
+        if !bounds_finite || !bounds.is_valid() {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Invalid layout bounds: {:?}", bounds),
+            });
+        }
+
+        for (node_id, position) in &self.positions {
+            if !position.x.is_finite() || !position.y.is_finite() {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Non-finite position for node {}: ({}, {})", node_id, position.x, position.y),
+                });
+            }
+
+            let inside = position.x >= bounds.min_x - Self::BOUNDS_EPSILON
+                && position.x <= bounds.max_x + Self::BOUNDS_EPSILON
+                && position.y >= bounds.min_y - Self::BOUNDS_EPSILON
+                && position.y <= bounds.max_y + Self::BOUNDS_EPSILON;
+
+            if !inside {
+                return Err(MindmapError::InvalidOperation {
+                    message: format!("Position of node {} lies outside layout bounds", node_id),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move every position, the bounds and any label boxes by an offset
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        for position in self.positions.values_mut() {
+            position.x += dx;
+            position.y += dy;
+        }
+
+        let shift = |bounds: &mut LayoutBounds| {
+            bounds.min_x += dx;
+            bounds.max_x += dx;
+            bounds.min_y += dy;
+            bounds.max_y += dy;
+        };
+        shift(&mut self.bounds);
+        if let Some(node_bounds) = self.node_bounds.as_mut() {
+            node_bounds.values_mut().for_each(shift);
+        }
+    }
+
+    /// Validate the result and check that every node in the graph has a position
+    pub fn validate_for_graph(&self, graph: &Graph) -> MindmapResult<()> {
+        self.validate()?;
+
+        if let Some(node) = graph.nodes().find(|node| !self.positions.contains_key(&node.id)) {
+            return Err(MindmapError::InvalidOperation {
+                message: format!("Layout is missing a position for node {}", node.id),
+            });
+        }
+
+        Ok(())
+    }
+}
+
 impl LayoutBounds {
     /// Create new layout bounds
     pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
         Self { min_x, min_y, max_x, max_y }
     }
 
+    /// Create bounds of the given size centered on a point
+    pub fn around(center: &Point, width: f64, height: f64) -> Self {
+        Self::new(
+            center.x - width / 2.0,
+            center.y - height / 2.0,
+            center.x + width / 2.0,
+            center.y + height / 2.0,
+        )
+    }
+
+    /// Check whether two bounds overlap, treating touching edges as apart
+    pub fn overlaps(&self, other: &LayoutBounds) -> bool {
+        self.min_x < other.max_x && other.min_x < self.max_x
+            && self.min_y < other.max_y && other.min_y < self.max_y
+    }
+
     /// Create bounds from a set of points
     pub fn from_points(points: &[Point]) -> Self {
         if points.is_empty() {
@@ -237,6 +674,134 @@ pub mod utils {
         radius.max(min_distance).max(node_size * 2.0)
     }
 
+    /// Offset each position by a random amount of at most `amount` on each axis
+    ///
+    /// Nodes are visited in ID order so the result only depends on the generator
+    /// seed, not on map iteration order.
+    pub fn jitter_positions(points: &mut HashMap<NodeId, Point>, amount: f64, rng: &mut RngSource) {
+        let mut ids: Vec<NodeId> = points.keys().copied().collect();
+        ids.sort_by_key(|id| id.as_uuid());
+
+        for id in ids {
+            if let Some(position) = points.get_mut(&id) {
+                position.x += rng.jitter(amount);
+                position.y += rng.jitter(amount);
+            }
+        }
+    }
+
+    /// Nudge nodes apart until their label boxes are at least `min_gap` apart
+    ///
+    /// Overlapping pairs are pushed apart along the axis that needs the smaller
+    /// move, each node taking half. Nodes without a size are treated as points.
+    /// Returns false if overlaps remain after the iteration limit.
+    pub fn resolve_overlaps(
+        positions: &mut HashMap<NodeId, Point>,
+        sizes: &HashMap<NodeId, (f64, f64)>,
+        min_gap: f64,
+    ) -> bool {
+        resolve_overlaps_with_fixed(positions, sizes, min_gap, &HashSet::new())
+    }
+
+    /// Like [`resolve_overlaps`], but never moves the nodes in `fixed`
+    ///
+    /// A node overlapping a fixed node takes the whole move; overlaps
+    /// between two fixed nodes are left alone.
+    pub fn resolve_overlaps_with_fixed(
+        positions: &mut HashMap<NodeId, Point>,
+        sizes: &HashMap<NodeId, (f64, f64)>,
+        min_gap: f64,
+        fixed: &HashSet<NodeId>,
+    ) -> bool {
+        const MAX_ITERATIONS: usize = 100;
+
+        let mut ids: Vec<NodeId> = positions.keys().copied().collect();
+        ids.sort_by_key(|id| id.as_uuid());
+        let size_of = |id: &NodeId| sizes.get(id).copied().unwrap_or((0.0, 0.0));
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut moved = false;
+
+            for i in 0..ids.len() {
+                for j in i + 1..ids.len() {
+                    let (p1, p2) = (positions[&ids[i]], positions[&ids[j]]);
+                    let ((w1, h1), (w2, h2)) = (size_of(&ids[i]), size_of(&ids[j]));
+
+                    // Penetration depth on each axis, including the gap
+                    let overlap_x = (w1 + w2) / 2.0 + min_gap - (p2.x - p1.x).abs();
+                    let overlap_y = (h1 + h2) / 2.0 + min_gap - (p2.y - p1.y).abs();
+                    if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                        continue;
+                    }
+
+                    let (fixed1, fixed2) = (fixed.contains(&ids[i]), fixed.contains(&ids[j]));
+                    if fixed1 && fixed2 {
+                        continue;
+                    }
+                    // Each node takes half the move, or all of it next to a fixed node
+                    let (share1, share2) = match (fixed1, fixed2) {
+                        (true, _) => (0.0, 1.0),
+                        (_, true) => (1.0, 0.0),
+                        _ => (0.5, 0.5),
+                    };
+
+                    let (dx, dy) = if overlap_x <= overlap_y {
+                        let direction = if p2.x >= p1.x { 1.0 } else { -1.0 };
+                        (direction * overlap_x, 0.0)
+                    } else {
+                        let direction = if p2.y >= p1.y { 1.0 } else { -1.0 };
+                        (0.0, direction * overlap_y)
+                    };
+
+                    if let Some(p) = positions.get_mut(&ids[i]) {
+                        p.x -= dx * share1;
+                        p.y -= dy * share1;
+                    }
+                    if let Some(p) = positions.get_mut(&ids[j]) {
+                        p.x += dx * share2;
+                        p.y += dy * share2;
+                    }
+                    moved = true;
+                }
+            }
+
+            if !moved {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Move pinned nodes back to their pinned coordinates
+    ///
+    /// Every other node moves by the same offset as its nearest pinned
+    /// ancestor, so subtrees stay arranged around their anchor. Nodes
+    /// without a pinned ancestor are left where they are.
+    pub fn anchor_pinned(graph: &Graph, positions: &mut HashMap<NodeId, Point>, pinned: &HashMap<NodeId, Point>) {
+        if pinned.is_empty() {
+            return;
+        }
+
+        let offsets: HashMap<NodeId, Point> = pinned
+            .iter()
+            .filter_map(|(node_id, target)| {
+                let current = positions.get(node_id)?;
+                Some((*node_id, Point::new(target.x - current.x, target.y - current.y)))
+            })
+            .collect();
+
+        for (&node_id, position) in positions.iter_mut() {
+            let anchor_offset = std::iter::once(node_id)
+                .chain(graph.get_ancestors(node_id))
+                .find_map(|id| offsets.get(&id));
+            if let Some(offset) = anchor_offset {
+                position.x += offset.x;
+                position.y += offset.y;
+            }
+        }
+    }
+
     /// Scale points to fit within bounds
     pub fn scale_to_fit(points: &mut HashMap<NodeId, Point>, target_bounds: &LayoutBounds) {
         if points.is_empty() {
@@ -273,6 +838,85 @@ pub mod utils {
     }
 }
 
+/// Easing curve applied to animation progress
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Accelerate during the first half, decelerate during the second
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map linear progress in [0, 1] onto the curve
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+                }
+            }
+        }
+    }
+}
+
+/// Positions part way between `from` and the layout `to`, for `t` in [0, 1]
+///
+/// Nodes missing from `from` move out from the center of the target layout,
+/// and nodes missing from `to` are dropped. Interpolation is linear; see
+/// [`animate_with_easing`] for other curves.
+pub fn animate(from: &HashMap<NodeId, Point>, to: &LayoutResult, t: f64) -> HashMap<NodeId, Point> {
+    animate_with_easing(from, to, t, Easing::Linear)
+}
+
+/// Like [`animate`], with `t` mapped through an easing curve first
+pub fn animate_with_easing(
+    from: &HashMap<NodeId, Point>,
+    to: &LayoutResult,
+    t: f64,
+    easing: Easing,
+) -> HashMap<NodeId, Point> {
+    let progress = easing.apply(t);
+    let origin = to.bounds.center();
+
+    to.positions
+        .iter()
+        .map(|(&node_id, target)| {
+            let start = from.get(&node_id).copied().unwrap_or(origin);
+            let position = Point::new(
+                start.x + (target.x - start.x) * progress,
+                start.y + (target.y - start.y) * progress,
+            );
+            (node_id, position)
+        })
+        .collect()
+}
+
+/// Keyframes from `from` to the layout `to`, evenly spaced in time
+///
+/// The first frame holds the `from` positions and the last the target
+/// layout, with `easing` shaping the frames in between. A single frame is
+/// just the target.
+pub fn animation_frames(
+    from: &HashMap<NodeId, Point>,
+    to: &LayoutResult,
+    frame_count: usize,
+    easing: Easing,
+) -> Vec<HashMap<NodeId, Point>> {
+    let steps = frame_count.saturating_sub(1).max(1) as f64;
+    (0..frame_count)
+        .map(|index| {
+            let t = if frame_count == 1 { 1.0 } else { index as f64 / steps };
+            animate_with_easing(from, to, t, easing)
+        })
+        .collect()
+}
+
 /// Concrete layout engine that can handle multiple layout algorithms
 pub struct LayoutEngineImpl {
     current_layout: LayoutType,
@@ -300,22 +944,41 @@ impl LayoutEngineImpl {
 
     /// Calculate layout for a graph using the specified layout type
     pub fn calculate_layout(&self, graph: &Graph, layout_type: LayoutType) -> MindmapResult<LayoutResult> {
-        let config = LayoutConfig::default();
+        let engine = Self::with_layout_type(layout_type);
+        LayoutEngine::calculate_layout(&engine, graph, &LayoutConfig::default())
+    }
 
-        match layout_type {
-            LayoutType::Radial => {
-                let radial_engine = radial::RadialLayoutEngine::default();
-                radial_engine.calculate_layout(graph, &config)
-            }
-            LayoutType::Tree => {
-                let tree_engine = tree::TreeLayoutEngine::default();
-                tree_engine.calculate_layout(graph, &config)
-            }
-            LayoutType::Force => {
-                let force_engine = force::ForceLayoutEngine::default();
-                force_engine.calculate_layout(graph, &config)
+    /// Calculate a layout for the subtree below `root_id` only
+    ///
+    /// The rest of the graph is ignored: the subtree is laid out as if
+    /// `root_id` were the only root, centered on the canvas, and only its
+    /// nodes receive positions.
+    pub fn calculate_subtree_layout(&self, graph: &Graph, root_id: NodeId, layout_type: LayoutType) -> MindmapResult<LayoutResult> {
+        let mut root = graph.get_node(root_id)
+            .ok_or(MindmapError::NodeNotFound { id: root_id })?
+            .clone();
+        root.parent_id = None;
+
+        // Descendants come after their parents, as bulk insertion expects
+        let descendants = graph.get_descendants(root_id);
+        let mut nodes = Vec::with_capacity(descendants.len() + 1);
+        nodes.push(root);
+        nodes.extend(descendants.iter().filter_map(|&id| graph.get_node(id).cloned()));
+
+        let mut subtree = Graph::new();
+        subtree.add_nodes_bulk(nodes)?;
+        for edge in graph.edges() {
+            if subtree.contains_node(edge.from_node) && subtree.contains_node(edge.to_node) {
+                subtree.add_edge(edge.clone())?;
             }
         }
+
+        let mut result = self.calculate_layout(&subtree, layout_type)?;
+
+        let center = LayoutConfig::default().center;
+        let bounds_center = result.bounds.center();
+        result.translate(center.x - bounds_center.x, center.y - bounds_center.y);
+        Ok(result)
     }
 }
 
@@ -325,9 +988,40 @@ impl Default for LayoutEngineImpl {
     }
 }
 
+impl LayoutEngineImpl {
+    /// Graph of the visible nodes only, if collapsed subtrees must be skipped
+    fn visible_graph(graph: &Graph, config: &LayoutConfig) -> MindmapResult<Option<(Graph, HashSet<NodeId>)>> {
+        if !config.parameters.get(params::RESPECT_COLLAPSED).is_some_and(|&value| value > 0.0) {
+            return Ok(None);
+        }
+        let hidden = graph.hidden_node_ids();
+        if hidden.is_empty() {
+            return Ok(None);
+        }
+
+        let nodes = graph.nodes()
+            .filter(|node| !hidden.contains(&node.id))
+            .cloned()
+            .collect();
+        let mut visible = Graph::new();
+        visible.add_nodes_bulk(nodes)?;
+        for edge in graph.edges() {
+            if visible.contains_node(edge.from_node) && visible.contains_node(edge.to_node) {
+                visible.add_edge(edge.clone())?;
+            }
+        }
+        Ok(Some((visible, hidden)))
+    }
+}
+
 impl LayoutEngine for LayoutEngineImpl {
     fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
-        match self.current_layout {
+        let started = Instant::now();
+        let visible = Self::visible_graph(graph, config)?;
+        let full_graph = graph;
+        let graph = visible.as_ref().map_or(graph, |(visible, _)| visible);
+
+        let mut result = match self.current_layout {
             LayoutType::Radial => {
                 let radial_engine = radial::RadialLayoutEngine::default();
                 radial_engine.calculate_layout(graph, config)
@@ -340,7 +1034,35 @@ impl LayoutEngine for LayoutEngineImpl {
                 let force_engine = force::ForceLayoutEngine::default();
                 force_engine.calculate_layout(graph, config)
             }
+            LayoutType::Hyperbolic => {
+                let hyperbolic_engine = hyperbolic::HyperbolicLayoutEngine::default();
+                hyperbolic_engine.calculate_layout(graph, config)
+            }
+            LayoutType::Grid => {
+                let grid_engine = grid::GridLayoutEngine::default();
+                grid_engine.calculate_layout(graph, config)
+            }
+        }?;
+
+        // Hidden nodes sit on their nearest visible ancestor, ready to unfold
+        if let Some((_, hidden)) = &visible {
+            for &node_id in hidden {
+                let anchor = full_graph.get_ancestors(node_id)
+                    .into_iter()
+                    .find_map(|ancestor_id| result.positions.get(&ancestor_id).copied());
+                if let Some(position) = anchor {
+                    result.positions.insert(node_id, position);
+                }
+            }
         }
+
+        crate::events::emit(EngineEvent::LayoutComputed {
+            layout_type: self.current_layout,
+            node_count: result.positions.len(),
+            iterations: result.iterations,
+            duration: started.elapsed(),
+        });
+        Ok(result)
     }
 
     fn layout_type(&self) -> LayoutType {
@@ -352,6 +1074,25 @@ impl LayoutEngine for LayoutEngineImpl {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_layout_timing_is_recorded() {
+        use crate::metrics::{registry, MetricCategory, MetricId};
+
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Timed")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Child")).unwrap();
+
+        let engine = tree::TreeLayoutEngine::default();
+        engine.calculate_layout(&graph, &LayoutConfig::default()).unwrap();
+
+        let report = registry().report();
+        assert!(report.categories.contains_key(&MetricCategory::Layout));
+        let timed = registry().entries().into_iter()
+            .any(|entry| entry.id == MetricId::layout("tree") && entry.value.as_duration().is_some());
+        assert!(timed);
+    }
+
     #[test]
     fn test_layout_config_default() {
         let config = LayoutConfig::default();
@@ -378,6 +1119,45 @@ mod tests {
         assert_eq!(bounds.height(), 75.0);
     }
 
+    #[test]
+    fn test_layout_result_validation() {
+        let mut graph = Graph::new();
+        let root = crate::models::Node::new("Root");
+        let root_id = root.id;
+        graph.add_node(root).unwrap();
+        graph.add_node(crate::models::Node::new_child(root_id, "Child")).unwrap();
+
+        let engine = LayoutEngineImpl::new();
+        for layout_type in [LayoutType::Radial, LayoutType::Tree, LayoutType::Force, LayoutType::Hyperbolic, LayoutType::Grid] {
+            let result = engine.calculate_layout(&graph, layout_type).unwrap();
+            assert!(result.validate().is_ok());
+            assert!(result.validate_for_graph(&graph).is_ok());
+        }
+
+        let mut positions = HashMap::new();
+        positions.insert(root_id, Point::new(f64::NAN, 0.0));
+        let result = LayoutResult {
+            positions,
+            bounds: LayoutBounds::new(0.0, 0.0, 10.0, 10.0),
+            converged: true,
+            iterations: 0,
+            energy: 0.0,
+            node_bounds: None,
+        };
+        assert!(result.validate().is_err());
+
+        // Position outside of bounds
+        let mut outside = result.clone();
+        outside.positions.insert(root_id, Point::new(50.0, 5.0));
+        assert!(outside.validate().is_err());
+
+        // Missing node position
+        let mut partial = result;
+        partial.positions.insert(root_id, Point::new(5.0, 5.0));
+        assert!(partial.validate().is_ok());
+        assert!(partial.validate_for_graph(&graph).is_err());
+    }
+
     #[test]
     fn test_bounds_center() {
         let bounds = LayoutBounds::new(0.0, 0.0, 100.0, 50.0);
@@ -470,4 +1250,206 @@ mod tests {
         assert!(result_bounds.width() <= target_bounds.width());
         assert!(result_bounds.height() <= target_bounds.height());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_layout_config_presets() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(crate::models::Node::new("Root")).unwrap();
+        for i in 0..4 {
+            graph.add_node(crate::models::Node::new_child(root_id, format!("Child {}", i))).unwrap();
+        }
+
+        let config = LayoutConfig::for_force();
+        let engine = ForceLayoutEngine::default();
+        assert!(engine.validate_config(&config).is_ok());
+        let result = engine.calculate_layout(&graph, &config).unwrap();
+        assert_eq!(result.positions.len(), graph.node_count());
+
+        assert!(RadialLayoutEngine::default().validate_config(&LayoutConfig::for_radial()).is_ok());
+        assert!(TreeLayoutEngine::default().validate_config(&LayoutConfig::for_tree()).is_ok());
+        for layout_type in [LayoutType::Radial, LayoutType::Tree, LayoutType::Force] {
+            assert!(LayoutConfig::for_layout(layout_type).validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_layout_config_builder() {
+        let config = LayoutConfig::builder()
+            .canvas_size(400.0, 300.0)
+            .min_distance(20.0)
+            .parameter(params::SPRING_LENGTH, 80.0)
+            .build()
+            .unwrap();
+        assert_eq!(config.center, Point::new(200.0, 150.0));
+        assert_eq!(config.parameters.get(params::SPRING_LENGTH), Some(&80.0));
+
+        let result = LayoutConfig::builder().canvas_size(0.0, 300.0).build();
+        assert!(matches!(result, Err(MindmapError::InvalidOperation { .. })));
+
+        let result = LayoutConfig::builder().parameter(params::DAMPING, f64::NAN).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resolve_overlaps_separates_wide_siblings() {
+        let left = NodeId::new();
+        let right = NodeId::new();
+        let mut positions = HashMap::new();
+        positions.insert(left, Point::new(100.0, 200.0));
+        positions.insert(right, Point::new(160.0, 205.0));
+        let mut sizes = HashMap::new();
+        sizes.insert(left, (200.0, 30.0));
+        sizes.insert(right, (180.0, 30.0));
+
+        let boxes = |positions: &HashMap<NodeId, Point>| {
+            let (w1, h1) = sizes[&left];
+            let (w2, h2) = sizes[&right];
+            (LayoutBounds::around(&positions[&left], w1, h1), LayoutBounds::around(&positions[&right], w2, h2))
+        };
+        let (a, b) = boxes(&positions);
+        assert!(a.overlaps(&b));
+
+        assert!(utils::resolve_overlaps(&mut positions, &sizes, 10.0));
+        let (a, b) = boxes(&positions);
+        assert!(!a.overlaps(&b));
+        // Pushed apart vertically, the cheaper direction, keeping the gap
+        assert!(b.min_y - a.max_y >= 10.0 - 1e-9);
+        assert_eq!(positions[&left].x, 100.0);
+    }
+
+    #[test]
+    fn test_engines_keep_label_boxes_apart() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        for i in 0..4 {
+            graph.add_node(Node::new_child(root_id, format!("A rather long label for child {}", i))).unwrap();
+        }
+
+        // Roughly 8 pixels per character, one line high
+        let measure = |node: &Node| (node.text.chars().count() as f64 * 8.0, 24.0);
+        for layout_type in [LayoutType::Tree, LayoutType::Radial] {
+            let config = LayoutConfig::builder().measure_nodes(&graph, measure).build().unwrap();
+            let result = LayoutEngine::calculate_layout(&LayoutEngineImpl::with_layout_type(layout_type), &graph, &config).unwrap();
+
+            let node_bounds = result.node_bounds.as_ref().unwrap();
+            assert_eq!(node_bounds.len(), graph.node_count());
+            let boxes: Vec<&LayoutBounds> = node_bounds.values().collect();
+            for i in 0..boxes.len() {
+                for j in i + 1..boxes.len() {
+                    assert!(!boxes[i].overlaps(boxes[j]), "{:?} boxes overlap", layout_type);
+                }
+            }
+        }
+
+        let unsized_result = LayoutEngineImpl::new().calculate_layout(&graph, LayoutType::Tree).unwrap();
+        assert!(unsized_result.node_bounds.is_none());
+    }
+
+    #[test]
+    fn test_layout_can_skip_collapsed_subtrees() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let branch_id = graph.add_node(Node::new_child(root_id, "Branch")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Other")).unwrap();
+        let mut hidden_ids = Vec::new();
+        for i in 0..20 {
+            let child_id = graph.add_node(Node::new_child(branch_id, format!("Child {}", i))).unwrap();
+            hidden_ids.push(child_id);
+            hidden_ids.push(graph.add_node(Node::new_child(child_id, format!("Leaf {}", i))).unwrap());
+        }
+        graph.get_node_mut(branch_id).unwrap().set_collapsed(true);
+
+        let engine = LayoutEngineImpl::with_layout_type(LayoutType::Grid);
+        let respect = LayoutConfig::builder().parameter(params::RESPECT_COLLAPSED, 1.0).build().unwrap();
+        let result = LayoutEngine::calculate_layout(&engine, &graph, &respect).unwrap();
+        assert!(result.validate_for_graph(&graph).is_ok());
+
+        // Only three cells are used; hidden nodes sit on the collapsed branch
+        assert_eq!(result.bounds.width(), 2.0 * 100.0);
+        for id in &hidden_ids {
+            assert_eq!(result.positions[id], result.positions[&branch_id]);
+        }
+
+        // Without the flag every node gets its own cell
+        let full = LayoutEngine::calculate_layout(&engine, &graph, &LayoutConfig::default()).unwrap();
+        assert_ne!(full.positions[&hidden_ids[0]], full.positions[&branch_id]);
+    }
+
+    #[test]
+    fn test_subtree_layout_ignores_rest_of_graph() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let focus_id = graph.add_node(Node::new_child(root_id, "Focus")).unwrap();
+        let sibling_id = graph.add_node(Node::new_child(root_id, "Sibling")).unwrap();
+        let mut subtree_ids = vec![focus_id];
+        for i in 0..3 {
+            let child_id = graph.add_node(Node::new_child(focus_id, format!("Child {}", i))).unwrap();
+            subtree_ids.push(child_id);
+            subtree_ids.push(graph.add_node(Node::new_child(child_id, format!("Leaf {}", i))).unwrap());
+        }
+        graph.add_node(Node::new_child(sibling_id, "Unrelated")).unwrap();
+
+        let engine = LayoutEngineImpl::new();
+        let center = LayoutConfig::default().center;
+        for layout_type in [LayoutType::Radial, LayoutType::Tree, LayoutType::Force, LayoutType::Hyperbolic, LayoutType::Grid] {
+            let result = engine.calculate_subtree_layout(&graph, focus_id, layout_type).unwrap();
+
+            let mut laid_out: Vec<NodeId> = result.positions.keys().copied().collect();
+            laid_out.sort_by_key(|id| id.as_uuid());
+            let mut expected = subtree_ids.clone();
+            expected.sort_by_key(|id| id.as_uuid());
+            assert_eq!(laid_out, expected, "{:?}", layout_type);
+
+            assert!(result.validate().is_ok());
+            let bounds_center = result.bounds.center();
+            assert!((bounds_center.x - center.x).abs() < 1e-6 && (bounds_center.y - center.y).abs() < 1e-6);
+        }
+
+        assert!(matches!(
+            engine.calculate_subtree_layout(&graph, NodeId::new(), LayoutType::Tree),
+            Err(MindmapError::NodeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_animate_interpolates_between_layouts() {
+        let (a, b, new) = (NodeId::new(), NodeId::new(), NodeId::new());
+        let from: HashMap<NodeId, Point> = [(a, Point::new(0.0, 0.0)), (b, Point::new(100.0, 0.0))].into();
+        let positions: HashMap<NodeId, Point> = [
+            (a, Point::new(100.0, 100.0)),
+            (b, Point::new(100.0, 0.0)),
+            (new, Point::new(200.0, 200.0)),
+        ].into();
+        let to = LayoutResult {
+            bounds: LayoutBounds::from_points(&positions.values().copied().collect::<Vec<_>>()),
+            positions,
+            converged: true,
+            iterations: 1,
+            energy: 0.0,
+            node_bounds: None,
+        };
+
+        // The endpoints are the source and the target; new nodes start at the center
+        let start = animate(&from, &to, 0.0);
+        assert_eq!(start[&a], from[&a]);
+        assert_eq!(start[&b], from[&b]);
+        assert_eq!(start[&new], to.bounds.center());
+        assert_eq!(animate(&from, &to, 1.0), to.positions);
+        assert_eq!(animate_with_easing(&from, &to, 1.0, Easing::EaseInOut), to.positions);
+
+        assert_eq!(animate(&from, &to, 0.5)[&a], Point::new(50.0, 50.0));
+        assert_eq!(animate_with_easing(&from, &to, 0.25, Easing::EaseInOut)[&a], Point::new(12.5, 12.5));
+
+        // Progress outside [0, 1] is clamped
+        assert_eq!(animate(&from, &to, 2.0), to.positions);
+
+        // Keyframes run from the source to the target at even time steps
+        let frames = animation_frames(&from, &to, 5, Easing::EaseInOut);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0][&a], from[&a]);
+        assert_eq!(frames[1][&a], Point::new(12.5, 12.5));
+        assert_eq!(frames[4], to.positions);
+        assert_eq!(animation_frames(&from, &to, 1, Easing::Linear), vec![to.positions.clone()]);
+        assert!(animation_frames(&from, &to, 0, Easing::Linear).is_empty());
+    }
+}
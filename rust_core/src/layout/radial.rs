@@ -107,6 +107,7 @@ impl RadialLayoutEngine {
 
     /// Build level structure for radial layout
     fn build_levels(&self, graph: &Graph, root_id: NodeId) -> HashMap<usize, Vec<NodeId>> {
+        let children_map = graph.children_by_parent();
         let mut levels = HashMap::new();
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
@@ -127,7 +128,7 @@ impl RadialLayoutEngine {
             levels.entry(level).or_insert_with(Vec::new).push(node_id);
 
             // Add children to next level
-            for child in graph.get_children(node_id) {
+            for child in children_map.get(&node_id).into_iter().flatten() {
                 if !visited.contains(&child.id) {
                     visited.insert(child.id);
                     queue.push_back((child.id, level + 1));
@@ -182,7 +183,8 @@ impl RadialLayoutEngine {
             }
 
             // Calculate radius for this level
-            let node_size = config.parameters.get("node_size").copied().unwrap_or(40.0);
+            let node_size = config.parameters.get("node_size").copied().unwrap_or(40.0)
+                .max(config.max_node_extent());
             let radius = self.calculate_level_radius(level, child_count, node_size);
 
             // Distribute children evenly around the circle
@@ -206,7 +208,8 @@ impl RadialLayoutEngine {
         config: &LayoutConfig,
     ) {
         let min_distance = config.min_distance;
-        let node_size = config.parameters.get("node_size").copied().unwrap_or(40.0);
+        let node_size = config.parameters.get("node_size").copied().unwrap_or(40.0)
+            .max(config.max_node_extent());
         let min_separation = (min_distance + node_size).max(50.0);
 
         let nodes: Vec<NodeId> = positions.keys().copied().collect();
@@ -273,10 +276,9 @@ impl RadialLayoutEngine {
             position.y = position.y.clamp(min_y, max_y);
         }
     }
-}
 
-impl LayoutEngine for RadialLayoutEngine {
-    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+    /// Position every node; timed by [`LayoutEngine::calculate_layout`]
+    fn compute_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
         self.validate_config(config)?;
 
         if graph.node_count() == 0 {
@@ -286,6 +288,7 @@ impl LayoutEngine for RadialLayoutEngine {
                 converged: true,
                 iterations: 0,
                 energy: 0.0,
+                node_bounds: None,
             });
         }
 
@@ -315,6 +318,16 @@ impl LayoutEngine for RadialLayoutEngine {
         // Constrain to canvas bounds
         self.constrain_to_canvas(&mut positions, config);
 
+        // Pinned nodes keep their coordinates and take their subtrees along
+        let pinned = config.pinned_positions(graph);
+        utils::anchor_pinned(graph, &mut positions, &pinned);
+
+        // Label boxes keep their size, so separate them after fitting the canvas, moving only unpinned nodes
+        if !config.node_sizes.is_empty() {
+            let fixed: HashSet<NodeId> = pinned.keys().copied().collect();
+            utils::resolve_overlaps_with_fixed(&mut positions, &config.node_sizes, config.label_gap(), &fixed);
+        }
+
         // Calculate final bounds and energy
         let all_points: Vec<Point> = positions.values().copied().collect();
         let bounds = LayoutBounds::from_points(&all_points);
@@ -327,14 +340,22 @@ impl LayoutEngine for RadialLayoutEngine {
             }
         }
 
+        let node_bounds = config.node_bounds(&positions);
         Ok(LayoutResult {
             positions,
             bounds,
             converged: true,
             iterations: 1,
             energy: total_energy,
+            node_bounds,
         })
     }
+}
+
+impl LayoutEngine for RadialLayoutEngine {
+    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        timed_layout!("radial", self.compute_layout(graph, config))
+    }
 
     fn layout_type(&self) -> LayoutType {
         LayoutType::Radial
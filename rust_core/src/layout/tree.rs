@@ -36,10 +36,15 @@ pub struct TreeLayoutEngine {
 }
 
 /// Internal structure for tree node layout calculations
+///
+/// A tree is a list of these in pre-order, root first, so every parent comes
+/// before its children and the layout passes can run as plain loops instead
+/// of recursing once per level.
 #[derive(Debug, Clone)]
 struct TreeNode {
     id: NodeId,
-    children: Vec<TreeNode>,
+    /// Indices of the children in the tree's node list
+    children: Vec<usize>,
     width: f64,
     height: f64,
     x_offset: f64,
@@ -151,98 +156,134 @@ impl TreeLayoutEngine {
     }
 
     /// Build tree structure from graph
-    fn build_tree(&self, graph: &Graph, root_id: NodeId) -> MindmapResult<TreeNode> {
+    ///
+    /// Returns the nodes in pre-order with the root at index 0. The walk uses
+    /// an explicit stack, so maps thousands of levels deep don't overflow.
+    fn build_tree(&self, graph: &Graph, root_id: NodeId) -> MindmapResult<Vec<TreeNode>> {
+        let children_map = graph.children_by_parent();
+        let mut tree: Vec<TreeNode> = Vec::new();
         let mut visited = HashSet::new();
-        self.build_tree_recursive(graph, root_id, &mut visited)
-    }
+        let mut stack: Vec<(NodeId, Option<usize>)> = vec![(root_id, None)];
 
-    /// Recursively build tree structure
-    fn build_tree_recursive(
-        &self,
-        graph: &Graph,
-        node_id: NodeId,
-        visited: &mut HashSet<NodeId>,
-    ) -> MindmapResult<TreeNode> {
-        if visited.contains(&node_id) {
-            return Err(MindmapError::InvalidOperation {
-                message: "Circular dependency detected in tree layout".to_string(),
+        while let Some((node_id, parent_index)) = stack.pop() {
+            if !visited.insert(node_id) {
+                return Err(MindmapError::InvalidOperation {
+                    message: "Circular dependency detected in tree layout".to_string(),
+                });
+            }
+
+            let index = tree.len();
+            tree.push(TreeNode {
+                id: node_id,
+                children: Vec::new(),
+                width: self.node_size,
+                height: self.node_size, // Currently used for node spacing calculations
+                x_offset: 0.0,
+                y_offset: 0.0,
+                subtree_width: 0.0,
             });
+            if let Some(parent_index) = parent_index {
+                tree[parent_index].children.push(index);
+            }
+
+            // Push in reverse so children are visited, and numbered, in order
+            if let Some(children) = children_map.get(&node_id) {
+                stack.extend(children.iter().rev().map(|child| (child.id, Some(index))));
+            }
         }
 
-        visited.insert(node_id);
+        Ok(tree)
+    }
 
-        let mut children = Vec::new();
-        for child in graph.get_children(node_id) {
-            let child_tree = self.build_tree_recursive(graph, child.id, visited)?;
-            children.push(child_tree);
+    /// Widen nodes to their label box along the axis siblings are spread on
+    fn apply_node_sizes(&self, tree: &mut [TreeNode], config: &LayoutConfig, orientation: TreeOrientation) {
+        for node in tree.iter_mut() {
+            if let Some(&(width, height)) = config.node_sizes.get(&node.id) {
+                let (along, across) = match orientation {
+                    TreeOrientation::TopDown | TreeOrientation::BottomUp => (width, height),
+                    TreeOrientation::LeftRight | TreeOrientation::RightLeft => (height, width),
+                };
+                node.width = node.width.max(along);
+                node.height = node.height.max(across);
+            }
         }
+    }
 
-        visited.remove(&node_id);
-
-        Ok(TreeNode {
-            id: node_id,
-            children,
-            width: self.node_size,
-            height: self.node_size, // Currently used for node spacing calculations
-            x_offset: 0.0,
-            y_offset: 0.0,
-            subtree_width: 0.0,
-        })
+    /// Largest node extent across levels, used to keep levels apart
+    fn max_level_extent(&self, tree: &[TreeNode]) -> f64 {
+        tree.iter().map(|node| node.height).fold(0.0, f64::max)
     }
 
     /// Calculate subtree dimensions and positions
     fn calculate_tree_layout(
         &self,
-        tree: &mut TreeNode,
+        tree: &mut [TreeNode],
         horizontal_spacing: f64,
         vertical_spacing: f64,
     ) {
-        // Calculate layout for all children first (post-order traversal)
-        for child in &mut tree.children {
-            self.calculate_tree_layout(child, horizontal_spacing, vertical_spacing);
+        // Children come after their parent, so walking backwards lays out
+        // every subtree before the node that contains it (post-order)
+        for index in (0..tree.len()).rev() {
+            self.layout_children(tree, index, horizontal_spacing, vertical_spacing);
         }
+    }
 
-        if tree.children.is_empty() {
+    /// Size one node's subtree and place its children, whose subtrees are already laid out
+    fn layout_children(
+        &self,
+        tree: &mut [TreeNode],
+        index: usize,
+        horizontal_spacing: f64,
+        vertical_spacing: f64,
+    ) {
+        let children = std::mem::take(&mut tree[index].children);
+
+        if children.is_empty() {
             // Leaf node
-            tree.subtree_width = tree.width;
+            tree[index].subtree_width = tree[index].width;
             return;
         }
 
         // Calculate total width needed for all children
         let mut total_children_width = 0.0;
-        for (i, child) in tree.children.iter().enumerate() {
-            total_children_width += child.subtree_width;
+        for (i, &child) in children.iter().enumerate() {
+            total_children_width += tree[child].subtree_width;
             if i > 0 {
                 total_children_width += horizontal_spacing;
             }
         }
 
         // Set subtree width to maximum of node width and children width
-        tree.subtree_width = tree.width.max(total_children_width);
+        let subtree_width = tree[index].width.max(total_children_width);
+        tree[index].subtree_width = subtree_width;
 
         // Position children horizontally
-        let mut current_x = -(tree.subtree_width / 2.0);
+        let mut current_x = -(subtree_width / 2.0);
 
         // If children are narrower than parent, center them
-        if total_children_width < tree.subtree_width {
-            current_x += (tree.subtree_width - total_children_width) / 2.0;
+        if total_children_width < subtree_width {
+            current_x += (subtree_width - total_children_width) / 2.0;
         }
 
-        for child in &mut tree.children {
+        for &child in &children {
+            let child = &mut tree[child];
             child.x_offset = current_x + child.subtree_width / 2.0;
             child.y_offset = vertical_spacing;
             current_x += child.subtree_width + horizontal_spacing;
         }
 
+        tree[index].children = children;
+
         // Balance subtrees if enabled
-        if self.balance_subtrees && tree.children.len() > 1 {
-            self.balance_tree_positions(tree);
+        if self.balance_subtrees && tree[index].children.len() > 1 {
+            self.balance_tree_positions(tree, index);
         }
     }
 
     /// Balance positions of child subtrees for better visual distribution
-    fn balance_tree_positions(&self, tree: &mut TreeNode) {
-        if tree.children.len() <= 1 {
+    fn balance_tree_positions(&self, tree: &mut [TreeNode], index: usize) {
+        let children = tree[index].children.clone();
+        if children.len() <= 1 {
             return;
         }
 
@@ -250,10 +291,10 @@ impl TreeLayoutEngine {
         let mut total_weight = 0.0;
         let mut weighted_position = 0.0;
 
-        for child in &tree.children {
-            let weight = self.calculate_subtree_weight(child);
+        for &child in &children {
+            let weight = self.calculate_subtree_weight(tree, child);
             total_weight += weight;
-            weighted_position += child.x_offset * weight;
+            weighted_position += tree[child].x_offset * weight;
         }
 
         if total_weight > 0.0 {
@@ -261,18 +302,20 @@ impl TreeLayoutEngine {
 
             // Adjust positions to center the subtree
             let adjustment = -center_of_mass;
-            for child in &mut tree.children {
-                child.x_offset += adjustment;
+            for &child in &children {
+                tree[child].x_offset += adjustment;
             }
         }
     }
 
     /// Calculate weight of a subtree for balancing
-    fn calculate_subtree_weight(&self, tree: &TreeNode) -> f64 {
-        let mut weight = 1.0; // Weight of the node itself
+    fn calculate_subtree_weight(&self, tree: &[TreeNode], index: usize) -> f64 {
+        let mut weight = 0.0;
+        let mut stack = vec![index];
 
-        for child in &tree.children {
-            weight += self.calculate_subtree_weight(child);
+        while let Some(current) = stack.pop() {
+            weight += 1.0; // Weight of the node itself
+            stack.extend(tree[current].children.iter().copied());
         }
 
         weight
@@ -281,30 +324,24 @@ impl TreeLayoutEngine {
     /// Convert tree positions to absolute coordinates
     fn tree_to_positions(
         &self,
-        tree: &TreeNode,
+        tree: &[TreeNode],
         positions: &mut HashMap<NodeId, Point>,
-        base_x: f64,
-        base_y: f64,
         orientation: TreeOrientation,
     ) {
-        // Calculate absolute position for this node
-        let (x, y) = self.transform_coordinates(
-            base_x + tree.x_offset,
-            base_y + tree.y_offset,
-            orientation,
-        );
+        // Offsets are relative to the parent, which is always placed first
+        let mut bases = vec![(0.0, 0.0); tree.len()];
+
+        for (index, node) in tree.iter().enumerate() {
+            let (base_x, base_y) = bases[index];
+            let (tree_x, tree_y) = (base_x + node.x_offset, base_y + node.y_offset);
 
-        positions.insert(tree.id, Point::new(x, y));
+            // Calculate absolute position for this node
+            let (x, y) = self.transform_coordinates(tree_x, tree_y, orientation);
+            positions.insert(node.id, Point::new(x, y));
 
-        // Process children
-        for child in &tree.children {
-            self.tree_to_positions(
-                child,
-                positions,
-                base_x + tree.x_offset,
-                base_y + tree.y_offset,
-                orientation,
-            );
+            for &child in &node.children {
+                bases[child] = (tree_x, tree_y);
+            }
         }
     }
 
@@ -412,10 +449,9 @@ impl TreeLayoutEngine {
             }
         }
     }
-}
 
-impl LayoutEngine for TreeLayoutEngine {
-    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+    /// Position every node; timed by [`LayoutEngine::calculate_layout`]
+    fn compute_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
         self.validate_config(config)?;
 
         if graph.node_count() == 0 {
@@ -425,6 +461,7 @@ impl LayoutEngine for TreeLayoutEngine {
                 converged: true,
                 iterations: 1,
                 energy: 0.0,
+                node_bounds: None,
             });
         }
 
@@ -441,12 +478,19 @@ impl LayoutEngine for TreeLayoutEngine {
         // Build tree structure
         let mut tree = self.build_tree(graph, root_id)?;
 
+        // Make room for label boxes
+        let mut vertical_spacing = vertical_spacing;
+        if !config.node_sizes.is_empty() {
+            self.apply_node_sizes(&mut tree, config, orientation);
+            vertical_spacing = vertical_spacing.max(self.max_level_extent(&tree) + config.label_gap());
+        }
+
         // Calculate tree layout
         self.calculate_tree_layout(&mut tree, horizontal_spacing, vertical_spacing);
 
         // Convert to absolute positions
         let mut positions = HashMap::new();
-        self.tree_to_positions(&mut tree, &mut positions, 0.0, 0.0, orientation);
+        self.tree_to_positions(&tree, &mut positions, orientation);
 
         // Apply distance constraints if needed
         if config.min_distance > 0.0 {
@@ -456,6 +500,16 @@ impl LayoutEngine for TreeLayoutEngine {
         // Scale and center within canvas
         self.scale_and_center_layout(&mut positions, config);
 
+        // Pinned nodes keep their coordinates and take their subtrees along
+        let pinned = config.pinned_positions(graph);
+        utils::anchor_pinned(graph, &mut positions, &pinned);
+
+        // Label boxes keep their size, so separate them after scaling, moving only unpinned nodes
+        if !config.node_sizes.is_empty() {
+            let fixed: HashSet<NodeId> = pinned.keys().copied().collect();
+            utils::resolve_overlaps_with_fixed(&mut positions, &config.node_sizes, config.label_gap(), &fixed);
+        }
+
         // Calculate final bounds and energy
         let all_points: Vec<Point> = positions.values().copied().collect();
         let bounds = LayoutBounds::from_points(&all_points);
@@ -466,14 +520,22 @@ impl LayoutEngine for TreeLayoutEngine {
             total_energy = bounds.width() * bounds.height();
         }
 
+        let node_bounds = config.node_bounds(&positions);
         Ok(LayoutResult {
             positions,
             bounds,
             converged: true,
             iterations: 1,
             energy: total_energy,
+            node_bounds,
         })
     }
+}
+
+impl LayoutEngine for TreeLayoutEngine {
+    fn calculate_layout(&self, graph: &Graph, config: &LayoutConfig) -> MindmapResult<LayoutResult> {
+        timed_layout!("tree", self.compute_layout(graph, config))
+    }
 
     fn layout_type(&self) -> LayoutType {
         LayoutType::Tree
@@ -737,12 +799,13 @@ mod tests {
         assert!(result.is_ok());
 
         let tree = result.unwrap();
-        assert_eq!(tree.id, root_id);
-        assert_eq!(tree.children.len(), 3); // 3 children
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree[0].id, root_id);
+        assert_eq!(tree[0].children.len(), 3); // 3 children
 
         // Each child should have 2 grandchildren
-        for child in &tree.children {
-            assert_eq!(child.children.len(), 2);
+        for &child in &tree[0].children {
+            assert_eq!(tree[child].children.len(), 2);
         }
     }
 
@@ -755,7 +818,7 @@ mod tests {
         let root_id = root_nodes[0].id;
 
         let tree = engine.build_tree(&graph, root_id).unwrap();
-        let weight = engine.calculate_subtree_weight(&tree);
+        let weight = engine.calculate_subtree_weight(&tree, 0);
 
         // Total nodes: 1 root + 3 children + 6 grandchildren = 10
         assert_eq!(weight, 10.0);
@@ -11,7 +11,8 @@
 //! - **Persistence**: SQLite-based storage with auto-save
 //! - **Import/Export**: OPML and Markdown format support
 //! - **FFI Interface**: Flutter-Rust bridge for cross-platform UI
-//! - **Cross-Platform**: macOS, Windows, and iOS support
+//! - **Cross-Platform**: macOS, Windows, and iOS support, plus the browser via
+//!   `wasm32-unknown-unknown` with `--no-default-features --features wasm`
 
 // Platform-specific conditional compilation
 #[cfg(target_os = "macos")]
@@ -38,6 +39,9 @@ pub mod layout;
 
 pub mod search;
 
+// Structured events for host telemetry
+pub mod events;
+
 // Data persistence and I/O
 #[cfg(feature = "sqlite")]
 pub mod persistence;
@@ -69,13 +73,35 @@ pub const PLATFORM: &str = {
     { "iOS" }
     #[cfg(target_os = "linux")]
     { "Linux" }
-    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios", target_os = "linux")))]
+    #[cfg(target_arch = "wasm32")]
+    { "Web" }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios", target_os = "linux", target_arch = "wasm32")))]
     { "Unknown" }
 };
 
+/// Outcome of the first call to [`init`], shared by every later call
+static INIT_RESULT: std::sync::OnceLock<Result<(), String>> = std::sync::OnceLock::new();
+
 /// Initialize the mindmap core engine
+///
+/// Initialization runs once per process; repeated calls are safe and return
+/// the outcome of the first call.
 pub fn init() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init();
+    INIT_RESULT
+        .get_or_init(|| initialize_engine().map_err(|e| e.to_string()))
+        .clone()
+        .map_err(Into::into)
+}
+
+/// Check if the engine has been initialized
+pub fn is_initialized() -> bool {
+    matches!(INIT_RESULT.get(), Some(Ok(())))
+}
+
+/// Perform one-time engine initialization
+fn initialize_engine() -> Result<(), Box<dyn std::error::Error>> {
+    // The host application may already have installed a logger
+    let _ = env_logger::try_init();
     log::info!("Mindmap Core Engine v{} initialized on {}", VERSION, PLATFORM);
 
     // Platform-specific initialization
@@ -121,6 +147,9 @@ fn get_enabled_features() -> Vec<&'static str> {
     #[cfg(feature = "debug-ui")]
     features.push("debug-ui");
 
+    #[cfg(feature = "wasm")]
+    features.push("wasm");
+
     #[cfg(feature = "flutter_rust_bridge_feature")]
     features.push("flutter_rust_bridge");
 
@@ -158,6 +187,13 @@ mod tests {
         assert!(init().is_ok());
     }
 
+    #[test]
+    fn test_init_is_idempotent() {
+        assert!(init().is_ok());
+        assert!(init().is_ok());
+        assert!(is_initialized());
+    }
+
     #[test]
     fn test_version() {
         assert!(!VERSION.is_empty());
@@ -167,7 +203,7 @@ mod tests {
     #[test]
     fn test_platform() {
         assert!(!PLATFORM.is_empty());
-        assert!(["macOS", "Windows", "iOS", "Linux", "Unknown"].contains(&PLATFORM));
+        assert!(["macOS", "Windows", "iOS", "Linux", "Web", "Unknown"].contains(&PLATFORM));
     }
 
     #[test]
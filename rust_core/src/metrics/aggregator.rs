@@ -4,7 +4,8 @@
 //! and analyzing metric data to generate meaningful insights.
 
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use crate::types::clock::SystemTime;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use super::{MetricEntry, MetricId, MetricValue, MetricCategory, TimingStats, CategorySummary};
 
@@ -282,13 +283,19 @@ impl MetricsAggregator {
         }
 
         let timing_summaries = timing_metrics.into_iter()
-            .map(|(name, durations)| (name, super::TimingSummary {
-                count: durations.len(),
-                total_duration: durations.iter().sum(),
-                average_duration: if durations.is_empty() { Duration::ZERO } else { durations.iter().sum::<Duration>() / durations.len() as u32 },
-                min_duration: durations.iter().min().copied().unwrap_or(Duration::ZERO),
-                max_duration: durations.iter().max().copied().unwrap_or(Duration::ZERO),
-            }))
+            .map(|(name, mut durations)| {
+                durations.sort();
+                (name, super::TimingSummary {
+                    count: durations.len(),
+                    total_duration: durations.iter().sum(),
+                    average_duration: if durations.is_empty() { Duration::ZERO } else { durations.iter().sum::<Duration>() / durations.len() as u32 },
+                    min_duration: durations.first().copied().unwrap_or(Duration::ZERO),
+                    max_duration: durations.last().copied().unwrap_or(Duration::ZERO),
+                    p50_duration: percentile_of_sorted(&durations, 50.0),
+                    p95_duration: percentile_of_sorted(&durations, 95.0),
+                    p99_duration: percentile_of_sorted(&durations, 99.0),
+                })
+            })
             .collect();
 
         CategorySummary {
@@ -300,6 +307,18 @@ impl MetricsAggregator {
     }
 }
 
+/// The `percentile`th value of sorted durations, or zero if there are none
+///
+/// Picks the value at rank `(n - 1) * percentile / 100`, rounded down, like
+/// [`MetricsAggregator::percentiles`].
+fn percentile_of_sorted(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = ((sorted.len() - 1) as f64 * percentile / 100.0) as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
 impl Default for MetricsAggregator {
     fn default() -> Self {
         Self::new()
@@ -659,4 +678,31 @@ mod tests {
         assert!(percentiles.contains_key("p95"));
         assert!(percentiles.contains_key("p99"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_timing_summary_percentiles() {
+        let mut aggregator = MetricsAggregator::new();
+        // 1ms to 100ms, added out of order
+        let entries = (1..=100u64).rev().map(|ms| {
+            MetricEntry::new(
+                MetricId::new(MetricCategory::Layout, "tree"),
+                MetricValue::Duration(Duration::from_millis(ms)),
+            )
+        }).collect();
+        aggregator.add_entries(entries);
+
+        let categories = aggregator.by_category();
+        let tree = &categories[&MetricCategory::Layout].timing_metrics["tree"];
+        assert_eq!(tree.count, 100);
+        assert_eq!(tree.min_duration, Duration::from_millis(1));
+        assert_eq!(tree.max_duration, Duration::from_millis(100));
+        assert_eq!(tree.p50_duration, Duration::from_millis(50));
+        assert_eq!(tree.p95_duration, Duration::from_millis(95));
+        assert_eq!(tree.p99_duration, Duration::from_millis(99));
+
+        // A single sample is every percentile; no samples are zero
+        let single = [Duration::from_millis(7)];
+        assert_eq!(percentile_of_sorted(&single, 99.0), Duration::from_millis(7));
+        assert_eq!(percentile_of_sorted(&[], 50.0), Duration::ZERO);
+    }
+}
@@ -6,9 +6,10 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 //
-use std::time::{SystemTime, Duration, Instant};
+use crate::types::clock::{Instant, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
-use super::{MetricEntry, MetricId, MetricValue};
+use super::{MetricEntry, MetricId, MetricValue, RegistryLink};
 
 /// Thread-safe counter for tracking operation counts
 #[derive(Debug)]
@@ -16,15 +17,22 @@ pub struct Counter {
     id: MetricId,
     value: AtomicU64,
     created_at: SystemTime,
+    registry: RegistryLink,
 }
 
 impl Counter {
-    /// Create a new counter
+    /// Create a new counter reporting into the global registry
     pub fn new(id: MetricId) -> Self {
+        Self::linked(id, RegistryLink::default())
+    }
+
+    /// Create a new counter reporting into a specific registry
+    pub(crate) fn linked(id: MetricId, registry: RegistryLink) -> Self {
         Self {
             id,
             value: AtomicU64::new(0),
             created_at: SystemTime::now(),
+            registry,
         }
     }
 
@@ -43,9 +51,8 @@ impl Counter {
         let old_value = self.value.fetch_add(value, Ordering::Relaxed);
 
         // Record the metric
-        let registry = super::registry();
         let entry = MetricEntry::new(self.id.clone(), MetricValue::Count(old_value + value));
-        registry.record(entry);
+        self.registry.record(entry);
     }
 
     /// Subtract a value from the counter
@@ -54,9 +61,8 @@ impl Counter {
 
         // Record the metric (ensure we don't go below 0)
         let new_value = if old_value >= value { old_value - value } else { 0 };
-        let registry = super::registry();
         let entry = MetricEntry::new(self.id.clone(), MetricValue::Count(new_value));
-        registry.record(entry);
+        self.registry.record(entry);
     }
 
     /// Set the counter to a specific value
@@ -64,9 +70,8 @@ impl Counter {
         self.value.store(value, Ordering::Relaxed);
 
         // Record the metric
-        let registry = super::registry();
         let entry = MetricEntry::new(self.id.clone(), MetricValue::Count(value));
-        registry.record(entry);
+        self.registry.record(entry);
     }
 
     /// Get the current value
@@ -111,8 +116,13 @@ pub struct CounterRegistry {
 impl CounterRegistry {
     /// Create a new counter registry
     pub fn new(id: MetricId) -> Self {
+        Self::linked(id, RegistryLink::default())
+    }
+
+    /// Create a new counter registry whose counter reports into a specific registry
+    pub(crate) fn linked(id: MetricId, registry: RegistryLink) -> Self {
         Self {
-            counter: Arc::new(Counter::new(id.clone())),
+            counter: Arc::new(Counter::linked(id.clone(), registry)),
             id,
             snapshots: Arc::new(Mutex::new(Vec::new())),
         }
@@ -187,7 +197,7 @@ impl CounterSnapshot {
     /// Get timestamp as milliseconds since epoch
     pub fn timestamp_millis(&self) -> u64 {
         self.timestamp
-            .duration_since(SystemTime::UNIX_EPOCH)
+            .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() as u64
     }
@@ -325,6 +335,9 @@ impl RateLimiter {
     }
 
     /// Wait until tokens are available (blocking)
+    ///
+    /// Not available on wasm, where the main thread cannot sleep.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn consume(&self, tokens: u64) {
         while !self.try_consume(tokens) {
             std::thread::sleep(Duration::from_millis(10));
@@ -5,9 +5,10 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, Duration};
+use crate::types::clock::SystemTime;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
-use super::{MetricEntry, MetricId, MetricValue};
+use super::{MetricEntry, MetricId, MetricValue, RegistryLink};
 
 /// Memory tracker for monitoring allocation patterns
 #[derive(Debug)]
@@ -16,28 +17,40 @@ pub struct MemoryTracker {
     total_allocated: Mutex<u64>,
     total_deallocated: Mutex<u64>,
     peak_usage: Mutex<u64>,
+    /// Highest usage seen for each metric since the last reset
+    metric_peaks: Mutex<HashMap<MetricId, u64>>,
     snapshots: Mutex<Vec<MemorySnapshot>>,
+    registry: RegistryLink,
 }
 
 impl MemoryTracker {
-    /// Create a new memory tracker
+    /// Create a new memory tracker reporting into the global registry
     pub fn new() -> Self {
+        Self::linked(RegistryLink::default())
+    }
+
+    /// Create a new memory tracker reporting into a specific registry
+    pub(crate) fn linked(registry: RegistryLink) -> Self {
         Self {
             allocations: Mutex::new(HashMap::new()),
             total_allocated: Mutex::new(0),
             total_deallocated: Mutex::new(0),
             peak_usage: Mutex::new(0),
+            metric_peaks: Mutex::new(HashMap::new()),
             snapshots: Mutex::new(Vec::new()),
+            registry,
         }
     }
 
     /// Record a memory allocation
     pub fn allocate(&self, id: MetricId, bytes: u64) {
+        let mut usage = bytes;
         if let Ok(mut allocations) = self.allocations.lock() {
             let allocation = allocations.entry(id.clone()).or_insert_with(|| {
                 MemoryAllocation::new(id.clone())
             });
             allocation.allocate(bytes);
+            usage = allocation.current_usage();
         }
 
         if let Ok(mut total) = self.total_allocated.lock() {
@@ -45,6 +58,7 @@ impl MemoryTracker {
         }
 
         self.update_peak_usage();
+        self.update_metric_peak(&id, usage);
         self.record_metric(id, bytes);
     }
 
@@ -62,8 +76,29 @@ impl MemoryTracker {
     }
 
     /// Record memory usage for a metric
+    ///
+    /// The last value written is the metric's current usage: recording 100,
+    /// then 50, then 200 bytes leaves a current usage of 200, not 350. The
+    /// highest value written is kept as the metric's peak.
     pub fn record(&self, id: MetricId, bytes: u64) {
-        self.allocate(id, bytes);
+        let current = self.usage_for(&id);
+        if bytes >= current {
+            let grown = bytes - current;
+            if let Ok(mut allocations) = self.allocations.lock() {
+                allocations.entry(id.clone())
+                    .or_insert_with(|| MemoryAllocation::new(id.clone()))
+                    .allocate(grown);
+            }
+            if let Ok(mut total) = self.total_allocated.lock() {
+                *total += grown;
+            }
+        } else {
+            self.deallocate(id.clone(), current - bytes);
+        }
+
+        self.update_peak_usage();
+        self.update_metric_peak(&id, bytes);
+        self.record_metric(id, bytes);
     }
 
     /// Get current memory usage for a specific metric
@@ -109,8 +144,8 @@ impl MemoryTracker {
         self.total_allocated() - self.total_deallocated()
     }
 
-    /// Get peak memory usage
-    pub fn peak_usage(&self) -> u64 {
+    /// Get peak total memory usage
+    pub fn peak_total_usage(&self) -> u64 {
         if let Ok(peak) = self.peak_usage.lock() {
             *peak
         } else {
@@ -118,6 +153,24 @@ impl MemoryTracker {
         }
     }
 
+    /// Get the highest usage seen for each metric since the last reset
+    pub fn peak_usage(&self) -> HashMap<MetricId, u64> {
+        if let Ok(peaks) = self.metric_peaks.lock() {
+            peaks.clone()
+        } else {
+            HashMap::new()
+        }
+    }
+
+    /// Get the highest usage seen for a specific metric
+    pub fn peak_usage_for(&self, id: &MetricId) -> u64 {
+        if let Ok(peaks) = self.metric_peaks.lock() {
+            peaks.get(id).copied().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
     /// Take a snapshot of current memory state
     pub fn snapshot(&self) -> MemorySnapshot {
         let snapshot = MemorySnapshot {
@@ -125,7 +178,7 @@ impl MemoryTracker {
             total_allocated: self.total_allocated(),
             total_deallocated: self.total_deallocated(),
             current_usage: self.current_total_usage(),
-            peak_usage: self.peak_usage(),
+            peak_usage: self.peak_total_usage(),
             allocations: self.current_usage(),
         };
 
@@ -154,7 +207,7 @@ impl MemoryTracker {
     /// Get memory statistics
     pub fn stats(&self) -> MemoryStats {
         let snapshots = self.snapshots();
-        MemoryStats::from_snapshots(&snapshots, self.current_total_usage(), self.peak_usage())
+        MemoryStats::from_snapshots(&snapshots, self.current_total_usage(), self.peak_total_usage())
     }
 
     /// Detect potential memory leaks
@@ -193,6 +246,9 @@ impl MemoryTracker {
         if let Ok(mut peak) = self.peak_usage.lock() {
             *peak = 0;
         }
+        if let Ok(mut peaks) = self.metric_peaks.lock() {
+            peaks.clear();
+        }
         if let Ok(mut snapshots) = self.snapshots.lock() {
             snapshots.clear();
         }
@@ -208,11 +264,18 @@ impl MemoryTracker {
         }
     }
 
+    /// Raise a metric's peak to `usage` if it is higher
+    fn update_metric_peak(&self, id: &MetricId, usage: u64) {
+        if let Ok(mut peaks) = self.metric_peaks.lock() {
+            let peak = peaks.entry(id.clone()).or_insert(0);
+            *peak = (*peak).max(usage);
+        }
+    }
+
     /// Record memory metric to registry
     fn record_metric(&self, id: MetricId, bytes: u64) {
-        let registry = super::registry();
         let entry = MetricEntry::new(id, MetricValue::Bytes(bytes));
-        registry.record(entry);
+        self.registry.record(entry);
     }
 }
 
@@ -603,4 +666,29 @@ mod tests {
         assert_eq!(stats.growth_rate_per_minute, 500.0); // 500 bytes per minute
         assert_eq!(stats.allocation_efficiency, 0.25); // 500/2000
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_record_keeps_last_value_and_peak() {
+        let tracker = MemoryTracker::new();
+        let id = MetricId::new(MetricCategory::Memory, "layout_buffer");
+
+        tracker.record(id.clone(), 100);
+        tracker.record(id.clone(), 50);
+        assert_eq!(tracker.usage_for(&id), 50);
+        assert_eq!(tracker.peak_usage_for(&id), 100);
+
+        tracker.record(id.clone(), 200);
+        assert_eq!(tracker.usage_for(&id), 200);
+        assert_eq!(tracker.peak_usage()[&id], 200);
+        assert_eq!(tracker.current_total_usage(), 200);
+
+        // Allocations raise the peak too, and reset forgets it
+        let other = MetricId::new(MetricCategory::Memory, "index");
+        tracker.allocate(other.clone(), 300);
+        tracker.deallocate(other.clone(), 300);
+        assert_eq!(tracker.peak_usage_for(&other), 300);
+
+        tracker.reset();
+        assert!(tracker.peak_usage().is_empty());
+    }
+}
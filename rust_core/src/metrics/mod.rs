@@ -5,8 +5,10 @@
 //! counting, and aggregated reporting functionality.
 
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, RwLock};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use crate::types::clock::{SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use crate::types::{MindmapResult, MindmapError};
 
@@ -34,6 +36,45 @@ pub fn registry() -> &'static MetricsRegistry {
     METRICS_REGISTRY.get_or_init(|| MetricsRegistry::new())
 }
 
+thread_local! {
+    /// Registries installed by [`scoped`] on this thread, innermost last
+    static SCOPED_REGISTRIES: std::cell::RefCell<Vec<MetricsRegistry>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Get the registry operations on this thread should record into
+///
+/// This is the innermost registry installed with [`scoped`], or the global
+/// [`registry`] when none is installed.
+pub fn current() -> MetricsRegistry {
+    SCOPED_REGISTRIES
+        .with(|scoped| scoped.borrow().last().cloned())
+        .unwrap_or_else(|| registry().clone())
+}
+
+/// Run `func` with `registry` as the [`current`] registry on this thread
+///
+/// Graph, layout and search operations called from `func` record their
+/// metrics into `registry` instead of the global one.
+pub fn scoped<F, R>(registry: &MetricsRegistry, func: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    /// Pops the installed registry even if `func` panics
+    struct ScopeGuard;
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            SCOPED_REGISTRIES.with(|scoped| {
+                scoped.borrow_mut().pop();
+            });
+        }
+    }
+
+    SCOPED_REGISTRIES.with(|scoped| scoped.borrow_mut().push(registry.clone()));
+    let _guard = ScopeGuard;
+    func()
+}
+
 /// Initialize the metrics system
 pub fn init() -> MindmapResult<()> {
     let registry = registry();
@@ -171,6 +212,14 @@ impl MetricValue {
             _ => None,
         }
     }
+
+    /// Whether entries with this value are subject to `MetricsConfig::sample_rate`
+    ///
+    /// Counts and byte sizes are running totals rather than events, so they
+    /// are always kept; dropping them would leave stale totals behind.
+    pub fn is_sampled(&self) -> bool {
+        !matches!(self, MetricValue::Count(_) | MetricValue::Bytes(_))
+    }
 }
 
 /// A single metric data point
@@ -212,29 +261,110 @@ impl MetricEntry {
 }
 
 /// Central registry for all metrics
-#[derive(Debug)]
+///
+/// A registry is a cheap handle: clones share the same underlying storage,
+/// while registries created with [`MetricsRegistry::new`] are fully independent
+/// of each other and of the global [`registry`].
+#[derive(Debug, Clone)]
 pub struct MetricsRegistry {
-    entries: Arc<RwLock<Vec<MetricEntry>>>,
-    timers: Arc<Mutex<HashMap<MetricId, TimerRegistry>>>,
-    counters: Arc<Mutex<HashMap<MetricId, CounterRegistry>>>,
-    memory_tracker: Arc<Mutex<MemoryTracker>>,
-    config: Arc<RwLock<MetricsConfig>>,
+    state: Arc<RegistryState>,
+}
+
+/// Storage shared by all handles to one registry
+#[derive(Debug)]
+struct RegistryState {
+    entries: RwLock<Vec<MetricEntry>>,
+    timers: Mutex<HashMap<MetricId, TimerRegistry>>,
+    counters: Mutex<HashMap<MetricId, CounterRegistry>>,
+    memory_tracker: Mutex<MemoryTracker>,
+    config: RwLock<MetricsConfig>,
+    sampler: Sampler,
+}
+
+/// Lock-free SplitMix64 generator that decides which entries are sampled
+#[derive(Debug)]
+struct Sampler(AtomicU64);
+
+impl Sampler {
+    /// Create a generator seeded from the clock
+    fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        Self(AtomicU64::new(seed))
+    }
+
+    /// Next value, uniformly distributed in `[0, 1)`
+    fn next_unit(&self) -> f64 {
+        let mut z = self.0.fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Link from a counter or tracker back to the registry it reports into
+///
+/// The link is weak so that components owned by a registry do not keep it
+/// alive. An unlinked component reports into the global registry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RegistryLink(Option<Weak<RegistryState>>);
+
+impl RegistryLink {
+    /// Link to a specific registry
+    fn to(registry: &MetricsRegistry) -> Self {
+        Self(Some(Arc::downgrade(&registry.state)))
+    }
+
+    /// Record an entry into the linked registry
+    pub(crate) fn record(&self, entry: MetricEntry) {
+        match &self.0 {
+            Some(state) => {
+                if let Some(state) = state.upgrade() {
+                    MetricsRegistry { state }.record(entry);
+                }
+            }
+            None => registry().record(entry),
+        }
+    }
 }
 
 impl MetricsRegistry {
+    /// Create a new registry independent of all others
     pub fn new() -> Self {
-        Self {
-            entries: Arc::new(RwLock::new(Vec::new())),
-            timers: Arc::new(Mutex::new(HashMap::new())),
-            counters: Arc::new(Mutex::new(HashMap::new())),
-            memory_tracker: Arc::new(Mutex::new(MemoryTracker::new())),
-            config: Arc::new(RwLock::new(MetricsConfig::default())),
-        }
+        let state = Arc::new_cyclic(|weak: &Weak<RegistryState>| RegistryState {
+            entries: RwLock::new(Vec::new()),
+            timers: Mutex::new(HashMap::new()),
+            counters: Mutex::new(HashMap::new()),
+            memory_tracker: Mutex::new(MemoryTracker::linked(RegistryLink(Some(weak.clone())))),
+            config: RwLock::new(MetricsConfig::default()),
+            sampler: Sampler::new(),
+        });
+
+        Self { state }
+    }
+
+    /// Check if two handles refer to the same registry
+    pub fn same_registry(&self, other: &MetricsRegistry) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+
+    /// Whether entries in `category` are currently collected
+    ///
+    /// Lets callers skip work, such as reading the clock, that would only be
+    /// thrown away by `record`.
+    pub fn is_recording(&self, category: MetricCategory) -> bool {
+        self.state.config.read()
+            .map(|config| config.enabled && config.enabled_categories.contains(&category))
+            .unwrap_or(false)
     }
 
     /// Record a metric entry
     pub fn record(&self, entry: MetricEntry) {
-        if let Ok(config) = self.config.read() {
+        if let Ok(config) = self.state.config.read() {
             if !config.enabled {
                 return;
             }
@@ -243,16 +373,23 @@ impl MetricsRegistry {
             if !config.enabled_categories.contains(&entry.id.category) {
                 return;
             }
+
+            // Keep roughly `sample_rate` of the sampled entries
+            if config.sample_rate < 1.0
+                && entry.value.is_sampled()
+                && self.state.sampler.next_unit() >= config.sample_rate
+            {
+                return;
+            }
         }
 
-        if let Ok(mut entries) = self.entries.write() {
+        if let Ok(mut entries) = self.state.entries.write() {
             entries.push(entry);
 
             // Maintain maximum entries limit
-            if let Ok(config) = self.config.read() {
+            if let Ok(config) = self.state.config.read() {
                 if entries.len() > config.max_entries {
-                    let remove_count = entries.len() - config.max_entries;
-                    entries.drain(0..remove_count);
+                    evict_entries(&mut entries, &config);
                 }
             }
         }
@@ -260,41 +397,50 @@ impl MetricsRegistry {
 
     /// Get timer registry for a metric
     pub fn timer(&self, id: MetricId) -> MindmapResult<TimerHandle> {
-        let mut timers = self.timers.lock()
+        let mut timers = self.state.timers.lock()
             .map_err(|_| MindmapError::MetricsError("Failed to acquire timer lock".to_string()))?;
 
         let timer_registry = timers.entry(id.clone()).or_insert_with(|| TimerRegistry::new(id.clone()));
-        Ok(timer_registry.start())
+        Ok(timer_registry.start_for(self.clone()))
     }
 
     /// Get counter for a metric
     pub fn counter(&self, id: MetricId) -> MindmapResult<Arc<Counter>> {
-        let mut counters = self.counters.lock()
+        let mut counters = self.state.counters.lock()
             .map_err(|_| MindmapError::MetricsError("Failed to acquire counter lock".to_string()))?;
 
-        let counter_registry = counters.entry(id.clone()).or_insert_with(|| CounterRegistry::new(id.clone()));
+        let counter_registry = counters.entry(id.clone()).or_insert_with(|| CounterRegistry::linked(id.clone(), RegistryLink::to(self)));
         Ok(counter_registry.get_counter())
     }
 
     /// Record memory usage
     pub fn record_memory(&self, id: MetricId, bytes: u64) {
-        if let Ok(tracker) = self.memory_tracker.lock() {
+        if let Ok(tracker) = self.state.memory_tracker.lock() {
             tracker.record(id, bytes);
         }
     }
 
     /// Get current memory usage
     pub fn memory_usage(&self) -> HashMap<MetricId, u64> {
-        if let Ok(tracker) = self.memory_tracker.lock() {
+        if let Ok(tracker) = self.state.memory_tracker.lock() {
             tracker.current_usage()
         } else {
             HashMap::new()
         }
     }
 
+    /// Get the highest memory usage seen for each metric since the last reset
+    pub fn memory_peaks(&self) -> HashMap<MetricId, u64> {
+        if let Ok(tracker) = self.state.memory_tracker.lock() {
+            tracker.peak_usage()
+        } else {
+            HashMap::new()
+        }
+    }
+
     /// Get all recorded entries
     pub fn entries(&self) -> Vec<MetricEntry> {
-        if let Ok(entries) = self.entries.read() {
+        if let Ok(entries) = self.state.entries.read() {
             entries.clone()
         } else {
             Vec::new()
@@ -303,7 +449,7 @@ impl MetricsRegistry {
 
     /// Get entries for a specific category
     pub fn entries_for_category(&self, category: MetricCategory) -> Vec<MetricEntry> {
-        if let Ok(entries) = self.entries.read() {
+        if let Ok(entries) = self.state.entries.read() {
             entries.iter()
                 .filter(|entry| entry.id.category == category)
                 .cloned()
@@ -315,7 +461,7 @@ impl MetricsRegistry {
 
     /// Get entries for a specific metric ID
     pub fn entries_for_metric(&self, id: &MetricId) -> Vec<MetricEntry> {
-        if let Ok(entries) = self.entries.read() {
+        if let Ok(entries) = self.state.entries.read() {
             entries.iter()
                 .filter(|entry| &entry.id == id)
                 .cloned()
@@ -327,30 +473,30 @@ impl MetricsRegistry {
 
     /// Reset all metrics
     pub fn reset(&self) {
-        if let Ok(mut entries) = self.entries.write() {
+        if let Ok(mut entries) = self.state.entries.write() {
             entries.clear();
         }
-        if let Ok(mut timers) = self.timers.lock() {
+        if let Ok(mut timers) = self.state.timers.lock() {
             timers.clear();
         }
-        if let Ok(mut counters) = self.counters.lock() {
+        if let Ok(mut counters) = self.state.counters.lock() {
             counters.clear();
         }
-        if let Ok(tracker) = self.memory_tracker.lock() {
+        if let Ok(tracker) = self.state.memory_tracker.lock() {
             tracker.reset();
         }
     }
 
     /// Update configuration
     pub fn configure(&self, config: MetricsConfig) {
-        if let Ok(mut current_config) = self.config.write() {
+        if let Ok(mut current_config) = self.state.config.write() {
             *current_config = config;
         }
     }
 
     /// Get current configuration
     pub fn config(&self) -> MetricsConfig {
-        if let Ok(config) = self.config.read() {
+        if let Ok(config) = self.state.config.read() {
             config.clone()
         } else {
             MetricsConfig::default()
@@ -361,6 +507,7 @@ impl MetricsRegistry {
     pub fn report(&self) -> MetricsReport {
         let entries = self.entries();
         let memory_usage = self.memory_usage();
+        let memory_peaks = self.memory_peaks();
 
         let mut aggregator = MetricsAggregator::new();
         aggregator.add_entries(entries);
@@ -370,6 +517,7 @@ impl MetricsRegistry {
             total_entries: aggregator.total_count(),
             categories: aggregator.by_category(),
             memory_usage,
+            memory_peaks,
             summary: aggregator.summary(),
         }
     }
@@ -381,6 +529,63 @@ impl Default for MetricsRegistry {
     }
 }
 
+/// Drop entries until at most `config.max_entries` remain
+fn evict_entries(entries: &mut Vec<MetricEntry>, config: &MetricsConfig) {
+    let excess = entries.len().saturating_sub(config.max_entries);
+    if excess == 0 {
+        return;
+    }
+
+    match config.eviction_policy {
+        EvictionPolicy::Fifo => {
+            entries.drain(0..excess);
+        }
+        EvictionPolicy::CategoryQuota => {
+            let mut counts: HashMap<MetricCategory, usize> = HashMap::new();
+            for entry in entries.iter() {
+                *counts.entry(entry.id.category).or_default() += 1;
+            }
+
+            // Take from the largest category, which is over its share whenever the
+            // buffer is full; ties go to the category with the oldest entry
+            let mut remove = vec![false; entries.len()];
+            for _ in 0..excess {
+                let Some(&largest) = counts.values().max() else {
+                    break;
+                };
+                let oldest = (0..entries.len())
+                    .find(|&index| !remove[index] && counts.get(&entries[index].id.category) == Some(&largest));
+                if let Some(index) = oldest {
+                    remove[index] = true;
+                    if let Some(count) = counts.get_mut(&entries[index].id.category) {
+                        *count -= 1;
+                    }
+                }
+            }
+
+            let mut index = 0;
+            entries.retain(|_| {
+                let keep = !remove[index];
+                index += 1;
+                keep
+            });
+        }
+    }
+}
+
+/// How the entries buffer makes room once `max_entries` is reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// Drop the oldest entries, whatever their category
+    #[default]
+    Fifo,
+    /// Drop the oldest entries of the category holding the most entries
+    ///
+    /// Every category keeps an equal share of the buffer, so rare metrics
+    /// survive floods of frequent ones.
+    CategoryQuota,
+}
+
 /// Configuration for the metrics system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsConfig {
@@ -388,9 +593,15 @@ pub struct MetricsConfig {
     pub enabled: bool,
     /// Maximum number of entries to keep in memory
     pub max_entries: usize,
+    /// Which entries are dropped once `max_entries` is reached
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
     /// Enabled metric categories
     pub enabled_categories: Vec<MetricCategory>,
-    /// Sample rate (0.0 to 1.0)
+    /// Fraction of timing and other event entries to keep (0.0 to 1.0)
+    ///
+    /// Each entry is kept at random with this probability. Counter values
+    /// and memory sizes are running totals and are never sampled out.
     pub sample_rate: f64,
     /// Whether to include stack traces for timing metrics
     pub include_stack_traces: bool,
@@ -401,6 +612,7 @@ impl Default for MetricsConfig {
         Self {
             enabled: true,
             max_entries: 10000,
+            eviction_policy: EvictionPolicy::Fifo,
             enabled_categories: vec![
                 MetricCategory::Graph,
                 MetricCategory::Layout,
@@ -422,7 +634,11 @@ pub struct MetricsReport {
     pub timestamp: SystemTime,
     pub total_entries: usize,
     pub categories: HashMap<MetricCategory, CategorySummary>,
+    /// Last recorded memory usage per metric
     pub memory_usage: HashMap<MetricId, u64>,
+    /// Highest memory usage per metric since the registry was last reset
+    #[serde(default)]
+    pub memory_peaks: HashMap<MetricId, u64>,
     pub summary: MetricsSummary,
 }
 
@@ -443,6 +659,15 @@ pub struct TimingSummary {
     pub average_duration: Duration,
     pub min_duration: Duration,
     pub max_duration: Duration,
+    /// Median duration
+    #[serde(default)]
+    pub p50_duration: Duration,
+    /// Duration 95% of the timings stay within
+    #[serde(default)]
+    pub p95_duration: Duration,
+    /// Duration 99% of the timings stay within
+    #[serde(default)]
+    pub p99_duration: Duration,
 }
 
 /// Overall metrics summary
@@ -461,7 +686,7 @@ pub struct MetricsSummary {
 macro_rules! time_operation {
     ($category:expr, $name:expr, $operation:expr) => {{
         let id = MetricId::new($category, $name);
-        let timer = $crate::metrics::registry().timer(id);
+        let timer = $crate::metrics::current().timer(id);
         let result = $operation;
         if let Ok(timer_handle) = timer {
             timer_handle.finish();
@@ -474,7 +699,7 @@ macro_rules! time_operation {
 macro_rules! count_operation {
     ($category:expr, $name:expr) => {{
         let id = MetricId::new($category, $name);
-        if let Ok(counter) = $crate::metrics::registry().counter(id) {
+        if let Ok(counter) = $crate::metrics::current().counter(id) {
             counter.increment();
         }
     }};
@@ -484,7 +709,7 @@ macro_rules! count_operation {
 macro_rules! record_memory {
     ($category:expr, $name:expr, $bytes:expr) => {{
         let id = MetricId::new($category, $name);
-        $crate::metrics::registry().record_memory(id, $bytes);
+        $crate::metrics::current().record_memory(id, $bytes);
     }};
 }
 
@@ -554,4 +779,94 @@ mod tests {
         registry.reset();
         assert_eq!(registry.entries().len(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_report_includes_memory_peaks() {
+        let registry = MetricsRegistry::new();
+        let id = MetricId::memory("node_cache");
+        for bytes in [100, 50, 200, 120] {
+            registry.record_memory(id.clone(), bytes);
+        }
+
+        let report = registry.report();
+        assert_eq!(report.memory_usage[&id], 120);
+        assert_eq!(report.memory_peaks[&id], 200);
+
+        registry.reset();
+        assert!(registry.report().memory_peaks.is_empty());
+    }
+
+    #[test]
+    fn test_registry_timer_records_into_owning_registry() {
+        let registry = MetricsRegistry::new();
+        let id = MetricId::application("owned_registry_timer");
+
+        registry.timer(id.clone()).unwrap().finish();
+
+        assert_eq!(registry.entries_for_metric(&id).len(), 1);
+        assert!(super::registry().entries_for_metric(&id).is_empty());
+
+        let handle = registry.clone();
+        assert!(handle.same_registry(&registry));
+        assert!(!MetricsRegistry::new().same_registry(&registry));
+    }
+
+    #[test]
+    fn test_category_quota_keeps_rare_entries() {
+        let flood = |policy: EvictionPolicy| {
+            let registry = MetricsRegistry::new();
+            registry.configure(MetricsConfig {
+                max_entries: 20,
+                eviction_policy: policy,
+                ..MetricsConfig::default()
+            });
+
+            registry.record(MetricEntry::new(MetricId::layout("radial"), MetricValue::Duration(Duration::from_millis(40))));
+            for i in 0..500 {
+                registry.record(MetricEntry::new(MetricId::graph("add_node"), MetricValue::Count(i)));
+            }
+            registry
+        };
+
+        let fifo = flood(EvictionPolicy::Fifo);
+        assert_eq!(fifo.entries().len(), 20);
+        assert!(fifo.entries_for_category(MetricCategory::Layout).is_empty());
+
+        let quota = flood(EvictionPolicy::CategoryQuota);
+        assert_eq!(quota.entries().len(), 20);
+        assert_eq!(quota.entries_for_category(MetricCategory::Layout).len(), 1);
+
+        // The newest graph entries are the ones kept
+        let graph_entries = quota.entries_for_category(MetricCategory::Graph);
+        assert_eq!(graph_entries.len(), 19);
+        assert_eq!(graph_entries.last().unwrap().value.as_count(), Some(499));
+        assert_eq!(graph_entries[0].value.as_count(), Some(481));
+    }
+
+    #[test]
+    fn test_sample_rate_keeps_a_fraction_of_entries() {
+        let registry = MetricsRegistry::new();
+        registry.configure(MetricsConfig {
+            max_entries: 20_000,
+            sample_rate: 0.2,
+            ..MetricsConfig::default()
+        });
+
+        let id = MetricId::layout("sampled");
+        for _ in 0..10_000 {
+            registry.record(MetricEntry::new(id.clone(), MetricValue::Duration(Duration::from_micros(5))));
+        }
+        // Binomial(10000, 0.2) has a standard deviation of 40
+        let kept = registry.entries_for_metric(&id).len();
+        assert!((1700..=2300).contains(&kept), "kept {} of 10000 entries", kept);
+
+        // Counters are exempt, so their totals stay exact
+        let counter = registry.counter(MetricId::graph("nodes_added")).unwrap();
+        for _ in 0..100 {
+            counter.increment();
+        }
+        let counted = registry.entries_for_metric(&MetricId::graph("nodes_added"));
+        assert_eq!(counted.len(), 100);
+        assert_eq!(counted.last().unwrap().value.as_count(), Some(100));
+    }
+}
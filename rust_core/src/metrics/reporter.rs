@@ -1,9 +1,10 @@
 //! Metrics reporting utilities for output formatting and export
 //!
 //! This module provides various reporting formats for metrics data including
-//! console output, JSON export, CSV files, and dashboard-style summaries.
+//! console output, JSON export, CSV files, Prometheus scrapes, and
+//! dashboard-style summaries.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{self, Write};
 use std::time::{Duration, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
@@ -84,33 +85,110 @@ impl MetricsReporter {
         output
     }
 
-    /// Generate a JSON report
-    pub fn json_report(&self, report: &MetricsReport) -> MindmapResult<String> {
+    /// Serialize a report as JSON for dashboards
+    ///
+    /// The report time is given both as RFC 3339 (`generated_at`) and as
+    /// milliseconds since the epoch (`timestamp`); every duration is a
+    /// number of milliseconds.
+    pub fn to_json(&self, report: &MetricsReport) -> MindmapResult<String> {
         let json_report = JsonReport::from_metrics_report(report);
         serde_json::to_string_pretty(&json_report)
             .map_err(|e| format!("Failed to serialize JSON report: {}", e).into())
     }
 
-    /// Generate a CSV report for timing metrics
-    pub fn csv_timing_report(&self, report: &MetricsReport) -> String {
-        let mut output = String::new();
-        output.push_str("Category,Metric,Count,Average(ms),Min(ms),Max(ms),Total(ms)\n");
+    /// Flatten the timing metrics of every category into CSV rows
+    ///
+    /// Columns are `category,metric,count,avg_ms,min_ms,max_ms`, sorted by
+    /// category and then metric name.
+    pub fn to_csv(&self, report: &MetricsReport) -> String {
+        let mut rows: Vec<(String, &String, &super::TimingSummary)> = report.categories.iter()
+            .flat_map(|(category, summary)| {
+                summary.timing_metrics.iter().map(move |(name, timing)| (format!("{:?}", category), name, timing))
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+
+        let mut output = String::from("category,metric,count,avg_ms,min_ms,max_ms\n");
+        for (category, name, timing) in rows {
+            output.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                category,
+                crate::io::utils::escape_csv(name),
+                timing.count,
+                timing.average_duration.as_secs_f64() * 1000.0,
+                timing.min_duration.as_secs_f64() * 1000.0,
+                timing.max_duration.as_secs_f64() * 1000.0,
+            ));
+        }
+        output
+    }
 
-        for (category, summary) in &report.categories {
-            for (metric_name, timing) in &summary.timing_metrics {
-                output.push_str(&format!(
-                    "{:?},{},{},{:.2},{:.2},{:.2},{:.2}\n",
-                    category,
-                    metric_name,
-                    timing.count,
-                    timing.average_duration.as_secs_f64() * 1000.0,
-                    timing.min_duration.as_secs_f64() * 1000.0,
-                    timing.max_duration.as_secs_f64() * 1000.0,
-                    timing.total_duration.as_secs_f64() * 1000.0,
-                ));
+    /// Generate a report in the Prometheus text exposition format
+    ///
+    /// Every sample carries a `category` label. Timers become summaries in
+    /// seconds, with the minimum, p50, p95, p99 and maximum as quantiles;
+    /// counters become counters and memory metrics gauges in bytes. Metric
+    /// names are prefixed with `mindmap_` and sanitized to `[a-z0-9_]`.
+    pub fn to_prometheus(&self, report: &MetricsReport) -> String {
+        let mut families: BTreeMap<String, PrometheusFamily> = BTreeMap::new();
+        let mut categories: Vec<_> = report.categories.iter().collect();
+        categories.sort_by_key(|(category, _)| format!("{:?}", category));
+
+        for (category, summary) in &categories {
+            let label = format!("category=\"{}\"", prometheus_category(category));
+
+            for (name, timing) in &summary.timing_metrics {
+                let family = PrometheusFamily::entry(&mut families, name, "_seconds", "summary", "Duration of", "operations in seconds");
+                let quantiles = [
+                    ("0", timing.min_duration),
+                    ("0.5", timing.p50_duration),
+                    ("0.95", timing.p95_duration),
+                    ("0.99", timing.p99_duration),
+                    ("1", timing.max_duration),
+                ];
+                for (quantile, duration) in quantiles {
+                    family.samples.push(format!("{}{{{},quantile=\"{}\"}} {}", family.name, label, quantile, duration.as_secs_f64()));
+                }
+                family.samples.push(format!("{}_sum{{{}}} {}", family.name, label, timing.total_duration.as_secs_f64()));
+                family.samples.push(format!("{}_count{{{}}} {}", family.name, label, timing.count));
+            }
+
+            for (name, count) in &summary.counter_metrics {
+                let family = PrometheusFamily::entry(&mut families, name, "_total", "counter", "Number of", "events");
+                family.samples.push(format!("{}{{{}}} {}", family.name, label, count));
+            }
+
+            if self.config.include_memory_details {
+                for (name, bytes) in &summary.memory_metrics {
+                    let family = PrometheusFamily::entry(&mut families, name, "_bytes", "gauge", "Memory used by", "in bytes");
+                    family.samples.push(format!("{}{{{}}} {}", family.name, label, bytes));
+                }
+            }
+        }
+
+        // Tracked memory not already reported through the categories
+        if self.config.include_memory_details {
+            let mut tracked: Vec<_> = report.memory_usage.iter()
+                .filter(|(id, _)| !report.categories.get(&id.category)
+                    .is_some_and(|summary| summary.memory_metrics.contains_key(&id.name)))
+                .collect();
+            tracked.sort_by_key(|(id, _)| id.to_string());
+            for (id, bytes) in tracked {
+                let label = format!("category=\"{}\"", prometheus_category(&id.category));
+                let family = PrometheusFamily::entry(&mut families, &id.name, "_bytes", "gauge", "Memory used by", "in bytes");
+                family.samples.push(format!("{}{{{}}} {}", family.name, label, bytes));
             }
         }
 
+        let mut output = String::new();
+        for family in families.values() {
+            output.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+            output.push_str(&format!("# TYPE {} {}\n", family.name, family.kind));
+            for sample in &family.samples {
+                output.push_str(sample);
+                output.push('\n');
+            }
+        }
         output
     }
 
@@ -276,7 +354,10 @@ pub enum TimeFormat {
 /// JSON-serializable metrics report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonReport {
+    /// Milliseconds since the Unix epoch
     pub timestamp: u64,
+    /// RFC 3339 report time in UTC
+    pub generated_at: String,
     pub total_entries: usize,
     pub summary: JsonSummary,
     pub categories: HashMap<String, JsonCategorySummary>,
@@ -303,6 +384,7 @@ impl JsonReport {
 
         Self {
             timestamp,
+            generated_at: chrono::DateTime::<chrono::Utc>::from(report.timestamp).to_rfc3339(),
             total_entries: report.total_entries,
             summary: JsonSummary::from_metrics_summary(&report.summary),
             categories,
@@ -384,13 +466,59 @@ impl JsonTimingStats {
             average_ms: timing.average_duration.as_secs_f64() * 1000.0,
             min_ms: timing.min_duration.as_secs_f64() * 1000.0,
             max_ms: timing.max_duration.as_secs_f64() * 1000.0,
-            median_ms: timing.average_duration.as_secs_f64() * 1000.0, // Use average as proxy for median
-            percentile_95_ms: timing.max_duration.as_secs_f64() * 1000.0, // Use max as proxy
-            percentile_99_ms: timing.max_duration.as_secs_f64() * 1000.0, // Use max as proxy
+            median_ms: timing.p50_duration.as_secs_f64() * 1000.0,
+            percentile_95_ms: timing.p95_duration.as_secs_f64() * 1000.0,
+            percentile_99_ms: timing.p99_duration.as_secs_f64() * 1000.0,
         }
     }
 }
 
+/// Samples of one Prometheus metric family, written under a single `# TYPE`
+#[derive(Debug)]
+struct PrometheusFamily {
+    name: String,
+    kind: &'static str,
+    help: String,
+    samples: Vec<String>,
+}
+
+impl PrometheusFamily {
+    /// Family for metric `name` with the given unit suffix, created on first use
+    fn entry<'a>(
+        families: &'a mut BTreeMap<String, PrometheusFamily>,
+        name: &str,
+        suffix: &str,
+        kind: &'static str,
+        help_prefix: &str,
+        help_suffix: &str,
+    ) -> &'a mut PrometheusFamily {
+        let family_name = format!("mindmap_{}{}", prometheus_name(name), suffix);
+        families.entry(family_name.clone()).or_insert_with(|| PrometheusFamily {
+            name: family_name,
+            kind,
+            help: format!("{} {} {}", help_prefix, name.replace('\\', "\\\\").replace('\n', "\\n"), help_suffix),
+            samples: Vec::new(),
+        })
+    }
+}
+
+/// Metric name reduced to lowercase `[a-z0-9_]`
+fn prometheus_name(name: &str) -> String {
+    let sanitized: String = name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if sanitized.is_empty() || sanitized.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{}", sanitized)
+    } else {
+        sanitized
+    }
+}
+
+/// Value of the `category` label
+fn prometheus_category(category: &MetricCategory) -> String {
+    format!("{:?}", category).to_lowercase()
+}
+
 /// Live metrics dashboard for real-time monitoring
 pub struct LiveDashboard {
     config: DashboardConfig,
@@ -424,7 +552,7 @@ impl LiveDashboard {
                     println!("{}", reporter.console_report(report));
                 }
                 DashboardFormat::Json => {
-                    if let Ok(json) = reporter.json_report(report) {
+                    if let Ok(json) = reporter.to_json(report) {
                         println!("{}", json);
                     }
                 }
@@ -540,7 +668,7 @@ tr:hover {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::metrics::{MetricCategory, MetricsRegistry};
+    use crate::metrics::{MetricCategory, MetricEntry, MetricId, MetricValue, MetricsRegistry};
     use std::time::Duration;
 
     fn create_test_report() -> MetricsReport {
@@ -562,7 +690,7 @@ mod tests {
     fn test_json_report() {
         let report = create_test_report();
         let reporter = MetricsReporter::default();
-        let json_result = reporter.json_report(&report);
+        let json_result = reporter.to_json(&report);
 
         assert!(json_result.is_ok());
         let json = json_result.unwrap();
@@ -574,9 +702,77 @@ mod tests {
     fn test_csv_timing_report() {
         let report = create_test_report();
         let reporter = MetricsReporter::default();
-        let csv = reporter.csv_timing_report(&report);
+        let csv = reporter.to_csv(&report);
+
+        assert!(csv.starts_with("category,metric,count,avg_ms,min_ms,max_ms\n"));
+    }
+
+    #[test]
+    fn test_prometheus_exposition_is_well_formed() {
+        let registry = MetricsRegistry::new();
+        registry.record(MetricEntry::new(MetricId::graph("add node"), MetricValue::Duration(Duration::from_millis(4))));
+        registry.record(MetricEntry::new(MetricId::graph("add node"), MetricValue::Duration(Duration::from_millis(6))));
+        registry.record(MetricEntry::new(MetricId::search("queries"), MetricValue::Count(3)));
+        let output = MetricsReporter::default().to_prometheus(&registry.report());
+
+        let comment = regex::Regex::new(r"^# (HELP [a-zA-Z_:][a-zA-Z0-9_:]* .+|TYPE [a-zA-Z_:][a-zA-Z0-9_:]* (counter|gauge|summary|histogram|untyped))$").unwrap();
+        let sample = regex::Regex::new(r#"^[a-zA-Z_:][a-zA-Z0-9_:]*(\{[a-zA-Z_][a-zA-Z0-9_]*="[^"\\]*"(,[a-zA-Z_][a-zA-Z0-9_]*="[^"\\]*")*\})? \S+$"#).unwrap();
+        for line in output.lines() {
+            if line.starts_with('#') {
+                assert!(comment.is_match(line), "bad comment line: {}", line);
+            } else {
+                assert!(sample.is_match(line), "bad sample line: {}", line);
+                let value = line.rsplit(' ').next().unwrap();
+                assert!(value.parse::<f64>().is_ok(), "bad value: {}", line);
+            }
+        }
+
+        assert!(output.contains("# TYPE mindmap_add_node_seconds summary\n"));
+        assert!(output.contains("mindmap_add_node_seconds_count{category=\"graph\"} 2\n"));
+        assert!(output.contains("mindmap_add_node_seconds{category=\"graph\",quantile=\"1\"} 0.006\n"));
+        assert!(output.contains("# TYPE mindmap_queries_total counter\n"));
+        assert!(output.contains("mindmap_queries_total{category=\"search\"} 3\n"));
+        assert_eq!(output.matches("# TYPE").count(), 2);
+    }
+
+    #[test]
+    fn test_json_uses_rfc3339_and_milliseconds() {
+        let registry = MetricsRegistry::new();
+        registry.record(MetricEntry::new(MetricId::layout("tree"), MetricValue::Duration(Duration::from_millis(12))));
+        registry.record(MetricEntry::new(MetricId::layout("tree"), MetricValue::Duration(Duration::from_millis(8))));
+        let report = registry.report();
 
-        assert!(csv.contains("Category,Metric,Count"));
+        let json = MetricsReporter::default().to_json(&report).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let generated_at = value["generated_at"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(generated_at).is_ok());
+        assert!(value["timestamp"].is_u64());
+
+        let tree = &value["categories"]["Layout"]["timing_metrics"]["tree"];
+        assert_eq!(tree["count"], 2);
+        for field in ["total_ms", "average_ms", "min_ms", "max_ms"] {
+            assert!(tree[field].is_f64(), "{} is not numeric", field);
+        }
+        assert_eq!(tree["average_ms"].as_f64(), Some(10.0));
+        assert_eq!(tree["min_ms"].as_f64(), Some(8.0));
+        assert_eq!(tree["total_ms"].as_f64(), Some(20.0));
+    }
+
+    #[test]
+    fn test_csv_rows_per_timing_metric() {
+        let registry = MetricsRegistry::new();
+        registry.record(MetricEntry::new(MetricId::search("query, fuzzy"), MetricValue::Duration(Duration::from_millis(3))));
+        registry.record(MetricEntry::new(MetricId::graph("add_node"), MetricValue::Duration(Duration::from_millis(1))));
+        registry.record(MetricEntry::new(MetricId::graph("nodes"), MetricValue::Count(5)));
+
+        let csv = MetricsReporter::default().to_csv(&registry.report());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, vec![
+            "category,metric,count,avg_ms,min_ms,max_ms",
+            "Graph,add_node,1,1,1,1",
+            "Search,\"query, fuzzy\",1,3,3,3",
+        ]);
     }
 
     #[test]
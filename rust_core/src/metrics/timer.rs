@@ -3,7 +3,8 @@
 //! This module provides high-precision timing functionality for measuring
 //! operation durations and performance profiling.
 
-use std::time::{Duration, Instant};
+use crate::types::clock::Instant;
+use std::time::Duration;
 use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 use super::{MetricEntry, MetricId, MetricValue, MetricsRegistry};
@@ -13,16 +14,13 @@ use super::{MetricEntry, MetricId, MetricValue, MetricsRegistry};
 pub struct TimerHandle {
     id: MetricId,
     start_time: Instant,
-    registry: Option<*const MetricsRegistry>,
+    registry: Option<MetricsRegistry>,
     finished: Arc<Mutex<bool>>,
 }
 
-// TimerHandle is Send because we control access to the registry pointer
-unsafe impl Send for TimerHandle {}
-
 impl TimerHandle {
     /// Create a new timer handle
-    pub fn new(id: MetricId, registry: Option<*const MetricsRegistry>) -> Self {
+    pub fn new(id: MetricId, registry: Option<MetricsRegistry>) -> Self {
         Self {
             id,
             start_time: Instant::now(),
@@ -46,12 +44,9 @@ impl TimerHandle {
         }
 
         // Record the metric if registry is available
-        if let Some(registry_ptr) = self.registry {
-            unsafe {
-                let registry = &*registry_ptr;
-                let entry = MetricEntry::new(self.id.clone(), MetricValue::Duration(duration));
-                registry.record(entry);
-            }
+        if let Some(registry) = &self.registry {
+            let entry = MetricEntry::new(self.id.clone(), MetricValue::Duration(duration));
+            registry.record(entry);
         }
 
         duration
@@ -77,12 +72,9 @@ impl Drop for TimerHandle {
                 *finished = true;
             }
 
-            if let Some(registry_ptr) = self.registry {
-                unsafe {
-                    let registry = &*registry_ptr;
-                    let entry = MetricEntry::new(self.id.clone(), MetricValue::Duration(duration));
-                    registry.record(entry);
-                }
+            if let Some(registry) = &self.registry {
+                let entry = MetricEntry::new(self.id.clone(), MetricValue::Duration(duration));
+                registry.record(entry);
             }
         }
     }
@@ -108,7 +100,17 @@ impl TimerRegistry {
 
     /// Start a new timer
     pub fn start(&mut self) -> TimerHandle {
-        let handle = TimerHandle::new(self.id.clone(), None);
+        self.start_with(None)
+    }
+
+    /// Start a new timer that records its duration into the given registry
+    pub fn start_for(&mut self, registry: MetricsRegistry) -> TimerHandle {
+        self.start_with(Some(registry))
+    }
+
+    /// Start a new timer with an optional destination registry
+    fn start_with(&mut self, registry: Option<MetricsRegistry>) -> TimerHandle {
+        let handle = TimerHandle::new(self.id.clone(), registry);
 
         if let Ok(mut timers) = self.active_timers.lock() {
             timers.push(TimerHandle::new(self.id.clone(), None));
@@ -237,44 +239,62 @@ impl TimingStats {
 }
 
 /// Scoped timer that automatically records timing when dropped
+///
+/// Timing starts on construction and is recorded when the timer goes out of
+/// scope, including through early returns, so no `finish()` call is needed.
+/// If the registry is disabled for the metric's category when the timer
+/// starts, nothing is recorded.
+#[derive(Debug)]
 pub struct ScopedTimer {
-    handle: Option<TimerHandle>,
+    id: MetricId,
+    start_time: Instant,
+    /// Registry to record into; `None` if collection was disabled at start
+    registry: Option<MetricsRegistry>,
+    finished: bool,
 }
 
 impl ScopedTimer {
-    /// Create a new scoped timer
+    /// Create a new scoped timer recording into the global registry
     pub fn new(id: MetricId) -> Self {
-        let registry = super::registry();
-        let handle = TimerHandle::new(id, Some(registry as *const MetricsRegistry));
+        Self::with_registry(id, super::registry())
+    }
+
+    /// Create a new scoped timer recording into a specific registry
+    pub fn with_registry(id: MetricId, registry: &MetricsRegistry) -> Self {
+        let registry = registry.is_recording(id.category).then(|| registry.clone());
         Self {
-            handle: Some(handle),
+            id,
+            start_time: Instant::now(),
+            registry,
+            finished: false,
         }
     }
 
     /// Get the elapsed time without finishing
     pub fn elapsed(&self) -> Duration {
-        if let Some(handle) = &self.handle {
-            handle.elapsed()
-        } else {
-            Duration::ZERO
-        }
+        self.start_time.elapsed()
     }
 
     /// Manually finish the timer early
+    ///
+    /// Returns `None` if the timer was already finished.
     pub fn finish(&mut self) -> Option<Duration> {
-        if let Some(handle) = self.handle.take() {
-            Some(handle.finish())
-        } else {
-            None
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+
+        let duration = self.elapsed();
+        if let Some(registry) = &self.registry {
+            registry.record(MetricEntry::new(self.id.clone(), MetricValue::Duration(duration)));
         }
+        Some(duration)
     }
 }
 
 impl Drop for ScopedTimer {
     fn drop(&mut self) {
-        if let Some(handle) = self.handle.take() {
-            handle.finish();
-        }
+        self.finish();
     }
 }
 
@@ -435,6 +455,48 @@ mod tests {
         assert!(duration >= Duration::from_millis(5));
     }
 
+    #[test]
+    fn test_scoped_timer_records_on_drop() {
+        let id = MetricId::new(MetricCategory::Application, "test_scoped_drop");
+
+        let early_return = |fail: bool| -> Result<(), ()> {
+            let _timer = ScopedTimer::new(id.clone());
+            thread::sleep(Duration::from_millis(20));
+            if fail {
+                return Err(());
+            }
+            Ok(())
+        };
+        let _ = early_return(true);
+
+        let entries = crate::metrics::registry().entries_for_metric(&id);
+        assert_eq!(entries.len(), 1);
+        let duration = entries[0].value.as_duration().unwrap();
+        assert!(duration >= Duration::from_millis(20) && duration < Duration::from_secs(2), "{:?}", duration);
+
+        // Finishing early records once, not again on drop
+        let mut timer = ScopedTimer::new(id.clone());
+        assert!(timer.finish().is_some());
+        assert!(timer.finish().is_none());
+        drop(timer);
+        assert_eq!(crate::metrics::registry().entries_for_metric(&id).len(), 2);
+    }
+
+    #[test]
+    fn test_scoped_timer_is_noop_when_disabled() {
+        let registry = MetricsRegistry::new();
+        registry.configure(crate::metrics::MetricsConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        let id = MetricId::new(MetricCategory::Graph, "disabled_scope");
+
+        {
+            let _timer = ScopedTimer::with_registry(id.clone(), &registry);
+        }
+        assert!(registry.entries().is_empty());
+    }
+
     #[test]
     fn test_timing_stats() {
         let durations = vec![
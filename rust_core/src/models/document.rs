@@ -50,6 +50,10 @@ pub struct Document {
 
     /// Whether the document has unsaved changes
     pub is_dirty: bool,
+
+    /// Number of successful saves; the stored version this copy is based on
+    #[serde(default)]
+    pub version: u64,
 }
 
 impl Document {
@@ -74,15 +78,34 @@ impl Document {
             updated_at: now,
             last_saved_at: None,
             is_dirty: false,
+            version: 0,
         }
     }
 
+    /// The stored version this document is based on, 0 if never saved
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Set the root node for this document
     pub fn set_root_node(&mut self, node_id: NodeId) {
         self.root_node = node_id;
         self.mark_dirty();
     }
 
+    /// Detach the document from its root node, e.g. when the root was lost
+    ///
+    /// The root becomes the nil ID until a new one is set.
+    pub fn clear_root_node(&mut self) {
+        self.root_node = NodeId::from_uuid(uuid::Uuid::nil());
+        self.mark_dirty();
+    }
+
+    /// Check whether the document points at a root node
+    pub fn has_root_node(&self) -> bool {
+        !self.root_node.as_uuid().is_nil()
+    }
+
     /// Update the document title
     pub fn set_title(&mut self, title: impl Into<String>) {
         let title_str = title.into();
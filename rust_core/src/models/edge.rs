@@ -54,6 +54,10 @@ pub struct Edge {
     /// Visual styling for the edge
     pub style: EdgeStyle,
 
+    /// Optional traversal cost used by weighted path finding (1.0 when unset)
+    #[serde(default)]
+    pub weight: Option<f64>,
+
     /// When this edge was created
     pub created_at: Timestamp,
 
@@ -72,6 +76,7 @@ impl Edge {
             to_node,
             label: None,
             style: EdgeStyle::default(),
+            weight: None,
             created_at: now,
             updated_at: now,
         }
@@ -96,6 +101,17 @@ impl Edge {
         self.updated_at = chrono::Utc::now();
     }
 
+    /// Set the edge weight
+    pub fn set_weight(&mut self, weight: Option<f64>) {
+        self.weight = weight;
+        self.updated_at = chrono::Utc::now();
+    }
+
+    /// Weight used for path finding, defaulting to 1.0
+    pub fn effective_weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+
     /// Check if this edge connects the given nodes (in either direction)
     pub fn connects(&self, node1: NodeId, node2: NodeId) -> bool {
         (self.from_node == node1 && self.to_node == node2) ||
@@ -107,13 +123,19 @@ impl Edge {
         self.label.is_some()
     }
 
-    /// Validate that the edge doesn't connect a node to itself
+    /// Validate that the edge doesn't connect a node to itself and has a usable weight
     pub fn validate(&self) -> Result<(), String> {
         if self.from_node == self.to_node {
-            Err("Edge cannot connect a node to itself".to_string())
-        } else {
-            Ok(())
+            return Err("Edge cannot connect a node to itself".to_string());
         }
+
+        if let Some(weight) = self.weight {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!("Edge weight must be a non-negative number, got {}", weight));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -211,4 +233,34 @@ mod tests {
         assert_eq!(style.arrow_type, ArrowType::Simple);
         assert_eq!(style.width, 2.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_edge_weight() {
+        let node1 = NodeId::new();
+        let node2 = NodeId::new();
+        let mut edge = Edge::new(node1, node2);
+        assert_eq!(edge.weight, None);
+        assert_eq!(edge.effective_weight(), 1.0);
+
+        edge.set_weight(Some(2.5));
+        assert_eq!(edge.effective_weight(), 2.5);
+        assert!(edge.validate().is_ok());
+
+        edge.set_weight(Some(-1.0));
+        assert!(edge.validate().is_err());
+
+        edge.set_weight(Some(f64::NAN));
+        assert!(edge.validate().is_err());
+    }
+
+    #[test]
+    fn test_edge_deserializes_without_weight() {
+        let edge = Edge::new(NodeId::new(), NodeId::new());
+        let mut value = serde_json::to_value(&edge).unwrap();
+        value.as_object_mut().unwrap().remove("weight");
+
+        let restored: Edge = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.weight, None);
+        assert_eq!(restored, edge);
+    }
+}
@@ -3,7 +3,7 @@
 //! This module defines the Node struct which represents individual nodes
 //! in a mindmap with their content, styling, position, and metadata.
 
-use crate::types::{ids::NodeId, Point, Color, Timestamp};
+use crate::types::{ids::{AttachmentId, NodeId}, Point, Color, Timestamp};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -54,6 +54,165 @@ pub enum NodeShape {
     Ellipse,
 }
 
+/// How duplicate tags on a node are detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TagDedupe {
+    /// Tags differing only in letter case are duplicates ("AI" and "ai")
+    #[default]
+    CaseInsensitive,
+    /// Only identical tags are duplicates
+    CaseSensitive,
+}
+
+impl TagDedupe {
+    /// Check whether two tags are considered the same
+    pub fn matches(self, a: &str, b: &str) -> bool {
+        self.key(a) == self.key(b)
+    }
+
+    /// Key under which tags that are the same compare equal
+    pub fn key(self, tag: &str) -> String {
+        match self {
+            TagDedupe::CaseInsensitive => tag.to_lowercase(),
+            TagDedupe::CaseSensitive => tag.to_string(),
+        }
+    }
+}
+
+/// How metadata maps are combined when two nodes are merged
+///
+/// Keys present on only one side are always kept; the policy decides what
+/// happens when both nodes have a key with different values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MetadataMergePolicy {
+    /// Keep both values, joined as "left; right"
+    #[default]
+    Union,
+    /// Keep the value of the node being merged into
+    PreferLeft,
+    /// Keep the value of the node being merged in
+    PreferRight,
+    /// Refuse to merge
+    ErrorOnConflict,
+}
+
+impl MetadataMergePolicy {
+    /// Combine two metadata maps, `left` belonging to the surviving node
+    pub fn merge(
+        self,
+        left: &HashMap<String, String>,
+        right: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, String> {
+        let mut merged = left.clone();
+
+        for (key, right_value) in right {
+            let Some(left_value) = merged.get_mut(key) else {
+                merged.insert(key.clone(), right_value.clone());
+                continue;
+            };
+            if left_value == right_value {
+                continue;
+            }
+
+            match self {
+                MetadataMergePolicy::Union => *left_value = format!("{}; {}", left_value, right_value),
+                MetadataMergePolicy::PreferLeft => {}
+                MetadataMergePolicy::PreferRight => *left_value = right_value.clone(),
+                MetadataMergePolicy::ErrorOnConflict => {
+                    return Err(format!(
+                        "Conflicting metadata for key '{}': '{}' vs '{}'",
+                        key, left_value, right_value
+                    ));
+                }
+            }
+        }
+
+        Ok(merged)
+    }
+}
+
+/// Approximate font measurements used to estimate node dimensions
+///
+/// Widths and heights are expressed relative to the node's font size, so a
+/// single set of metrics works for every node style.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FontMetrics {
+    /// Average character width as a fraction of the font size
+    pub char_width_ratio: f64,
+    /// Line height as a multiple of the font size
+    pub line_height_ratio: f64,
+    /// Extra width factor applied to bold text
+    pub bold_width_factor: f64,
+    /// Maximum text width in pixels before wrapping (0 disables wrapping)
+    pub max_line_width: f64,
+    /// Horizontal padding on each side of the text
+    pub padding_x: f64,
+    /// Vertical padding above and below the text
+    pub padding_y: f64,
+    /// Minimum node width
+    pub min_width: f64,
+    /// Minimum node height
+    pub min_height: f64,
+}
+
+impl Default for FontMetrics {
+    fn default() -> Self {
+        Self {
+            char_width_ratio: 0.6,
+            line_height_ratio: 1.2,
+            bold_width_factor: 1.1,
+            max_line_width: 240.0,
+            padding_x: 12.0,
+            padding_y: 8.0,
+            min_width: 60.0,
+            min_height: 32.0,
+        }
+    }
+}
+
+impl FontMetrics {
+    /// Split text into lines, wrapping words that exceed the maximum width
+    fn wrap_lines(&self, text: &str, char_width: f64) -> Vec<usize> {
+        let max_chars = if self.max_line_width > 0.0 && char_width > 0.0 {
+            ((self.max_line_width / char_width).floor() as usize).max(1)
+        } else {
+            usize::MAX
+        };
+
+        let mut line_lengths = Vec::new();
+        for paragraph in text.lines() {
+            let mut current = 0usize;
+            for word in paragraph.split_whitespace() {
+                let word_len = word.chars().count();
+                let needed = if current == 0 { word_len } else { current + 1 + word_len };
+
+                if needed <= max_chars {
+                    current = needed;
+                    continue;
+                }
+
+                if current > 0 {
+                    line_lengths.push(current);
+                }
+
+                // Words longer than a full line are broken across lines
+                let mut remaining = word_len;
+                while remaining > max_chars {
+                    line_lengths.push(max_chars);
+                    remaining -= max_chars;
+                }
+                current = remaining;
+            }
+            line_lengths.push(current);
+        }
+
+        line_lengths
+    }
+}
+
+/// Maximum size of an attachment's contents in bytes (100 MB)
+pub const MAX_ATTACHMENT_SIZE: u64 = 100 * 1024 * 1024;
+
 /// File attachment for a node
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attachment {
@@ -71,6 +230,24 @@ pub struct Attachment {
     pub created_at: Timestamp,
 }
 
+/// A set of field changes to apply to a node at once
+///
+/// Fields left as `None` are kept. Applying a patch that changes anything
+/// advances the node's `updated_at`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodePatch {
+    /// New text content
+    pub text: Option<String>,
+    /// New position
+    pub position: Option<Point>,
+    /// Replacement tags
+    pub tags: Option<Vec<String>>,
+    /// Replacement metadata
+    pub metadata: Option<HashMap<String, String>>,
+    /// New collapsed state
+    pub collapsed: Option<bool>,
+}
+
 /// A node in the mindmap
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
@@ -103,6 +280,22 @@ pub struct Node {
 
     /// Custom metadata as key-value pairs
     pub metadata: HashMap<String, String>,
+
+    /// Whether this node's subtree is collapsed (hidden) in the view
+    #[serde(default)]
+    pub collapsed: bool,
+
+    /// Position among siblings; lower values are listed first
+    #[serde(default)]
+    pub order: i64,
+
+    /// Whether layouts that preserve positions must keep this node in place
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// Longer markdown notes shown alongside the short `text` label
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 impl Node {
@@ -121,6 +314,10 @@ impl Node {
             created_at: now,
             updated_at: now,
             metadata: HashMap::new(),
+            collapsed: false,
+            order: 0,
+            pinned: false,
+            note: None,
         }
     }
 
@@ -131,6 +328,14 @@ impl Node {
         node
     }
 
+    /// Mark this node as modified now
+    ///
+    /// The setters below call this; code that edits fields directly should
+    /// call it too so `updated_at` stays accurate for conflict resolution.
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now();
+    }
+
     /// Update the text content and mark as modified
     pub fn set_text(&mut self, text: impl Into<String>) {
         self.text = text.into();
@@ -143,13 +348,79 @@ impl Node {
         self.updated_at = chrono::Utc::now();
     }
 
-    /// Add a tag to this node
+    /// Replace all tags on this node
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        if self.tags != tags {
+            self.tags = tags;
+            self.touch();
+        }
+    }
+
+    /// Replace all metadata on this node
+    pub fn set_all_metadata(&mut self, metadata: HashMap<String, String>) {
+        if self.metadata != metadata {
+            self.metadata = metadata;
+            self.touch();
+        }
+    }
+
+    /// Apply every change in a patch
+    pub fn apply_patch(&mut self, patch: NodePatch) {
+        if let Some(text) = patch.text {
+            self.set_text(text);
+        }
+        if let Some(position) = patch.position {
+            self.set_position(position);
+        }
+        if let Some(tags) = patch.tags {
+            self.set_tags(tags);
+        }
+        if let Some(metadata) = patch.metadata {
+            self.set_all_metadata(metadata);
+        }
+        if let Some(collapsed) = patch.collapsed {
+            self.set_collapsed(collapsed);
+        }
+    }
+
+    /// Add a tag to this node, ignoring case-insensitive duplicates
     pub fn add_tag(&mut self, tag: impl Into<String>) {
+        self.add_tag_with(tag, TagDedupe::default());
+    }
+
+    /// Add a tag to this node unless an equivalent tag is already present
+    ///
+    /// Returns true if the tag was added.
+    pub fn add_tag_with(&mut self, tag: impl Into<String>, dedupe: TagDedupe) -> bool {
         let tag = tag.into();
-        if !self.tags.contains(&tag) {
-            self.tags.push(tag);
+        if self.tags.iter().any(|existing| dedupe.matches(existing, &tag)) {
+            return false;
+        }
+
+        self.tags.push(tag);
+        self.updated_at = chrono::Utc::now();
+        true
+    }
+
+    /// Remove duplicate tags, keeping the first occurrence of each
+    ///
+    /// Returns the number of tags removed.
+    pub fn dedupe_tags(&mut self, dedupe: TagDedupe) -> usize {
+        let original_len = self.tags.len();
+        let mut kept: Vec<String> = Vec::with_capacity(original_len);
+
+        for tag in self.tags.drain(..) {
+            if !kept.iter().any(|existing| dedupe.matches(existing, &tag)) {
+                kept.push(tag);
+            }
+        }
+
+        self.tags = kept;
+        let removed = original_len - self.tags.len();
+        if removed > 0 {
             self.updated_at = chrono::Utc::now();
         }
+        removed
     }
 
     /// Remove a tag from this node
@@ -191,6 +462,18 @@ impl Node {
         self.metadata.get(key)
     }
 
+    /// Merge another node's metadata into this one under the given policy
+    ///
+    /// Leaves this node untouched if the policy rejects a conflict.
+    pub fn merge_metadata_from(&mut self, other: &Node, policy: MetadataMergePolicy) -> Result<(), String> {
+        let merged = policy.merge(&self.metadata, &other.metadata)?;
+        if merged != self.metadata {
+            self.metadata = merged;
+            self.updated_at = chrono::Utc::now();
+        }
+        Ok(())
+    }
+
     /// Remove a metadata key
     pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
         let result = self.metadata.remove(key);
@@ -200,6 +483,39 @@ impl Node {
         result
     }
 
+    /// Collapse or expand this node's subtree
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        if self.collapsed != collapsed {
+            self.collapsed = collapsed;
+            self.updated_at = chrono::Utc::now();
+        }
+    }
+
+    /// Pin this node in place for layouts that preserve positions, or unpin it
+    pub fn set_pinned(&mut self, pinned: bool) {
+        if self.pinned != pinned {
+            self.pinned = pinned;
+            self.updated_at = chrono::Utc::now();
+        }
+    }
+
+    /// Replace the node's note; an empty or blank note removes it
+    pub fn set_note(&mut self, note: Option<String>) {
+        let note = note.filter(|note| !note.trim().is_empty());
+        if self.note != note {
+            self.note = note;
+            self.touch();
+        }
+    }
+
+    /// Set the position of this node among its siblings
+    pub fn set_order(&mut self, order: i64) {
+        if self.order != order {
+            self.order = order;
+            self.updated_at = chrono::Utc::now();
+        }
+    }
+
     /// Check if this node is a root node (has no parent)
     pub fn is_root(&self) -> bool {
         self.parent_id.is_none()
@@ -210,6 +526,11 @@ impl Node {
         self.parent_id == Some(parent_id)
     }
 
+    /// Number of whitespace-separated words in the node text
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+
     /// Validate that the text content is not empty
     pub fn validate_text(&self) -> Result<(), String> {
         if self.text.trim().is_empty() {
@@ -240,6 +561,27 @@ impl Node {
         self.validate_parent()?;
         Ok(())
     }
+
+    /// Estimate the rendered (width, height) of this node from its text
+    ///
+    /// Text is wrapped at word boundaries to `max_line_width` and the result
+    /// includes padding, never falling below the configured minimum size.
+    pub fn estimated_size(&self, font_metrics: &FontMetrics) -> (f64, f64) {
+        let weight_factor = match self.style.font_weight {
+            FontWeight::Bold => font_metrics.bold_width_factor,
+            FontWeight::Normal => 1.0,
+        };
+        let char_width = self.style.font_size * font_metrics.char_width_ratio * weight_factor;
+        let line_height = self.style.font_size * font_metrics.line_height_ratio;
+
+        let lines = font_metrics.wrap_lines(&self.text, char_width);
+        let longest = lines.iter().copied().max().unwrap_or(0);
+
+        let width = longest as f64 * char_width + 2.0 * font_metrics.padding_x;
+        let height = lines.len() as f64 * line_height + 2.0 * font_metrics.padding_y;
+
+        (width.max(font_metrics.min_width), height.max(font_metrics.min_height))
+    }
 }
 
 impl Default for NodeStyle {
@@ -285,7 +627,7 @@ impl Attachment {
         path: impl Into<String>,
     ) -> Self {
         Self {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: AttachmentId::new().as_uuid().to_string(),
             filename: filename.into(),
             mime_type: mime_type.into(),
             size,
@@ -294,6 +636,11 @@ impl Attachment {
         }
     }
 
+    /// Typed ID of this attachment, if `id` holds one
+    pub fn attachment_id(&self) -> Option<AttachmentId> {
+        self.id.parse().ok()
+    }
+
     /// Check if this is an image attachment
     pub fn is_image(&self) -> bool {
         self.mime_type.starts_with("image/")
@@ -372,6 +719,50 @@ mod tests {
         assert!(!node.remove_tag("nonexistent"));
     }
 
+    #[test]
+    fn test_metadata_merge_policies() {
+        let left: HashMap<String, String> = [("a", "1"), ("shared", "left")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let right: HashMap<String, String> = [("b", "2"), ("shared", "right")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let union = MetadataMergePolicy::Union.merge(&left, &right).unwrap();
+        assert_eq!(union.len(), 3);
+        assert_eq!(union["a"], "1");
+        assert_eq!(union["b"], "2");
+        assert_eq!(union["shared"], "left; right");
+
+        assert_eq!(MetadataMergePolicy::PreferLeft.merge(&left, &right).unwrap()["shared"], "left");
+        assert_eq!(MetadataMergePolicy::PreferRight.merge(&left, &right).unwrap()["shared"], "right");
+        assert!(MetadataMergePolicy::ErrorOnConflict.merge(&left, &right).is_err());
+
+        // Identical values are not a conflict
+        assert!(MetadataMergePolicy::ErrorOnConflict.merge(&left, &left).is_ok());
+    }
+
+    #[test]
+    fn test_tag_dedupe_modes() {
+        let mut node = Node::new("Test");
+        assert!(node.add_tag_with("AI", TagDedupe::CaseInsensitive));
+        assert!(!node.add_tag_with("ai", TagDedupe::CaseInsensitive));
+        assert_eq!(node.tags, vec!["AI".to_string()]);
+
+        let mut node = Node::new("Test");
+        assert!(node.add_tag_with("AI", TagDedupe::CaseSensitive));
+        assert!(node.add_tag_with("ai", TagDedupe::CaseSensitive));
+        assert_eq!(node.tags, vec!["AI".to_string(), "ai".to_string()]);
+
+        // Dedupe keeps the first occurrence and preserves order
+        node.tags = vec!["b".into(), "AI".into(), "B".into(), "ai".into(), "c".into()];
+        assert_eq!(node.dedupe_tags(TagDedupe::CaseSensitive), 0);
+        assert_eq!(node.dedupe_tags(TagDedupe::CaseInsensitive), 2);
+        assert_eq!(node.tags, vec!["b".to_string(), "AI".to_string(), "c".to_string()]);
+    }
+
     #[test]
     fn test_metadata_management() {
         let mut node = Node::new("Test");
@@ -419,4 +810,63 @@ mod tests {
         assert_eq!(style.text_align, TextAlign::Center);
         assert_eq!(style.shape, NodeShape::RoundedRectangle);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_estimated_size() {
+        let metrics = FontMetrics::default();
+
+        let (empty_width, empty_height) = Node::new("").estimated_size(&metrics);
+        assert_eq!(empty_width, metrics.min_width);
+        assert_eq!(empty_height, metrics.min_height);
+
+        let (short_width, short_height) = Node::new("Short topic").estimated_size(&metrics);
+        let (long_width, _) = Node::new("A considerably longer topic").estimated_size(&metrics);
+        assert!(long_width > short_width);
+
+        // Text beyond the maximum line width wraps onto more lines
+        let paragraph = "word ".repeat(60);
+        let (wrapped_width, wrapped_height) = Node::new(paragraph).estimated_size(&metrics);
+        assert!(wrapped_width <= metrics.max_line_width + 2.0 * metrics.padding_x);
+        assert!(wrapped_height > short_height);
+    }
+
+    #[test]
+    fn test_word_count() {
+        assert_eq!(Node::new("").word_count(), 0);
+        assert_eq!(Node::new("   ").word_count(), 0);
+        assert_eq!(Node::new("Plan").word_count(), 1);
+        assert_eq!(Node::new("  Plan the\nQ3   launch\t").word_count(), 4);
+    }
+
+    #[test]
+    fn test_collapsed_defaults_to_false_for_older_data() {
+        let mut value = serde_json::to_value(Node::new("Legacy")).unwrap();
+        value.as_object_mut().unwrap().remove("collapsed");
+        let node: Node = serde_json::from_value(value).unwrap();
+        assert!(!node.collapsed);
+    }
+
+    #[test]
+    fn test_note_defaults_to_none_for_older_data() {
+        let mut node = Node::new("Legacy");
+        node.set_note(Some("## Background\nLonger *markdown* notes".to_string()));
+        let value = serde_json::to_value(&node).unwrap();
+        let restored: Node = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(restored.note, node.note);
+
+        let mut value = value;
+        value.as_object_mut().unwrap().remove("note");
+        let node: Node = serde_json::from_value(value).unwrap();
+        assert_eq!(node.note, None);
+    }
+
+    #[test]
+    fn test_set_note_clears_blank_notes() {
+        let mut node = Node::new("Topic");
+        node.set_note(Some("Details".to_string()));
+        assert_eq!(node.note.as_deref(), Some("Details"));
+
+        node.set_note(Some("  \n".to_string()));
+        assert_eq!(node.note, None);
+    }
+}
@@ -278,6 +278,10 @@ impl SqliteDatabase {
                     style,
                     created_at: Timestamp::from_timestamp(row.get(9)?, 0).unwrap(),
                     updated_at: Timestamp::from_timestamp(row.get(10)?, 0).unwrap(),
+                    collapsed: false,
+                    order: 0,
+                    pinned: false,
+                    note: None,
                 })
             }
         ).optional().map_err(|e| MindmapError::DatabaseError {
@@ -350,6 +354,7 @@ impl SqliteDatabase {
                         .map_err(|_| rusqlite::Error::InvalidColumnType(2, "to_node_id".to_string(), rusqlite::types::Type::Text))?,
                     label: row.get(3)?,
                     style,
+                    weight: None,
                     created_at: Timestamp::from_timestamp(row.get(5)?, 0).unwrap(),
                     updated_at: Timestamp::from_timestamp(row.get(6)?, 0).unwrap(),
                 })
@@ -450,6 +455,10 @@ impl SqliteDatabase {
                 style,
                 created_at: Timestamp::from_timestamp(row.get(9)?, 0).unwrap(),
                 updated_at: Timestamp::from_timestamp(row.get(10)?, 0).unwrap(),
+                collapsed: false,
+                order: 0,
+                pinned: false,
+                note: None,
             })
         }).map_err(|e| MindmapError::DatabaseError {
             message: format!("Failed to query nodes: {}", e),
@@ -496,6 +505,7 @@ impl SqliteDatabase {
                     .map_err(|_| rusqlite::Error::InvalidColumnType(2, "to_node_id".to_string(), rusqlite::types::Type::Text))?,
                 label: row.get(3)?,
                 style,
+                weight: None,
                 created_at: Timestamp::from_timestamp(row.get(5)?, 0).unwrap(),
                 updated_at: Timestamp::from_timestamp(row.get(6)?, 0).unwrap(),
             })
@@ -679,6 +689,7 @@ impl DatabaseOperations for SqliteDatabase {
             page_count,
             free_pages,
             schema_version: 1, // Will be managed by migrations
+            wal_size_bytes: 0,
         })
     }
 
@@ -3,10 +3,15 @@
 //! This is a working subset implementation focused on basic functionality
 
 use super::*;
-// Models will be used in future implementation
-use crate::types::{MindmapResult, MindmapError};
-use rusqlite::{Connection, params};
+use crate::graph::{Graph, MissingRootPolicy};
+use crate::models::document::{Document, DocumentMetadata};
+use crate::models::{Edge, Node, MAX_ATTACHMENT_SIZE};
+use crate::types::{ids::{AttachmentId, DocumentId, EdgeId, NodeId}, MindmapResult, MindmapError, Point, Timestamp};
+use rusqlite::{Connection, params, OptionalExtension, Row};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Simplified SQLite database implementation
 pub struct SimpleSqliteDatabase {
@@ -23,6 +28,14 @@ impl SimpleSqliteDatabase {
             message: format!("Failed to open database: {}", e),
         })?;
 
+        // In-memory databases report "memory" and stay out of WAL mode
+        if config.wal_mode {
+            connection.query_row("PRAGMA journal_mode = WAL", params![], |row| row.get::<_, String>(0))
+                .map_err(|e| MindmapError::DatabaseError {
+                    message: format!("Failed to enable WAL mode: {}", e),
+                })?;
+        }
+
         let db = Self {
             connection: Arc::new(Mutex::new(connection)),
             config,
@@ -32,23 +45,161 @@ impl SimpleSqliteDatabase {
     }
 
     /// Get database statistics
+    ///
+    /// The WAL size is that of the `-wal` file next to the database, which
+    /// only exists while WAL mode is on.
     pub fn get_stats(&self) -> MindmapResult<DatabaseStats> {
-        let _conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
             message: "Failed to acquire database lock".to_string(),
         })?;
 
         let file_size = std::fs::metadata(&self.config.path)
             .map(|m| m.len())
             .unwrap_or(0);
+        let wal_size_bytes = std::fs::metadata(format!("{}-wal", self.config.path))
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        let pragma = |name: &str| -> MindmapResult<i64> {
+            conn.query_row(&format!("PRAGMA {}", name), params![], |row| row.get(0))
+                .map_err(|e| MindmapError::DatabaseError {
+                    message: format!("Failed to read {}: {}", name, e),
+                })
+        };
 
         Ok(DatabaseStats {
             document_count: 0,
             node_count: 0,
             edge_count: 0,
             file_size,
-            page_count: 0,
-            free_pages: 0,
-            schema_version: 1,
+            page_count: pragma("page_count")? as u64,
+            free_pages: pragma("freelist_count")? as u64,
+            schema_version: pragma("user_version")? as u32,
+            wal_size_bytes,
+        })
+    }
+
+    /// Save a document, replacing any stored copy with the same ID
+    ///
+    /// Timestamps are stored in milliseconds. The title is kept in the
+    /// metadata, which always mirrors it.
+    pub fn save_document(&mut self, document: &Document) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let mut metadata = document.metadata.clone();
+        metadata.title = document.title.clone();
+        let metadata_json = serde_json::to_string(&metadata).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Failed to serialize document metadata: {}", e),
+        })?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO documents (
+                id, metadata, root_node_id,
+                created_at, updated_at, last_saved_at, is_dirty, version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                document.id.as_uuid().to_string(),
+                metadata_json,
+                document.has_root_node().then(|| document.root_node.as_uuid().to_string()),
+                document.created_at.timestamp_millis(),
+                document.updated_at.timestamp_millis(),
+                document.last_saved_at.map(|ts| ts.timestamp_millis()),
+                document.is_dirty,
+                document.version as i64
+            ],
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to save document: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Save a document as the next version after the one it is based on
+    ///
+    /// Stores the document with `version() + 1` and returns that version. If
+    /// another writer has already stored a newer version than
+    /// `document.version()`, nothing is written and a
+    /// [`MindmapError::ConflictError`] is returned. The check and the write
+    /// run in one immediate transaction, so concurrent connections to the
+    /// same file can't both win.
+    pub fn save_document_version(&mut self, document: &Document) -> MindmapResult<u64> {
+        let expected = document.version;
+        let mut next = document.clone();
+        next.version = expected + 1;
+
+        self.execute_immediate(|db| {
+            if let Some(current) = db.document_version(document.id)? {
+                if current > expected {
+                    return Err(MindmapError::ConflictError { current, expected });
+                }
+            }
+            db.save_document(&next)
+        })?;
+
+        Ok(next.version)
+    }
+
+    /// The stored version of a document, or `None` if it was never saved
+    pub fn document_version(&self, id: DocumentId) -> MindmapResult<Option<u64>> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let version: Option<i64> = conn.query_row(
+            "SELECT version FROM documents WHERE id = ?1",
+            params![id.as_uuid().to_string()],
+            |row| row.get(0),
+        ).optional().map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to read document version: {}", e),
+        })?;
+
+        Ok(version.map(|version| version as u64))
+    }
+
+    /// Run `f` in a transaction that takes the write lock up front
+    fn execute_immediate<T, F>(&mut self, f: F) -> MindmapResult<T>
+    where
+        F: FnOnce(&mut Self) -> MindmapResult<T>,
+    {
+        self.execute_batch("BEGIN IMMEDIATE")?;
+        match f(self) {
+            Ok(result) => {
+                self.execute_batch("COMMIT")?;
+                Ok(result)
+            }
+            Err(e) => {
+                self.execute_batch("ROLLBACK").ok();
+                Err(e)
+            }
+        }
+    }
+
+    /// Run SQL statements that return no rows
+    fn execute_batch(&self, sql: &str) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+        conn.execute_batch(sql).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to run \"{}\": {}", sql, e),
+        })
+    }
+
+    /// Load a document, or `None` if no document has the ID
+    pub fn load_document(&self, id: DocumentId) -> MindmapResult<Option<Document>> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        conn.query_row(
+            "SELECT id, metadata, root_node_id,
+                    created_at, updated_at, last_saved_at, is_dirty, version
+             FROM documents WHERE id = ?1",
+            params![id.as_uuid().to_string()],
+            document_from_row,
+        ).optional().map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to load document: {}", e),
         })
     }
 
@@ -67,7 +218,8 @@ impl SimpleSqliteDatabase {
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 last_saved_at INTEGER,
-                is_dirty BOOLEAN NOT NULL DEFAULT FALSE
+                is_dirty BOOLEAN NOT NULL DEFAULT FALSE,
+                version INTEGER NOT NULL DEFAULT 0
             )
             "#,
             params![],
@@ -75,6 +227,9 @@ impl SimpleSqliteDatabase {
             message: format!("Failed to create documents table: {}", e),
         })?;
 
+        // Databases created before documents were versioned lack the column
+        add_missing_column(&conn, "documents", "version", "INTEGER NOT NULL DEFAULT 0")?;
+
         conn.execute(
             r#"
             CREATE TABLE IF NOT EXISTS nodes (
@@ -89,7 +244,11 @@ impl SimpleSqliteDatabase {
                 style TEXT NOT NULL,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
-                version INTEGER NOT NULL DEFAULT 1
+                version INTEGER NOT NULL DEFAULT 1,
+                collapsed BOOLEAN NOT NULL DEFAULT FALSE,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                pinned BOOLEAN NOT NULL DEFAULT FALSE,
+                note TEXT
             )
             "#,
             params![],
@@ -97,6 +256,12 @@ impl SimpleSqliteDatabase {
             message: format!("Failed to create nodes table: {}", e),
         })?;
 
+        // Outline state was added after the first node schema
+        add_missing_column(&conn, "nodes", "collapsed", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+        add_missing_column(&conn, "nodes", "sort_order", "INTEGER NOT NULL DEFAULT 0")?;
+        add_missing_column(&conn, "nodes", "pinned", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+        add_missing_column(&conn, "nodes", "note", "TEXT")?;
+
         conn.execute(
             r#"
             CREATE TABLE IF NOT EXISTS edges (
@@ -107,7 +272,8 @@ impl SimpleSqliteDatabase {
                 style TEXT NOT NULL,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
-                version INTEGER NOT NULL DEFAULT 1
+                version INTEGER NOT NULL DEFAULT 1,
+                weight REAL
             )
             "#,
             params![],
@@ -115,6 +281,428 @@ impl SimpleSqliteDatabase {
             message: format!("Failed to create edges table: {}", e),
         })?;
 
+        add_missing_column(&conn, "edges", "weight", "REAL")?;
+
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS document_nodes (
+                document_id TEXT NOT NULL,
+                node_id TEXT NOT NULL,
+                PRIMARY KEY (document_id, node_id)
+            );
+            CREATE TABLE IF NOT EXISTS document_edges (
+                document_id TEXT NOT NULL,
+                edge_id TEXT NOT NULL,
+                PRIMARY KEY (document_id, edge_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_document_nodes_node ON document_nodes(node_id);
+            CREATE INDEX IF NOT EXISTS idx_document_edges_edge ON document_edges(edge_id);
+            "#,
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to create document association tables: {}", e),
+        })?;
+
+        // Attachment contents live here rather than in the node rows
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS attachments (
+                id TEXT PRIMARY KEY,
+                document_id TEXT NOT NULL,
+                node_id TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                data BLOB NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_attachments_document ON attachments(document_id);
+            "#,
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to create attachments table: {}", e),
+        })?;
+
+        migrate_schema(&conn)
+    }
+
+    /// Find a document's nodes whose text or tags contain `query`
+    ///
+    /// Searches the database's full-text index, so the graph need not be
+    /// loaded. Matching is case-insensitive on substrings of three or more
+    /// characters, ranked by BM25; shorter queries fall back to a plain
+    /// substring scan of node text. Returns node IDs with their text, best
+    /// match first.
+    pub fn search_nodes(&self, document_id: DocumentId, query: &str, limit: usize) -> MindmapResult<Vec<(NodeId, String)>> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+        let query_error = |e: rusqlite::Error| MindmapError::DatabaseError {
+            message: format!("Failed to search nodes: {}", e),
+        };
+
+        // The trigram index can't match fewer than three characters
+        let (sql, pattern) = if query.chars().count() >= 3 {
+            (
+                "SELECT n.id, n.text FROM nodes_fts
+                 JOIN nodes n ON n.rowid = nodes_fts.rowid
+                 JOIN document_nodes dn ON dn.node_id = n.id
+                 WHERE nodes_fts MATCH ?1 AND dn.document_id = ?2
+                 ORDER BY bm25(nodes_fts)
+                 LIMIT ?3",
+                format!("\"{}\"", query.replace('"', "\"\"")),
+            )
+        } else {
+            (
+                "SELECT n.id, n.text FROM nodes n
+                 JOIN document_nodes dn ON dn.node_id = n.id
+                 WHERE n.text LIKE ?1 ESCAPE '\\' AND dn.document_id = ?2
+                 ORDER BY length(n.text)
+                 LIMIT ?3",
+                format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")),
+            )
+        };
+
+        let mut statement = conn.prepare(sql).map_err(query_error)?;
+        let rows = statement.query_map(
+            params![pattern, document_id.as_uuid().to_string(), limit as i64],
+            |row| Ok((id_column(row, 0, "id")?, row.get(1)?)),
+        ).map_err(query_error)?;
+
+        rows.collect::<rusqlite::Result<_>>().map_err(query_error)
+    }
+
+    /// Save a node, replacing any stored copy with the same ID
+    pub fn save_node(&mut self, node: &Node) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        // Update in place rather than replace, so the search index triggers see the change
+        conn.execute(
+            "INSERT INTO nodes (
+                id, parent_id, text, position_x, position_y, metadata, tags, attachments,
+                style, created_at, updated_at, collapsed, sort_order, pinned, note
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            ON CONFLICT(id) DO UPDATE SET
+                parent_id = excluded.parent_id, text = excluded.text,
+                position_x = excluded.position_x, position_y = excluded.position_y,
+                metadata = excluded.metadata, tags = excluded.tags,
+                attachments = excluded.attachments, style = excluded.style,
+                created_at = excluded.created_at, updated_at = excluded.updated_at,
+                collapsed = excluded.collapsed, sort_order = excluded.sort_order,
+                pinned = excluded.pinned, note = excluded.note",
+            params![
+                node.id.as_uuid().to_string(),
+                node.parent_id.map(|id| id.as_uuid().to_string()),
+                node.text,
+                self.config.position_encoding.quantize(node.position.x),
+                self.config.position_encoding.quantize(node.position.y),
+                node_json(&node.metadata, "metadata")?,
+                node_json(&node.tags, "tags")?,
+                node_json(&node.attachments, "attachments")?,
+                node_json(&node.style, "style")?,
+                node.created_at.timestamp_millis(),
+                node.updated_at.timestamp_millis(),
+                node.collapsed,
+                node.order,
+                node.pinned,
+                node.note
+            ],
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to save node: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Load a node, or `None` if no node has the ID
+    pub fn load_node(&self, id: NodeId) -> MindmapResult<Option<Node>> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        conn.query_row(
+            &format!("SELECT {} FROM nodes WHERE id = ?1", NODE_COLUMNS),
+            params![id.as_uuid().to_string()],
+            node_from_row,
+        ).optional().map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to load node: {}", e),
+        })
+    }
+
+    /// Save an edge, replacing any stored copy with the same ID
+    pub fn save_edge(&mut self, edge: &Edge) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let style_json = serde_json::to_string(&edge.style).map_err(|e| MindmapError::InvalidOperation {
+            message: format!("Failed to serialize edge style: {}", e),
+        })?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO edges (
+                id, from_node_id, to_node_id, label, style,
+                created_at, updated_at, weight
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                edge.id.as_uuid().to_string(),
+                edge.from_node.as_uuid().to_string(),
+                edge.to_node.as_uuid().to_string(),
+                edge.label,
+                style_json,
+                edge.created_at.timestamp_millis(),
+                edge.updated_at.timestamp_millis(),
+                edge.weight
+            ],
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to save edge: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Load an edge, or `None` if no edge has the ID
+    pub fn load_edge(&self, id: EdgeId) -> MindmapResult<Option<Edge>> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        conn.query_row(
+            &format!("SELECT {} FROM edges WHERE id = ?1", EDGE_COLUMNS),
+            params![id.as_uuid().to_string()],
+            edge_from_row,
+        ).optional().map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to load edge: {}", e),
+        })
+    }
+
+    /// Save the contents of a node attachment
+    ///
+    /// Attachments never change once added, so contents already stored under
+    /// the ID are kept. Contents larger than [`MAX_ATTACHMENT_SIZE`] are
+    /// rejected with `AttachmentTooLarge`.
+    pub fn save_attachment(
+        &mut self,
+        document_id: DocumentId,
+        node_id: NodeId,
+        attachment_id: AttachmentId,
+        data: &[u8],
+    ) -> MindmapResult<()> {
+        let size = data.len() as u64;
+        if size > MAX_ATTACHMENT_SIZE {
+            return Err(MindmapError::AttachmentTooLarge { size, limit: MAX_ATTACHMENT_SIZE });
+        }
+
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        conn.execute(
+            "INSERT INTO attachments (id, document_id, node_id, size, data) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO NOTHING",
+            params![
+                attachment_id.as_uuid().to_string(),
+                document_id.as_uuid().to_string(),
+                node_id.as_uuid().to_string(),
+                size as i64,
+                data
+            ],
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to save attachment: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Load the contents of an attachment, or `None` if none are stored
+    pub fn load_attachment(&self, attachment_id: AttachmentId) -> MindmapResult<Option<Vec<u8>>> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        conn.query_row(
+            "SELECT data FROM attachments WHERE id = ?1",
+            params![attachment_id.as_uuid().to_string()],
+            |row| row.get(0),
+        ).optional().map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to load attachment: {}", e),
+        })
+    }
+
+    /// Save the nodes and edges of a document's graph
+    ///
+    /// Replaces whatever graph was stored for the document before, including
+    /// removing nodes and edges that are no longer in it. Attachment contents
+    /// are written only when not stored yet, and dropped once no node of the
+    /// document refers to them.
+    pub fn save_graph(&mut self, document_id: DocumentId, graph: &Graph) -> MindmapResult<()> {
+        self.with_transaction(|db| {
+            db.clear_document_graph(document_id)?;
+
+            for node in graph.nodes() {
+                db.save_node(node)?;
+            }
+            for edge in graph.edges() {
+                db.save_edge(edge)?;
+            }
+
+            db.associate_graph_with_document(document_id, graph)?;
+            db.sync_document_attachments(document_id, graph)
+        })
+    }
+
+    /// Load the graph stored for a document
+    ///
+    /// A document without a stored graph loads as an empty graph.
+    pub fn load_graph(&self, document_id: DocumentId) -> MindmapResult<Graph> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+        let query_error = |e: rusqlite::Error| MindmapError::DatabaseError {
+            message: format!("Failed to load graph: {}", e),
+        };
+
+        let document_id = document_id.as_uuid().to_string();
+        let nodes: Vec<Node> = conn.prepare(&format!(
+            "SELECT {} FROM nodes
+             WHERE id IN (SELECT node_id FROM document_nodes WHERE document_id = ?1)",
+            NODE_COLUMNS,
+        )).map_err(query_error)?
+            .query_map(params![document_id], node_from_row).map_err(query_error)?
+            .collect::<rusqlite::Result<_>>().map_err(query_error)?;
+
+        let edges: Vec<Edge> = conn.prepare(&format!(
+            "SELECT {} FROM edges
+             WHERE id IN (SELECT edge_id FROM document_edges WHERE document_id = ?1)",
+            EDGE_COLUMNS,
+        )).map_err(query_error)?
+            .query_map(params![document_id], edge_from_row).map_err(query_error)?
+            .collect::<rusqlite::Result<_>>().map_err(query_error)?;
+
+        let attachments: Vec<(String, Vec<u8>)> = conn.prepare(
+            "SELECT id, data FROM attachments WHERE document_id = ?1",
+        ).map_err(query_error)?
+            .query_map(params![document_id], |row| Ok((row.get(0)?, row.get(1)?))).map_err(query_error)?
+            .collect::<rusqlite::Result<_>>().map_err(query_error)?;
+
+        // Rows come back in no particular order; the bulk insert puts parents first
+        let mut graph = Graph::new();
+        graph.add_nodes_bulk(nodes)?;
+        for edge in edges {
+            graph.add_edge(edge)?;
+        }
+        for (id, data) in attachments {
+            if let Ok(attachment_id) = AttachmentId::from_str(&id) {
+                graph.insert_attachment_data(attachment_id, data);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Load a document together with its graph
+    ///
+    /// A stored root that is not among the loaded nodes (e.g. after
+    /// corruption or a partial save) is repaired according to `policy`; see
+    /// [`Graph::resolve_document_root`]. Repairs are not written back until
+    /// the document is saved again.
+    pub fn load_document_graph(&self, document_id: DocumentId, policy: MissingRootPolicy) -> MindmapResult<(Document, Graph)> {
+        let mut document = self.load_document(document_id)?
+            .ok_or(MindmapError::DocumentNotFound { id: document_id })?;
+        let mut graph = self.load_graph(document_id)?;
+        graph.resolve_document_root(&mut document, policy)?;
+        Ok((document, graph))
+    }
+
+    /// Store new attachment contents of a document's graph and drop unused ones
+    fn sync_document_attachments(&mut self, document_id: DocumentId, graph: &Graph) -> MindmapResult<()> {
+        let referenced: HashMap<AttachmentId, NodeId> = graph.nodes()
+            .flat_map(|node| node.attachments.iter().filter_map(move |attachment| {
+                attachment.attachment_id().map(|attachment_id| (attachment_id, node.id))
+            }))
+            .collect();
+
+        let stored: HashSet<AttachmentId> = {
+            let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+                message: "Failed to acquire database lock".to_string(),
+            })?;
+            let query_error = |e: rusqlite::Error| MindmapError::DatabaseError {
+                message: format!("Failed to sync attachments: {}", e),
+            };
+
+            let stored: Vec<String> = conn.prepare("SELECT id FROM attachments WHERE document_id = ?1")
+                .map_err(query_error)?
+                .query_map(params![document_id.as_uuid().to_string()], |row| row.get(0)).map_err(query_error)?
+                .collect::<rusqlite::Result<_>>().map_err(query_error)?;
+            let stored: HashSet<AttachmentId> = stored.iter().filter_map(|id| id.parse().ok()).collect();
+
+            for unused in stored.iter().filter(|id| !referenced.contains_key(id)) {
+                conn.execute(
+                    "DELETE FROM attachments WHERE id = ?1 AND document_id = ?2",
+                    params![unused.as_uuid().to_string(), document_id.as_uuid().to_string()],
+                ).map_err(query_error)?;
+            }
+            stored
+        };
+
+        // Attachments that only record a file path have no contents to store
+        for (&attachment_id, &node_id) in referenced.iter().filter(|(id, _)| !stored.contains(id)) {
+            if let Some(data) = graph.get_attachment(attachment_id) {
+                self.save_attachment(document_id, node_id, attachment_id, data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete the nodes and edges stored for a document, and their associations
+    fn clear_document_graph(&mut self, document_id: DocumentId) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let document_id = document_id.as_uuid().to_string();
+        for statement in [
+            "DELETE FROM nodes WHERE id IN (SELECT node_id FROM document_nodes WHERE document_id = ?1)",
+            "DELETE FROM edges WHERE id IN (SELECT edge_id FROM document_edges WHERE document_id = ?1)",
+            "DELETE FROM document_nodes WHERE document_id = ?1",
+            "DELETE FROM document_edges WHERE document_id = ?1",
+        ] {
+            conn.execute(statement, params![document_id]).map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to clear document graph: {}", e),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Record which nodes and edges belong to a document
+    fn associate_graph_with_document(&mut self, document_id: DocumentId, graph: &Graph) -> MindmapResult<()> {
+        let conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let document_id = document_id.as_uuid().to_string();
+        for node in graph.nodes() {
+            conn.execute(
+                "INSERT INTO document_nodes (document_id, node_id) VALUES (?1, ?2)",
+                params![document_id, node.id.as_uuid().to_string()],
+            ).map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to associate node with document: {}", e),
+            })?;
+        }
+
+        for edge in graph.edges() {
+            conn.execute(
+                "INSERT INTO document_edges (document_id, edge_id) VALUES (?1, ?2)",
+                params![document_id, edge.id.as_uuid().to_string()],
+            ).map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to associate edge with document: {}", e),
+            })?;
+        }
+
         Ok(())
     }
 }
@@ -209,20 +797,248 @@ impl DatabaseOperations for SimpleSqliteDatabase {
                 message: format!("Failed to analyze database: {}", e),
             })?;
 
+        // Rebuild the file without its free pages
+        conn.execute("VACUUM", params![])
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to vacuum database: {}", e),
+            })?;
+
+        // Copy the WAL back into the database and truncate it
+        if self.config.wal_mode {
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", params![], |row| row.get::<_, i64>(0))
+                .map_err(|e| MindmapError::DatabaseError {
+                    message: format!("Failed to checkpoint WAL: {}", e),
+                })?;
+        }
+
         Ok(())
     }
 
-    fn backup(&self, _path: &str) -> MindmapResult<()> {
-        // Simplified implementation - just return ok for now
+    fn backup(&self, path: &str) -> MindmapResult<()> {
+        let source_conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let mut backup_conn = Connection::open(path).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to open backup database: {}", e),
+        })?;
+
+        let backup = rusqlite::backup::Backup::new(&source_conn, &mut backup_conn)
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to create backup: {}", e),
+            })?;
+
+        backup.run_to_completion(5, Duration::from_millis(100), None)
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to complete backup: {}", e),
+            })?;
+
         Ok(())
     }
 
-    fn restore(&mut self, _path: &str) -> MindmapResult<()> {
-        // Simplified implementation - just return ok for now
+    fn restore(&mut self, path: &str) -> MindmapResult<()> {
+        // Opening a missing path would create an empty database and restore nothing
+        if !Path::new(path).is_file() {
+            return Err(MindmapError::DatabaseError {
+                message: format!("Backup file not found: {}", path),
+            });
+        }
+
+        let source_conn = Connection::open(path).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to open backup database: {}", e),
+        })?;
+
+        let mut target_conn = self.connection.lock().map_err(|_| MindmapError::DatabaseError {
+            message: "Failed to acquire database lock".to_string(),
+        })?;
+
+        let backup = rusqlite::backup::Backup::new(&source_conn, &mut target_conn)
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to create restore backup: {}", e),
+            })?;
+
+        backup.run_to_completion(5, Duration::from_millis(100), None)
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to complete restore: {}", e),
+            })?;
+
         Ok(())
     }
 }
 
+/// Columns read by [`node_from_row`], in order
+const NODE_COLUMNS: &str = "id, parent_id, text, position_x, position_y, metadata, tags, attachments, \
+    style, created_at, updated_at, collapsed, sort_order, pinned, note";
+
+/// Columns read by [`edge_from_row`], in order
+const EDGE_COLUMNS: &str = "id, from_node_id, to_node_id, label, style, created_at, updated_at, weight";
+
+/// Schema version recorded in `PRAGMA user_version` once all upgrades ran
+const SCHEMA_VERSION: i64 = 2;
+
+/// Apply the versioned upgrades a database has not seen yet
+///
+/// Version 1 is the base tables created by `init_schema`. Version 2 adds the
+/// `nodes_fts` full-text index over node text and tags, kept in sync by
+/// triggers and filled from any nodes already stored.
+fn migrate_schema(conn: &Connection) -> MindmapResult<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", params![], |row| row.get(0))
+        .map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to read schema version: {}", e),
+        })?;
+
+    if version < 2 {
+        conn.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(
+                text,
+                tags,
+                content='nodes',
+                content_rowid='rowid',
+                tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS nodes_fts_insert AFTER INSERT ON nodes BEGIN
+                INSERT INTO nodes_fts(rowid, text, tags) VALUES (new.rowid, new.text, new.tags);
+            END;
+            CREATE TRIGGER IF NOT EXISTS nodes_fts_delete AFTER DELETE ON nodes BEGIN
+                INSERT INTO nodes_fts(nodes_fts, rowid, text, tags) VALUES ('delete', old.rowid, old.text, old.tags);
+            END;
+            CREATE TRIGGER IF NOT EXISTS nodes_fts_update AFTER UPDATE ON nodes BEGIN
+                INSERT INTO nodes_fts(nodes_fts, rowid, text, tags) VALUES ('delete', old.rowid, old.text, old.tags);
+                INSERT INTO nodes_fts(rowid, text, tags) VALUES (new.rowid, new.text, new.tags);
+            END;
+            INSERT INTO nodes_fts(nodes_fts) VALUES ('rebuild');
+            "#,
+        ).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to create node search index: {}", e),
+        })?;
+    }
+
+    if version < SCHEMA_VERSION {
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION).map_err(|e| MindmapError::DatabaseError {
+            message: format!("Failed to record schema version: {}", e),
+        })?;
+    }
+    Ok(())
+}
+
+/// Add a column to a table created by an older schema, if it is missing
+fn add_missing_column(conn: &Connection, table: &str, column: &str, definition: &str) -> MindmapResult<()> {
+    let exists: bool = conn.query_row(
+        &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?1", table),
+        params![column],
+        |row| row.get(0),
+    ).map_err(|e| MindmapError::DatabaseError {
+        message: format!("Failed to inspect {} table: {}", table, e),
+    })?;
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), params![])
+            .map_err(|e| MindmapError::DatabaseError {
+                message: format!("Failed to add {}.{} column: {}", table, column, e),
+            })?;
+    }
+    Ok(())
+}
+
+/// Serialize a node field for a JSON text column
+fn node_json(value: &impl serde::Serialize, field: &str) -> MindmapResult<String> {
+    serde_json::to_string(value).map_err(|e| MindmapError::InvalidOperation {
+        message: format!("Failed to serialize node {}: {}", field, e),
+    })
+}
+
+/// Timestamp stored in milliseconds in column `index`
+fn timestamp_column(row: &Row<'_>, index: usize, column: &str) -> rusqlite::Result<Timestamp> {
+    Timestamp::from_timestamp_millis(row.get(index)?).ok_or_else(|| {
+        rusqlite::Error::InvalidColumnType(index, column.to_string(), rusqlite::types::Type::Integer)
+    })
+}
+
+/// Value stored as JSON text in column `index`
+fn json_column<T: serde::de::DeserializeOwned>(row: &Row<'_>, index: usize, column: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(&row.get::<_, String>(index)?).map_err(|_| {
+        rusqlite::Error::InvalidColumnType(index, column.to_string(), rusqlite::types::Type::Text)
+    })
+}
+
+/// ID stored as text in column `index`
+fn id_column<T: FromStr>(row: &Row<'_>, index: usize, column: &str) -> rusqlite::Result<T> {
+    T::from_str(&row.get::<_, String>(index)?).map_err(|_| {
+        rusqlite::Error::InvalidColumnType(index, column.to_string(), rusqlite::types::Type::Text)
+    })
+}
+
+/// Build a node from a row of [`NODE_COLUMNS`]
+fn node_from_row(row: &Row<'_>) -> rusqlite::Result<Node> {
+    Ok(Node {
+        id: id_column(row, 0, "id")?,
+        parent_id: match row.get::<_, Option<String>>(1)? {
+            Some(_) => Some(id_column(row, 1, "parent_id")?),
+            None => None,
+        },
+        text: row.get(2)?,
+        position: Point::new(row.get(3)?, row.get(4)?),
+        metadata: json_column(row, 5, "metadata")?,
+        tags: json_column(row, 6, "tags")?,
+        attachments: json_column(row, 7, "attachments")?,
+        style: json_column(row, 8, "style")?,
+        created_at: timestamp_column(row, 9, "created_at")?,
+        updated_at: timestamp_column(row, 10, "updated_at")?,
+        collapsed: row.get(11)?,
+        order: row.get(12)?,
+        pinned: row.get(13)?,
+        note: row.get(14)?,
+    })
+}
+
+/// Build an edge from a row of [`EDGE_COLUMNS`]
+fn edge_from_row(row: &Row<'_>) -> rusqlite::Result<Edge> {
+    Ok(Edge {
+        id: id_column(row, 0, "id")?,
+        from_node: id_column(row, 1, "from_node_id")?,
+        to_node: id_column(row, 2, "to_node_id")?,
+        label: row.get(3)?,
+        style: json_column(row, 4, "style")?,
+        weight: row.get(7)?,
+        created_at: timestamp_column(row, 5, "created_at")?,
+        updated_at: timestamp_column(row, 6, "updated_at")?,
+    })
+}
+
+/// Build a document from a `documents` row
+fn document_from_row(row: &Row<'_>) -> rusqlite::Result<Document> {
+    let invalid = |index: usize, column: &str| {
+        rusqlite::Error::InvalidColumnType(index, column.to_string(), rusqlite::types::Type::Text)
+    };
+    let timestamp = |millis: i64, index: usize, column: &str| {
+        Timestamp::from_timestamp_millis(millis).ok_or_else(|| invalid(index, column))
+    };
+
+    let metadata: DocumentMetadata = serde_json::from_str(&row.get::<_, String>(1)?)
+        .map_err(|_| invalid(1, "metadata"))?;
+    let root_node_id: Option<String> = row.get(2)?;
+
+    Ok(Document {
+        id: DocumentId::from_str(&row.get::<_, String>(0)?).map_err(|_| invalid(0, "id"))?,
+        title: metadata.title.clone(),
+        root_node: root_node_id
+            .as_deref()
+            .map(NodeId::from_str)
+            .transpose()
+            .map_err(|_| invalid(2, "root_node_id"))?
+            .unwrap_or(NodeId::from_uuid(uuid::Uuid::nil())),
+        metadata,
+        created_at: timestamp(row.get(3)?, 3, "created_at")?,
+        updated_at: timestamp(row.get(4)?, 4, "updated_at")?,
+        last_saved_at: row.get::<_, Option<i64>>(5)?
+            .map(|millis| timestamp(millis, 5, "last_saved_at"))
+            .transpose()?,
+        is_dirty: row.get(6)?,
+        version: row.get::<_, i64>(7)? as u64,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +1086,19 @@ mod tests {
         assert!(db.rollback_transaction().is_ok());
     }
 
+    #[test]
+    fn test_positions_stored_with_configured_precision() {
+        let config = create_test_config().with_position_encoding(PositionEncoding::integer());
+        let mut db = SimpleSqliteDatabase::open(&config).unwrap();
+
+        let mut node = Node::new("Rounded");
+        node.position = Point::new(12.4, -40.6);
+        db.save_node(&node).unwrap();
+
+        let loaded = db.load_node(node.id).unwrap().unwrap();
+        assert_eq!(loaded.position, Point::new(12.0, -41.0));
+    }
+
     #[test]
     fn test_with_transaction() {
         let config = create_test_config();
@@ -279,4 +1108,230 @@ mod tests {
         let result = db.with_transaction(|_| Ok(42));
         assert_eq!(result.unwrap(), 42);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_node_save_load() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let parent = Node::new("Parent");
+        let mut node = Node::new_child(parent.id, "Test Node");
+        node.position = Point::new(12.5, -40.0);
+        node.add_tag("draft");
+        node.set_metadata("owner", "ana");
+        node.order = 3;
+        node.collapsed = true;
+        node.set_note(Some("Longer *markdown* note".to_string()));
+
+        assert!(db.save_node(&node).is_ok());
+
+        let loaded = db.load_node(node.id).unwrap().unwrap();
+        assert_eq!(loaded.id, node.id);
+        assert_eq!(loaded.text, "Test Node");
+        assert_eq!(loaded.parent_id, Some(parent.id));
+        assert_eq!(loaded.position, node.position);
+        assert_eq!(loaded.tags, node.tags);
+        assert_eq!(loaded.metadata, node.metadata);
+        assert_eq!(loaded.style, node.style);
+        assert_eq!((loaded.order, loaded.collapsed), (3, true));
+        assert_eq!(loaded.note, node.note);
+
+        assert!(db.load_node(parent.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_edge_save_load() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let node1 = Node::new("Node 1");
+        let node2 = Node::new("Node 2");
+        let mut edge = Edge::new(node1.id, node2.id);
+        edge.label = Some("depends on".to_string());
+        edge.weight = Some(0.5);
+
+        assert!(db.save_edge(&edge).is_ok());
+
+        let loaded = db.load_edge(edge.id).unwrap().unwrap();
+        assert_eq!(loaded.id, edge.id);
+        assert_eq!(loaded.from_node, node1.id);
+        assert_eq!(loaded.to_node, node2.id);
+        assert_eq!(loaded.label.as_deref(), Some("depends on"));
+        assert_eq!(loaded.weight, Some(0.5));
+    }
+
+    #[test]
+    fn test_graph_save_load() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Root")).unwrap();
+        let child = graph.add_node(Node::new_child(root, "Child")).unwrap();
+        let grandchild = graph.add_node(Node::new_child(child, "Grandchild")).unwrap();
+        let link = graph.add_edge(Edge::new(grandchild, root)).unwrap();
+        let document = Document::new("Graph", root);
+        db.save_document(&document).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+
+        let loaded = db.load_graph(document.id).unwrap();
+        assert_eq!(loaded.node_count(), 3);
+        assert_eq!(loaded.get_node(grandchild).unwrap().parent_id, Some(child));
+        assert!(loaded.contains_edge(link));
+
+        // Saving again replaces the stored graph
+        graph.remove_node(grandchild).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+        let loaded = db.load_graph(document.id).unwrap();
+        assert_eq!(loaded.node_count(), 2);
+        assert_eq!(loaded.edge_count(), 0);
+        assert!(db.load_node(grandchild).unwrap().is_none());
+
+        assert_eq!(db.load_graph(DocumentId::new()).unwrap().node_count(), 0);
+    }
+
+    #[test]
+    fn test_load_document_graph_with_missing_root() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let mut graph = Graph::new();
+        let topic = graph.add_node(Node::new("Topic")).unwrap();
+        graph.add_node(Node::new_child(topic, "Detail")).unwrap();
+        // The stored root has no matching node, as after a partial save
+        let missing = NodeId::new();
+        let document = Document::new("Partial", missing);
+        db.save_document(&document).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+
+        let error = db.load_document_graph(document.id, MissingRootPolicy::Error).unwrap_err();
+        assert!(matches!(error, MindmapError::NodeNotFound { id } if id == missing));
+
+        let (picked, _) = db.load_document_graph(document.id, MissingRootPolicy::PickAvailableRoot).unwrap();
+        assert_eq!(picked.get_root_node(), topic);
+
+        let (created, loaded) = db.load_document_graph(document.id, MissingRootPolicy::CreateRoot).unwrap();
+        assert_eq!(loaded.get_node(created.get_root_node()).unwrap().text, "Partial");
+        assert_eq!(loaded.node_count(), 3);
+
+        // A cleared root is stored as NULL and stays cleared on the next load
+        let (cleared, _) = db.load_document_graph(document.id, MissingRootPolicy::Clear).unwrap();
+        assert!(!cleared.has_root_node());
+        db.save_document(&cleared).unwrap();
+        let (reloaded, _) = db.load_document_graph(document.id, MissingRootPolicy::Error).unwrap();
+        assert!(!reloaded.has_root_node());
+
+        let error = db.load_document_graph(DocumentId::new(), MissingRootPolicy::Error).unwrap_err();
+        assert!(matches!(error, MindmapError::DocumentNotFound { .. }));
+    }
+
+    #[test]
+    fn test_attachment_round_trip() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Root")).unwrap();
+        let child = graph.add_node(Node::new_child(root, "Child")).unwrap();
+        let data: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+        let attachment_id = graph.add_attachment(child, "scan.png", data.clone(), "image/png").unwrap();
+        let document = Document::new("Attachments", root);
+        db.save_document(&document).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+
+        // Contents are stored apart from the node, which keeps the description
+        assert_eq!(db.load_attachment(attachment_id).unwrap(), Some(data.clone()));
+        let loaded = db.load_graph(document.id).unwrap();
+        assert_eq!(loaded.get_attachment(attachment_id), Some(data.as_slice()));
+        let attachment = &loaded.get_node(child).unwrap().attachments[0];
+        assert_eq!((attachment.filename.as_str(), attachment.mime_type.as_str()), ("scan.png", "image/png"));
+        assert_eq!(attachment.size, data.len() as u64);
+
+        // Removing the node drops its attachment from storage on the next save
+        graph.remove_node(child).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+        assert_eq!(db.load_attachment(attachment_id).unwrap(), None);
+
+        let oversized = vec![0u8; MAX_ATTACHMENT_SIZE as usize + 1];
+        let error = db.save_attachment(document.id, root, AttachmentId::new(), &oversized).unwrap_err();
+        assert!(matches!(error, MindmapError::AttachmentTooLarge { .. }));
+    }
+
+    #[test]
+    fn test_pasted_attachment_is_stored_per_document() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Root")).unwrap();
+        let child = graph.add_node(Node::new_child(root, "Child")).unwrap();
+        let data = b"GIF89a tiny diagram".to_vec();
+        let attachment_id = graph.add_attachment(child, "diagram.gif", data.clone(), "image/gif").unwrap();
+        let document = Document::new("Original", root);
+        db.save_document(&document).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+
+        let mut other = Graph::new();
+        let other_root = other.add_node(Node::new("Other")).unwrap();
+        let pasted = other.paste_subtree(&graph.copy_subtree(child).unwrap(), Some(other_root)).unwrap();
+        let other_document = Document::new("Copy", other_root);
+        db.save_document(&other_document).unwrap();
+        db.save_graph(other_document.id, &other).unwrap();
+
+        // Removing the original leaves the pasted copy's contents in place
+        graph.remove_node(child).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+        assert_eq!(db.load_attachment(attachment_id).unwrap(), None);
+        let loaded = db.load_graph(other_document.id).unwrap();
+        let pasted_id = loaded.get_node(pasted).unwrap().attachments[0].attachment_id().unwrap();
+        assert_eq!(loaded.get_attachment(pasted_id), Some(data.as_slice()));
+    }
+
+    #[test]
+    fn test_search_nodes_by_substring() {
+        let mut db = SimpleSqliteDatabase::open(&create_test_config()).unwrap();
+
+        let mut graph = Graph::new();
+        let root = graph.add_node(Node::new("Product launch")).unwrap();
+        let pricing = graph.add_node(Node::new_child(root, "Competitive pricing analysis")).unwrap();
+        let mut tagged = Node::new_child(root, "Press kit");
+        tagged.add_tag("marketing");
+        let tagged = graph.add_node(tagged).unwrap();
+        let document = Document::new("Launch", root);
+        db.save_graph(document.id, &graph).unwrap();
+
+        // A substring in the middle of a word finds the node, case-insensitively
+        let results = db.search_nodes(document.id, "PRICING anal", 10).unwrap();
+        assert_eq!(results, vec![(pricing, "Competitive pricing analysis".to_string())]);
+        assert_eq!(db.search_nodes(document.id, "arketi", 10).unwrap()[0].0, tagged);
+        assert_eq!(db.search_nodes(document.id, "la", 10).unwrap()[0].0, root);
+
+        // Updated text is reindexed and other documents are not searched
+        let mut renamed = graph.get_node(pricing).unwrap().clone();
+        renamed.set_text("Pricing tiers");
+        db.save_node(&renamed).unwrap();
+        assert!(db.search_nodes(document.id, "analysis", 10).unwrap().is_empty());
+        assert_eq!(db.search_nodes(document.id, "tiers", 10).unwrap().len(), 1);
+        assert!(db.search_nodes(DocumentId::new(), "tiers", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_index_added_to_existing_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig::new(temp_dir.path().join("old.db").to_str().unwrap());
+        let node = Node::new("Quarterly roadmap");
+        let document_id = DocumentId::new();
+
+        // A database written before the search index existed
+        {
+            let mut db = SimpleSqliteDatabase::open(&config).unwrap();
+            let conn = db.connection.lock().unwrap();
+            conn.execute_batch(
+                "DROP TRIGGER nodes_fts_insert; DROP TRIGGER nodes_fts_update; DROP TRIGGER nodes_fts_delete;
+                 DROP TABLE nodes_fts; PRAGMA user_version = 0;",
+            ).unwrap();
+            drop(conn);
+            let mut graph = Graph::new();
+            graph.add_node(node.clone()).unwrap();
+            db.save_graph(document_id, &graph).unwrap();
+        }
+
+        let db = SimpleSqliteDatabase::open(&config).unwrap();
+        let results = db.search_nodes(document_id, "roadmap", 5).unwrap();
+        assert_eq!(results, vec![(node.id, "Quarterly roadmap".to_string())]);
+    }
+}
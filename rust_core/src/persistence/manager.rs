@@ -4,13 +4,21 @@
 //! auto-save functionality, backup mechanisms, and recovery capabilities.
 
 use super::{DatabaseConfig, DatabaseOperations, SimpleSqliteDatabase};
+use crate::events::EngineEvent;
 use crate::models::document::Document;
-use crate::types::{MindmapResult, MindmapError, Timestamp};
+use crate::types::{ids::DocumentId, MindmapResult, MindmapError, Timestamp};
+use crate::types::clock::Instant;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Slot holding the currently loaded document
+type DocumentSlot = Arc<RwLock<Option<Arc<RwLock<Document>>>>>;
+
 /// Configuration for persistence manager
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistenceConfig {
@@ -24,6 +32,9 @@ pub struct PersistenceConfig {
     pub compress_backups: bool,
     /// Minimum time between backups in seconds
     pub backup_interval: u64,
+    /// Age in seconds after which backups are deleted (0 to keep them regardless of age)
+    #[serde(default)]
+    pub max_backup_age: u64,
 }
 
 impl Default for PersistenceConfig {
@@ -34,6 +45,7 @@ impl Default for PersistenceConfig {
             backup_directory: "backups".to_string(),
             compress_backups: true,
             backup_interval: 300, // Backup every 5 minutes
+            max_backup_age: 30 * 24 * 60 * 60, // Keep backups for 30 days
         }
     }
 }
@@ -61,12 +73,14 @@ pub struct PersistenceManager {
     config: PersistenceConfig,
     /// Database configuration
     db_config: DatabaseConfig,
-    /// Currently loaded document
-    current_document: Option<Arc<RwLock<Document>>>,
+    /// Currently loaded document, shared with the auto-save task
+    current_document: DocumentSlot,
     /// Auto-save enabled flag
     auto_save_enabled: bool,
-    /// Last auto-save timestamp
-    last_auto_save: Option<SystemTime>,
+    /// Last auto-save timestamp, shared with the auto-save task
+    last_auto_save: Arc<RwLock<Option<SystemTime>>>,
+    /// Stops the background auto-save task when signalled or dropped
+    auto_save_stop: Mutex<Option<Sender<()>>>,
     /// Last backup timestamp
     last_backup: Option<SystemTime>,
 }
@@ -82,41 +96,66 @@ impl PersistenceManager {
             database,
             config,
             db_config,
-            current_document: None,
+            current_document: Arc::new(RwLock::new(None)),
             auto_save_enabled,
-            last_auto_save: None,
+            last_auto_save: Arc::new(RwLock::new(None)),
+            auto_save_stop: Mutex::new(None),
             last_backup: None,
         })
     }
 
+    /// The currently loaded document, if any
+    fn current(&self) -> MindmapResult<Option<Arc<RwLock<Document>>>> {
+        let slot = self.current_document.read().map_err(|_| MindmapError::InvalidOperation {
+            message: "Failed to acquire current document lock".to_string(),
+        })?;
+        Ok(slot.clone())
+    }
+
+    /// Replace the currently loaded document
+    fn set_current(&self, document: Option<Arc<RwLock<Document>>>) -> MindmapResult<()> {
+        let mut slot = self.current_document.write().map_err(|_| MindmapError::InvalidOperation {
+            message: "Failed to acquire current document lock".to_string(),
+        })?;
+        *slot = document;
+        Ok(())
+    }
+
     /// Load a document from the database
     pub fn load_document(&mut self, document_id: &str) -> MindmapResult<Arc<RwLock<Document>>> {
-        // For now, create a simple document since we haven't implemented full database CRUD
-        // This will be expanded when we implement the full database layer
-        let document = Document::new(
-            format!("Document {}", document_id),
-            crate::types::ids::NodeId::new()
-        );
+        let id = DocumentId::from_str(document_id).map_err(|_| MindmapError::InvalidOperation {
+            message: format!("Invalid document ID: {}", document_id),
+        })?;
+
+        let document = {
+            let db = self.database.read().map_err(|_| MindmapError::InvalidOperation {
+                message: "Failed to acquire database read lock".to_string(),
+            })?;
+            db.load_document(id)?.ok_or(MindmapError::DocumentNotFound { id })?
+        };
+
+        crate::events::emit(EngineEvent::DocumentOpened {
+            document_id: document.id,
+            title: document.title.clone(),
+        });
 
         let doc_arc = Arc::new(RwLock::new(document));
-        self.current_document = Some(doc_arc.clone());
+        self.set_current(Some(doc_arc.clone()))?;
 
         Ok(doc_arc)
     }
 
     /// Save the current document
     pub fn save_document(&mut self) -> MindmapResult<()> {
-        if let Some(ref document_arc) = self.current_document {
+        if let Some(document_arc) = self.current()? {
             let mut document = document_arc.write().map_err(|_| MindmapError::InvalidOperation {
                 message: "Failed to acquire document write lock".to_string(),
             })?;
+            let saved = write_document(&self.database, &mut document, &self.last_auto_save)?;
 
-            // Mark as saved and update timestamp
-            document.mark_saved();
-            self.last_auto_save = Some(SystemTime::now());
-
-            // In a full implementation, this would save to the database
-            // For now, we'll just mark it as saved
+            // Listeners may read the document, so release it first
+            drop(document);
+            crate::events::emit(saved);
             Ok(())
         } else {
             Err(MindmapError::InvalidOperation {
@@ -129,8 +168,12 @@ impl PersistenceManager {
     pub fn create_document(&mut self, title: impl Into<String>) -> MindmapResult<Arc<RwLock<Document>>> {
         let mut document = Document::new(title, crate::types::ids::NodeId::new());
         document.mark_dirty(); // New documents have unsaved changes
+        crate::events::emit(EngineEvent::DocumentOpened {
+            document_id: document.id,
+            title: document.title.clone(),
+        });
         let doc_arc = Arc::new(RwLock::new(document));
-        self.current_document = Some(doc_arc.clone());
+        self.set_current(Some(doc_arc.clone()))?;
 
         Ok(doc_arc)
     }
@@ -141,7 +184,10 @@ impl PersistenceManager {
             return Ok(false);
         }
 
-        let should_save = if let Some(last_save) = self.last_auto_save {
+        let last_auto_save = *self.last_auto_save.read().map_err(|_| MindmapError::InvalidOperation {
+            message: "Failed to acquire auto-save timestamp lock".to_string(),
+        })?;
+        let should_save = if let Some(last_save) = last_auto_save {
             let elapsed = SystemTime::now()
                 .duration_since(last_save)
                 .unwrap_or(Duration::from_secs(0));
@@ -160,7 +206,7 @@ impl PersistenceManager {
 
     /// Check if the current document has unsaved changes
     pub fn has_unsaved_changes(&self) -> MindmapResult<bool> {
-        if let Some(ref document_arc) = self.current_document {
+        if let Some(document_arc) = self.current()? {
             let document = document_arc.read().map_err(|_| MindmapError::InvalidOperation {
                 message: "Failed to acquire document read lock".to_string(),
             })?;
@@ -173,6 +219,53 @@ impl PersistenceManager {
     /// Enable or disable auto-save
     pub fn set_auto_save_enabled(&mut self, enabled: bool) {
         self.auto_save_enabled = enabled && self.config.auto_save_interval > 0;
+        if !self.auto_save_enabled {
+            self.stop_auto_save();
+        }
+    }
+
+    /// Start a background thread that saves the current document every auto-save interval
+    ///
+    /// The thread only saves when the document has unsaved changes, and
+    /// follows documents loaded or created after it starts. The interval is
+    /// read when the task starts. Starting a new task stops the previous one;
+    /// the task also stops on [`stop_auto_save`](Self::stop_auto_save), when
+    /// auto-save is disabled, or when the manager is dropped. If auto-save is
+    /// disabled, the returned thread exits immediately.
+    pub fn start_auto_save_task(&self) -> JoinHandle<()> {
+        self.stop_auto_save();
+
+        let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+        if self.auto_save_enabled {
+            if let Ok(mut stop) = self.auto_save_stop.lock() {
+                *stop = Some(stop_sender);
+            }
+        }
+
+        let interval = Duration::from_secs(self.config.auto_save_interval);
+        let database = self.database.clone();
+        let current_document = self.current_document.clone();
+        let last_auto_save = self.last_auto_save.clone();
+        thread::spawn(move || {
+            // Any message, or the sender being dropped, ends the loop
+            while let Err(RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                if let Err(e) = save_if_dirty(&database, &current_document, &last_auto_save) {
+                    log::warn!("Auto-save failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Stop the background auto-save task, if one is running
+    ///
+    /// The task finishes any save in progress and then exits; join its
+    /// handle to wait for that.
+    pub fn stop_auto_save(&self) {
+        if let Ok(mut stop) = self.auto_save_stop.lock() {
+            if let Some(sender) = stop.take() {
+                let _ = sender.send(());
+            }
+        }
     }
 
     /// Get auto-save status
@@ -216,30 +309,12 @@ impl PersistenceManager {
 
         let backup_path = backup_dir.join(&backup_filename);
 
-        // Perform the backup using database backup functionality
+        // Copy the live database page by page into the backup file
         {
             let db = self.database.read().map_err(|_| MindmapError::InvalidOperation {
                 message: "Failed to acquire database read lock".to_string(),
             })?;
-
-            // For now, create a simple backup file since the SimpleSqliteDatabase backup method is not fully implemented
-            // In a full implementation, this would use the actual database backup
-            match db.backup(backup_path.to_str().unwrap()) {
-                Ok(_) => {
-                    // The backup method succeeds but doesn't create a file, so we need a fallback
-                    if !backup_path.exists() {
-                        std::fs::write(&backup_path, b"backup_placeholder").map_err(|e| MindmapError::InvalidOperation {
-                            message: format!("Failed to create backup file: {}", e),
-                        })?;
-                    }
-                }
-                Err(_) => {
-                    // Fallback: create a simple backup marker file
-                    std::fs::write(&backup_path, b"backup_placeholder").map_err(|e| MindmapError::InvalidOperation {
-                        message: format!("Failed to create backup file: {}", e),
-                    })?;
-                }
-            }
+            db.backup(&backup_path.to_string_lossy())?;
         }
 
         // Get file size
@@ -248,7 +323,7 @@ impl PersistenceManager {
             .unwrap_or(0);
 
         // Get document title if available
-        let document_title = if let Some(ref document_arc) = self.current_document {
+        let document_title = if let Some(document_arc) = self.current()? {
             let document = document_arc.read().map_err(|_| MindmapError::InvalidOperation {
                 message: "Failed to acquire document read lock".to_string(),
             })?;
@@ -268,7 +343,11 @@ impl PersistenceManager {
         self.last_backup = Some(SystemTime::now());
 
         // Clean up old backups
-        self.cleanup_old_backups(&backup_dir)?;
+        let max_age = match self.config.max_backup_age {
+            0 => Duration::MAX,
+            seconds => Duration::from_secs(seconds),
+        };
+        self.prune_backups(self.config.max_backups as usize, max_age)?;
 
         Ok(backup_info)
     }
@@ -301,15 +380,7 @@ impl PersistenceManager {
                     })?;
 
                     // Extract timestamp from filename
-                    let created_at = if let Some(timestamp_str) = filename.strip_prefix("backup_").and_then(|s| s.strip_suffix(".db")) {
-                        if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                            chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_else(chrono::Utc::now)
-                        } else {
-                            chrono::Utc::now()
-                        }
-                    } else {
-                        chrono::Utc::now()
-                    };
+                    let created_at = backup_timestamp(filename).unwrap_or_else(chrono::Utc::now);
 
                     backups.push(BackupInfo {
                         filename: filename.to_string(),
@@ -343,62 +414,53 @@ impl PersistenceManager {
             });
         }
 
-        // Close current database connection
+        // Copy the backup over the live database, then reopen the restored file
         {
             let mut db = self.database.write().map_err(|_| MindmapError::InvalidOperation {
                 message: "Failed to acquire database write lock".to_string(),
             })?;
+            db.restore(&backup_path.to_string_lossy())?;
             db.close()?;
-        }
-
-        // Restore the backup
-        {
-            let mut db = self.database.write().map_err(|_| MindmapError::InvalidOperation {
-                message: "Failed to acquire database write lock".to_string(),
-            })?;
-            db.restore(backup_path.to_str().unwrap())?;
+            *db = SimpleSqliteDatabase::open(&self.db_config)?;
         }
 
         // Clear current document to force reload
-        self.current_document = None;
+        self.set_current(None)?;
 
         Ok(())
     }
 
-    /// Clean up old backups beyond the configured limit
-    fn cleanup_old_backups(&self, backup_dir: &Path) -> MindmapResult<()> {
-        let mut backup_files: Vec<PathBuf> = std::fs::read_dir(backup_dir)
-            .map_err(|e| MindmapError::InvalidOperation {
-                message: format!("Failed to read backup directory: {}", e),
-            })?
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                let path = entry.path();
-                if path.is_file() && path.extension().map(|s| s == "db").unwrap_or(false) {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
-            .collect();
+    /// Delete backups beyond `max_count` or older than `max_age`
+    ///
+    /// Ages come from the `backup_<timestamp>.db` file names; files not named
+    /// that way are left alone. The most recent backup is always kept, even
+    /// when it is older than `max_age`. Returns the deleted backups, newest
+    /// first.
+    pub fn prune_backups(&self, max_count: usize, max_age: Duration) -> MindmapResult<Vec<BackupInfo>> {
+        let backup_dir = Path::new(&self.db_config.path)
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(&self.config.backup_directory);
 
-        // Sort by modification time, newest first
-        backup_files.sort_by(|a, b| {
-            let a_time = std::fs::metadata(a).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
-            let b_time = std::fs::metadata(b).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
-            b_time.cmp(&a_time)
-        });
+        let now = chrono::Utc::now();
+        let mut removed = Vec::new();
+        let backups = self.list_backups()?
+            .into_iter()
+            .filter(|backup| backup_timestamp(&backup.filename).is_some());
 
-        // Remove excess backups
-        if backup_files.len() > self.config.max_backups as usize {
-            for backup_file in backup_files.iter().skip(self.config.max_backups as usize) {
-                std::fs::remove_file(backup_file).map_err(|e| MindmapError::InvalidOperation {
-                    message: format!("Failed to remove old backup: {}", e),
-                })?;
+        for (index, backup) in backups.enumerate() {
+            let age = (now - backup.created_at).to_std().unwrap_or(Duration::ZERO);
+            if index == 0 || (index < max_count && age <= max_age) {
+                continue;
             }
+
+            std::fs::remove_file(backup_dir.join(&backup.filename)).map_err(|e| MindmapError::InvalidOperation {
+                message: format!("Failed to remove old backup: {}", e),
+            })?;
+            removed.push(backup);
         }
 
-        Ok(())
+        Ok(removed)
     }
 
     /// Get persistence statistics
@@ -416,7 +478,7 @@ impl PersistenceManager {
             backup_count: backup_count as u32,
             auto_save_enabled: self.auto_save_enabled,
             has_unsaved_changes: self.has_unsaved_changes().unwrap_or(false),
-            last_save_time: self.last_auto_save,
+            last_save_time: self.last_auto_save.read().ok().and_then(|time| *time),
             last_backup_time: self.last_backup,
         })
     }
@@ -433,6 +495,73 @@ impl PersistenceManager {
     }
 }
 
+/// Write a document to the database, then mark it saved and record the save time
+///
+/// Returns the `DocumentSaved` event for the caller to emit once it has
+/// released its lock on the document.
+fn write_document(
+    database: &RwLock<SimpleSqliteDatabase>,
+    document: &mut Document,
+    last_auto_save: &RwLock<Option<SystemTime>>,
+) -> MindmapResult<EngineEvent> {
+    let started = Instant::now();
+
+    // Store the saved state, and only adopt it once the write succeeded; a
+    // version conflict leaves the document dirty at its old base version
+    let mut saved = document.clone();
+    saved.mark_saved();
+    saved.version = database.write().map_err(|_| MindmapError::InvalidOperation {
+        message: "Failed to acquire database write lock".to_string(),
+    })?.save_document_version(&saved)?;
+    *document = saved;
+
+    *last_auto_save.write().map_err(|_| MindmapError::InvalidOperation {
+        message: "Failed to acquire auto-save timestamp lock".to_string(),
+    })? = Some(SystemTime::now());
+
+    Ok(EngineEvent::DocumentSaved {
+        document_id: document.id,
+        duration: started.elapsed(),
+    })
+}
+
+/// Creation time encoded in a `backup_<timestamp>.db` file name
+fn backup_timestamp(filename: &str) -> Option<Timestamp> {
+    let seconds = filename.strip_prefix("backup_")?.strip_suffix(".db")?.parse().ok()?;
+    chrono::DateTime::from_timestamp(seconds, 0)
+}
+
+/// Save the current document if it has unsaved changes, for the auto-save task
+fn save_if_dirty(
+    database: &RwLock<SimpleSqliteDatabase>,
+    current_document: &DocumentSlot,
+    last_auto_save: &RwLock<Option<SystemTime>>,
+) -> MindmapResult<bool> {
+    // Release the slot before locking the document, as the manager does
+    let document_arc = match current_document.read() {
+        Ok(slot) => slot.clone(),
+        Err(_) => {
+            return Err(MindmapError::InvalidOperation {
+                message: "Failed to acquire current document lock".to_string(),
+            })
+        }
+    };
+    let Some(document_arc) = document_arc else {
+        return Ok(false);
+    };
+
+    let mut document = document_arc.write().map_err(|_| MindmapError::InvalidOperation {
+        message: "Failed to acquire document write lock".to_string(),
+    })?;
+    if !document.is_dirty {
+        return Ok(false);
+    }
+    let saved = write_document(database, &mut document, last_auto_save)?;
+    drop(document);
+    crate::events::emit(saved);
+    Ok(true)
+}
+
 /// Statistics about persistence operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistenceStats {
@@ -502,6 +631,39 @@ mod tests {
         assert!(manager.is_auto_save_enabled());
     }
 
+    #[test]
+    fn test_auto_save_task_saves_dirty_document() {
+        let temp_dir = tempdir().unwrap();
+        let db_config = DatabaseConfig::new(temp_dir.path().join("test.db").to_str().unwrap());
+        let config = PersistenceConfig { auto_save_interval: 1, ..PersistenceConfig::default() };
+        let mut manager = PersistenceManager::new(db_config, config).unwrap();
+
+        let doc = manager.create_document("Auto-saved Document").unwrap();
+        let handle = manager.start_auto_save_task();
+
+        doc.write().unwrap().mark_dirty();
+        assert!(manager.has_unsaved_changes().unwrap());
+
+        // Wait one interval, with slack for a busy test machine
+        thread::sleep(Duration::from_millis(1200));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while manager.has_unsaved_changes().unwrap() && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!manager.has_unsaved_changes().unwrap());
+        assert!(manager.get_stats().unwrap().last_save_time.is_some());
+
+        manager.stop_auto_save();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_auto_save_task_exits_when_disabled() {
+        let (mut manager, _temp_dir) = create_test_manager().unwrap();
+        manager.set_auto_save_enabled(false);
+        manager.start_auto_save_task().join().unwrap();
+    }
+
     #[test]
     fn test_backup_operations() {
         let (mut manager, _temp_dir) = create_test_manager().unwrap();
@@ -550,4 +712,33 @@ mod tests {
         assert_eq!(current_config.auto_save_interval, 60);
         assert_eq!(current_config.max_backups, 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_listener_receives_document_saved_event() {
+        use crate::events::{add_listener, remove_listener};
+        use std::sync::Mutex;
+
+        let (mut manager, _temp_dir) = create_test_manager().unwrap();
+        let doc = manager.create_document("Listened Document").unwrap();
+        let document_id = doc.read().unwrap().id;
+
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let sink = saved.clone();
+        let listened_doc = doc.clone();
+        let listener_id = add_listener(Arc::new(move |event: &EngineEvent| {
+            // Listeners are process-wide; ignore events from concurrent tests
+            if let EngineEvent::DocumentSaved { document_id: saved_id, .. } = event {
+                if *saved_id == document_id {
+                    // The document is no longer locked when the event arrives
+                    assert!(!listened_doc.try_write().unwrap().is_dirty);
+                    sink.lock().unwrap().push(*saved_id);
+                }
+            }
+        }));
+
+        manager.save_document().unwrap();
+        assert!(remove_listener(listener_id));
+
+        assert_eq!(*saved.lock().unwrap(), vec![document_id]);
+    }
+}
@@ -33,7 +33,7 @@ pub use manager::*;
 //#[cfg(feature = "sqlite")]
 //pub use queries::*;
 
-use crate::types::{ids::*, MindmapResult, MindmapError};
+use crate::types::{ids::*, MindmapResult, MindmapError, PositionEncoding};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -56,6 +56,9 @@ pub struct DatabaseConfig {
     pub page_size: u32,
     /// Cache size in pages
     pub cache_size: i32,
+    /// Precision node positions are stored with
+    #[serde(default)]
+    pub position_encoding: PositionEncoding,
 }
 
 impl Default for DatabaseConfig {
@@ -69,6 +72,7 @@ impl Default for DatabaseConfig {
             auto_vacuum: true,
             page_size: 4096,
             cache_size: 1000,
+            position_encoding: PositionEncoding::Float,
         }
     }
 }
@@ -106,6 +110,12 @@ impl DatabaseConfig {
         self
     }
 
+    /// Set the precision node positions are stored with
+    pub fn with_position_encoding(mut self, encoding: PositionEncoding) -> Self {
+        self.position_encoding = encoding;
+        self
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> MindmapResult<()> {
         if self.path.is_empty() {
@@ -166,6 +176,9 @@ pub struct DatabaseStats {
     pub free_pages: u64,
     /// Schema version
     pub schema_version: u32,
+    /// Size of the write-ahead log in bytes, 0 outside WAL mode
+    #[serde(default)]
+    pub wal_size_bytes: u64,
 }
 
 /// Trait for database operations
@@ -0,0 +1,324 @@
+//! Search result caching
+//!
+//! This module provides a small, bounded LRU cache for graph searches. Entries
+//! are keyed on the query, the search options and context, and a fingerprint
+//! of the graph contents, so any mutation of the graph causes a cache miss.
+
+use super::{ScoreCombination, SearchContext, SearchOptions, SearchResult};
+use crate::graph::Graph;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Default number of searches kept by a [`SearchCache`]
+pub const DEFAULT_SEARCH_CACHE_CAPACITY: usize = 32;
+
+/// Key identifying a cached search
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheKey {
+    /// Raw query string
+    query: String,
+    /// Hash of the search options and context
+    options_hash: u64,
+    /// Fingerprint of the graph the search ran against
+    fingerprint: u64,
+}
+
+/// Bounded least-recently-used cache of search results
+#[derive(Debug, Clone)]
+pub struct SearchCache {
+    /// Maximum number of cached searches; zero disables caching
+    capacity: usize,
+    /// Cached searches, least recently used first
+    entries: VecDeque<(CacheKey, Vec<SearchResult>)>,
+    /// Number of lookups answered from the cache
+    hits: u64,
+    /// Number of lookups that had to run the search
+    misses: u64,
+}
+
+impl SearchCache {
+    /// Create a cache holding at most `capacity` searches
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Maximum number of cached searches
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached searches
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups that missed the cache
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Remove all cached searches
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Look up a search, marking it as most recently used
+    fn get(&mut self, key: &CacheKey) -> Option<Vec<SearchResult>> {
+        let Some(index) = self.entries.iter().position(|(cached, _)| cached == key) else {
+            self.misses += 1;
+            return None;
+        };
+
+        self.hits += 1;
+        let entry = self.entries.remove(index)?;
+        let results = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(results)
+    }
+
+    /// Store a search, evicting stale and least recently used entries
+    fn insert(&mut self, key: CacheKey, results: Vec<SearchResult>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        // Entries for an older version of the graph can never hit again
+        self.entries.retain(|(cached, _)| cached.fingerprint == key.fingerprint);
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, results));
+    }
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEARCH_CACHE_CAPACITY)
+    }
+}
+
+/// Hash the search options and context into a single value
+fn hash_options(options: &SearchOptions, context: &SearchContext) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    options.limit.hash(&mut hasher);
+    options.offset.hash(&mut hasher);
+    options.min_score.to_bits().hash(&mut hasher);
+    options.case_sensitive.hash(&mut hasher);
+    options.include_tags.hash(&mut hasher);
+    options.include_metadata.hash(&mut hasher);
+    options.include_notes.hash(&mut hasher);
+    options.exact_match_boost.to_bits().hash(&mut hasher);
+    options.include_collapsed.hash(&mut hasher);
+    options.timeout.hash(&mut hasher);
+    options.mode.hash(&mut hasher);
+    options.match_mode.hash(&mut hasher);
+    options.position_granularity.hash(&mut hasher);
+    options.fuzzy_algorithm.hash(&mut hasher);
+    match options.score_combination {
+        ScoreCombination::Max => 0u8.hash(&mut hasher),
+        ScoreCombination::Sum => 1u8.hash(&mut hasher),
+        ScoreCombination::WeightedSum { text, tags, metadata, notes } => {
+            2u8.hash(&mut hasher);
+            [text, tags, metadata, notes].map(f64::to_bits).hash(&mut hasher);
+        }
+    }
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash a single value with a fresh hasher
+fn hash_one<T: Hash>(value: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Graph {
+    /// Compute a fingerprint of the graph contents
+    ///
+    /// The fingerprint changes whenever a node or edge is added, removed or
+    /// modified, independently of the internal iteration order.
+    pub fn content_fingerprint(&self) -> u64 {
+        let nodes = self.nodes().fold(0u64, |acc, node| {
+            let metadata = node
+                .metadata
+                .iter()
+                .fold(0u64, |acc, entry| acc.wrapping_add(hash_one(entry)));
+
+            let mut hasher = DefaultHasher::new();
+            node.id.hash(&mut hasher);
+            node.parent_id.hash(&mut hasher);
+            node.text.hash(&mut hasher);
+            node.tags.hash(&mut hasher);
+            node.note.hash(&mut hasher);
+            metadata.hash(&mut hasher);
+            node.collapsed.hash(&mut hasher);
+            node.order.hash(&mut hasher);
+            node.updated_at.hash(&mut hasher);
+            acc.wrapping_add(hasher.finish())
+        });
+
+        let edges = self.edges().fold(0u64, |acc, edge| {
+            acc.wrapping_add(hash_one((edge.id, edge.from_node, edge.to_node, &edge.label)))
+        });
+
+        hash_one((self.node_count(), self.edge_count(), nodes, edges))
+    }
+
+    /// Search for nodes, reusing results from `cache` when the graph is unchanged
+    pub fn search_cached(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        cache: &mut SearchCache,
+    ) -> Vec<SearchResult> {
+        self.search_with_context_cached(query, options, &SearchContext::All, cache)
+    }
+
+    /// Search with context filtering, reusing results from `cache` when possible
+    pub fn search_with_context_cached(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        context: &SearchContext,
+        cache: &mut SearchCache,
+    ) -> Vec<SearchResult> {
+        if cache.capacity() == 0 {
+            return self.search_with_context(query, options, context);
+        }
+
+        let key = CacheKey {
+            query: query.to_string(),
+            options_hash: hash_options(options, context),
+            fingerprint: self.content_fingerprint(),
+        };
+
+        if let Some(results) = cache.get(&key) {
+            return results;
+        }
+
+        // Partial results from a timed-out search must not be reused
+        let result_set = self.search_result_set(query, options, context);
+        if !result_set.truncated {
+            cache.insert(key, result_set.results.clone());
+        }
+        result_set.results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Node;
+    use crate::search::{MatchMode, PositionGranularity};
+
+    fn create_test_graph() -> Graph {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Machine Learning")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Neural Networks")).unwrap();
+        graph.add_node(Node::new_child(root_id, "Decision Trees")).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_identical_searches_hit_cache() {
+        let graph = create_test_graph();
+        let options = SearchOptions::default();
+        let mut cache = SearchCache::default();
+
+        let first = graph.search_cached("neural", &options, &mut cache);
+        let second = graph.search_cached("neural", &options, &mut cache);
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.hits(), 1);
+
+        // Different options are a separate entry
+        let strict = SearchOptions { min_score: 0.9, ..SearchOptions::default() };
+        graph.search_cached("neural", &strict, &mut cache);
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.len(), 2);
+
+        let suffix = SearchOptions { match_mode: MatchMode::Suffix, ..SearchOptions::default() };
+        let suffixed = graph.search_cached("neural", &suffix, &mut cache);
+        assert_eq!(cache.misses(), 3);
+        assert!(suffixed.is_empty());
+
+        let words = SearchOptions {
+            position_granularity: PositionGranularity::Word,
+            ..SearchOptions::default()
+        };
+        let widened = graph.search_cached("neur", &words, &mut cache);
+        let narrow = graph.search_cached("neur", &options, &mut cache);
+        assert_eq!(cache.misses(), 5);
+        assert_ne!(widened[0].match_positions, narrow[0].match_positions);
+    }
+
+    #[test]
+    fn test_mutation_invalidates_cache() {
+        let mut graph = create_test_graph();
+        let options = SearchOptions::default();
+        let mut cache = SearchCache::default();
+
+        let before = graph.search_cached("forest", &options, &mut cache);
+        assert!(before.is_empty());
+
+        let trees_id = graph.nodes().find(|n| n.text == "Decision Trees").unwrap().id;
+        graph.get_node_mut(trees_id).unwrap().text = "Random Forest".to_string();
+
+        let after = graph.search_cached("forest", &options, &mut cache);
+        assert!(after.iter().any(|r| r.node_id == trees_id));
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), 2);
+        // The stale entry was dropped
+        assert_eq!(cache.len(), 1);
+
+        // Notes are searchable, so editing one invalidates too
+        let with_notes = SearchOptions { include_notes: true, ..SearchOptions::default() };
+        assert!(graph.search_cached("quokka", &with_notes, &mut cache).is_empty());
+        graph.get_node_mut(trees_id).unwrap().note = Some("Quokka habitats".to_string());
+        let noted = graph.search_cached("quokka", &with_notes, &mut cache);
+        assert!(noted.iter().any(|r| r.node_id == trees_id));
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let graph = create_test_graph();
+        let options = SearchOptions::default();
+        let mut cache = SearchCache::new(2);
+
+        graph.search_cached("neural", &options, &mut cache);
+        graph.search_cached("decision", &options, &mut cache);
+        graph.search_cached("neural", &options, &mut cache);
+        graph.search_cached("machine", &options, &mut cache);
+        assert_eq!(cache.len(), 2);
+
+        // "decision" was least recently used and got evicted
+        graph.search_cached("neural", &options, &mut cache);
+        graph.search_cached("decision", &options, &mut cache);
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 4);
+
+        let mut disabled = SearchCache::new(0);
+        graph.search_cached("neural", &options, &mut disabled);
+        assert!(disabled.is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::models::Node;
 use crate::types::{NodeId, MindmapResult, MindmapError};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 /// Search result with ranking information
@@ -227,6 +227,88 @@ impl SearchEngine {
         }
     }
 
+    /// Number of times each indexed word occurs, keyed by its lowercase form
+    pub fn word_frequencies(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for text in self.text_index.values() {
+            for (start, end) in word_spans(text) {
+                *counts.entry(text[start..end].to_lowercase()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Number of nodes carrying each indexed tag, keyed by its lowercase form
+    pub fn tag_frequencies(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for tags in self.tag_index.values() {
+            for tag in tags {
+                *counts.entry(tag.to_lowercase()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Indexed words completing `prefix`, most frequent first
+    pub fn get_word_completions(&self, prefix: &str, limit: usize) -> Vec<String> {
+        completions(self.word_frequencies(), prefix, limit)
+    }
+
+    /// Indexed tags completing `prefix`, most frequent first
+    pub fn get_tag_completions(&self, prefix: &str, limit: usize) -> Vec<String> {
+        completions(self.tag_frequencies(), prefix, limit)
+    }
+
+    /// Find nodes whose text shares words with the text of `node_id`
+    ///
+    /// The score is the Jaccard similarity of the two word sets and the
+    /// match positions are the shared words in the similar node's text.
+    pub fn find_similar_nodes(
+        &self,
+        node_id: NodeId,
+        limit: usize,
+        min_similarity: f64,
+    ) -> MindmapResult<Vec<SearchResult>> {
+        let source = self.text_index.get(&node_id).ok_or(MindmapError::NodeNotFound { id: node_id })?;
+        let source_words = word_set(source);
+        if source_words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results = Vec::new();
+        for (&other_id, text) in &self.text_index {
+            if other_id == node_id {
+                continue;
+            }
+
+            let words = word_set(text);
+            let shared = source_words.intersection(&words).count();
+            let union = source_words.union(&words).count();
+            if shared == 0 {
+                continue;
+            }
+
+            let score = shared as f64 / union as f64;
+            if score >= min_similarity {
+                let match_positions = word_spans(text)
+                    .filter(|&(start, end)| source_words.contains(&text[start..end].to_lowercase()))
+                    .collect();
+                results.push(SearchResult {
+                    node_id: other_id,
+                    text: text.clone(),
+                    score,
+                    match_positions,
+                });
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.text.cmp(&b.text))
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+
     /// Clear the search index
     pub fn clear(&mut self) {
         self.text_index.clear();
@@ -244,6 +326,40 @@ impl SearchEngine {
     }
 }
 
+/// Byte ranges of the alphanumeric words in `text`
+fn word_spans(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(index, c)| match (c.is_alphanumeric(), start) {
+            (true, None) => {
+                start = Some(index);
+                None
+            }
+            (false, Some(word_start)) => {
+                start = None;
+                Some((word_start, index))
+            }
+            _ => None,
+        })
+}
+
+/// Lowercase words of `text`
+fn word_set(text: &str) -> HashSet<String> {
+    word_spans(text).map(|(start, end)| text[start..end].to_lowercase()).collect()
+}
+
+/// Entries of `counts` that extend `prefix`, most frequent first, then alphabetically
+fn completions(counts: HashMap<String, usize>, prefix: &str, limit: usize) -> Vec<String> {
+    let prefix = prefix.trim().to_lowercase();
+    let mut matches: Vec<(String, usize)> = counts
+        .into_iter()
+        .filter(|(entry, _)| entry.len() > prefix.len() && entry.starts_with(&prefix))
+        .collect();
+    matches.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    matches.into_iter().take(limit).map(|(entry, _)| entry).collect()
+}
+
 impl Default for SearchEngine {
     fn default() -> Self {
         Self::new()
@@ -388,4 +504,34 @@ mod tests {
         assert_eq!(results[0].match_positions[0], (0, 5));
         assert_eq!(results[0].match_positions[1], (12, 17));
     }
+    #[test]
+    fn test_word_and_tag_completions() {
+        let mut engine = SearchEngine::new();
+        engine.index_node(&create_test_node("Help with hello", vec!["Health", "work"]));
+        engine.index_node(&create_test_node("hello again", vec!["health"]));
+
+        assert_eq!(engine.get_word_completions("He", 5), vec!["hello", "help"]);
+        assert_eq!(engine.get_word_completions("hel", 1), vec!["hello"]);
+        assert!(engine.get_word_completions("hello", 5).is_empty());
+        assert_eq!(engine.get_tag_completions("h", 5), vec!["health"]);
+    }
+
+    #[test]
+    fn test_find_similar_nodes() {
+        let mut engine = SearchEngine::new();
+        let source = create_test_node("rust memory safety", vec![]);
+        let close = create_test_node("Memory safety in Rust", vec![]);
+        let far = create_test_node("rust belt", vec![]);
+        let unrelated = create_test_node("gardening", vec![]);
+        for node in [&source, &close, &far, &unrelated] {
+            engine.index_node(node);
+        }
+
+        let results = engine.find_similar_nodes(source.id, 10, 0.2).unwrap();
+        let ids: Vec<NodeId> = results.iter().map(|result| result.node_id).collect();
+        assert_eq!(ids, vec![close.id, far.id]);
+        assert_eq!(results[0].match_positions, vec![(0, 6), (7, 13), (17, 21)]);
+
+        assert!(engine.find_similar_nodes(NodeId::new(), 10, 0.0).is_err());
+    }
 }
\ No newline at end of file
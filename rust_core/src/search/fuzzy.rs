@@ -4,6 +4,63 @@
 //! substring matching, Levenshtein distance, and basic ranking.
 
 use std::cmp;
+use regex::Regex;
+
+/// Score, snippet and match positions in a text
+///
+/// Positions are `(start, end)` byte offsets into the searched text, end
+/// exclusive, and always fall on UTF-8 character boundaries.
+pub type TextMatch = (f64, String, Vec<(usize, usize)>);
+
+/// Minimum edit-distance similarity for a `Levenshtein` match
+pub const LEVENSHTEIN_THRESHOLD: f64 = 0.6;
+
+/// Minimum similarity for a `JaroWinkler` match
+pub const JARO_WINKLER_THRESHOLD: f64 = 0.85;
+
+/// Scoring strategy used for fuzzy matching
+///
+/// `Combined` tries exact, substring, word, subsequence and edit-distance
+/// matching in turn and accepts the first that fits; it finds the most but
+/// lets short queries match almost anything through partial subsequences.
+/// `Subsequence` is the strictest: every query character must appear in
+/// order, so abbreviations match but transposed letters don't. `Levenshtein`
+/// and `JaroWinkler` compare the query with runs of whole words, which
+/// tolerates typos and swapped letters but not prefixes of longer words;
+/// Jaro-Winkler favors words sharing the query's first letters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FuzzyAlgorithm {
+    /// The layered matching `fuzzy_search` has always used
+    #[default]
+    Combined,
+    /// All query characters in order, scored by how compact the match is
+    Subsequence,
+    /// Edit distance to the closest run of words
+    Levenshtein,
+    /// Jaro-Winkler similarity to the closest run of words
+    JaroWinkler,
+}
+
+/// Fuzzy search with the given scoring strategy
+///
+/// Scores are in `[0, 1]`, except that a text equal to the query scores
+/// `1.0 + exact_match_boost` under every strategy.
+pub fn fuzzy_match(text: &str, query: &str, exact_match_boost: f64, algorithm: FuzzyAlgorithm) -> Option<TextMatch> {
+    if query.is_empty() || text.is_empty() {
+        return None;
+    }
+
+    let (score, positions) = match algorithm {
+        FuzzyAlgorithm::Combined => return fuzzy_search(text, query, exact_match_boost),
+        _ if text == query => return whole_search(text, query, exact_match_boost),
+        FuzzyAlgorithm::Subsequence => subsequence_match(text, query)?,
+        FuzzyAlgorithm::Levenshtein => best_word_run(text, query, LEVENSHTEIN_THRESHOLD, levenshtein_similarity)?,
+        FuzzyAlgorithm::JaroWinkler => best_word_run(text, query, JARO_WINKLER_THRESHOLD, jaro_winkler_similarity)?,
+    };
+    let (first_start, first_end) = positions[0];
+    let snippet = create_snippet(text, &positions, first_end - first_start);
+    Some((score.clamp(0.0, 1.0), snippet, positions))
+}
 
 /// Perform fuzzy search on text and return score, snippet, and match positions
 pub fn fuzzy_search(text: &str, query: &str, exact_match_boost: f64) -> Option<(f64, String, Vec<(usize, usize)>)> {
@@ -39,8 +96,7 @@ pub fn fuzzy_search(text: &str, query: &str, exact_match_boost: f64) -> Option<(
     }
 
     // Try Levenshtein distance for similar words
-    let words: Vec<&str> = text.split_whitespace().collect();
-    for (word_idx, word) in words.iter().enumerate() {
+    for (word_start, word) in word_offsets(text) {
         let distance = levenshtein_distance(word, query);
         let max_len = cmp::max(word.len(), query.len());
 
@@ -50,17 +106,7 @@ pub fn fuzzy_search(text: &str, query: &str, exact_match_boost: f64) -> Option<(
             // Accept if similarity is above threshold
             if similarity >= 0.6 {
                 let score = similarity * 0.7; // Penalty for fuzzy match
-
-                // Find the word position in original text
-                let mut char_pos = 0;
-                for (i, w) in words.iter().enumerate() {
-                    if i == word_idx {
-                        break;
-                    }
-                    char_pos += w.len() + 1; // +1 for space
-                }
-
-                let positions = vec![(char_pos, char_pos + word.len())];
+                let positions = vec![(word_start, word_start + word.len())];
                 let snippet = create_snippet(text, &positions, word.len());
                 return Some((score, snippet, positions));
             }
@@ -70,6 +116,102 @@ pub fn fuzzy_search(text: &str, query: &str, exact_match_boost: f64) -> Option<(
     None
 }
 
+/// Literal search: matches only if the query occurs verbatim in the text
+pub fn exact_search(text: &str, query: &str, exact_match_boost: f64) -> Option<TextMatch> {
+    if query.is_empty() || text.is_empty() {
+        return None;
+    }
+
+    let score = exact_match(text, query, exact_match_boost).or_else(|| substring_match(text, query))?;
+    let positions = find_all_occurrences(text, query);
+    let snippet = create_snippet(text, &positions, query.len());
+    Some((score, snippet, positions))
+}
+
+/// Anchored literal search: the text must start with the query
+pub fn prefix_search(text: &str, query: &str, exact_match_boost: f64) -> Option<TextMatch> {
+    if query.is_empty() || !text.starts_with(query) {
+        return None;
+    }
+    anchored_match(text, query, 0, exact_match_boost)
+}
+
+/// Anchored literal search: the text must end with the query
+pub fn suffix_search(text: &str, query: &str, exact_match_boost: f64) -> Option<TextMatch> {
+    if query.is_empty() || !text.ends_with(query) {
+        return None;
+    }
+    anchored_match(text, query, text.len() - query.len(), exact_match_boost)
+}
+
+/// Whole-text search: the text must equal the query
+pub fn whole_search(text: &str, query: &str, exact_match_boost: f64) -> Option<TextMatch> {
+    if query.is_empty() {
+        return None;
+    }
+    let score = exact_match(text, query, exact_match_boost)?;
+    Some((score, text.to_string(), vec![(0, text.len())]))
+}
+
+/// Score a single occurrence of `query` starting at byte `start`
+fn anchored_match(text: &str, query: &str, start: usize, exact_match_boost: f64) -> Option<TextMatch> {
+    let score = exact_match(text, query, exact_match_boost)
+        .unwrap_or_else(|| occurrence_score(text, query, start));
+    let positions = vec![(start, start + query.len())];
+    let snippet = create_snippet(text, &positions, query.len());
+    Some((score, snippet, positions))
+}
+
+/// Regular expression search, scored by how much of the text the matches cover
+pub fn regex_search(text: &str, pattern: &Regex) -> Option<TextMatch> {
+    let positions: Vec<(usize, usize)> = pattern
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(0))
+        .filter(|matched| !matched.is_empty())
+        .map(|matched| (matched.start(), matched.end()))
+        .collect();
+    let (first_start, first_end) = *positions.first()?;
+
+    let covered: usize = positions.iter().map(|(start, end)| end - start).sum();
+    let score = (0.5 + 0.5 * covered as f64 / text.len() as f64).min(1.0);
+    let snippet = create_snippet(text, &positions, first_end - first_start);
+    Some((score, snippet, positions))
+}
+
+/// Widen match spans to cover the whole words they touch
+///
+/// Word characters are alphanumerics and underscores. Spans that end up
+/// overlapping, e.g. two matches inside one word, are merged.
+pub fn expand_to_words(text: &str, positions: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut expanded: Vec<(usize, usize)> = Vec::with_capacity(positions.len());
+
+    for &(start, end) in positions {
+        if start > end || end > text.len() || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            expanded.push((start, end));
+            continue;
+        }
+
+        let word_start = text[..start]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_word_char(c))
+            .last()
+            .map_or(start, |(index, _)| index);
+        let word_end = text[end..]
+            .char_indices()
+            .find(|&(_, c)| !is_word_char(c))
+            .map_or(text.len(), |(index, _)| end + index);
+
+        match expanded.last_mut() {
+            Some(last) if word_start <= last.1 && word_start >= last.0 => last.1 = last.1.max(word_end),
+            _ => expanded.push((word_start, word_end)),
+        }
+    }
+
+    expanded
+}
+
 /// Check for exact match (case insensitive)
 fn exact_match(text: &str, query: &str, boost: f64) -> Option<f64> {
     if text == query {
@@ -81,16 +223,16 @@ fn exact_match(text: &str, query: &str, boost: f64) -> Option<f64> {
 
 /// Check for substring match and calculate score based on position and coverage
 fn substring_match(text: &str, query: &str) -> Option<f64> {
-    if let Some(pos) = text.find(query) {
-        let coverage = query.len() as f64 / text.len() as f64;
-        let position_factor = 1.0 - (pos as f64 / text.len() as f64);
+    text.find(query).map(|pos| occurrence_score(text, query, pos))
+}
 
-        // Score based on coverage and position (earlier matches score higher)
-        let score = (coverage * 0.7 + position_factor * 0.3).min(1.0);
-        Some(score)
-    } else {
-        None
-    }
+/// Score an occurrence of `query` at byte `pos` by coverage and position
+fn occurrence_score(text: &str, query: &str, pos: usize) -> f64 {
+    let coverage = query.len() as f64 / text.len() as f64;
+    let position_factor = 1.0 - (pos as f64 / text.len() as f64);
+
+    // Score based on coverage and position (earlier matches score higher)
+    (coverage * 0.7 + position_factor * 0.3).min(1.0)
 }
 
 /// Check for word boundary matches (query matches complete words)
@@ -137,17 +279,13 @@ fn find_word_boundaries(text: &str, query: &str) -> Vec<(usize, usize)> {
     let mut positions = Vec::new();
     let query_words: Vec<&str> = query.split_whitespace().collect();
 
-    let mut char_pos = 0;
-    for word in text.split_whitespace() {
+    for (word_offset, word) in word_offsets(text) {
         for query_word in &query_words {
-            if word.contains(query_word) {
-                if let Some(word_start) = word.find(query_word) {
-                    positions.push((char_pos + word_start, char_pos + word_start + query_word.len()));
-                }
+            if let Some(word_start) = word.find(query_word) {
+                positions.push((word_offset + word_start, word_offset + word_start + query_word.len()));
                 break;
             }
         }
-        char_pos += word.len() + 1; // +1 for space
     }
 
     positions
@@ -170,7 +308,7 @@ fn fuzzy_character_match(text: &str, query: &str) -> Option<(f64, Vec<(usize, us
 
     while text_idx < text_chars.len() && query_idx < query_chars.len() {
         if text_chars[text_idx] == query_chars[query_idx] {
-            positions.push((char_position, char_position + 1));
+            positions.push((char_position, char_position + text_chars[text_idx].len_utf8()));
             matched_chars += 1;
             query_idx += 1;
         }
@@ -186,6 +324,70 @@ fn fuzzy_character_match(text: &str, query: &str) -> Option<(f64, Vec<(usize, us
     }
 }
 
+/// Strict subsequence matching: every query character, in order
+///
+/// Scores half by how tightly the matched characters cluster and half by
+/// how much of the text they cover.
+fn subsequence_match(text: &str, query: &str) -> Option<(f64, Vec<(usize, usize)>)> {
+    let mut query_chars = query.chars().peekable();
+    let mut positions = Vec::new();
+    for (index, ch) in text.char_indices() {
+        match query_chars.peek() {
+            Some(&wanted) if wanted == ch => {
+                positions.push((index, index + ch.len_utf8()));
+                query_chars.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    if query_chars.peek().is_some() {
+        return None;
+    }
+
+    let query_len = query.chars().count() as f64;
+    let (span_start, span_end) = (positions.first()?.0, positions.last()?.1);
+    let span_len = text[span_start..span_end].chars().count() as f64;
+    let text_len = text.chars().count() as f64;
+    let score = 0.5 * query_len / span_len + 0.5 * query_len / text_len;
+    Some((score, positions))
+}
+
+/// Best-scoring run of consecutive words, as many as the query has
+///
+/// Returns the similarity and the span of the run, if the similarity
+/// reaches `threshold`.
+fn best_word_run(
+    text: &str,
+    query: &str,
+    threshold: f64,
+    similarity: fn(&str, &str) -> f64,
+) -> Option<(f64, Vec<(usize, usize)>)> {
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let run_len = query.split(' ').count();
+    let words: Vec<(usize, &str)> = word_offsets(text).collect();
+
+    let mut best: Option<(f64, (usize, usize))> = None;
+    for run in words.windows(run_len.min(words.len()).max(1)) {
+        let candidate = run.iter().map(|(_, word)| *word).collect::<Vec<_>>().join(" ");
+        let score = similarity(&candidate, &query);
+        if score >= threshold && best.is_none_or(|(best_score, _)| score > best_score) {
+            let (last_start, last_word) = run[run.len() - 1];
+            best = Some((score, (run[0].0, last_start + last_word.len())));
+        }
+    }
+    best.map(|(score, span)| (score, vec![span]))
+}
+
+/// Edit-distance similarity in `[0, 1]`, relative to the longer string
+fn levenshtein_similarity(s1: &str, s2: &str) -> f64 {
+    let max_len = cmp::max(s1.chars().count(), s2.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein_distance(s1, s2) as f64 / max_len as f64
+}
+
 /// Find all occurrences of a substring
 fn find_all_occurrences(text: &str, pattern: &str) -> Vec<(usize, usize)> {
     let mut positions = Vec::new();
@@ -194,7 +396,8 @@ fn find_all_occurrences(text: &str, pattern: &str) -> Vec<(usize, usize)> {
     while let Some(pos) = text[start..].find(pattern) {
         let absolute_pos = start + pos;
         positions.push((absolute_pos, absolute_pos + pattern.len()));
-        start = absolute_pos + 1;
+        // Step over one whole character so overlapping matches are still found
+        start = absolute_pos + text[absolute_pos..].chars().next().map_or(1, char::len_utf8);
     }
 
     positions
@@ -207,10 +410,10 @@ fn create_snippet(text: &str, positions: &[(usize, usize)], match_len: usize) ->
     }
 
     let first_match = positions[0].0;
-    let context_size = 50; // Characters before and after
+    let context_size = 50; // Bytes before and after, widened to whole characters
 
-    let start = first_match.saturating_sub(context_size);
-    let end = (first_match + match_len + context_size).min(text.len());
+    let start = floor_char_boundary(text, first_match.saturating_sub(context_size));
+    let end = ceil_char_boundary(text, first_match + match_len + context_size);
 
     let mut snippet = String::new();
 
@@ -227,6 +430,30 @@ fn create_snippet(text: &str, positions: &[(usize, usize)], match_len: usize) ->
     snippet
 }
 
+/// Split text on whitespace, yielding each word with its byte offset
+fn word_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Largest character boundary at or before `index`
+pub(crate) fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Smallest character boundary at or after `index`
+pub(crate) fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
 /// Calculate Levenshtein distance between two strings
 fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     let s1_chars: Vec<char> = s1.chars().collect();
@@ -462,4 +689,73 @@ mod tests {
         assert!(fuzzy_search("text", "", 0.0).is_none());
         assert!(fuzzy_search("", "", 0.0).is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_expand_to_words() {
+        let text = "Cloud networking, net_work";
+        assert_eq!(expand_to_words(text, &[(6, 9)]), vec![(6, 16)]);
+        assert_eq!(expand_to_words(text, &[(7, 8), (10, 12)]), vec![(6, 16)]);
+        assert_eq!(expand_to_words(text, &[(18, 20)]), vec![(18, 26)]);
+        assert_eq!(expand_to_words(text, &[(0, 5)]), vec![(0, 5)]);
+    }
+
+    #[test]
+    fn test_multibyte_positions_are_char_boundaries() {
+        let text = "深度学习 deep learning 深度";
+        let check = |query: &str| {
+            let (_, snippet, positions) = fuzzy_search(text, query, 0.0).unwrap();
+            assert!(!snippet.is_empty());
+            for (start, end) in positions {
+                assert!(text.is_char_boundary(start) && text.is_char_boundary(end), "{}: {}..{}", query, start, end);
+                assert!(start < end && end <= text.len());
+            }
+        };
+
+        check("深度"); // substring, two occurrences
+        check("深学"); // character-by-character
+        check("learnign"); // edit distance
+
+        let long_text = format!("{}学习 learning", "深".repeat(40));
+        let (_, snippet, _) = fuzzy_search(&long_text, "learning", 0.0).unwrap();
+        assert!(snippet.starts_with("..."));
+    }
+
+    #[test]
+    fn test_algorithms_on_transposed_letters() {
+        let text = "please receive the parcel";
+
+        let (score, _, positions) = fuzzy_match(text, "recieve", 0.0, FuzzyAlgorithm::Levenshtein).unwrap();
+        assert!(score > LEVENSHTEIN_THRESHOLD && score < 1.0);
+        assert_eq!(&text[positions[0].0..positions[0].1], "receive");
+        assert!(fuzzy_match(text, "recieve", 0.0, FuzzyAlgorithm::JaroWinkler).is_some());
+        assert!(fuzzy_match(text, "recieve", 0.0, FuzzyAlgorithm::Subsequence).is_none());
+
+        // Subsequences match abbreviations, whole-word strategies don't
+        let (score, _, positions) = fuzzy_match(text, "rcv", 0.0, FuzzyAlgorithm::Subsequence).unwrap();
+        assert!(score > 0.0 && score <= 1.0);
+        assert_eq!(positions.len(), 3);
+        assert!(fuzzy_match(text, "rcv", 0.0, FuzzyAlgorithm::Levenshtein).is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_scores_and_default() {
+        assert_eq!(FuzzyAlgorithm::default(), FuzzyAlgorithm::Combined);
+        assert_eq!(
+            fuzzy_match("deep learning", "learn", 0.5, FuzzyAlgorithm::Combined),
+            fuzzy_search("deep learning", "learn", 0.5)
+        );
+
+        for algorithm in [FuzzyAlgorithm::Subsequence, FuzzyAlgorithm::Levenshtein, FuzzyAlgorithm::JaroWinkler] {
+            let (score, _, _) = fuzzy_match("neural network", "neural netwrk", 0.0, algorithm).unwrap();
+            assert!((0.0..=1.0).contains(&score), "{:?}: {}", algorithm, score);
+
+            let (score, _, _) = fuzzy_match("graph", "graph", 0.5, algorithm).unwrap();
+            assert_eq!(score, 1.5);
+            assert!(fuzzy_match("graph", "", 0.0, algorithm).is_none());
+        }
+
+        // Multi-word queries compare against runs of as many words
+        let (_, _, positions) = fuzzy_match("intro to neural netwrks today", "neural networks", 0.0, FuzzyAlgorithm::Levenshtein).unwrap();
+        assert_eq!(positions, vec![(9, 23)]);
+    }
+}
@@ -4,12 +4,18 @@
 //! by pre-processing node content and creating searchable indices.
 
 use crate::models::Node;
+use crate::search::{rank_and_page, score_fields, MatchMode, QueryMatcher, SearchMode, SearchOptions, SearchResult};
 use crate::types::ids::NodeId;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
 /// A search index for efficient text searching
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The index is maintained incrementally with [`SearchIndex::add_node`],
+/// [`SearchIndex::update_node`] and [`SearchIndex::remove_node`], so repeated
+/// queries only score nodes whose words, tags, metadata or notes contain the query
+/// instead of fuzzy matching every node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchIndex {
     /// Word to node IDs mapping
     word_index: HashMap<String, HashSet<NodeId>>,
@@ -17,12 +23,15 @@ pub struct SearchIndex {
     tag_index: HashMap<String, HashSet<NodeId>>,
     /// Metadata key-value to node IDs mapping
     metadata_index: HashMap<String, HashSet<NodeId>>,
+    /// Note word to node IDs mapping
+    #[serde(default)]
+    note_index: HashMap<String, HashSet<NodeId>>,
     /// Node ID to searchable content mapping
     content_cache: HashMap<NodeId, SearchableContent>,
 }
 
 /// Cached searchable content for a node
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchableContent {
     /// Processed words from node text
     words: Vec<String>,
@@ -32,6 +41,15 @@ pub struct SearchableContent {
     metadata_pairs: Vec<String>,
     /// Full text for snippet generation
     full_text: String,
+    /// Metadata entries formatted as "key: value" for scoring
+    #[serde(default)]
+    metadata_text: Vec<String>,
+    /// Processed words from the node's note
+    #[serde(default)]
+    note_words: Vec<String>,
+    /// Full note for scoring and snippets
+    #[serde(default)]
+    note: Option<String>,
 }
 
 /// Index statistics
@@ -56,6 +74,7 @@ impl SearchIndex {
             word_index: HashMap::new(),
             tag_index: HashMap::new(),
             metadata_index: HashMap::new(),
+            note_index: HashMap::new(),
             content_cache: HashMap::new(),
         }
     }
@@ -69,6 +88,7 @@ impl SearchIndex {
         let words = Self::extract_words(&node.text);
         let tags = node.tags.clone();
         let metadata_pairs = Self::extract_metadata(&node.metadata);
+        let note_words = node.note.as_deref().map(Self::extract_words).unwrap_or_default();
 
         // Cache the searchable content
         let content = SearchableContent {
@@ -76,6 +96,12 @@ impl SearchIndex {
             tags: tags.clone(),
             metadata_pairs: metadata_pairs.clone(),
             full_text: node.text.clone(),
+            metadata_text: node.metadata
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect(),
+            note_words: note_words.clone(),
+            note: node.note.clone(),
         };
         self.content_cache.insert(node.id, content);
 
@@ -103,6 +129,24 @@ impl SearchIndex {
                 .or_insert_with(HashSet::new)
                 .insert(node.id);
         }
+
+        // Index note words
+        for word in note_words {
+            self.note_index
+                .entry(word)
+                .or_default()
+                .insert(node.id);
+        }
+    }
+
+    /// Add a newly created node to the index
+    pub fn add_node(&mut self, node: &Node) {
+        self.index_node(node);
+    }
+
+    /// Re-index a node after its text, tags or metadata changed
+    pub fn update_node(&mut self, node: &Node) {
+        self.index_node(node);
     }
 
     /// Remove a node from the index
@@ -138,6 +182,16 @@ impl SearchIndex {
                     }
                 }
             }
+
+            // Remove from note index
+            for word in &content.note_words {
+                if let Some(node_set) = self.note_index.get_mut(word) {
+                    node_set.remove(&node_id);
+                    if node_set.is_empty() {
+                        self.note_index.remove(word);
+                    }
+                }
+            }
         }
     }
 
@@ -186,6 +240,87 @@ impl SearchIndex {
         result
     }
 
+    /// Find the nodes that may match a query without scoring them
+    ///
+    /// A node is a candidate when every query word occurs inside one of its
+    /// indexed words (or note words, if enabled), or when a tag (or metadata
+    /// entry, if enabled) contains the whole query. Only the index vocabulary is scanned, not the nodes.
+    /// Regex searches cannot be narrowed this way, so every node is a candidate.
+    pub fn candidates(&self, query: &str, options: &SearchOptions) -> HashSet<NodeId> {
+        if let SearchMode::Regex(_) = options.mode {
+            return self.content_cache.keys().copied().collect();
+        }
+
+        let query_lower = query.trim().to_lowercase();
+        if query_lower.is_empty() {
+            return HashSet::new();
+        }
+
+        // A field that starts with the query has words starting with each query token
+        let prefix_only = options.match_mode == MatchMode::Prefix;
+        let tokens = Self::extract_query_tokens(&query_lower);
+
+        let mut result = Self::word_candidates(&self.word_index, &tokens, prefix_only);
+
+        if options.include_notes {
+            result.extend(Self::word_candidates(&self.note_index, &tokens, prefix_only));
+        }
+
+        if options.include_tags {
+            for (tag, node_ids) in &self.tag_index {
+                if tag.contains(&query_lower) {
+                    result.extend(node_ids);
+                }
+            }
+        }
+
+        if options.include_metadata {
+            for (pair, node_ids) in &self.metadata_index {
+                if pair.contains(&query_lower) {
+                    result.extend(node_ids);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Search the index, scoring only candidate nodes
+    ///
+    /// Scores match [`crate::graph::Graph::search`] for the same node, but
+    /// typo-tolerant matches that share no word fragment with the query are
+    /// not found. Collapsed state is unknown here, so `collapsed_ancestors`
+    /// is always empty.
+    pub fn query(&self, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
+        let matcher = match QueryMatcher::new(query, options) {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                log::warn!("Index query failed: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut results: Vec<SearchResult> = self.candidates(query, options)
+            .into_iter()
+            .filter_map(|node_id| {
+                let content = self.content_cache.get(&node_id)?;
+                score_fields(
+                    node_id,
+                    &content.full_text,
+                    &content.tags,
+                    &content.metadata_text,
+                    content.note.as_deref(),
+                    &matcher,
+                    options,
+                )
+            })
+            .filter(|result| result.score >= options.min_score)
+            .collect();
+
+        rank_and_page(&mut results, options);
+        results
+    }
+
     /// Get searchable content for a node
     pub fn get_content(&self, node_id: NodeId) -> Option<&SearchableContent> {
         self.content_cache.get(&node_id)
@@ -235,6 +370,7 @@ impl SearchIndex {
         self.word_index.clear();
         self.tag_index.clear();
         self.metadata_index.clear();
+        self.note_index.clear();
         self.content_cache.clear();
     }
 
@@ -261,6 +397,28 @@ impl SearchIndex {
         }
     }
 
+    /// Nodes whose words in `index` contain every query token
+    fn word_candidates(index: &HashMap<String, HashSet<NodeId>>, tokens: &[String], prefix_only: bool) -> HashSet<NodeId> {
+        let mut result: Option<HashSet<NodeId>> = None;
+        for token in tokens {
+            let matching: HashSet<NodeId> = index
+                .iter()
+                .filter(|(word, _)| if prefix_only {
+                    word.starts_with(token.as_str())
+                } else {
+                    word.contains(token.as_str())
+                })
+                .flat_map(|(_, node_ids)| node_ids.iter().copied())
+                .collect();
+
+            result = Some(match result {
+                Some(previous) => previous.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+        result.unwrap_or_default()
+    }
+
     /// Extract words from text, normalized and filtered
     fn extract_words(text: &str) -> Vec<String> {
         text.to_lowercase()
@@ -275,6 +433,19 @@ impl SearchIndex {
             .collect()
     }
 
+    /// Split a query into normalized words, keeping single characters
+    fn extract_query_tokens(query: &str) -> Vec<String> {
+        query.to_lowercase()
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+
     /// Extract metadata key-value pairs for indexing
     fn extract_metadata(metadata: &HashMap<String, String>) -> Vec<String> {
         let mut pairs = Vec::new();
@@ -581,4 +752,50 @@ mod tests {
         assert!(index.tag_index.is_empty());
         assert!(index.content_cache.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_query_scores_only_candidates() {
+        let mut index = SearchIndex::new();
+        let machine = create_test_node("Machine Learning", vec!["AI"]);
+        let deep = create_test_node("Deep Learning Networks", vec![]);
+        let cooking = create_test_node("Cooking Recipes", vec![]);
+        index.add_node(&machine);
+        index.add_node(&deep);
+        index.add_node(&cooking);
+
+        let options = SearchOptions::default();
+        let candidates = index.candidates("learn", &options);
+        assert_eq!(candidates.len(), 2);
+        assert!(!candidates.contains(&cooking.id));
+
+        let results = index.query("learn", &options);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.node_id != cooking.id));
+
+        // Every query word has to match
+        let results = index.query("deep learn", &options);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].node_id, deep.id);
+
+        // Tags match on the whole query
+        let results = index.query("ai", &options);
+        assert!(results.iter().any(|r| r.node_id == machine.id));
+    }
+
+    #[test]
+    fn test_query_follows_incremental_updates() {
+        let mut index = SearchIndex::new();
+        let mut node = create_test_node("Project Plan", vec![]);
+        index.add_node(&node);
+        let options = SearchOptions::default();
+        assert_eq!(index.query("plan", &options).len(), 1);
+
+        node.text = "Project Budget".to_string();
+        index.update_node(&node);
+        assert!(index.query("plan", &options).is_empty());
+        assert_eq!(index.query("budget", &options)[0].node_id, node.id);
+
+        index.remove_node(node.id);
+        assert!(index.query("budget", &options).is_empty());
+    }
+}
@@ -6,16 +6,22 @@
 pub mod fuzzy;
 pub mod index;
 pub mod engine;
+pub mod cache;
 
 pub use fuzzy::*;
 pub use index::*;
 pub use engine::*;
+pub use cache::*;
 
 use crate::graph::Graph;
 use crate::models::Node;
 use crate::types::ids::NodeId;
+use crate::types::{MindmapError, MindmapResult};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use crate::types::clock::Instant;
+use std::time::Duration;
 
 /// A search result containing a node reference and match score
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -26,8 +32,16 @@ pub struct SearchResult {
     pub score: f64,
     /// The matched text snippet
     pub snippet: String,
-    /// Positions of matched terms in the text
+    /// Positions of matched terms in the matched field (node text, tag or metadata entry)
+    ///
+    /// Each position is a `(start, end)` pair of byte offsets, end exclusive,
+    /// on UTF-8 character boundaries, so `&text[start..end]` is the match.
     pub match_positions: Vec<(usize, usize)>,
+    /// Collapsed ancestors hiding this node, ordered from the root downwards
+    ///
+    /// Expanding these nodes in order reveals the match. Empty if the node is visible.
+    #[serde(default)]
+    pub collapsed_ancestors: Vec<NodeId>,
 }
 
 /// Search options for customizing search behavior
@@ -35,6 +49,8 @@ pub struct SearchResult {
 pub struct SearchOptions {
     /// Maximum number of results to return
     pub limit: Option<usize>,
+    /// Number of ranked results to skip before applying `limit`, for paging
+    pub offset: usize,
     /// Minimum score threshold (0.0 to 1.0)
     pub min_score: f64,
     /// Case sensitive search
@@ -43,12 +59,222 @@ pub struct SearchOptions {
     pub include_tags: bool,
     /// Search in metadata values
     pub include_metadata: bool,
+    /// Search in node notes
+    pub include_notes: bool,
     /// Boost score for exact matches
     pub exact_match_boost: f64,
+    /// Search inside collapsed subtrees as well as visible nodes
+    pub include_collapsed: bool,
+    /// Time budget for the search; the best results found so far are returned once exceeded
+    pub timeout: Option<Duration>,
+    /// How the query is matched against node content
+    pub mode: SearchMode,
+    /// Where a literal query must occur in each field; ignored in regex mode
+    pub match_mode: MatchMode,
+    /// Whether match positions cover just the matched characters or whole words
+    pub position_granularity: PositionGranularity,
+    /// How the scores of matches in different fields are combined
+    pub score_combination: ScoreCombination,
+    /// Scoring strategy for fuzzy matching; only used with `SearchMode::Fuzzy`
+    pub fuzzy_algorithm: FuzzyAlgorithm,
+}
+
+/// How a node's text, tag, metadata and note match scores become one score
+///
+/// Each field contributes its best match; tag matches count 0.8, note
+/// matches 0.7 and metadata matches 0.6 of a text match unless weighted
+/// explicitly. Combined scores can exceed 1.0.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScoreCombination {
+    /// The best single field score
+    #[default]
+    Max,
+    /// The sum of the field scores
+    Sum,
+    /// The sum of the raw field scores, each multiplied by its weight
+    WeightedSum {
+        text: f64,
+        tags: f64,
+        metadata: f64,
+        notes: f64,
+    },
+}
+
+impl ScoreCombination {
+    /// Weights applied to the raw text, tag, metadata and note scores
+    fn weights(&self) -> (f64, f64, f64, f64) {
+        match *self {
+            Self::Max | Self::Sum => (1.0, 0.8, 0.6, 0.7),
+            Self::WeightedSum { text, tags, metadata, notes } => (text, tags, metadata, notes),
+        }
+    }
+
+    /// Combine weighted field scores, absent fields being zero
+    fn combine(&self, scores: [f64; 4]) -> f64 {
+        match self {
+            Self::Max => scores.into_iter().fold(0.0, f64::max),
+            Self::Sum | Self::WeightedSum { .. } => scores.into_iter().sum(),
+        }
+    }
+}
+
+/// Extent of the spans reported in `SearchResult::match_positions`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionGranularity {
+    /// Exactly the matched characters
+    #[default]
+    Char,
+    /// Each match widened to the words it touches
+    Word,
+}
+
+/// How a search query is matched against node content
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    /// Typo-tolerant matching, scored by `SearchOptions::fuzzy_algorithm`
+    #[default]
+    Fuzzy,
+    /// The query must occur verbatim
+    Exact,
+    /// Match this regular expression; the query string is not used
+    Regex(String),
+}
+
+/// Where a query must occur within a text, tag or metadata field
+///
+/// All modes except `Fuzzy` compare literally. `Fuzzy` defers to
+/// `SearchOptions::mode`, so `SearchMode::Exact` still matches anywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// Anywhere in the field
+    Contains,
+    /// At the start of the field
+    Prefix,
+    /// At the end of the field
+    Suffix,
+    /// The whole field
+    Exact,
+    /// As selected by `SearchOptions::mode`
+    #[default]
+    Fuzzy,
+}
+
+/// A search query prepared once per search call
+#[derive(Debug, Clone)]
+pub(crate) enum QueryMatcher {
+    /// Normalized query for fuzzy matching
+    Fuzzy(String),
+    /// Normalized query for literal matching
+    Exact(String),
+    /// Normalized query the field must start with
+    Prefix(String),
+    /// Normalized query the field must end with
+    Suffix(String),
+    /// Normalized query the field must equal
+    Whole(String),
+    /// Compiled regular expression
+    Regex(Regex),
+}
+
+impl QueryMatcher {
+    /// Prepare `query` according to the search mode and case sensitivity
+    ///
+    /// Fails with `InvalidOperation` if the regex pattern does not compile.
+    pub(crate) fn new(query: &str, options: &SearchOptions) -> MindmapResult<Self> {
+        let normalized = || if options.case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+
+        match (&options.mode, options.match_mode) {
+            (SearchMode::Regex(pattern), _) => RegexBuilder::new(pattern)
+                .case_insensitive(!options.case_sensitive)
+                .build()
+                .map(Self::Regex)
+                .map_err(|e| MindmapError::InvalidOperation {
+                    message: format!("Invalid search pattern '{}': {}", pattern, e),
+                }),
+            (SearchMode::Fuzzy, MatchMode::Fuzzy) => Ok(Self::Fuzzy(normalized())),
+            (SearchMode::Exact, MatchMode::Fuzzy) | (_, MatchMode::Contains) => Ok(Self::Exact(normalized())),
+            (_, MatchMode::Prefix) => Ok(Self::Prefix(normalized())),
+            (_, MatchMode::Suffix) => Ok(Self::Suffix(normalized())),
+            (_, MatchMode::Exact) => Ok(Self::Whole(normalized())),
+        }
+    }
+
+    /// Check whether there is nothing to search for
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Self::Fuzzy(query)
+            | Self::Exact(query)
+            | Self::Prefix(query)
+            | Self::Suffix(query)
+            | Self::Whole(query) => query.trim().is_empty(),
+            Self::Regex(pattern) => pattern.as_str().is_empty(),
+        }
+    }
+
+    /// Match a piece of text, returning score, snippet and match positions
+    pub(crate) fn find(&self, text: &str, options: &SearchOptions) -> Option<TextMatch> {
+        let normalize = |value: &str| if options.case_sensitive {
+            value.to_string()
+        } else {
+            value.to_lowercase()
+        };
+
+        let (haystack, found) = match self {
+            Self::Fuzzy(query) => {
+                let haystack = normalize(text);
+                let found = fuzzy_match(&haystack, query, options.exact_match_boost, options.fuzzy_algorithm);
+                (haystack, found)
+            }
+            Self::Exact(query) => {
+                let haystack = normalize(text);
+                let found = exact_search(&haystack, query, options.exact_match_boost);
+                (haystack, found)
+            }
+            Self::Prefix(query) => {
+                let haystack = normalize(text);
+                let found = prefix_search(&haystack, query, options.exact_match_boost);
+                (haystack, found)
+            }
+            Self::Suffix(query) => {
+                let haystack = normalize(text);
+                let found = suffix_search(&haystack, query, options.exact_match_boost);
+                (haystack, found)
+            }
+            Self::Whole(query) => {
+                let haystack = normalize(text);
+                let found = whole_search(&haystack, query, options.exact_match_boost);
+                (haystack, found)
+            }
+            Self::Regex(pattern) => (text.to_string(), regex_search(text, pattern)),
+        };
+
+        let (score, snippet, positions) = found?;
+        let positions = remap_positions(&haystack, text, positions);
+        let positions = match options.position_granularity {
+            PositionGranularity::Char => positions,
+            PositionGranularity::Word => expand_to_words(text, &positions),
+        };
+        Some((score, snippet, positions))
+    }
+}
+
+/// Results of a search together with whether it finished
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchResultSet {
+    /// Matching nodes, best first
+    pub results: Vec<SearchResult>,
+    /// True if the search stopped early because its timeout was exceeded
+    pub truncated: bool,
+    /// Number of matches before `offset` and `limit` were applied
+    pub total_matches: usize,
 }
 
 /// Search context for filtering results
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SearchContext {
     /// Search all nodes
     All,
@@ -58,17 +284,28 @@ pub enum SearchContext {
     Depth(usize),
     /// Search only root nodes
     Roots,
+    /// Search only nodes carrying any of the given tags
+    Tagged(Vec<String>),
 }
 
 impl Default for SearchOptions {
     fn default() -> Self {
         Self {
             limit: Some(50),
+            offset: 0,
             min_score: 0.1,
             case_sensitive: false,
             include_tags: true,
             include_metadata: false,
+            include_notes: false,
             exact_match_boost: 0.5,
+            include_collapsed: true,
+            timeout: None,
+            mode: SearchMode::Fuzzy,
+            match_mode: MatchMode::Fuzzy,
+            position_granularity: PositionGranularity::Char,
+            score_combination: ScoreCombination::Max,
+            fuzzy_algorithm: FuzzyAlgorithm::Combined,
         }
     }
 }
@@ -86,6 +323,7 @@ impl SearchResult {
             score,
             snippet,
             match_positions,
+            collapsed_ancestors: Vec::new(),
         }
     }
 
@@ -95,6 +333,109 @@ impl SearchResult {
     }
 }
 
+/// Sort results best first and cut out the page selected by `offset` and `limit`
+///
+/// Equal scores are ordered by node ID so pages stay stable between calls.
+/// Returns the number of results before paging.
+pub(crate) fn rank_and_page(results: &mut Vec<SearchResult>, options: &SearchOptions) -> usize {
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.node_id.as_uuid().cmp(&b.node_id.as_uuid()))
+    });
+
+    let total_matches = results.len();
+    results.drain(..options.offset.min(total_matches));
+    if let Some(limit) = options.limit {
+        results.truncate(limit);
+    }
+    total_matches
+}
+
+/// Score a node's text, tags, "key: value" metadata entries and note against a query
+///
+/// Tag, metadata and note matches are only considered when enabled in `options`.
+pub(crate) fn score_fields(
+    node_id: NodeId,
+    text: &str,
+    tags: &[String],
+    metadata: &[String],
+    note: Option<&str>,
+    matcher: &QueryMatcher,
+    options: &SearchOptions,
+) -> Option<SearchResult> {
+    let (text_weight, tag_weight, metadata_weight, note_weight) = options.score_combination.weights();
+    // Best weighted score per field: text, tags, metadata, note
+    let mut field_scores = [0.0; 4];
+    let mut best_score = 0.0;
+    let mut best_snippet = String::new();
+    let mut best_positions = Vec::new();
+
+    // Search in node text
+    if let Some((score, snippet, positions)) = matcher.find(text, options) {
+        let adjusted_score = score * text_weight;
+        field_scores[0] = adjusted_score;
+        if adjusted_score > best_score {
+            best_score = adjusted_score;
+            best_snippet = snippet;
+            best_positions = positions;
+        }
+    }
+
+    // Search in tags if enabled
+    if options.include_tags {
+        for tag in tags {
+            if let Some((score, snippet, positions)) = matcher.find(tag, options) {
+                // Tag matches count less than text matches by default
+                let adjusted_score = score * tag_weight;
+                field_scores[1] = f64::max(field_scores[1], adjusted_score);
+                if adjusted_score > best_score {
+                    best_score = adjusted_score;
+                    best_snippet = format!("Tag: {}", snippet);
+                    best_positions = positions;
+                }
+            }
+        }
+    }
+
+    // Search in the note if enabled
+    if let Some(note) = note.filter(|_| options.include_notes) {
+        if let Some((score, snippet, positions)) = matcher.find(note, options) {
+            let adjusted_score = score * note_weight;
+            field_scores[3] = adjusted_score;
+            if adjusted_score > best_score {
+                best_score = adjusted_score;
+                best_snippet = format!("Note: {}", snippet);
+                best_positions = positions;
+            }
+        }
+    }
+
+    // Search in metadata if enabled
+    if options.include_metadata {
+        for entry in metadata {
+            if let Some((score, snippet, positions)) = matcher.find(entry, options) {
+                // Metadata matches count less again
+                let adjusted_score = score * metadata_weight;
+                field_scores[2] = f64::max(field_scores[2], adjusted_score);
+                if adjusted_score > best_score {
+                    best_score = adjusted_score;
+                    best_snippet = format!("Metadata: {}", snippet);
+                    best_positions = positions;
+                }
+            }
+        }
+    }
+
+    // The snippet and positions always come from the best single field
+    let score = options.score_combination.combine(field_scores);
+    if score > 0.0 {
+        Some(SearchResult::new(node_id, score, best_snippet, best_positions))
+    } else {
+        None
+    }
+}
+
 /// Main search interface for graphs
 impl Graph {
     /// Search for nodes containing the given query
@@ -109,112 +450,99 @@ impl Graph {
         options: &SearchOptions,
         context: &SearchContext,
     ) -> Vec<SearchResult> {
-        if query.trim().is_empty() {
-            return Vec::new();
+        self.search_result_set(query, options, context).results
+    }
+
+    /// Search for nodes, failing if the query cannot be used (e.g. an invalid regex)
+    pub fn try_search(&self, query: &str, options: &SearchOptions) -> MindmapResult<Vec<SearchResult>> {
+        self.try_search_result_set(query, options, &SearchContext::All)
+            .map(|result_set| result_set.results)
+    }
+
+    /// Search with context filtering, reporting whether the timeout cut the search short
+    ///
+    /// An invalid regex pattern yields no results; use
+    /// [`Graph::try_search_result_set`] to get the error instead.
+    pub fn search_result_set(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        context: &SearchContext,
+    ) -> SearchResultSet {
+        self.try_search_result_set(query, options, context).unwrap_or_else(|e| {
+            log::warn!("Search failed: {}", e);
+            SearchResultSet::default()
+        })
+    }
+
+    /// Search with context filtering, failing if the query cannot be used
+    pub fn try_search_result_set(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        context: &SearchContext,
+    ) -> MindmapResult<SearchResultSet> {
+        let matcher = QueryMatcher::new(query, options)?;
+        if matcher.is_empty() {
+            return Ok(SearchResultSet::default());
         }
 
+        let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+        let mut truncated = false;
         let mut results = Vec::new();
-        let search_query = if options.case_sensitive {
-            query.to_string()
-        } else {
-            query.to_lowercase()
-        };
 
-        // Get nodes to search based on context
-        let nodes_to_search = self.get_search_candidates(context);
+        // Get nodes to search based on context, narrowed by the index if there is one
+        let nodes_to_search = match (context, self.search_index()) {
+            (SearchContext::All, Some(index)) => {
+                let mut candidates = index.candidates(query, options);
+                candidates.extend(self.stale_search_nodes());
+                candidates.into_iter().filter_map(|id| self.get_node(id)).collect()
+            }
+            _ => self.get_search_candidates(context),
+        };
 
         for node in nodes_to_search {
-            if let Some(result) = self.search_node(node, &search_query, options) {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                truncated = true;
+                break;
+            }
+
+            let collapsed_ancestors = self.get_collapsed_ancestors(node.id);
+            if !options.include_collapsed && !collapsed_ancestors.is_empty() {
+                continue;
+            }
+
+            if let Some(mut result) = self.search_node(node, &matcher, options) {
                 if result.score >= options.min_score {
+                    result.collapsed_ancestors = collapsed_ancestors;
                     results.push(result);
                 }
             }
         }
 
-        // Sort by score (highest first)
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Apply limit
-        if let Some(limit) = options.limit {
-            results.truncate(limit);
-        }
-
-        results
+        let total_matches = rank_and_page(&mut results, options);
+        Ok(SearchResultSet { results, truncated, total_matches })
     }
 
     /// Search a single node for matches
-    fn search_node(&self, node: &Node, query: &str, options: &SearchOptions) -> Option<SearchResult> {
-        let mut best_score = 0.0;
-        let mut best_snippet = String::new();
-        let mut best_positions = Vec::new();
-
-        // Search in node text
-        let node_text = if options.case_sensitive {
-            node.text.clone()
+    fn search_node(&self, node: &Node, matcher: &QueryMatcher, options: &SearchOptions) -> Option<SearchResult> {
+        let metadata: Vec<String> = if options.include_metadata {
+            node.metadata.iter().map(|(key, value)| format!("{}: {}", key, value)).collect()
         } else {
-            node.text.to_lowercase()
+            Vec::new()
         };
 
-        if let Some((score, snippet, positions)) = fuzzy_search(&node_text, query, options.exact_match_boost) {
-            if score > best_score {
-                best_score = score;
-                best_snippet = snippet;
-                best_positions = positions;
-            }
-        }
-
-        // Search in tags if enabled
-        if options.include_tags {
-            for tag in &node.tags {
-                let tag_text = if options.case_sensitive {
-                    tag.clone()
-                } else {
-                    tag.to_lowercase()
-                };
-
-                if let Some((score, snippet, positions)) = fuzzy_search(&tag_text, query, options.exact_match_boost) {
-                    // Apply a slight penalty for tag matches vs text matches
-                    let adjusted_score = score * 0.8;
-                    if adjusted_score > best_score {
-                        best_score = adjusted_score;
-                        best_snippet = format!("Tag: {}", snippet);
-                        best_positions = positions;
-                    }
-                }
-            }
-        }
-
-        // Search in metadata if enabled
-        if options.include_metadata {
-            for (key, value) in &node.metadata {
-                let metadata_text = if options.case_sensitive {
-                    format!("{}: {}", key, value)
-                } else {
-                    format!("{}: {}", key, value).to_lowercase()
-                };
-
-                if let Some((score, snippet, positions)) = fuzzy_search(&metadata_text, query, options.exact_match_boost) {
-                    // Apply a penalty for metadata matches
-                    let adjusted_score = score * 0.6;
-                    if adjusted_score > best_score {
-                        best_score = adjusted_score;
-                        best_snippet = format!("Metadata: {}", snippet);
-                        best_positions = positions;
-                    }
-                }
-            }
-        }
+        score_fields(node.id, &node.text, &node.tags, &metadata, node.note.as_deref(), matcher, options)
+    }
 
-        if best_score > 0.0 {
-            Some(SearchResult::new(
-                node.id,
-                best_score,
-                best_snippet,
-                best_positions,
-            ))
-        } else {
-            None
-        }
+    /// Get the collapsed ancestors of a node, ordered from the root downwards
+    fn get_collapsed_ancestors(&self, node_id: NodeId) -> Vec<NodeId> {
+        let mut collapsed: Vec<NodeId> = self.get_ancestors(node_id)
+            .into_iter()
+            .filter(|id| self.get_node(*id).is_some_and(|node| node.collapsed))
+            .collect();
+        collapsed.reverse();
+        collapsed
     }
 
     /// Get candidate nodes based on search context
@@ -240,6 +568,12 @@ impl Graph {
                     .collect()
             }
             SearchContext::Roots => self.get_root_nodes(),
+            SearchContext::Tagged(tags) => {
+                let dedupe = self.tag_dedupe();
+                self.nodes()
+                    .filter(|node| node.tags.iter().any(|tag| tags.iter().any(|wanted| dedupe.matches(tag, wanted))))
+                    .collect()
+            }
         }
     }
 
@@ -299,6 +633,58 @@ impl Graph {
             .collect()
     }
 
+    /// Find nodes carrying the given tags
+    ///
+    /// Tags compare case-insensitively. With `match_all` a node must carry
+    /// every tag (AND), otherwise any one of them is enough (OR). The score is
+    /// the fraction of requested tags the node carries; results are ordered
+    /// best first, then by node ID.
+    pub fn search_by_tags(&self, tags: &[String], match_all: bool) -> Vec<SearchResult> {
+        let wanted: Vec<String> = tags.iter().map(|tag| tag.to_lowercase()).collect();
+        if wanted.is_empty() {
+            return Vec::new();
+        }
+
+        let mut results: Vec<SearchResult> = self
+            .nodes()
+            .filter_map(|node| {
+                let node_tags: Vec<String> = node.tags.iter().map(|tag| tag.to_lowercase()).collect();
+                let matched = wanted.iter().filter(|tag| node_tags.contains(tag)).count();
+                let accepted = if match_all { matched == wanted.len() } else { matched > 0 };
+                accepted.then(|| {
+                    SearchResult::new(node.id, matched as f64 / wanted.len() as f64, node.text.clone(), Vec::new())
+                })
+            })
+            .collect();
+
+        rank_and_page(&mut results, &SearchOptions { limit: None, ..SearchOptions::default() });
+        results
+    }
+
+    /// Count how many nodes carry each tag
+    ///
+    /// Tags that are the same under the graph's [`TagDedupe`](crate::models::TagDedupe) setting are
+    /// counted together, under the spelling that sorts first.
+    pub fn tag_counts(&self) -> HashMap<String, usize> {
+        let dedupe = self.tag_dedupe();
+        let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+        for node in self.nodes() {
+            let mut seen = HashSet::new();
+            for tag in &node.tags {
+                let key = dedupe.key(tag);
+                if !seen.insert(key.clone()) {
+                    continue;
+                }
+                let (spelling, count) = counts.entry(key).or_insert_with(|| (tag.clone(), 0));
+                if tag < spelling {
+                    *spelling = tag.clone();
+                }
+                *count += 1;
+            }
+        }
+        counts.into_values().collect()
+    }
+
     /// Search and highlight matches in the original text
     pub fn search_with_highlights(
         &self,
@@ -320,7 +706,10 @@ impl Graph {
 }
 
 /// Highlight matched positions in text with markers
-fn highlight_matches(text: &str, positions: &[(usize, usize)]) -> String {
+///
+/// Positions that are out of range, overlap an earlier one or do not fall on
+/// character boundaries are skipped rather than sliced.
+pub(crate) fn highlight_matches(text: &str, positions: &[(usize, usize)]) -> String {
     if positions.is_empty() {
         return text.to_string();
     }
@@ -329,29 +718,61 @@ fn highlight_matches(text: &str, positions: &[(usize, usize)]) -> String {
     let mut last_end = 0;
 
     for &(start, end) in positions {
-        // Add text before the match
-        if start > last_end {
-            result.push_str(&text[last_end..start]);
+        let usable = start >= last_end && start < end && end <= text.len()
+            && text.is_char_boundary(start) && text.is_char_boundary(end);
+        if !usable {
+            continue;
         }
 
+        // Add text before the match
+        result.push_str(&text[last_end..start]);
+
         // Add highlighted match
         result.push_str("**");
-        if end <= text.len() {
-            result.push_str(&text[start..end]);
-        }
+        result.push_str(&text[start..end]);
         result.push_str("**");
 
         last_end = end;
     }
 
     // Add remaining text
-    if last_end < text.len() {
-        result.push_str(&text[last_end..]);
-    }
+    result.push_str(&text[last_end..]);
 
     result
 }
 
+/// Translate positions found in a case-folded copy back to the original text
+///
+/// Lowercasing can change the byte length of some characters. When the
+/// character count is unchanged the positions are mapped by character index;
+/// otherwise they are clamped to character boundaries of the original.
+fn remap_positions(haystack: &str, original: &str, positions: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    if haystack.len() == original.len() {
+        return positions;
+    }
+
+    if haystack.chars().count() != original.chars().count() {
+        return positions
+            .into_iter()
+            .map(|(start, end)| {
+                let start = fuzzy::floor_char_boundary(original, start);
+                (start, fuzzy::ceil_char_boundary(original, end.max(start)))
+            })
+            .collect();
+    }
+
+    let to_original: HashMap<usize, usize> = haystack.char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(haystack.len()))
+        .zip(original.char_indices().map(|(index, _)| index).chain(std::iter::once(original.len())))
+        .collect();
+
+    positions
+        .into_iter()
+        .filter_map(|(start, end)| Some((*to_original.get(&start)?, *to_original.get(&end)?)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +859,38 @@ mod tests {
         assert!(results_no_tags.len() <= results.len()); // Should be less or equal (in case AI appears in text too)
     }
 
+    #[test]
+    fn test_sum_combination_rewards_matches_in_several_fields() {
+        let mut graph = Graph::new();
+        let title_only = graph.add_node(Node::new("Rust workshop")).unwrap();
+        let mut tagged = Node::new("Rust workshop");
+        tagged.add_tag("rust-meetups");
+        let tagged = graph.add_node(tagged).unwrap();
+
+        // The best field alone ties the two nodes
+        let max = graph.search("rust", &SearchOptions::default());
+        let score = |results: &[SearchResult], id| results.iter().find(|r| r.node_id == id).unwrap().score;
+        assert_eq!(score(&max, title_only), score(&max, tagged));
+
+        // Summing adds the tag match on top of the same title score
+        let options = SearchOptions {
+            score_combination: ScoreCombination::Sum,
+            ..SearchOptions::default()
+        };
+        let sum = graph.search("rust", &options);
+        assert_eq!(sum[0].node_id, tagged);
+        assert_eq!(score(&sum, title_only), score(&max, title_only));
+        assert!(score(&sum, tagged) > score(&sum, title_only));
+
+        // Explicit weights can ignore tags altogether
+        let options = SearchOptions {
+            score_combination: ScoreCombination::WeightedSum { text: 1.0, tags: 0.0, metadata: 0.0, notes: 0.0 },
+            ..SearchOptions::default()
+        };
+        let weighted = graph.search("rust", &options);
+        assert_eq!(score(&weighted, tagged), score(&weighted, title_only));
+    }
+
     #[test]
     fn test_metadata_search() {
         let graph = create_test_graph();
@@ -450,6 +903,34 @@ mod tests {
         assert!(!results.is_empty()); // Should find node with "advanced" in metadata
     }
 
+    #[test]
+    fn test_note_search() {
+        let mut graph = create_test_graph();
+        let mut node = Node::new("Reading list");
+        node.set_note(Some("## Sources\nStart with the *transformer* survey, then the benchmarks.".to_string()));
+        let node_id = graph.add_node(node).unwrap();
+
+        // Notes are only searched when enabled
+        let options = SearchOptions { mode: SearchMode::Exact, ..SearchOptions::default() };
+        assert!(graph.search("transformer", &options).is_empty());
+
+        let options = SearchOptions { include_notes: true, ..options };
+        let results = graph.search("transformer", &options);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].node_id, node_id);
+        assert!(results[0].snippet.starts_with("Note: "));
+
+        // The index finds note matches as well
+        graph.enable_search_index();
+        let indexed = graph.search("transformer", &options);
+        assert_eq!(indexed.len(), 1);
+        assert_eq!(indexed[0].score, results[0].score);
+
+        graph.get_node_mut(node_id).unwrap().set_note(None);
+        graph.refresh_search_index();
+        assert!(graph.search("transformer", &options).is_empty());
+    }
+
     #[test]
     fn test_search_with_context() {
         let graph = create_test_graph();
@@ -466,6 +947,11 @@ mod tests {
         assert!(!all_results.is_empty());
         assert_eq!(all_results.len(), subtree_results.len()); // All nodes are in the subtree
         assert!(root_results.is_empty()); // "Convolutional" not in root node
+
+        // Tags match as the graph deduplicates them, ignoring case by default
+        let tagged = |tags: &[&str]| SearchContext::Tagged(tags.iter().map(|tag| tag.to_string()).collect());
+        assert_eq!(graph.search_with_context("Convolutional", &options, &tagged(&["cnn"])).len(), 1);
+        assert!(graph.search_with_context("Convolutional", &options, &tagged(&["Algorithms"])).is_empty());
     }
 
     #[test]
@@ -562,4 +1048,386 @@ mod tests {
             assert!(results[i - 1].score >= results[i].score);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_search_in_collapsed_subtree() {
+        let mut graph = create_test_graph();
+        let find_id = |graph: &Graph, text: &str| graph.nodes().find(|n| n.text == text).unwrap().id;
+        let root_id = find_id(&graph, "Machine Learning Fundamentals");
+        let child_id = find_id(&graph, "Neural Networks and Deep Learning");
+        let grandchild_id = find_id(&graph, "Convolutional Neural Networks");
+
+        graph.get_node_mut(root_id).unwrap().set_collapsed(true);
+        graph.get_node_mut(child_id).unwrap().set_collapsed(true);
+
+        let results = graph.search("Convolutional", &SearchOptions::default());
+        let result = results.iter().find(|r| r.node_id == grandchild_id).unwrap();
+        assert_eq!(result.collapsed_ancestors, vec![root_id, child_id]);
+
+        // The collapsed root itself is visible
+        let results = graph.search("Fundamentals", &SearchOptions::default());
+        assert!(results.iter().any(|r| r.node_id == root_id && r.collapsed_ancestors.is_empty()));
+
+        let visible_only = SearchOptions {
+            include_collapsed: false,
+            ..SearchOptions::default()
+        };
+        let results = graph.search("Convolutional", &visible_only);
+        assert!(results.iter().all(|r| r.node_id != grandchild_id));
+    }
+
+    #[test]
+    fn test_search_timeout_returns_partial_results() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        for i in 0..20_000 {
+            graph.add_node(Node::new_child(root_id, format!("Topic about area {}", i))).unwrap();
+        }
+
+        let options = SearchOptions {
+            timeout: Some(Duration::from_millis(1)),
+            limit: None,
+            ..SearchOptions::default()
+        };
+
+        let started = Instant::now();
+        let result_set = graph.search_result_set("a", &options, &SearchContext::All);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(result_set.truncated);
+        assert!(result_set.results.len() < graph.node_count());
+        assert!(result_set.results.iter().all(|r| graph.contains_node(r.node_id) && r.score >= options.min_score));
+        assert!(result_set.results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        // Without a timeout the search runs to completion
+        let complete = graph.search_result_set("root", &SearchOptions::default(), &SearchContext::All);
+        assert!(!complete.truncated);
+        assert_eq!(complete.results[0].node_id, root_id);
+    }
+
+    #[test]
+    fn test_indexed_search_stays_in_sync() {
+        let mut graph = create_test_graph();
+        let options = SearchOptions::default();
+        let scanned = graph.search("learning", &options);
+
+        graph.enable_search_index();
+        let indexed = graph.search("learning", &options);
+
+        // Nodes containing the word score the same; the fuzzy-only match is skipped
+        assert_eq!(indexed.len(), 3);
+        assert!(scanned.len() > indexed.len());
+        for result in &indexed {
+            let scan = scanned.iter().find(|r| r.node_id == result.node_id).unwrap();
+            assert_eq!(scan.score, result.score);
+        }
+
+        // Added, updated and removed nodes are reflected without a rebuild
+        let new_id = graph.add_node(Node::new("Reinforcement Learning")).unwrap();
+        assert!(graph.search("reinforcement", &options).iter().any(|r| r.node_id == new_id));
+
+        let mut updated = graph.get_node(new_id).unwrap().clone();
+        updated.text = "Robotics".to_string();
+        graph.update_node(updated).unwrap();
+        assert!(graph.search("reinforcement", &options).is_empty());
+
+        // Direct mutation is picked up until the index is refreshed
+        graph.get_node_mut(new_id).unwrap().text = "Robotics Planning".to_string();
+        assert!(graph.stale_search_nodes().contains(&new_id));
+        assert_eq!(graph.search("planning", &options).len(), 1);
+        graph.refresh_search_index();
+        assert!(graph.stale_search_nodes().is_empty());
+        assert_eq!(graph.search("planning", &options).len(), 1);
+
+        graph.remove_node(new_id).unwrap();
+        assert!(graph.search("robotics", &options).is_empty());
+        assert!(!graph.search_index().unwrap().contains_node(new_id));
+
+        graph.disable_search_index();
+        assert!(graph.search_index().is_none());
+        assert_eq!(graph.search("learning", &options).len(), scanned.len());
+    }
+
+    #[test]
+    fn test_search_index_is_rebuilt_after_deserializing() {
+        let mut graph = create_test_graph();
+        graph.enable_search_index();
+        let options = SearchOptions::default();
+        let indexed = graph.search("learning", &options);
+
+        // Only the setting is stored, not the index itself
+        let json = serde_json::to_string(&graph).unwrap();
+        assert!(!json.contains("\"search_index\":{"));
+        let mut restored: Graph = serde_json::from_str(&json).unwrap();
+        assert!(restored.search_index().is_none());
+
+        restored.refresh_search_index();
+        assert_eq!(restored.search_index().unwrap().get_statistics().node_count, graph.node_count());
+        assert_eq!(restored.search("learning", &options).len(), indexed.len());
+
+        // Any mutation builds it too
+        let mut restored: Graph = serde_json::from_str(&json).unwrap();
+        let new_id = restored.add_node(Node::new("Transfer Learning")).unwrap();
+        assert!(restored.search_index().unwrap().contains_node(new_id));
+    }
+
+    #[test]
+    fn test_regex_search_mode() {
+        let mut graph = Graph::new();
+        let first = graph.add_node(Node::new("TODO-12 write tests")).unwrap();
+        let second = graph.add_node(Node::new("Review todo-7 and TODO-8")).unwrap();
+        graph.add_node(Node::new("TODO without a number")).unwrap();
+
+        let options = SearchOptions {
+            mode: SearchMode::Regex(r"TODO-\d+".to_string()),
+            case_sensitive: true,
+            ..SearchOptions::default()
+        };
+        let results = graph.try_search("", &options).unwrap();
+        let mut ids: Vec<NodeId> = results.iter().map(|r| r.node_id).collect();
+        ids.sort_by_key(|id| id.as_uuid());
+        let mut expected = vec![first, second];
+        expected.sort_by_key(|id| id.as_uuid());
+        assert_eq!(ids, expected);
+
+        let highlighted = graph.search_with_highlights("", &options);
+        let text_for = |id: NodeId| highlighted.iter().find(|(r, _)| r.node_id == id).unwrap().1.clone();
+        assert_eq!(text_for(first), "**TODO-12** write tests");
+        assert_eq!(text_for(second), "Review todo-7 and **TODO-8**");
+
+        // Case-insensitive regex picks up the lowercase match too
+        let options = SearchOptions { case_sensitive: false, ..options };
+        let highlighted = graph.search_with_highlights("", &options);
+        let (_, text) = highlighted.iter().find(|(r, _)| r.node_id == second).unwrap();
+        assert_eq!(text, "Review **todo-7** and **TODO-8**");
+    }
+
+    #[test]
+    fn test_invalid_regex_is_invalid_operation() {
+        let graph = create_test_graph();
+        let options = SearchOptions {
+            mode: SearchMode::Regex("(unclosed".to_string()),
+            ..SearchOptions::default()
+        };
+
+        let result = graph.try_search("", &options);
+        assert!(matches!(result, Err(MindmapError::InvalidOperation { .. })));
+        assert!(graph.search("", &options).is_empty());
+    }
+
+    #[test]
+    fn test_exact_search_mode() {
+        let graph = create_test_graph();
+        let fuzzy = graph.search("nets", &SearchOptions::default());
+        assert!(!fuzzy.is_empty());
+
+        let exact = SearchOptions { mode: SearchMode::Exact, ..SearchOptions::default() };
+        assert!(graph.search("nets", &exact).is_empty());
+        assert_eq!(graph.search("neural networks", &exact).len(), 2);
+    }
+
+    #[test]
+    fn test_fuzzy_algorithm_selection() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("Receive the shipment")).unwrap();
+
+        let levenshtein = SearchOptions { fuzzy_algorithm: FuzzyAlgorithm::Levenshtein, ..SearchOptions::default() };
+        let results = graph.search("recieve", &levenshtein);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].node_id, node_id);
+        assert!(results[0].score <= 1.0);
+
+        let subsequence = SearchOptions { fuzzy_algorithm: FuzzyAlgorithm::Subsequence, ..SearchOptions::default() };
+        assert!(graph.search("recieve", &subsequence).is_empty());
+        assert_eq!(graph.search("shpmnt", &subsequence).len(), 1);
+    }
+
+    #[test]
+    fn test_word_position_granularity() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("Cloud networking basics")).unwrap();
+        let start = "Cloud ".len();
+        let end = start + "networking".len();
+
+        let char_options = SearchOptions::default();
+        let results = graph.search("net", &char_options);
+        assert_eq!(results[0].node_id, node_id);
+        assert_eq!(results[0].match_positions, vec![(start, start + 3)]);
+
+        let word_options = SearchOptions {
+            position_granularity: PositionGranularity::Word,
+            ..SearchOptions::default()
+        };
+        let results = graph.search("net", &word_options);
+        assert_eq!(results[0].match_positions, vec![(start, end)]);
+
+        let highlighted = graph.search_with_highlights("net", &word_options);
+        assert_eq!(highlighted[0].1, "Cloud **networking** basics");
+    }
+
+    #[test]
+    fn test_word_position_granularity_after_case_folding() {
+        let mut graph = Graph::new();
+        // Lowercasing 'İ' adds a byte, so the folded haystack is longer than the text
+        let text = "Visit İstanbul today";
+        graph.add_node(Node::new(text)).unwrap();
+        let start = "Visit ".len();
+        let end = start + "İstanbul".len();
+
+        let word_options = SearchOptions {
+            position_granularity: PositionGranularity::Word,
+            ..SearchOptions::default()
+        };
+        let results = graph.search("stan", &word_options);
+        assert_eq!(results[0].match_positions, vec![(start, end)]);
+        assert_eq!(&text[start..end], "İstanbul");
+    }
+
+    #[test]
+    fn test_highlight_multibyte_text() {
+        let mut graph = Graph::new();
+        let node_id = graph.add_node(Node::new("深度学习 deep learning")).unwrap();
+        let options = SearchOptions::default();
+
+        let highlighted = graph.search_with_highlights("深度", &options);
+        assert_eq!(highlighted[0].0.node_id, node_id);
+        assert_eq!(highlighted[0].1, "**深度**学习 deep learning");
+
+        let highlighted = graph.search_with_highlights("learning", &options);
+        assert_eq!(highlighted[0].1, "深度学习 deep **learning**");
+
+        // Character-by-character matches highlight whole characters
+        let highlighted = graph.search_with_highlights("深学", &options);
+        assert_eq!(highlighted[0].1, "**深**度**学**习 deep learning");
+
+        // Case folding that changes byte lengths maps back to the original text
+        let node_id = graph.add_node(Node::new("ȺBC data")).unwrap();
+        let results = graph.search("data", &options);
+        let result = results.iter().find(|r| r.node_id == node_id).unwrap();
+        let text = &graph.get_node(node_id).unwrap().text;
+        let (start, end) = result.match_positions[0];
+        assert_eq!(&text[start..end], "data");
+    }
+
+    #[test]
+    fn test_search_pagination() {
+        let mut graph = Graph::new();
+        for i in 0..25 {
+            // Many identical texts so paging has to break ties
+            let text = if i % 5 == 0 { format!("Topic {}", i) } else { "Topic".to_string() };
+            graph.add_node(Node::new(text)).unwrap();
+        }
+
+        let all_options = SearchOptions { limit: None, ..SearchOptions::default() };
+        let all = graph.search_result_set("topic", &all_options, &SearchContext::All);
+        assert_eq!(all.total_matches, 25);
+        assert_eq!(all.results.len(), 25);
+
+        let mut paged = Vec::new();
+        for page in 0..3 {
+            let options = SearchOptions { limit: Some(10), offset: page * 10, ..SearchOptions::default() };
+            let result_set = graph.search_result_set("topic", &options, &SearchContext::All);
+            assert_eq!(result_set.total_matches, 25);
+            assert_eq!(result_set.results.len(), if page < 2 { 10 } else { 5 });
+            paged.extend(result_set.results);
+        }
+        assert_eq!(paged, all.results);
+
+        // The same page comes back in the same order every time
+        let options = SearchOptions { limit: Some(10), offset: 10, ..SearchOptions::default() };
+        let again = graph.search_result_set("topic", &options, &SearchContext::All);
+        assert_eq!(again.results, all.results[10..20].to_vec());
+
+        let past_end = SearchOptions { offset: 100, ..SearchOptions::default() };
+        let result_set = graph.search_result_set("topic", &past_end, &SearchContext::All);
+        assert!(result_set.results.is_empty());
+        assert_eq!(result_set.total_matches, 25);
+    }
+
+    #[test]
+    fn test_search_by_tags_and_or() {
+        let graph = create_test_graph();
+        let tags = vec!["ai".to_string(), "Deep Learning".to_string()];
+
+        let any = graph.search_by_tags(&tags, false);
+        assert_eq!(any.len(), 3);
+        assert_eq!(any[0].snippet, "Neural Networks and Deep Learning");
+        assert_eq!(any[0].score, 1.0);
+        assert!(any[1..].iter().all(|r| r.score == 0.5));
+
+        let all = graph.search_by_tags(&tags, true);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].snippet, "Neural Networks and Deep Learning");
+
+        assert!(graph.search_by_tags(&[], false).is_empty());
+        assert!(graph.search_by_tags(&["Missing".to_string()], false).is_empty());
+    }
+
+    #[test]
+    fn test_tag_counts_follow_removal() {
+        let mut graph = create_test_graph();
+        let counts = graph.tag_counts();
+        assert_eq!(counts["AI"], 3);
+        assert_eq!(counts["CNN"], 1);
+        assert_eq!(counts.len(), 5);
+
+        let cnn_id = graph.search_by_tags(&["CNN".to_string()], false)[0].node_id;
+        graph.remove_node(cnn_id).unwrap();
+        let algorithms_id = graph.search_by_tags(&["Algorithms".to_string()], false)[0].node_id;
+        graph.remove_node(algorithms_id).unwrap();
+
+        let counts = graph.tag_counts();
+        assert_eq!(counts["AI"], 2);
+        assert!(!counts.contains_key("CNN"));
+        assert!(!counts.contains_key("Algorithms"));
+    }
+
+    #[test]
+    fn test_tag_counts_follow_tag_dedupe() {
+        let mut graph = Graph::new();
+        for tags in [vec!["AI"], vec!["ai", "ML"], vec!["Ai"]] {
+            let mut node = Node::new("Tagged");
+            node.tags = tags.into_iter().map(str::to_string).collect();
+            graph.add_node(node).unwrap();
+        }
+
+        let counts = graph.tag_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["AI"], 3);
+        assert_eq!(counts["ML"], 1);
+
+        graph.set_tag_dedupe(crate::models::TagDedupe::CaseSensitive);
+        let counts = graph.tag_counts();
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts["ai"], 1);
+        assert_eq!(counts["Ai"], 1);
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let mut graph = Graph::new();
+        let network = graph.add_node(Node::new("Network")).unwrap();
+        let internet = graph.add_node(Node::new("Internet")).unwrap();
+
+        let matches = |graph: &Graph, query: &str, match_mode: MatchMode| {
+            let options = SearchOptions { match_mode, ..SearchOptions::default() };
+            let mut ids: Vec<NodeId> = graph.search(query, &options).into_iter().map(|r| r.node_id).collect();
+            ids.sort_by_key(|id| id.as_uuid());
+            ids
+        };
+        let sorted = |mut ids: Vec<NodeId>| {
+            ids.sort_by_key(|id| id.as_uuid());
+            ids
+        };
+
+        assert_eq!(matches(&graph, "net", MatchMode::Prefix), vec![network]);
+        assert_eq!(matches(&graph, "net", MatchMode::Suffix), vec![internet]);
+        assert_eq!(matches(&graph, "net", MatchMode::Contains), sorted(vec![network, internet]));
+        assert!(matches(&graph, "net", MatchMode::Exact).is_empty());
+        assert_eq!(matches(&graph, "network", MatchMode::Exact), vec![network]);
+
+        // The inverted index narrows prefix candidates without changing the results
+        graph.enable_search_index();
+        assert_eq!(matches(&graph, "net", MatchMode::Prefix), vec![network]);
+        assert_eq!(matches(&graph, "net", MatchMode::Contains), sorted(vec![network, internet]));
+    }
+}
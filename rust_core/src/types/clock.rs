@@ -0,0 +1,11 @@
+//! Platform-independent clock types
+//!
+//! `std::time::Instant` and `SystemTime` panic on `wasm32-unknown-unknown`.
+//! With the `wasm` feature these names resolve to JavaScript-backed
+//! replacements with the same API, so timing code works in the browser.
+
+#[cfg(not(feature = "wasm"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "wasm")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
@@ -8,6 +8,20 @@ use std::fmt;
 use std::str::FromStr;
 use uuid::Uuid;
 
+/// Number of hex digits kept by the short form of node and edge IDs
+///
+/// Short IDs are for display and diagnostics only. With 32 bits, two IDs
+/// share a short form with probability of about one in four billion, so
+/// collisions are unlikely in a single mindmap but possible across many;
+/// anything that must identify an entity uses the full ID.
+pub const SHORT_ID_LEN: usize = 8;
+
+/// First [`SHORT_ID_LEN`] hex digits of a UUID
+fn short_uuid(uuid: &Uuid) -> String {
+    let mut buffer = Uuid::encode_buffer();
+    uuid.simple().encode_lower(&mut buffer)[..SHORT_ID_LEN].to_string()
+}
+
 /// Strongly-typed wrapper for node identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NodeId(pub Uuid);
@@ -24,6 +38,10 @@ pub struct DocumentId(pub Uuid);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MindmapId(pub Uuid);
 
+/// Strongly-typed wrapper for node attachment identifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AttachmentId(pub Uuid);
+
 impl NodeId {
     /// Create a new random node ID
     pub fn new() -> Self {
@@ -39,6 +57,13 @@ impl NodeId {
     pub fn as_uuid(&self) -> Uuid {
         self.0
     }
+
+    /// Truncated form for display and diagnostics, e.g. `1a2b3c4d`
+    ///
+    /// Stable for a given ID but not guaranteed unique; see [`SHORT_ID_LEN`].
+    pub fn short(&self) -> String {
+        short_uuid(&self.0)
+    }
 }
 
 impl EdgeId {
@@ -56,6 +81,13 @@ impl EdgeId {
     pub fn as_uuid(&self) -> Uuid {
         self.0
     }
+
+    /// Truncated form for display and diagnostics, e.g. `1a2b3c4d`
+    ///
+    /// Stable for a given ID but not guaranteed unique; see [`SHORT_ID_LEN`].
+    pub fn short(&self) -> String {
+        short_uuid(&self.0)
+    }
 }
 
 impl DocumentId {
@@ -92,16 +124,43 @@ impl MindmapId {
     }
 }
 
+impl AttachmentId {
+    /// Create a new random attachment ID
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Create an attachment ID from an existing UUID
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Get the inner UUID
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
 // Display implementations
 impl fmt::Display for NodeId {
+    /// Formats as `node:<uuid>`, or `node:<short>` with the alternate flag (`{:#}`)
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "node:{}", self.0)
+        if f.alternate() {
+            write!(f, "node:{}", self.short())
+        } else {
+            write!(f, "node:{}", self.0)
+        }
     }
 }
 
 impl fmt::Display for EdgeId {
+    /// Formats as `edge:<uuid>`, or `edge:<short>` with the alternate flag (`{:#}`)
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "edge:{}", self.0)
+        if f.alternate() {
+            write!(f, "edge:{}", self.short())
+        } else {
+            write!(f, "edge:{}", self.0)
+        }
     }
 }
 
@@ -117,6 +176,12 @@ impl fmt::Display for MindmapId {
     }
 }
 
+impl fmt::Display for AttachmentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "attachment:{}", self.0)
+    }
+}
+
 // FromStr implementations for parsing from strings
 impl FromStr for NodeId {
     type Err = uuid::Error;
@@ -154,6 +219,15 @@ impl FromStr for MindmapId {
     }
 }
 
+impl FromStr for AttachmentId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let uuid_str = s.strip_prefix("attachment:").unwrap_or(s);
+        Ok(Self(Uuid::from_str(uuid_str)?))
+    }
+}
+
 // Default implementations
 impl Default for NodeId {
     fn default() -> Self {
@@ -179,6 +253,12 @@ impl Default for MindmapId {
     }
 }
 
+impl Default for AttachmentId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,11 +297,13 @@ mod tests {
         let edge_id = EdgeId::new();
         let doc_id = DocumentId::new();
         let mindmap_id = MindmapId::new();
+        let attachment_id = AttachmentId::new();
 
         assert!(node_id.to_string().starts_with("node:"));
         assert!(edge_id.to_string().starts_with("edge:"));
         assert!(doc_id.to_string().starts_with("doc:"));
         assert!(mindmap_id.to_string().starts_with("mindmap:"));
+        assert!(attachment_id.to_string().starts_with("attachment:"));
     }
 
     #[test]
@@ -235,6 +317,10 @@ mod tests {
         let uuid_str = node_id.to_string();
         let parsed = NodeId::from_str(&uuid_str).unwrap();
         assert_eq!(node_id, parsed);
+
+        let attachment_id = AttachmentId::new();
+        assert_eq!(AttachmentId::from_str(&attachment_id.to_string()).unwrap(), attachment_id);
+        assert_eq!(AttachmentId::from_str(&attachment_id.as_uuid().to_string()).unwrap(), attachment_id);
     }
 
     #[test]
@@ -244,4 +330,22 @@ mod tests {
         let deserialized: NodeId = serde_json::from_str(&json).unwrap();
         assert_eq!(node_id, deserialized);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_short_ids() {
+        let uuid = Uuid::parse_str("1a2b3c4d-5e6f-4a8b-9c0d-1e2f3a4b5c6d").unwrap();
+        let node_id = NodeId::from_uuid(uuid);
+        assert_eq!(node_id.short(), "1a2b3c4d");
+        assert_eq!(node_id.short(), NodeId::from_uuid(uuid).short());
+        assert_eq!(format!("{:#}", node_id), "node:1a2b3c4d");
+        assert_eq!(format!("{:#}", EdgeId::from_uuid(uuid)), "edge:1a2b3c4d");
+        assert_eq!(node_id.to_string(), format!("node:{}", uuid));
+
+        // Short forms can collide in principle; among a few hundred random
+        // IDs the odds are around one in a hundred thousand
+        let shorts: std::collections::HashSet<String> =
+            (0..300).map(|_| NodeId::new().short()).collect();
+        assert_eq!(shorts.len(), 300);
+        assert!(shorts.iter().all(|short| short.len() == SHORT_ID_LEN));
+    }
+}
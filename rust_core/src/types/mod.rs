@@ -7,12 +7,15 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 // Public exports for sub-modules
+pub mod clock;
 pub mod ids;
 pub mod position;
+pub mod rng;
 
 // Re-export commonly used types
 pub use ids::*;
 pub use position::*;
+pub use rng::*;
 
 // ID types are now defined in the ids module as proper structs
 
@@ -60,6 +63,12 @@ pub enum MindmapError {
 
     #[error("Database error: {message}")]
     DatabaseError { message: String },
+
+    #[error("Version conflict: stored version {current} is newer than base version {expected}")]
+    ConflictError { current: u64, expected: u64 },
+
+    #[error("Attachment is {size} bytes, more than the limit of {limit} bytes")]
+    AttachmentTooLarge { size: u64, limit: u64 },
 }
 
 /// Utility functions for type conversions and validation
@@ -105,6 +114,43 @@ pub mod utils {
         (r, g, b, a)
     }
 
+    /// Convert hue (degrees), saturation and value (0.0 to 1.0) to an opaque color
+    pub fn hsv_to_color(hue: f64, saturation: f64, value: f64) -> Color {
+        let hue = hue.rem_euclid(360.0);
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+        let m = value - chroma;
+
+        let (r, g, b) = match (hue / 60.0) as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+        rgb_to_color(channel(r), channel(g), channel(b))
+    }
+
+    /// Generate a palette of visually distinct colors
+    ///
+    /// Hues are spread by the golden angle from a random starting hue, so the
+    /// same generator seed always yields the same palette.
+    pub fn generate_palette(count: usize, rng: &mut RngSource) -> Vec<Color> {
+        const GOLDEN_ANGLE: f64 = 137.507_764_050_037_85;
+
+        let start_hue = rng.range_f64(0.0, 360.0);
+        (0..count)
+            .map(|i| {
+                let saturation = rng.range_f64(0.55, 0.75);
+                let value = rng.range_f64(0.80, 0.95);
+                hsv_to_color(start_hue + i as f64 * GOLDEN_ANGLE, saturation, value)
+            })
+            .collect()
+    }
+
     /// Get current timestamp
     pub fn now() -> Timestamp {
         chrono::Utc::now()
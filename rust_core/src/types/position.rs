@@ -56,6 +56,72 @@ pub struct Polar {
     pub angle: Coordinate, // in radians
 }
 
+/// Encoding used for coordinates when serializing positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PositionEncoding {
+    /// Store coordinates as full-precision floating point values
+    #[default]
+    Float,
+    /// Store coordinates as integers scaled by `10^decimals`
+    FixedPoint { decimals: u8 },
+}
+
+impl PositionEncoding {
+    /// Largest supported number of fixed-point decimals
+    pub const MAX_DECIMALS: u8 = 9;
+
+    /// Round coordinates to whole integers
+    pub fn integer() -> Self {
+        Self::FixedPoint { decimals: 0 }
+    }
+
+    /// Store coordinates with the given number of decimal places
+    pub fn fixed_point(decimals: u8) -> Self {
+        Self::FixedPoint {
+            decimals: decimals.min(Self::MAX_DECIMALS),
+        }
+    }
+
+    /// Check if coordinates are stored as integers
+    pub fn is_fixed_point(&self) -> bool {
+        matches!(self, Self::FixedPoint { .. })
+    }
+
+    /// Maximum difference between an original and a reloaded coordinate
+    pub fn precision(&self) -> Coordinate {
+        match self {
+            Self::Float => 0.0,
+            Self::FixedPoint { .. } => 0.5 / self.scale(),
+        }
+    }
+
+    /// Round a coordinate to the precision it is stored with
+    pub fn quantize(&self, value: Coordinate) -> Coordinate {
+        match self {
+            Self::Float => value,
+            Self::FixedPoint { .. } => self.decode(self.encode(value)),
+        }
+    }
+
+    /// Convert a coordinate to its stored integer representation
+    pub fn encode(&self, value: Coordinate) -> i64 {
+        (value * self.scale()).round() as i64
+    }
+
+    /// Convert a stored integer back to a coordinate
+    pub fn decode(&self, value: i64) -> Coordinate {
+        value as Coordinate / self.scale()
+    }
+
+    /// Multiplier applied to coordinates before rounding
+    fn scale(&self) -> Coordinate {
+        match self {
+            Self::Float => 1.0,
+            Self::FixedPoint { decimals } => 10f64.powi((*decimals).min(Self::MAX_DECIMALS) as i32),
+        }
+    }
+}
+
 impl Point {
     /// Create a new point
     pub fn new(x: Coordinate, y: Coordinate) -> Self {
@@ -417,4 +483,19 @@ mod tests {
         assert!((back.x - point.x).abs() < 1e-10);
         assert!((back.y - point.y).abs() < 1e-10);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_position_encoding_round_trip() {
+        let encoding = PositionEncoding::fixed_point(2);
+        assert_eq!(encoding.encode(123.456789), 12346);
+        assert!((encoding.decode(12346) - 123.46).abs() < 1e-9);
+        assert_eq!(encoding.precision(), 0.005);
+
+        let integer = PositionEncoding::integer();
+        assert_eq!(integer.encode(-7.6), -8);
+        assert_eq!(integer.decode(-8), -8.0);
+
+        assert_eq!(PositionEncoding::default(), PositionEncoding::Float);
+        assert_eq!(PositionEncoding::Float.precision(), 0.0);
+    }
+}
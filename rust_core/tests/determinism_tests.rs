@@ -0,0 +1,63 @@
+//! Reproducibility tests for randomized operations
+//!
+//! These tests change the process-wide seed, so they live in their own test
+//! binary and run sequentially inside a single test function.
+
+use mindmap_core::graph::Graph;
+use mindmap_core::layout::{force::ForceLayoutEngine, utils, LayoutConfig, LayoutEngine};
+use mindmap_core::models::Node;
+use mindmap_core::types::{ids::NodeId, set_global_seed, utils::generate_palette, Color, Point, RngSource};
+
+use std::collections::HashMap;
+
+fn create_test_graph() -> Graph {
+    let mut graph = Graph::new();
+    let root_id = graph.add_node(Node::new("Root")).unwrap();
+    for i in 0..4 {
+        let child_id = graph.add_node(Node::new_child(root_id, format!("Child {}", i))).unwrap();
+        for j in 0..3 {
+            graph
+                .add_node(Node::new_child(child_id, format!("Grandchild {}.{}", i, j)))
+                .unwrap();
+        }
+    }
+    graph
+}
+
+/// Run every randomized operation once, drawing from the global seed
+fn run_randomized_operations(graph: &Graph) -> (HashMap<NodeId, Point>, Vec<Color>, HashMap<NodeId, Point>) {
+    let layout = ForceLayoutEngine::default()
+        .calculate_layout(graph, &LayoutConfig::default())
+        .unwrap();
+
+    let mut rng = RngSource::from_seed_or_global(None);
+    let palette = generate_palette(8, &mut rng);
+
+    let mut jittered: HashMap<NodeId, Point> = graph.nodes().map(|node| (node.id, Point::new(0.0, 0.0))).collect();
+    utils::jitter_positions(&mut jittered, 5.0, &mut rng);
+
+    (layout.positions, palette, jittered)
+}
+
+#[test]
+fn test_global_seed_reproduces_randomized_output() {
+    let graph = create_test_graph();
+
+    set_global_seed(Some(2024));
+    let first = run_randomized_operations(&graph);
+    let second = run_randomized_operations(&graph);
+
+    set_global_seed(Some(7));
+    let other_seed = run_randomized_operations(&graph);
+
+    set_global_seed(None);
+
+    assert_eq!(first.0, second.0, "force layout differs between runs");
+    assert_eq!(first.1, second.1, "palette differs between runs");
+    assert_eq!(first.2, second.2, "jittered placement differs between runs");
+
+    assert_eq!(first.1.len(), 8);
+    assert_ne!(first.1, other_seed.1);
+    assert_ne!(first.2, other_seed.2);
+    assert!(first.2.values().all(|p| p.x.abs() <= 5.0 && p.y.abs() <= 5.0));
+}
@@ -739,7 +739,7 @@ mod property_tests {
     // Property-based test strategies
 
     prop_compose! {
-        fn arb_node_text()(text in "[a-zA-Z0-9 ]{1,20}") -> String {
+        fn arb_node_text()(text in "[a-zA-Z0-9][a-zA-Z0-9 ]{0,19}") -> String {
             text
         }
     }
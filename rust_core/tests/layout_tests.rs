@@ -741,6 +741,9 @@ mod force_layout_tests {
             time_step: 0.1,
             max_iterations: 500,
             convergence_threshold: 0.01,
+            gravity_wells: Vec::new(),
+            theta: 0.5,
+            barnes_hut_threshold: 200,
         };
 
         let custom_engine = ForceLayoutEngine::new(custom_params)
@@ -1040,6 +1043,82 @@ mod layout_engine_integration_tests {
             }
         }
     }
+
+    #[test]
+    fn test_pinned_nodes_keep_their_coordinates() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let mut pinned = Node::new_child(root_id, "Pinned");
+        pinned.position = Point::new(123.0, 456.0);
+        pinned.set_pinned(true);
+        let pinned_id = graph.add_node(pinned).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(pinned_id, "Leaf")).unwrap();
+        for text in ["Free 1", "Free 2"] {
+            graph.add_node(Node::new_child(root_id, text)).unwrap();
+        }
+        let parent_links: Vec<(NodeId, NodeId)> = graph.nodes()
+            .filter_map(|node| Some((node.parent_id?, node.id)))
+            .collect();
+        for (parent_id, child_id) in parent_links {
+            graph.add_edge(Edge::new(parent_id, child_id)).unwrap();
+        }
+
+        let config = LayoutConfig { preserve_positions: true, ..LayoutConfig::default() };
+
+        let engines: Vec<Box<dyn LayoutEngine>> = vec![
+            Box::new(RadialLayoutEngine::default()),
+            Box::new(TreeLayoutEngine::default()),
+            Box::new(ForceLayoutEngine::default().with_seed(3)),
+            Box::new(LayoutEngineImpl::with_layout_type(LayoutType::Hyperbolic)),
+        ];
+        for engine in engines {
+            let layout = engine.calculate_layout(&graph, &config).unwrap();
+            let position = layout.positions[&pinned_id];
+            assert!(position.distance_to(&Point::new(123.0, 456.0)) < 1e-9,
+                    "{:?} moved the pinned node to {:?}", engine.layout_type(), position);
+            assert_ne!(layout.positions[&leaf_id], position);
+            assert!(layout.bounds.width() > 0.0);
+        }
+
+        // Without preserve_positions the pin is ignored
+        let layout = RadialLayoutEngine::default()
+            .calculate_layout(&graph, &LayoutConfig::default())
+            .unwrap();
+        assert!(layout.positions[&pinned_id].distance_to(&Point::new(123.0, 456.0)) > 1.0);
+    }
+
+    #[test]
+    fn test_pinned_nodes_take_part_in_overlap_resolution() {
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        let pinned_id = graph.add_node(Node::new_child(root_id, "Pinned")).unwrap();
+        let free_id = graph.add_node(Node::new_child(root_id, "Free")).unwrap();
+
+        let mut config = LayoutConfig { preserve_positions: true, ..LayoutConfig::default() };
+        for id in [root_id, pinned_id, free_id] {
+            config.node_sizes.insert(id, (80.0, 30.0));
+        }
+
+        let engines: Vec<Box<dyn LayoutEngine>> = vec![
+            Box::new(RadialLayoutEngine::default()),
+            Box::new(TreeLayoutEngine::default()),
+        ];
+        for engine in engines {
+            // Pin the node right where its free sibling would be laid out
+            let target = engine.calculate_layout(&graph, &config).unwrap().positions[&free_id];
+            let mut pinned_graph = graph.clone();
+            let pinned = pinned_graph.get_node_mut(pinned_id).unwrap();
+            pinned.position = target;
+            pinned.set_pinned(true);
+
+            let layout = engine.calculate_layout(&pinned_graph, &config).unwrap();
+            let (pinned_at, free_at) = (layout.positions[&pinned_id], layout.positions[&free_id]);
+            assert!(pinned_at.distance_to(&target) < 1e-9,
+                    "{:?} moved the pinned node to {:?}", engine.layout_type(), pinned_at);
+            let separated = (pinned_at.x - free_at.x).abs() >= 80.0 || (pinned_at.y - free_at.y).abs() >= 30.0;
+            assert!(separated, "{:?} left {:?} overlapping the pinned node", engine.layout_type(), free_at);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1166,6 +1245,23 @@ mod performance_tests {
                 graph.node_count(), duration.as_millis(), layout.converged, layout.iterations);
     }
 
+    #[test]
+    fn test_force_layout_barnes_hut_scaling() {
+        // Work per iteration is covered by the engine's interaction counts;
+        // here both repulsion modes must run large graphs within the budget
+        for (node_count, theta) in [(250, 0.5), (1000, 0.5), (1000, 0.0)] {
+            let graph = create_dense_graph(node_count);
+            let params = ForceParameters { max_iterations: 5, theta, ..ForceParameters::default() };
+            let engine = ForceLayoutEngine::new(params).with_seed(42);
+
+            let layout = engine.calculate_layout(&graph, &LayoutConfig::default()).unwrap();
+            assert_eq!(layout.positions.len(), node_count);
+            assert!(layout.iterations >= 1 && layout.iterations <= 5,
+                    "{} iterations for {} nodes at theta {}", layout.iterations, node_count, theta);
+            assert!(layout.positions.values().all(|p| p.x.is_finite() && p.y.is_finite()));
+        }
+    }
+
     #[test]
     fn test_layout_scaling_characteristics() {
         let sizes = vec![10, 25, 50];
@@ -1508,6 +1604,60 @@ mod edge_case_tests {
 }
 
 // Benchmark functions for future criterion integration
+#[cfg(test)]
+mod deep_hierarchy_tests {
+    use super::*;
+
+    const CHAIN_DEPTH: usize = 10_000;
+
+    /// Build a single chain of parent-child links, returning the IDs from the root down
+    fn create_linear_chain(length: usize) -> (Graph, Vec<NodeId>) {
+        let mut graph = Graph::new();
+        let mut ids = Vec::with_capacity(length);
+
+        let root = Node::new("Level 0");
+        ids.push(graph.add_node(root).unwrap());
+        for level in 1..length {
+            let node = Node::new_child(ids[level - 1], format!("Level {}", level));
+            ids.push(graph.add_node(node).unwrap());
+        }
+
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_layout_of_deep_chain_does_not_overflow() {
+        let (graph, ids) = create_linear_chain(CHAIN_DEPTH);
+
+        // Pairwise spacing passes are quadratic; this test is about depth
+        let config = LayoutConfig::builder()
+            .min_distance(0.0)
+            .parameter("resolve_collisions", 0.0)
+            .build()
+            .unwrap();
+
+        let tree = TreeLayoutEngine::default().calculate_layout(&graph, &config).unwrap();
+        assert_eq!(tree.positions.len(), CHAIN_DEPTH);
+        assert!(tree.positions.contains_key(&ids[CHAIN_DEPTH - 1]));
+
+        let radial = RadialLayoutEngine::default().calculate_layout(&graph, &config).unwrap();
+        assert_eq!(radial.positions.len(), CHAIN_DEPTH);
+    }
+
+    #[test]
+    fn test_traversal_of_deep_chain_does_not_overflow() {
+        let (graph, ids) = create_linear_chain(CHAIN_DEPTH);
+        let (root_id, leaf_id) = (ids[0], ids[CHAIN_DEPTH - 1]);
+
+        assert_eq!(graph.get_descendants(root_id).len(), CHAIN_DEPTH - 1);
+        assert_eq!(graph.get_ancestors(leaf_id).len(), CHAIN_DEPTH - 1);
+        assert!(!graph.has_cycles());
+
+        let clone = graph.clone_subgraph(root_id, None).unwrap();
+        assert_eq!(clone.node_count(), CHAIN_DEPTH);
+    }
+}
+
 #[cfg(test)]
 mod benchmark_functions {
     use super::*;
@@ -4,19 +4,20 @@
 //! round-trip data integrity, auto-save, backup, database migration, and error recovery.
 
 use mindmap_core::models::document::Document;
-// use mindmap_core::models::{Node, Edge}; // Unused for now
+use mindmap_core::graph::Graph;
+use mindmap_core::models::{Node, Edge};
 use mindmap_core::persistence::{
     DatabaseConfig, DatabaseOperations, SimpleSqliteDatabase,
     PersistenceManager, PersistenceConfig, BackupInfo, PersistenceStats,
     IsolationLevel, DatabaseStats
 };
-use mindmap_core::types::{ids::*, MindmapError};
+use mindmap_core::types::{ids::*, MindmapError, Point};
 
 // use std::collections::HashMap; // Unused for now
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::RwLock;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tempfile::{tempdir, TempDir};
 
 // Test utilities
@@ -185,11 +186,46 @@ mod database_operations_tests {
         assert!(stats.is_ok());
 
         let stats = stats.unwrap();
-        assert_eq!(stats.schema_version, 1);
+        assert_eq!(stats.schema_version, 2);
         // File size should be a reasonable value for an in-memory database
         assert!(stats.file_size == 0 || stats.file_size > 0); // Always true, but demonstrates the check
     }
 
+    #[test]
+    fn test_optimize_reclaims_free_pages() {
+        let (config, _temp_dir) = create_temp_database_config();
+        let mut db = SimpleSqliteDatabase::open(&config).unwrap();
+
+        let mut graph = Graph::new();
+        let root_id = graph.add_node(Node::new("Root")).unwrap();
+        for i in 0..500 {
+            graph.add_node(Node::new_child(root_id, format!("Idea {} {}", i, "detail ".repeat(20)))).unwrap();
+        }
+        let document = Document::new("Large", root_id);
+        db.save_document(&document).unwrap();
+        db.save_graph(document.id, &graph).unwrap();
+
+        let full = db.get_stats().unwrap();
+        assert!(full.page_count > 0);
+        assert!(full.wal_size_bytes > 0);
+
+        // Replacing the graph with just its root frees the pages the children used
+        let mut small = Graph::new();
+        small.add_node(graph.get_node(root_id).unwrap().clone()).unwrap();
+        db.save_graph(document.id, &small).unwrap();
+        let cleared = db.get_stats().unwrap();
+        assert!(cleared.free_pages > 0);
+        assert_eq!(cleared.page_count, full.page_count);
+
+        db.optimize().unwrap();
+        let optimized = db.get_stats().unwrap();
+        assert_eq!(optimized.free_pages, 0);
+        assert!(optimized.page_count < cleared.page_count);
+        assert_eq!(optimized.wal_size_bytes, 0);
+        assert_eq!(optimized.file_size, optimized.page_count * 4096);
+        assert_eq!(db.load_graph(document.id).unwrap().node_count(), 1);
+    }
+
     #[test]
     fn test_database_migration() {
         let config = create_in_memory_config();
@@ -211,16 +247,28 @@ mod database_operations_tests {
 
     #[test]
     fn test_database_backup_restore() {
-        let config = create_in_memory_config();
-        let mut db = SimpleSqliteDatabase::open(&config).unwrap();
-
-        // Backup should succeed (even if simplified implementation)
-        let backup_result = db.backup("test_backup.db");
-        assert!(backup_result.is_ok());
-
-        // Restore should succeed (even if simplified implementation)
-        let restore_result = db.restore("test_backup.db");
-        assert!(restore_result.is_ok());
+        let temp_dir = tempdir().unwrap();
+        let backup_path = temp_dir.path().join("test_backup.db");
+        let backup_path = backup_path.to_str().unwrap();
+
+        let mut db = SimpleSqliteDatabase::open(&create_in_memory_config()).unwrap();
+        let document = Document::new("Backed Up", NodeId::new());
+        db.save_document(&document).unwrap();
+        assert!(db.backup(backup_path).is_ok());
+        assert!(Path::new(backup_path).exists());
+
+        // A fresh database holds the document only after the restore
+        let mut fresh = SimpleSqliteDatabase::open(&create_in_memory_config()).unwrap();
+        assert!(fresh.load_document(document.id).unwrap().is_none());
+        assert!(fresh.restore(backup_path).is_ok());
+
+        let restored = fresh.load_document(document.id).unwrap().unwrap();
+        assert_eq!(restored.title, "Backed Up");
+        assert_eq!(restored.root_node, document.root_node);
+        assert_eq!(restored.created_at.timestamp_millis(), document.created_at.timestamp_millis());
+
+        // Restoring from a missing file fails instead of restoring nothing
+        assert!(fresh.restore(temp_dir.path().join("missing.db").to_str().unwrap()).is_err());
     }
 
     #[test]
@@ -343,11 +391,44 @@ mod data_integrity_tests {
         assert_eq!(complex_doc.title, "Complex Test Document");
         assert!(complex_doc.is_dirty);
 
-        // In a full implementation, this would test:
-        // - All nodes are preserved with correct properties
-        // - All edges maintain correct relationships
-        // - Metadata and timestamps are accurate
-        // - Custom properties survive round-trip
+        // Build a graph under the document's root
+        let mut graph = Graph::new();
+        let mut root = Node::new("Complex root");
+        root.id = complex_doc.root_node;
+        root.position = Point::new(10.0, 20.0);
+        graph.add_node(root).unwrap();
+        let mut child = Node::new_child(complex_doc.root_node, "Child 🌟");
+        child.add_tag("important");
+        child.set_metadata("priority", "high");
+        child.position = Point::new(-150.5, 75.25);
+        let child_id = graph.add_node(child).unwrap();
+        let leaf_id = graph.add_node(Node::new_child(child_id, "Leaf")).unwrap();
+        let link_id = graph.add_edge(Edge::new(leaf_id, complex_doc.root_node)).unwrap();
+
+        // Save both and read them back through a fresh connection
+        let (config, _temp_dir) = create_temp_database_config();
+        {
+            let mut db = SimpleSqliteDatabase::open(&config).unwrap();
+            db.save_document(&complex_doc).unwrap();
+            db.save_graph(complex_doc.id, &graph).unwrap();
+        }
+        let db = SimpleSqliteDatabase::open(&config).unwrap();
+        let loaded_doc = db.load_document(complex_doc.id).unwrap().unwrap();
+        let loaded = db.load_graph(complex_doc.id).unwrap();
+
+        assert_eq!(loaded_doc.title, complex_doc.title);
+        assert_eq!(loaded_doc.root_node, complex_doc.root_node);
+        assert_eq!(loaded.node_count(), graph.node_count());
+        for node in graph.nodes() {
+            let stored = loaded.get_node(node.id).unwrap();
+            assert_eq!(stored.text, node.text);
+            assert_eq!(stored.tags, node.tags);
+            assert_eq!(stored.metadata, node.metadata);
+            assert_eq!(stored.position, node.position);
+            assert_eq!(stored.parent_id, node.parent_id);
+        }
+        let link = loaded.get_edge(link_id).unwrap();
+        assert_eq!((link.from_node, link.to_node), (leaf_id, complex_doc.root_node));
     }
 
     #[test]
@@ -417,6 +498,42 @@ mod data_integrity_tests {
 
         assert!(doc.created_at <= doc.updated_at);
     }
+
+    #[test]
+    fn test_concurrent_saves_conflict() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("shared.db");
+        let db_config = DatabaseConfig::new(db_path.to_str().unwrap());
+
+        // The first manager creates the document
+        let mut first = PersistenceManager::new(db_config.clone(), PersistenceConfig::default()).unwrap();
+        let first_doc = first.create_document("Shared Plan").unwrap();
+        let document_id = first_doc.read().unwrap().id;
+        first.save_document().unwrap();
+        assert_eq!(first_doc.read().unwrap().version(), 1);
+
+        // A second manager opens the same version
+        let mut second = PersistenceManager::new(db_config, PersistenceConfig::default()).unwrap();
+        let second_doc = second.load_document(&document_id.to_string()).unwrap();
+        assert_eq!(second_doc.read().unwrap().version(), 1);
+
+        // The first manager saves an edit
+        first_doc.write().unwrap().set_title("Shared Plan v2");
+        first.save_document().unwrap();
+        assert_eq!(first_doc.read().unwrap().version(), 2);
+
+        // The second manager's edit is based on a stale version
+        second_doc.write().unwrap().set_title("Conflicting Plan");
+        let result = second.save_document();
+        assert!(matches!(result, Err(MindmapError::ConflictError { current: 2, expected: 1 })));
+        let second_doc = second_doc.read().unwrap();
+        assert!(second_doc.is_dirty);
+        assert_eq!(second_doc.version(), 1);
+
+        // The stored document is the first manager's
+        let stored = first.load_document(&document_id.to_string()).unwrap();
+        assert_eq!(stored.read().unwrap().title, "Shared Plan v2");
+    }
 }
 
 // Auto-save and Backup Tests
@@ -494,6 +611,37 @@ mod auto_save_backup_tests {
         assert!(backup_info.file_size > 0);
     }
 
+    #[test]
+    fn test_backup_restore_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("round_trip.db");
+        let db_config = DatabaseConfig::new(db_path.to_str().unwrap());
+
+        // Write a document and back it up
+        let mut manager = PersistenceManager::new(db_config.clone(), PersistenceConfig::default()).unwrap();
+        let doc = manager.create_document("Quarterly Plan").unwrap();
+        let document_id = doc.read().unwrap().id;
+        manager.save_document().unwrap();
+        let backup_info = manager.create_backup().unwrap();
+        drop(manager);
+
+        // Wipe the database
+        std::fs::remove_file(&db_path).unwrap();
+        let mut manager = PersistenceManager::new(db_config, PersistenceConfig::default()).unwrap();
+        assert!(matches!(
+            manager.load_document(&document_id.to_string()),
+            Err(MindmapError::DocumentNotFound { .. })
+        ));
+
+        // Restore and read the document back
+        manager.restore_from_backup(&backup_info.filename).unwrap();
+        let restored = manager.load_document(&document_id.to_string()).unwrap();
+        let restored = restored.read().unwrap();
+        assert_eq!(restored.id, document_id);
+        assert_eq!(restored.title, "Quarterly Plan");
+        assert!(!restored.is_dirty);
+    }
+
     #[test]
     fn test_backup_listing() {
         let (mut manager, _temp_dir) = create_test_persistence_manager();
@@ -542,6 +690,55 @@ mod auto_save_backup_tests {
         assert!(config.max_backups > 0); // Cleanup is configured
     }
 
+    #[test]
+    fn test_prune_backups_by_count_and_age() {
+        let (mut manager, temp_dir) = create_test_persistence_manager();
+        let _doc = manager.create_document("Prune Test").unwrap();
+        let backup_dir = temp_dir.path().join("backups");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        let write_backup = |age_secs: u64| {
+            let filename = format!("backup_{}.db", now - age_secs);
+            std::fs::write(backup_dir.join(&filename), b"backup").unwrap();
+            filename
+        };
+        let removed_names = |removed: Vec<BackupInfo>| removed.into_iter().map(|b| b.filename).collect::<Vec<_>>();
+        const HOUR: u64 = 60 * 60;
+        const DAY: u64 = 24 * HOUR;
+
+        // Creating a backup prunes ones past the default 30 day age
+        let expired = write_backup(40 * DAY);
+        manager.create_backup().unwrap();
+        assert!(!backup_dir.join(&expired).exists());
+        for backup in manager.list_backups().unwrap() {
+            std::fs::remove_file(backup_dir.join(backup.filename)).unwrap();
+        }
+
+        let minute_old = write_backup(60);
+        let hour_old = write_backup(HOUR);
+        let two_hours_old = write_backup(2 * HOUR);
+        let three_days_old = write_backup(3 * DAY);
+        let five_days_old = write_backup(5 * DAY);
+        std::fs::write(backup_dir.join("notes.db"), b"not a backup").unwrap();
+
+        // Age limit: both backups older than a day go, though four fit the count
+        let removed = manager.prune_backups(4, Duration::from_secs(DAY)).unwrap();
+        assert_eq!(removed_names(removed), vec![three_days_old, five_days_old]);
+
+        // Count limit: only the two newest stay
+        let removed = manager.prune_backups(2, Duration::MAX).unwrap();
+        assert_eq!(removed_names(removed), vec![two_hours_old]);
+
+        // The newest backup survives even when it is past the age limit
+        let removed = manager.prune_backups(10, Duration::from_secs(30)).unwrap();
+        assert_eq!(removed_names(removed), vec![hour_old]);
+        let remaining: Vec<String> = manager.list_backups().unwrap().into_iter().map(|b| b.filename).collect();
+        assert!(remaining.contains(&minute_old));
+        assert!(backup_dir.join("notes.db").exists());
+        assert!(manager.prune_backups(0, Duration::ZERO).unwrap().is_empty());
+    }
+
     #[test]
     fn test_persistence_stats() {
         let (mut manager, _temp_dir) = create_test_persistence_manager();
@@ -589,7 +786,7 @@ mod migration_tests {
         assert!(db.is_connected());
 
         let stats = db.get_stats().unwrap();
-        assert_eq!(stats.schema_version, 1);
+        assert_eq!(stats.schema_version, 2);
     }
 
     #[test]
@@ -1021,6 +1218,7 @@ mod configuration_tests {
             page_count: 10,
             free_pages: 2,
             schema_version: 1,
+            wal_size_bytes: 0,
         };
 
         assert_eq!(stats.document_count, 5);
@@ -1039,6 +1237,7 @@ mod configuration_tests {
             page_count: 5,
             free_pages: 1,
             schema_version: 1,
+            wal_size_bytes: 0,
         };
 
         let persistence_stats = PersistenceStats {
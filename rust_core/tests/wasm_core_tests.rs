@@ -0,0 +1,74 @@
+//! In-memory smoke test for the browser-compatible subset of the engine
+//!
+//! Only uses the modules available without persistence, threads or native
+//! clocks (graph, layout, search and io), so it also serves as a check that
+//! the subset builds for the web:
+//!
+//! ```text
+//! cargo build --target wasm32-unknown-unknown --no-default-features --features wasm
+//! cargo test --no-default-features --features wasm --test wasm_core_tests
+//! ```
+
+use mindmap_core::graph::Graph;
+use mindmap_core::io::{FileFormat, FormatManager, ImportExportOptions};
+use mindmap_core::layout::{LayoutEngineImpl, LayoutType};
+use mindmap_core::models::{Document, Node};
+use mindmap_core::search::{SearchContext, SearchOptions};
+use mindmap_core::types::clock::Instant;
+
+use std::time::Duration;
+
+fn create_in_memory_map() -> (Document, Graph) {
+    let mut graph = Graph::new();
+    let root_id = graph.add_node(Node::new("Browser Map")).unwrap();
+    for topic in ["Rendering", "Storage", "Networking"] {
+        let topic_id = graph.add_node(Node::new_child(root_id, topic)).unwrap();
+        for detail in ["Overview", "Details"] {
+            graph
+                .add_node(Node::new_child(topic_id, format!("{} {}", topic, detail)))
+                .unwrap();
+        }
+    }
+    (Document::new("Browser Map", root_id), graph)
+}
+
+#[test]
+fn test_in_memory_map_without_native_services() {
+    let (document, mut graph) = create_in_memory_map();
+    assert_eq!(graph.node_count(), 10);
+    assert!(graph.validate().is_ok());
+
+    // Layout
+    let engine = LayoutEngineImpl::new();
+    for layout_type in [LayoutType::Radial, LayoutType::Tree, LayoutType::Force] {
+        let result = engine.calculate_layout(&graph, layout_type).unwrap();
+        assert_eq!(result.positions.len(), graph.node_count());
+        assert!(result.positions.values().all(|p| p.x.is_finite() && p.y.is_finite()));
+    }
+
+    // Search, including the clock-based timeout
+    let options = SearchOptions {
+        timeout: Some(Duration::from_secs(5)),
+        ..SearchOptions::default()
+    };
+    let started = Instant::now();
+    let results = graph.search_result_set("storage", &options, &SearchContext::All);
+    assert!(!results.truncated);
+    assert!(results.results.len() >= 3);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // Import/export round trip through strings only
+    let nodes: Vec<Node> = graph.nodes().cloned().collect();
+    let manager = FormatManager::new();
+    let exported = manager
+        .export(&document, &nodes, FileFormat::Json, &ImportExportOptions::default())
+        .unwrap();
+    let imported = manager
+        .import(&exported.content, FileFormat::Json, &ImportExportOptions::default())
+        .unwrap();
+    assert_eq!(imported.node_count, graph.node_count());
+
+    graph = imported.to_graph().unwrap();
+    assert_eq!(graph.node_count(), 10);
+    assert!(graph.validate().is_ok());
+}